@@ -0,0 +1,278 @@
+use crate::utils::clock::{system_clock, SharedClock};
+use axum::{
+    body::{Body, Bytes},
+    extract::{MatchedPath, OriginalUri, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+/// Seconds responses of read-heavy routes are cached unless configured otherwise
+pub const DEFAULT_CACHE_TTLS: &[(&str, u64)] = &[
+    ("/api/governance/proposals", 15),
+    ("/api/governance/proposals/trending", 15),
+    ("/api/governance/proposals/{id}/distribution", 15),
+    ("/api/governance/delegates/{address}", 60),
+    ("/api/governance/capabilities", 300),
+    ("/api/governance/duration-presets", 300),
+    ("/api/governance/quorum-feasibility", 60),
+];
+
+/// Routes whose responses depend on the signed-in viewer, e.g. votes hidden
+/// until close but shown to their voter. Never cached, whatever the config.
+pub const VIEWER_AWARE_ROUTES: &[&str] = &["/api/governance/proposals/{id}/votes"];
+
+#[derive(Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    expires_at: DateTime<Utc>,
+}
+
+/// Successful GET responses of routes with a TTL, keyed by path and query.
+/// Any successful write through the cached routes clears every entry.
+#[derive(Clone)]
+pub struct ResponseCache {
+    ttls: Arc<HashMap<String, Duration>>,
+    entries: Arc<RwLock<HashMap<String, CachedResponse>>>,
+    clock: SharedClock,
+}
+
+impl ResponseCache {
+    /// Routes keep their default TTL unless `overrides` sets one. A TTL of 0
+    /// disables caching for the route.
+    pub fn new(overrides: &BTreeMap<String, u64>) -> Self {
+        let mut ttls: HashMap<String, u64> = DEFAULT_CACHE_TTLS
+            .iter()
+            .map(|(route, ttl)| (route.to_string(), *ttl))
+            .collect();
+        ttls.extend(overrides.iter().map(|(route, ttl)| (route.clone(), *ttl)));
+
+        Self {
+            ttls: Arc::new(
+                ttls.into_iter()
+                    .filter(|(_, ttl)| *ttl > 0)
+                    .map(|(route, ttl)| (route, Duration::seconds(ttl as i64)))
+                    .collect(),
+            ),
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// TTL of a route, by its pattern, e.g. `/api/governance/proposals/{id}`
+    pub fn ttl(&self, route: &str) -> Option<Duration> {
+        self.ttls.get(route).copied()
+    }
+
+    pub fn invalidate_all(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let now = self.clock.now();
+        let mut entries = self.entries.write().unwrap();
+        match entries.get(key) {
+            Some(cached) if now < cached.expires_at => Some(cached.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+/// `Cache-Control` telling proxies and clients to keep the response for
+/// as long as the server does
+fn cache_control(remaining: Duration) -> HeaderValue {
+    HeaderValue::from_str(&format!("public, max-age={}", remaining.num_seconds().max(0))).unwrap()
+}
+
+/// Serve GETs of routes with a TTL from the cache, storing successful
+/// responses, and clear the cache after successful writes. Signed-in requests
+/// and viewer-aware routes bypass the cache and are marked `private`.
+pub async fn cache_responses(
+    State(cache): State<ResponseCache>,
+    matched: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::GET {
+        let response = next.run(request).await;
+        // Any write may change what cached reads would return
+        if response.status().is_success() {
+            cache.invalidate_all();
+        }
+        return response;
+    }
+
+    let route = matched.as_ref().map(MatchedPath::as_str);
+    let private = request.headers().contains_key(header::AUTHORIZATION)
+        || route.is_some_and(|route| VIEWER_AWARE_ROUTES.contains(&route));
+    if private {
+        let mut response = next.run(request).await;
+        response.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static("private"));
+        return response;
+    }
+
+    let Some(ttl) = route.and_then(|route| cache.ttl(route)) else {
+        return next.run(request).await;
+    };
+
+    // Nested routers see a stripped URI; key on the one the client sent
+    let uri = match request.extensions().get::<OriginalUri>() {
+        Some(OriginalUri(uri)) => uri.clone(),
+        None => request.uri().clone(),
+    };
+    let key = uri.path_and_query().map(|path| path.as_str()).unwrap_or(uri.path()).to_string();
+
+    if let Some(cached) = cache.get(&key) {
+        let mut response = (cached.status, cached.headers, cached.body).into_response();
+        let remaining = cached.expires_at - cache.clock.now();
+        response.headers_mut().insert(header::CACHE_CONTROL, cache_control(remaining));
+        return response;
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for caching: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    parts.headers.insert(header::CACHE_CONTROL, cache_control(ttl));
+    cache.entries.write().unwrap().insert(
+        key,
+        CachedResponse {
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: bytes.clone(),
+            expires_at: cache.clock.now() + ttl,
+        },
+    );
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+    use axum::{middleware, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    /// Router counting how often its handler actually runs
+    fn counting_app(cache: ResponseCache, hits: Arc<AtomicUsize>) -> Router {
+        let handler = move || {
+            let hits = hits.clone();
+            async move { hits.fetch_add(1, Ordering::SeqCst).to_string() }
+        };
+        Router::new()
+            .route("/api/governance/capabilities", get(handler.clone()).post(|| async { "ok" }))
+            .route("/api/governance/proposals/{id}/votes", get(handler.clone()))
+            .route("/uncached", get(handler))
+            .route_layer(middleware::from_fn_with_state(cache, cache_responses))
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str) -> Response {
+        let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    async fn send_signed_in(app: &Router, uri: &str) -> Response {
+        let request = Request::builder()
+            .uri(uri)
+            .header(header::AUTHORIZATION, "Bearer token")
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        String::from_utf8(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_served_from_cache_until_ttl() {
+        let clock = MockClock::default();
+        let cache = ResponseCache::new(&BTreeMap::new()).with_clock(Arc::new(clock.clone()));
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = counting_app(cache, hits.clone());
+
+        let first = send(&app, Method::GET, "/api/governance/capabilities").await;
+        assert_eq!(first.headers()[header::CACHE_CONTROL], "public, max-age=300");
+        assert_eq!(body(first).await, "0");
+
+        clock.advance(Duration::seconds(100));
+        let second = send(&app, Method::GET, "/api/governance/capabilities").await;
+        assert_eq!(second.headers()[header::CACHE_CONTROL], "public, max-age=200");
+        assert_eq!(body(second).await, "0");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // A different query is a different entry
+        send(&app, Method::GET, "/api/governance/capabilities?page=2").await;
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        clock.advance(Duration::seconds(200));
+        assert_eq!(body(send(&app, Method::GET, "/api/governance/capabilities").await).await, "2");
+
+        send(&app, Method::GET, "/uncached").await;
+        let uncached = send(&app, Method::GET, "/uncached").await;
+        assert!(uncached.headers().get(header::CACHE_CONTROL).is_none());
+        assert_eq!(hits.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_and_zero_ttl_disables() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = counting_app(ResponseCache::new(&BTreeMap::new()), hits.clone());
+
+        send(&app, Method::GET, "/api/governance/capabilities").await;
+        send(&app, Method::POST, "/api/governance/capabilities").await;
+        assert_eq!(body(send(&app, Method::GET, "/api/governance/capabilities").await).await, "1");
+
+        let overrides = BTreeMap::from([("/api/governance/capabilities".to_string(), 0)]);
+        let disabled = ResponseCache::new(&overrides);
+        assert!(disabled.ttl("/api/governance/capabilities").is_none());
+        assert_eq!(disabled.ttl("/api/governance/proposals/trending"), Some(Duration::seconds(15)));
+    }
+
+    #[tokio::test]
+    async fn test_signed_in_and_viewer_aware_requests_bypass_cache() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let overrides = BTreeMap::from([("/api/governance/proposals/{id}/votes".to_string(), 60)]);
+        let app = counting_app(ResponseCache::new(&overrides), hits.clone());
+
+        // A signed-in response is neither served from nor stored in the cache
+        send(&app, Method::GET, "/api/governance/capabilities").await;
+        let signed_in = send_signed_in(&app, "/api/governance/capabilities").await;
+        assert_eq!(signed_in.headers()[header::CACHE_CONTROL], "private");
+        assert_eq!(body(signed_in).await, "1");
+        assert_eq!(body(send(&app, Method::GET, "/api/governance/capabilities").await).await, "0");
+
+        // Viewer-aware routes stay uncached even when configured with a TTL
+        for _ in 0..2 {
+            let votes = send(&app, Method::GET, "/api/governance/proposals/1/votes").await;
+            assert_eq!(votes.headers()[header::CACHE_CONTROL], "private");
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+}
@@ -1,2 +1,818 @@
-// Placeholder for API handlers
-pub struct ApiHandlers;
\ No newline at end of file
+use crate::api::health::HealthReport;
+use crate::auth::audit::SignedMessageRecord;
+use crate::auth::middleware::{ApiResponse, AuthenticatedUser};
+use crate::auth::response_signing::ServerVerificationKey;
+use crate::auth::security::{RequestDomain, SourceIp};
+use crate::auth::wallet_auth::{
+    AuthRequest, AuthResponse, ChallengeMessage, ChallengeRequest, ChallengeResponse, SessionInfo,
+    SignatureCheck, SignatureCheckRequest,
+};
+use crate::blockchain::client::parse_ethereum_address;
+use crate::config::Config;
+use crate::blockchain::contracts::ExecutionResult;
+use crate::blockchain::transactions::PendingTransaction;
+use crate::governance::analytics::{
+    build_vote_timeline, QuorumFeasibility, VoteDistribution, VoteTimeline, DEFAULT_TIMELINE_BUCKET_SECONDS,
+    DEFAULT_TRENDING_WINDOW_SECONDS,
+};
+use crate::governance::audit_trail::AuditEntry;
+use crate::governance::bundle::proposal_bundle;
+use crate::governance::delegation::{DelegateStats, DelegationDirection, DelegationListing};
+use crate::governance::diff::ProposalDiff;
+use crate::governance::drafts::{draft_not_found, DraftContent, DraftView};
+use crate::governance::proposals::{
+    ExecutionSimulation, GovernanceCapabilities, HypotheticalVote, ProposalDetail, ProposalEstimate,
+    ProposalFinalization, ProposalListEntry, ProposalStatusEntry, ProposalVotes, VotingDurationOptions, WhatIfTally,
+};
+use crate::governance::receipts::VoteInclusionProof;
+use crate::governance::signed_votes::{SignedVote, SignedVoteAggregate};
+use crate::governance::voting::{CastVoteOutcome, VotePreflight};
+use crate::indexer::content_indexer::TrendingProposal;
+use crate::ipfs::content_types::{NotificationSettings, ResolvedContent};
+use crate::utils::errors::{GovernanceError, Result};
+use crate::utils::helpers::{PaginatedResponse, PaginationParams, PAGINATION_NOTICE_HEADER};
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Placeholder for routes that are reserved but not built yet
+pub async fn not_implemented() -> Result<Json<ApiResponse<()>>> {
+    Err(GovernanceError::NotImplemented("This endpoint is not available yet".to_string()))
+}
+
+/// Liveness plus the versions of the IPFS and RPC nodes behind the service.
+/// Node probes that fail are reported in the body; the check still succeeds.
+pub async fn health_check(State(state): State<AppState>) -> Json<ApiResponse<HealthReport>> {
+    let nodes = state
+        .node_versions
+        .get(&state.ipfs_client, &state.blockchain_client)
+        .await;
+    Json(ApiResponse::success(HealthReport {
+        status: "OK",
+        websocket_connections: state.socket_hub.active_connections(),
+        nodes,
+    }))
+}
+
+/// Registered metrics in the Prometheus text exposition format
+pub async fn metrics(State(state): State<AppState>) -> Result<Response> {
+    let encoder = prometheus::TextEncoder::new();
+    let body = encoder
+        .encode_to_string(&state.metrics.gather())
+        .map_err(|e| GovernanceError::Internal(e.into()))?;
+    Ok(([(header::CONTENT_TYPE, encoder.format_type().to_string())], body).into_response())
+}
+
+/// Publish the key used to sign responses in signed-response mode
+pub async fn server_key(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ServerVerificationKey>>> {
+    let signer = state
+        .response_signer
+        .as_ref()
+        .ok_or_else(|| GovernanceError::not_found("Response signing is not enabled"))?;
+
+    Ok(Json(ApiResponse::success(signer.verification_key())))
+}
+
+/// Issue a sign-in challenge for a wallet address
+pub async fn create_challenge(
+    State(state): State<AppState>,
+    RequestDomain(domain): RequestDomain,
+    Json(request): Json<ChallengeRequest>,
+) -> Result<Json<ApiResponse<ChallengeResponse>>> {
+    let challenge = state
+        .auth_service
+        .create_challenge_with_purpose(&request.address, domain.as_deref(), request.purpose.as_deref())
+        .await?;
+    Ok(Json(ApiResponse::success(challenge)))
+}
+
+/// Exchange a signed challenge for a session token
+pub async fn authenticate(
+    State(state): State<AppState>,
+    SourceIp(source_ip): SourceIp,
+    RequestDomain(domain): RequestDomain,
+    Json(request): Json<AuthRequest>,
+) -> Result<Json<ApiResponse<AuthResponse>>> {
+    let response = state
+        .auth_service
+        .authenticate_from(request, source_ip, domain.as_deref())
+        .await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Check a wallet signature for integrators, without creating a session
+pub async fn verify_signature(
+    State(state): State<AppState>,
+    Json(request): Json<SignatureCheckRequest>,
+) -> Result<Json<ApiResponse<SignatureCheck>>> {
+    let check = state.auth_service.check_signature(&request)?;
+    Ok(Json(ApiResponse::success(check)))
+}
+
+/// Exact signable message for an address's outstanding challenge
+pub async fn challenge_message(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<ApiResponse<ChallengeMessage>>> {
+    let message = state
+        .auth_service
+        .challenge_message(&address)
+        .await?
+        .ok_or_else(|| GovernanceError::not_found("No active challenge for this address"))?;
+
+    Ok(Json(ApiResponse::success(message)))
+}
+
+/// Cancel an address's outstanding challenge so a fresh one can be issued.
+/// Only the address itself or an admin may; mounted behind `require_auth`.
+pub async fn cancel_challenge(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<()>>> {
+    let target = parse_ethereum_address(&address)?;
+    if user.address != target && !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Only the address itself can cancel its challenge"));
+    }
+
+    if !state.auth_service.cancel_challenge(&address).await? {
+        return Err(GovernanceError::not_found("No active challenge for this address"));
+    }
+
+    Ok(Json(ApiResponse::success_empty()))
+}
+
+/// Address and expiry of the caller's session. Mounted behind `require_auth`.
+pub async fn me(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<SessionInfo>>> {
+    let session = state
+        .auth_service
+        .session_info(&user.token)
+        .await?
+        .ok_or_else(|| GovernanceError::invalid_signature("Invalid or expired token"))?;
+
+    Ok(Json(ApiResponse::success(session)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignedMessageQuery {
+    pub address: Option<String>,
+}
+
+/// Recorded sign-in messages, for resolving disputes over what a wallet
+/// signed. Admin only; mounted behind `require_auth`.
+pub async fn signed_messages(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<SignedMessageQuery>,
+) -> Result<Json<ApiResponse<Vec<SignedMessageRecord>>>> {
+    if !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Admin access required"));
+    }
+
+    let records = state.auth_service.signed_messages(query.address.as_deref())?;
+    Ok(Json(ApiResponse::success(records)))
+}
+
+/// Content at a CID, typed as a proposal, vote or profile when recognized
+pub async fn resolve_ipfs_content(
+    State(state): State<AppState>,
+    Path(cid): Path<String>,
+) -> Result<Json<ApiResponse<ResolvedContent>>> {
+    let content = state.ipfs_client.resolve_content(&cid).await?;
+    Ok(Json(ApiResponse::success(content)))
+}
+
+/// Voting durations proposers can pick from
+pub async fn duration_presets(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<VotingDurationOptions>>> {
+    Ok(Json(ApiResponse::success(state.governance_engine.duration_options())))
+}
+
+/// Proposal types, limits and optional features this deployment supports
+pub async fn capabilities(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<GovernanceCapabilities>>> {
+    Ok(Json(ApiResponse::success(state.governance_engine.capabilities())))
+}
+
+/// Whether the configured quorum looks reachable, for proposers to check
+/// before creating a proposal
+pub async fn quorum_feasibility(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<QuorumFeasibility>>> {
+    let feasibility = state.governance_engine.quorum_feasibility().await?;
+    Ok(Json(ApiResponse::success(feasibility)))
+}
+
+/// Proposals newest first, with content where IPFS can serve it
+pub async fn list_proposals(
+    State(state): State<AppState>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Response> {
+    pagination.validate()?;
+    let proposals = state.governance_engine.list_proposals(&pagination).await?;
+    Ok(with_pagination_notice(&pagination, Json(ApiResponse::success(proposals))))
+}
+
+/// Proposal detail including voting options and current tallies
+pub async fn get_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+) -> Result<Json<ApiResponse<ProposalDetail>>> {
+    let detail = state.governance_engine.get_proposal_detail(proposal_id).await?;
+    Ok(Json(ApiResponse::success(detail)))
+}
+
+/// Proposal detail for a proposal of a specific governance contract, for
+/// deployments serving several contracts that each number from 1
+pub async fn get_contract_proposal(
+    State(state): State<AppState>,
+    Path((contract, proposal_id)): Path<(String, u64)>,
+) -> Result<Json<ApiResponse<ProposalDetail>>> {
+    let contract = parse_ethereum_address(&contract)?;
+    let engine = &state.governance_engine;
+    let detail = engine
+        .get_scoped_proposal_detail(engine.proposal_key(contract, proposal_id))
+        .await?;
+    Ok(Json(ApiResponse::success(detail)))
+}
+
+/// Tally and individual votes. Signed-in callers always see their own vote,
+/// even while a private proposal hides everyone else's.
+pub async fn proposal_votes(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    user: Option<Extension<AuthenticatedUser>>,
+) -> Result<Json<ApiResponse<ProposalVotes>>> {
+    let viewer = user.map(|Extension(user)| user.address);
+    let votes = state.governance_engine.proposal_votes(proposal_id, viewer).await?;
+    Ok(Json(ApiResponse::success(votes)))
+}
+
+/// Vote counts and power per choice, with a power concentration metric,
+/// without individual voters
+pub async fn vote_distribution(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+) -> Result<Json<ApiResponse<VoteDistribution>>> {
+    let distribution = state.governance_engine.vote_distribution(proposal_id).await?;
+    Ok(Json(ApiResponse::success(distribution)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VotePreflightRequest {
+    pub choice: u8,
+}
+
+/// Eligibility checks and gas estimate for the caller voting on a proposal,
+/// without submitting anything. Mounted behind `require_auth`.
+pub async fn vote_preflight(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<VotePreflightRequest>,
+) -> Result<Json<ApiResponse<VotePreflight>>> {
+    let preflight = state
+        .governance_engine
+        .vote_preflight(user.address, proposal_id, request.choice)
+        .await?;
+    Ok(Json(ApiResponse::success(preflight)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CastVoteRequest {
+    /// Option voted for; votes on weighted proposals give `weights` instead
+    pub choice: Option<u8>,
+    /// Power per option, by index, as decimal strings. Validated against the
+    /// caller's available power.
+    pub weights: Option<Vec<String>>,
+    /// IPFS hash of the vote's comment, if any
+    pub ipfs_hash: Option<String>,
+    /// Sent a signed `TransactionCallback` once the vote's transaction settles
+    pub callback_url: Option<String>,
+}
+
+/// Cast the caller's vote on a proposal. Mounted behind `require_auth`.
+pub async fn cast_vote(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CastVoteRequest>,
+) -> Result<Json<ApiResponse<CastVoteOutcome>>> {
+    let engine = &state.governance_engine;
+    let outcome = match (request.choice, request.weights) {
+        (Some(choice), None) => {
+            engine
+                .cast_vote_with_callback(user.address, proposal_id, choice, request.ipfs_hash, request.callback_url)
+                .await?
+        }
+        (None, Some(weights)) => {
+            let weights = weights
+                .iter()
+                .map(|weight| {
+                    U256::from_dec_str(weight.trim())
+                        .map_err(|_| GovernanceError::invalid_request(format!("Invalid vote weight: {}", weight)))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            engine
+                .cast_weighted_vote(user.address, proposal_id, weights, request.ipfs_hash, request.callback_url)
+                .await?
+        }
+        _ => return Err(GovernanceError::invalid_request("Give either a choice or weights")),
+    };
+    Ok(Json(ApiResponse::success(outcome)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WhatIfRequest {
+    /// Voter whose power to count, and whose existing vote to replace
+    pub voter: Option<String>,
+    /// Power to count, as a decimal string; overrides the voter's
+    pub power: Option<String>,
+    pub choice: u8,
+}
+
+/// Projected tally and outcome of a proposal if a hypothetical vote were
+/// cast. Nothing is recorded.
+pub async fn what_if_tally(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Json(request): Json<WhatIfRequest>,
+) -> Result<Json<ApiResponse<WhatIfTally>>> {
+    let voter = request.voter.as_deref().map(parse_ethereum_address).transpose()?;
+    let power = request
+        .power
+        .as_deref()
+        .map(|power| {
+            U256::from_dec_str(power.trim())
+                .map_err(|_| GovernanceError::invalid_request(format!("Invalid voting power: {}", power)))
+        })
+        .transpose()?;
+
+    let vote = HypotheticalVote {
+        voter,
+        power,
+        choice: request.choice,
+    };
+    let tally = state.governance_engine.what_if(proposal_id, vote).await?;
+    Ok(Json(ApiResponse::success(tally)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchRequest {
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalWatch {
+    pub proposal_id: u64,
+    pub watching: bool,
+}
+
+/// Follow a proposal for status change and deadline notifications. Mounted
+/// behind `require_auth`.
+pub async fn watch_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<WatchRequest>,
+) -> Result<Json<ApiResponse<ProposalWatch>>> {
+    state
+        .governance_engine
+        .watch_proposal(user.address, proposal_id, request.notifications)?;
+    Ok(Json(ApiResponse::success(ProposalWatch {
+        proposal_id,
+        watching: true,
+    })))
+}
+
+/// Stop following a proposal. Mounted behind `require_auth`.
+pub async fn unwatch_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<ProposalWatch>>> {
+    state.governance_engine.unwatch_proposal(user.address, proposal_id);
+    Ok(Json(ApiResponse::success(ProposalWatch {
+        proposal_id,
+        watching: false,
+    })))
+}
+
+/// Configuration in effect after file and environment merging, with
+/// secrets redacted. Admin only; mounted behind `require_auth`.
+pub async fn effective_config(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<Config>>> {
+    if !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Admin access required"));
+    }
+
+    Ok(Json(ApiResponse::success(state.config.redacted())))
+}
+
+/// Apply a closed proposal's final status now, for recovering proposals left
+/// active past their deadline. Admin only; mounted behind `require_auth`.
+pub async fn finalize_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<ProposalFinalization>>> {
+    if !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Admin access required"));
+    }
+
+    let finalization = state.governance_engine.finalize_proposal(proposal_id).await?;
+    Ok(Json(ApiResponse::success(finalization)))
+}
+
+/// Save a new proposal draft for the caller
+pub async fn create_draft(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(content): Json<DraftContent>,
+) -> Result<Json<ApiResponse<DraftView>>> {
+    let draft = state.drafts.create(user.address, content)?;
+    Ok(Json(ApiResponse::success(draft)))
+}
+
+/// The caller's proposal drafts, most recently edited first
+pub async fn list_drafts(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<Vec<DraftView>>>> {
+    let drafts = state.drafts.list(user.address)?;
+    Ok(Json(ApiResponse::success(drafts)))
+}
+
+pub async fn get_draft(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<DraftView>>> {
+    let draft = state
+        .drafts
+        .get(user.address, &id)?
+        .ok_or_else(|| draft_not_found(&id))?;
+    Ok(Json(ApiResponse::success(draft)))
+}
+
+/// Replace a draft's content, which also restarts its expiry
+pub async fn update_draft(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(content): Json<DraftContent>,
+) -> Result<Json<ApiResponse<DraftView>>> {
+    let draft = state.drafts.update(user.address, &id, content)?;
+    Ok(Json(ApiResponse::success(draft)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DraftEstimateRequest {
+    pub voting_duration: u64,
+}
+
+/// CID and creation gas for submitting one of the caller's drafts as a
+/// proposal, after the checks a submission would get. Nothing is pinned or
+/// sent on-chain.
+pub async fn estimate_draft(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<DraftEstimateRequest>,
+) -> Result<Json<ApiResponse<ProposalEstimate>>> {
+    let draft = state
+        .drafts
+        .get(user.address, &id)?
+        .ok_or_else(|| draft_not_found(&id))?;
+    let estimate = state
+        .governance_engine
+        .estimate_draft(user.address, draft.draft.content, request.voting_duration)
+        .await?;
+    Ok(Json(ApiResponse::success(estimate)))
+}
+
+pub async fn delete_draft(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<()>>> {
+    if !state.drafts.delete(user.address, &id)? {
+        return Err(draft_not_found(&id));
+    }
+    Ok(Json(ApiResponse::success_empty()))
+}
+
+/// The caller's submitted transactions still awaiting confirmation, oldest
+/// first; mounted behind `require_auth`
+pub async fn my_pending_transactions(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<Vec<PendingTransaction>>>> {
+    let pending = state.pending_transactions.for_origin(user.address).await;
+    Ok(Json(ApiResponse::success(pending)))
+}
+
+/// The caller's proposals, votes, delegated votes and watches, most recent
+/// first; mounted behind `require_auth`
+pub async fn my_activity(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Response> {
+    pagination.validate()?;
+    let activity = state.governance_engine.activity_feed(user.address, &pagination);
+    Ok(with_pagination_notice(&pagination, Json(ApiResponse::success(activity))))
+}
+
+/// Chronological audit trail of a proposal for dispute resolution. Admins
+/// and the proposal's proposer only; mounted behind `require_auth`.
+pub async fn proposal_audit_trail(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<Vec<AuditEntry>>>> {
+    let proposal = state
+        .governance_engine
+        .indexer()
+        .get_proposal(proposal_id)
+        .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+    if proposal.proposer != user.address && !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Only admins and the proposer can view the audit trail"));
+    }
+
+    let trail = state.governance_engine.proposal_audit_trail(proposal_id)?;
+    Ok(Json(ApiResponse::success(trail)))
+}
+
+/// Zip of the proposal, its attachments and votes for offline review,
+/// streamed as it is assembled
+pub async fn proposal_bundle_download(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+) -> Result<Response> {
+    let bundle = proposal_bundle(&state.governance_engine, proposal_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"proposal-{}.zip\"", proposal_id),
+            ),
+        ],
+        Body::from_stream(bundle),
+    )
+        .into_response())
+}
+
+/// Merkle inclusion proof for an address's vote in the final vote set
+pub async fn vote_proof(
+    State(state): State<AppState>,
+    Path((proposal_id, address)): Path<(u64, String)>,
+) -> Result<Json<ApiResponse<VoteInclusionProof>>> {
+    let voter = parse_ethereum_address(&address)?;
+    let proof = state.governance_engine.vote_proof(proposal_id, voter).await?;
+    Ok(Json(ApiResponse::success(proof)))
+}
+
+/// Dry run of a proposal's execution call against current chain state
+pub async fn simulate_execution(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+) -> Result<Json<ApiResponse<ExecutionSimulation>>> {
+    let simulation = state.governance_engine.simulate_execution(proposal_id).await?;
+    Ok(Json(ApiResponse::success(simulation)))
+}
+
+/// Recorded outcome of a proposal's execution
+pub async fn proposal_execution(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+) -> Result<Json<ApiResponse<ExecutionResult>>> {
+    let indexer = state.governance_engine.indexer();
+    if indexer.get_proposal(proposal_id).is_none() {
+        return Err(GovernanceError::ProposalNotFound { proposal_id });
+    }
+
+    let result = indexer
+        .get_execution(proposal_id)
+        .ok_or_else(|| GovernanceError::not_found(format!("Proposal {} has not been executed", proposal_id)))?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignedVoteRequest {
+    pub voter: String,
+    pub choice: u8,
+    /// EIP-712 signature over `Vote(proposalId, voter, choice)`
+    pub signature: String,
+    /// Relayer passing the vote on; must be the signed-in address
+    pub relayer: Option<String>,
+}
+
+/// Collect an off-chain signed vote for later on-chain aggregation. Relayed
+/// votes are only taken from trusted relayers signed in as themselves.
+pub async fn submit_signed_vote(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    user: Option<Extension<AuthenticatedUser>>,
+    Json(request): Json<SignedVoteRequest>,
+) -> Result<Json<ApiResponse<SignedVote>>> {
+    let voter = parse_ethereum_address(&request.voter)?;
+    let engine = &state.governance_engine;
+    let vote = match request.relayer.as_deref().map(parse_ethereum_address).transpose()? {
+        Some(relayer) => {
+            if user.map(|Extension(user)| user.address) != Some(relayer) {
+                return Err(GovernanceError::unauthorized("Sign in as the relayer to relay votes"));
+            }
+            engine
+                .submit_relayed_vote(relayer, proposal_id, voter, request.choice, request.signature)
+                .await?
+        }
+        None => {
+            engine
+                .submit_signed_vote(proposal_id, voter, request.choice, request.signature)
+                .await?
+        }
+    };
+    Ok(Json(ApiResponse::success(vote)))
+}
+
+/// Post a closed proposal's signed votes on-chain as one aggregate tally.
+/// Admin only, as it spends the service's gas; mounted behind `require_auth`.
+pub async fn aggregate_signed_votes(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<ApiResponse<SignedVoteAggregate>>> {
+    if !state.config.auth.is_admin(user.address) {
+        return Err(GovernanceError::forbidden("Admin access required"));
+    }
+
+    let aggregate = state.governance_engine.aggregate_signed_votes(proposal_id).await?;
+    Ok(Json(ApiResponse::success(aggregate)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalDiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// What changed between two content versions (CIDs) of a proposal
+pub async fn proposal_diff(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Query(query): Query<ProposalDiffQuery>,
+) -> Result<Json<ApiResponse<ProposalDiff>>> {
+    let diff = state.governance_engine.proposal_diff(proposal_id, &query.from, &query.to).await?;
+    Ok(Json(ApiResponse::success(diff)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    pub bucket_seconds: Option<u64>,
+}
+
+/// Time-bucketed cumulative vote power for charting a proposal
+pub async fn proposal_timeline(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<u64>,
+    Query(query): Query<TimelineQuery>,
+) -> Result<Json<ApiResponse<VoteTimeline>>> {
+    let indexer = state.governance_engine.indexer();
+    let proposal = indexer
+        .get_proposal(proposal_id)
+        .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+
+    let votes = indexer.get_votes(proposal_id);
+    let bucket_seconds = query.bucket_seconds.unwrap_or(DEFAULT_TIMELINE_BUCKET_SECONDS);
+    let timeline = build_vote_timeline(&proposal, &votes, bucket_seconds)?;
+
+    Ok(Json(ApiResponse::success(timeline)))
+}
+
+/// Status and tally for a list of proposal ids, keyed by id
+pub async fn proposal_status_batch(
+    State(state): State<AppState>,
+    Json(ids): Json<Vec<u64>>,
+) -> Result<Json<ApiResponse<BTreeMap<u64, ProposalStatusEntry>>>> {
+    let statuses = state.governance_engine.proposal_statuses(&ids).await?;
+    Ok(Json(ApiResponse::success(statuses)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendingQuery {
+    pub window_seconds: Option<u64>,
+}
+
+/// Open proposals with the most recent voting activity first
+pub async fn trending_proposals(
+    State(state): State<AppState>,
+    Query(query): Query<TrendingQuery>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Response> {
+    pagination.validate()?;
+    let window = query.window_seconds.unwrap_or(DEFAULT_TRENDING_WINDOW_SECONDS);
+    let trending = state.governance_engine.trending_proposals(window, &pagination)?;
+    Ok(with_pagination_notice(&pagination, Json(ApiResponse::success(trending))))
+}
+
+/// Tell the client, via a header, when its requested page size was clamped
+fn with_pagination_notice(pagination: &PaginationParams, body: impl IntoResponse) -> Response {
+    let mut response = body.into_response();
+    if let Some(notice) = pagination.clamp_notice().and_then(|notice| HeaderValue::from_str(&notice).ok()) {
+        response.headers_mut().insert(PAGINATION_NOTICE_HEADER, notice);
+    }
+    response
+}
+
+/// Delegate profile: own power, received delegated power and cap status
+pub async fn delegate_stats(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<ApiResponse<DelegateStats>>> {
+    let address = parse_ethereum_address(&address)?;
+    let stats = state.governance_engine.delegate_stats(address).await?;
+    Ok(Json(ApiResponse::success(stats)))
+}
+
+/// Addresses delegating to `address`, with the power each contributes
+pub async fn incoming_delegations(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Response> {
+    list_delegations(state, &address, DelegationDirection::Incoming, pagination).await
+}
+
+/// The delegate `address` passes its power to, if any
+pub async fn outgoing_delegations(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Response> {
+    list_delegations(state, &address, DelegationDirection::Outgoing, pagination).await
+}
+
+async fn list_delegations(
+    state: AppState,
+    address: &str,
+    direction: DelegationDirection,
+    pagination: PaginationParams,
+) -> Result<Response> {
+    pagination.validate()?;
+    let address = parse_ethereum_address(address)?;
+    let listing = state
+        .governance_engine
+        .list_delegations(address, direction, &pagination)
+        .await?;
+    Ok(with_pagination_notice(&pagination, Json(ApiResponse::success(listing))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VotingPowerQuery {
+    pub block: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VotingPowerAt {
+    pub address: Address,
+    pub block: u64,
+    pub power: U256,
+}
+
+/// Voting power an address held at a given block
+pub async fn voting_power_at(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<VotingPowerQuery>,
+) -> Result<Json<ApiResponse<VotingPowerAt>>> {
+    let address = parse_ethereum_address(&address)?;
+    let power = state.blockchain_client.get_voting_power_at(address, query.block).await?;
+
+    Ok(Json(ApiResponse::success(VotingPowerAt {
+        address,
+        block: query.block,
+        power,
+    })))
+}
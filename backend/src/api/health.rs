@@ -0,0 +1,89 @@
+use crate::blockchain::client::SomniaClient;
+use crate::blockchain::node_info::RpcNodeInfo;
+use crate::ipfs::client::IpfsClient;
+use crate::utils::clock::SharedClock;
+use crate::utils::errors::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// How long probed node versions are reused before asking the nodes again
+pub const NODE_VERSION_TTL_SECONDS: i64 = 30;
+
+/// Outcome of asking one backend for its version. A failed probe reports
+/// its error instead of failing the health check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionProbe<T> {
+    pub version: Option<T>,
+    pub error: Option<String>,
+}
+
+impl<T> From<Result<T>> for VersionProbe<T> {
+    fn from(result: Result<T>) -> Self {
+        match result {
+            Ok(version) => Self {
+                version: Some(version),
+                error: None,
+            },
+            Err(e) => Self {
+                version: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Versions of the IPFS and RPC nodes the service talks to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVersions {
+    pub ipfs: VersionProbe<String>,
+    pub rpc: VersionProbe<RpcNodeInfo>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: &'static str,
+    /// Open governance WebSocket connections
+    pub websocket_connections: usize,
+    #[serde(flatten)]
+    pub nodes: NodeVersions,
+}
+
+/// Node versions from the last probe, refreshed once they are
+/// `NODE_VERSION_TTL_SECONDS` old
+#[derive(Clone)]
+pub struct NodeVersionCache {
+    clock: SharedClock,
+    cached: Arc<Mutex<Option<NodeVersions>>>,
+}
+
+impl NodeVersionCache {
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            clock,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn get(&self, ipfs: &IpfsClient, blockchain: &SomniaClient) -> NodeVersions {
+        // Held across the probe so concurrent health checks share one refresh
+        let mut cached = self.cached.lock().await;
+        let now = self.clock.now();
+        if let Some(versions) = cached.as_ref() {
+            if now - versions.checked_at < Duration::seconds(NODE_VERSION_TTL_SECONDS) {
+                return versions.clone();
+            }
+        }
+
+        let (ipfs_version, rpc_info) = tokio::join!(ipfs.node_version(), blockchain.node_info());
+        let versions = NodeVersions {
+            ipfs: ipfs_version.into(),
+            rpc: rpc_info.into(),
+            checked_at: now,
+        };
+        *cached = Some(versions.clone());
+        versions
+    }
+}
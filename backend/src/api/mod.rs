@@ -1,4 +1,6 @@
 pub mod routes;
 pub mod handlers;
+pub mod cache;
+pub mod health;
 pub mod middleware;
 pub mod websocket;
\ No newline at end of file
@@ -1,28 +1,183 @@
-use axum::{routing::get, Router};
+use axum::{extract::DefaultBodyLimit, middleware, routing::{delete, get, post}, Extension, Router};
+use crate::api::cache::cache_responses;
+use crate::api::handlers;
+use crate::api::websocket;
+use crate::auth::middleware::{envelope_errors, optional_auth, require_auth, sign_response};
+use crate::auth::rate_limit::{rate_limit_by_ip, RateLimiter};
+use crate::auth::security::TrustedProxies;
 use crate::AppState;
 
+/// Assemble all route groups with the given state
+pub fn app_router(state: AppState) -> Router {
+    let mut api = Router::new()
+        .nest("/api/health", health_routes())
+        .nest("/api/auth", auth_routes(&state))
+        .nest("/api/governance", governance_routes(&state))
+        .nest("/api/ipfs", ipfs_routes())
+        .nest("/api/admin", admin_routes(&state))
+        .layer(middleware::from_fn(envelope_errors));
+
+    if let Some(signer) = state.response_signer.clone() {
+        api = api.layer(middleware::from_fn_with_state(signer, sign_response));
+    }
+
+    let body_limit = request_body_limit(state.config.server.max_request_bytes);
+    let trusted_proxies = TrustedProxies::new(state.config.server.trusted_proxies.iter().copied());
+
+    api.nest("/ws", websocket_routes())
+        .route("/metrics", get(handlers::metrics))
+        .layer(body_limit)
+        .layer(Extension(trusted_proxies))
+        .with_state(state)
+}
+
+/// Cap request bodies so oversized payloads are rejected with 413 before
+/// extractors buffer them into memory
+fn request_body_limit(max_request_bytes: usize) -> DefaultBodyLimit {
+    DefaultBodyLimit::max(max_request_bytes)
+}
+
 pub fn health_routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(health_check))
+        .route("/", get(handlers::health_check))
+        .route("/server-key", get(handlers::server_key))
 }
 
-pub fn auth_routes() -> Router<AppState> {
+pub fn auth_routes(state: &AppState) -> Router<AppState> {
+    let protected = Router::new()
+        .route("/me", get(handlers::me))
+        .route("/challenge/{address}", delete(handlers::cancel_challenge))
+        .route("/audit/signed-messages", get(handlers::signed_messages))
+        .route_layer(middleware::from_fn_with_state(state.auth_service.clone(), require_auth));
+
+    let verify_limiter = RateLimiter::new(state.config.auth.verify_rate_limit, chrono::Duration::minutes(1));
+    let rate_limited = Router::new()
+        .route("/verify", post(handlers::verify_signature))
+        .route_layer(middleware::from_fn_with_state(verify_limiter, rate_limit_by_ip));
+
     Router::new()
-        .route("/challenge", get(|| async { "Challenge endpoint" }))
-        .route("/authenticate", get(|| async { "Authenticate endpoint" }))
+        .route("/challenge", post(handlers::create_challenge))
+        .route("/authenticate", post(handlers::authenticate))
+        .route("/message/{address}", get(handlers::challenge_message))
+        .merge(protected)
+        .merge(rate_limited)
 }
 
-pub fn governance_routes() -> Router<AppState> {
+pub fn governance_routes(state: &AppState) -> Router<AppState> {
+    let viewer_aware = Router::new()
+        .route("/proposals/{id}/votes", get(handlers::proposal_votes))
+        .route("/proposals/{id}/signed-votes", post(handlers::submit_signed_vote))
+        .route_layer(middleware::from_fn_with_state(state.auth_service.clone(), optional_auth));
+
+    let protected = Router::new()
+        .route("/proposals/{id}/votes", post(handlers::cast_vote))
+        .route("/proposals/{id}/preflight", post(handlers::vote_preflight))
+        .route("/proposals/{id}/finalize", post(handlers::finalize_proposal))
+        .route("/proposals/{id}/signed-votes/aggregate", post(handlers::aggregate_signed_votes))
+        .route("/proposals/{id}/audit", get(handlers::proposal_audit_trail))
+        .route("/proposals/{id}/watch", post(handlers::watch_proposal).delete(handlers::unwatch_proposal))
+        .route("/me/pending-transactions", get(handlers::my_pending_transactions))
+        .route("/me/activity", get(handlers::my_activity))
+        .route("/drafts", get(handlers::list_drafts).post(handlers::create_draft))
+        .route("/drafts/{id}", get(handlers::get_draft).put(handlers::update_draft).delete(handlers::delete_draft))
+        .route("/drafts/{id}/estimate", post(handlers::estimate_draft))
+        .route_layer(middleware::from_fn_with_state(state.auth_service.clone(), require_auth));
+
     Router::new()
-        .route("/proposals", get(|| async { "Proposals endpoint" }))
-        .route("/votes", get(|| async { "Votes endpoint" }))
+        .route("/proposals", get(handlers::list_proposals))
+        .route("/proposals/trending", get(handlers::trending_proposals))
+        .route("/proposals/status-batch", post(handlers::proposal_status_batch))
+        .route("/proposals/{id}/whatif", post(handlers::what_if_tally))
+        .route("/proposals/{id}", get(handlers::get_proposal))
+        .route("/duration-presets", get(handlers::duration_presets))
+        .route("/capabilities", get(handlers::capabilities))
+        .route("/quorum-feasibility", get(handlers::quorum_feasibility))
+        .route("/proposals/{id}/timeline", get(handlers::proposal_timeline))
+        .route("/proposals/{id}/diff", get(handlers::proposal_diff))
+        .route("/proposals/{id}/distribution", get(handlers::vote_distribution))
+        .route("/proposals/{id}/execution", get(handlers::proposal_execution))
+        .route("/proposals/{id}/simulation", get(handlers::simulate_execution))
+        .route("/proposals/{id}/bundle", get(handlers::proposal_bundle_download))
+        .route("/proposals/{id}/votes/{address}/proof", get(handlers::vote_proof))
+        .route("/contracts/{contract}/proposals/{id}", get(handlers::get_contract_proposal))
+        .route("/votes", get(handlers::not_implemented))
+        .route("/delegates/{address}", get(handlers::delegate_stats))
+        .route("/delegations/{address}/incoming", get(handlers::incoming_delegations))
+        .route("/delegations/{address}/outgoing", get(handlers::outgoing_delegations))
+        .route("/voting-power/{address}", get(handlers::voting_power_at))
+        .merge(viewer_aware)
+        .merge(protected)
+        .route_layer(middleware::from_fn_with_state(state.response_cache.clone(), cache_responses))
+}
+
+pub fn admin_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/config", get(handlers::effective_config))
+        .route_layer(middleware::from_fn_with_state(state.auth_service.clone(), require_auth))
+}
+
+pub fn ipfs_routes() -> Router<AppState> {
+    Router::new().route("/{cid}", get(handlers::resolve_ipfs_content))
 }
 
 pub fn websocket_routes() -> Router<AppState> {
     Router::new()
-        .route("/governance", get(|| async { "WebSocket endpoint" }))
+        .route("/governance", get(websocket::governance_socket))
 }
 
-async fn health_check() -> &'static str {
-    "OK"
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{Request, StatusCode}, Json};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn limited_app(limit: usize, executed: Arc<AtomicBool>) -> Router {
+        Router::new()
+            .route(
+                "/",
+                post(move |Json(_body): Json<serde_json::Value>| {
+                    let executed = executed.clone();
+                    async move {
+                        executed.store(true, Ordering::SeqCst);
+                        "ok"
+                    }
+                }),
+            )
+            .layer(request_body_limit(limit))
+    }
+
+    fn json_request(body: String) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_rejected() {
+        let executed = Arc::new(AtomicBool::new(false));
+        let app = limited_app(1024, executed.clone());
+
+        let body = serde_json::json!({ "description": "a".repeat(4096) }).to_string();
+        let response = app.oneshot(json_request(body)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!executed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_body_within_limit_accepted() {
+        let executed = Arc::new(AtomicBool::new(false));
+        let app = limited_app(1024, executed.clone());
+
+        let body = serde_json::json!({ "description": "short" }).to_string();
+        let response = app.oneshot(json_request(body)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(executed.load(Ordering::SeqCst));
+    }
+}
@@ -1,2 +1,454 @@
-// Placeholder for WebSocket handlers
-pub struct WebSocketHandler;
\ No newline at end of file
+use crate::utils::errors::{GovernanceError, Result};
+use crate::AppState;
+use axum::extract::ws::{Message, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use prometheus::{IntGauge, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{interval_at, Instant};
+
+/// How often the server pings an idle client
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Most proposals one subscription filter may name
+pub const MAX_FILTER_PROPOSALS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    ProposalCreated,
+    VoteCast,
+    ProposalExecuted,
+}
+
+/// Which events a client wants. Empty lists match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionFilter {
+    #[serde(default)]
+    pub events: Vec<EventKind>,
+    #[serde(default)]
+    pub proposal_ids: Vec<u64>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, event: &SocketEvent) -> bool {
+        (self.events.is_empty() || self.events.contains(&event.kind))
+            && (self.proposal_ids.is_empty() || self.proposal_ids.contains(&event.proposal_id))
+    }
+}
+
+/// A governance event pushed to subscribed clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocketEvent {
+    pub kind: EventKind,
+    pub proposal_id: u64,
+}
+
+/// Messages a client may send
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe {
+        #[serde(default)]
+        filter: SubscriptionFilter,
+    },
+    Unsubscribe,
+    Ping,
+}
+
+/// Frames the server sends in reply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    Subscribed { filter: SubscriptionFilter },
+    Unsubscribed,
+    Pong,
+    Error { message: String },
+    Event { event: EventKind, proposal_id: u64 },
+}
+
+impl ServerFrame {
+    fn error(message: impl Into<String>) -> Self {
+        ServerFrame::Error {
+            message: message.into(),
+        }
+    }
+
+    fn to_message(&self) -> Message {
+        Message::Text(serde_json::to_string(self).unwrap_or_default().into())
+    }
+}
+
+/// One connection's subscription state
+#[derive(Debug, Default)]
+pub struct Subscription {
+    filter: Option<SubscriptionFilter>,
+}
+
+impl Subscription {
+    /// The accepted filter, while subscribed
+    pub fn filter(&self) -> Option<&SubscriptionFilter> {
+        self.filter.as_ref()
+    }
+
+    /// Answer one text frame from the client. Every frame gets a reply, so
+    /// bad input is reported instead of silently ignored.
+    pub fn handle(&mut self, text: &str) -> ServerFrame {
+        let message: ClientMessage = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => return ServerFrame::error(format!("Malformed message: {}", e)),
+        };
+
+        match message {
+            ClientMessage::Subscribe { filter } => {
+                if filter.proposal_ids.len() > MAX_FILTER_PROPOSALS {
+                    return ServerFrame::error(format!(
+                        "Filter names {} proposals: at most {} allowed",
+                        filter.proposal_ids.len(),
+                        MAX_FILTER_PROPOSALS
+                    ));
+                }
+                self.filter = Some(filter.clone());
+                ServerFrame::Subscribed { filter }
+            }
+            ClientMessage::Unsubscribe => match self.filter.take() {
+                Some(_) => ServerFrame::Unsubscribed,
+                None => ServerFrame::error("Not subscribed"),
+            },
+            ClientMessage::Ping => ServerFrame::Pong,
+        }
+    }
+}
+
+/// Open WebSocket connections, capped at a maximum, and the fan-out of
+/// events to them. Each connection has a bounded queue; one that falls a
+/// full queue behind is dropped so broadcasting never waits on it.
+#[derive(Clone)]
+pub struct SocketHub {
+    max_connections: usize,
+    send_buffer: usize,
+    active: Arc<AtomicUsize>,
+    next_id: Arc<AtomicU64>,
+    senders: Arc<Mutex<HashMap<u64, mpsc::Sender<SocketEvent>>>>,
+    active_gauge: IntGauge,
+}
+
+impl SocketHub {
+    pub fn new(max_connections: usize, send_buffer: usize) -> Self {
+        Self {
+            max_connections,
+            send_buffer: send_buffer.max(1),
+            active: Arc::new(AtomicUsize::new(0)),
+            next_id: Arc::new(AtomicU64::new(0)),
+            senders: Arc::new(Mutex::new(HashMap::new())),
+            active_gauge: IntGauge::new("websocket_connections_active", "Open governance WebSocket connections")
+                .expect("valid metric definition"),
+        }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.active_gauge.clone()))
+    }
+
+    pub fn active_connections(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Claim a connection slot, or fail with 503 at the limit. The slot is
+    /// freed when the returned connection is dropped.
+    pub fn connect(&self) -> Result<SocketConnection> {
+        self.active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                (active < self.max_connections).then_some(active + 1)
+            })
+            .map_err(|_| {
+                GovernanceError::ServiceUnavailable(format!(
+                    "WebSocket connection limit of {} reached",
+                    self.max_connections
+                ))
+            })?;
+        self.active_gauge.set(self.active_connections() as i64);
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, events) = mpsc::channel(self.send_buffer);
+        self.senders.lock().unwrap().insert(id, sender);
+
+        Ok(SocketConnection {
+            id,
+            hub: self.clone(),
+            events,
+        })
+    }
+
+    /// Queue `event` for every connection without waiting. Connections whose
+    /// queue is full are dropped. Returns how many connections it was queued for.
+    pub fn broadcast(&self, event: SocketEvent) -> usize {
+        let mut senders = self.senders.lock().unwrap();
+        senders.retain(|id, sender| match sender.try_send(event) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                tracing::warn!(connection = id, "Dropping WebSocket client too slow to keep up");
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+        senders.len()
+    }
+}
+
+/// A claimed connection slot and the events queued for it
+pub struct SocketConnection {
+    id: u64,
+    hub: SocketHub,
+    events: mpsc::Receiver<SocketEvent>,
+}
+
+impl SocketConnection {
+    /// Next queued event, or `None` once the hub has dropped the connection
+    pub async fn next_event(&mut self) -> Option<SocketEvent> {
+        self.events.recv().await
+    }
+}
+
+impl Drop for SocketConnection {
+    fn drop(&mut self) {
+        self.hub.senders.lock().unwrap().remove(&self.id);
+        let active = self.hub.active.fetch_sub(1, Ordering::SeqCst) - 1;
+        self.hub.active_gauge.set(active as i64);
+    }
+}
+
+/// Upgrade to the governance event socket, refusing with 503 once the
+/// connection limit is reached
+pub async fn governance_socket(State(state): State<AppState>, ws: WebSocketUpgrade) -> Result<Response> {
+    let connection = state.socket_hub.connect()?;
+    Ok(ws.on_upgrade(|socket| async move {
+        let (sender, receiver) = socket.split();
+        serve_connection(sender, receiver, connection, KEEPALIVE_INTERVAL).await;
+    }))
+}
+
+/// Answer client messages, forward events matching the client's
+/// subscription and ping the client every `keepalive`. A client that sends
+/// nothing, not even a pong, for two intervals is disconnected, as is one
+/// the hub dropped for falling behind.
+pub async fn serve_connection<W, R, E>(
+    mut sender: W,
+    mut receiver: R,
+    mut connection: SocketConnection,
+    keepalive: Duration,
+) where
+    W: Sink<Message> + Unpin,
+    R: Stream<Item = std::result::Result<Message, E>> + Unpin,
+{
+    let mut subscription = Subscription::default();
+    let mut ticks = interval_at(Instant::now() + keepalive, keepalive);
+    let mut last_seen = Instant::now();
+
+    loop {
+        tokio::select! {
+            // Messages first, so a pong already received counts before the deadline check
+            biased;
+            message = receiver.next() => {
+                let reply = match message {
+                    Some(Ok(Message::Text(text))) => subscription.handle(text.as_str()),
+                    Some(Ok(Message::Binary(_))) => ServerFrame::error("Binary frames are not supported"),
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => {
+                        last_seen = Instant::now();
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                };
+                last_seen = Instant::now();
+                if sender.send(reply.to_message()).await.is_err() {
+                    return;
+                }
+            }
+            event = connection.next_event() => {
+                let Some(event) = event else {
+                    let _ = sender.send(Message::Close(None)).await;
+                    return;
+                };
+                if !subscription.filter().is_some_and(|filter| filter.matches(&event)) {
+                    continue;
+                }
+                let frame = ServerFrame::Event {
+                    event: event.kind,
+                    proposal_id: event.proposal_id,
+                };
+                if sender.send(frame.to_message()).await.is_err() {
+                    return;
+                }
+            }
+            _ = ticks.tick() => {
+                if last_seen.elapsed() >= keepalive * 2 {
+                    tracing::debug!("Closing unresponsive WebSocket client");
+                    let _ = sender.send(Message::Close(None)).await;
+                    return;
+                }
+                if sender.send(Message::Ping(Default::default())).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use futures::channel::mpsc;
+
+    struct TestClient {
+        outgoing: mpsc::UnboundedSender<std::result::Result<Message, axum::Error>>,
+        incoming: mpsc::UnboundedReceiver<Message>,
+    }
+
+    impl TestClient {
+        fn connect() -> Self {
+            Self::connect_to(&SocketHub::new(10, 8))
+        }
+
+        fn connect_to(hub: &SocketHub) -> Self {
+            let (outgoing, server_receiver) = mpsc::unbounded();
+            let (server_sender, incoming) = mpsc::unbounded();
+            let connection = hub.connect().unwrap();
+            tokio::spawn(serve_connection(server_sender, server_receiver, connection, KEEPALIVE_INTERVAL));
+            Self { outgoing, incoming }
+        }
+
+        fn send(&self, message: Message) {
+            self.outgoing.unbounded_send(Ok(message)).unwrap();
+        }
+
+        async fn request(&mut self, text: &str) -> ServerFrame {
+            self.send(Message::Text(text.into()));
+            match self.incoming.next().await {
+                Some(Message::Text(text)) => serde_json::from_str(text.as_str()).unwrap(),
+                other => panic!("Expected a text frame, got {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_acknowledged_with_filter() {
+        let mut client = TestClient::connect();
+
+        let frame = client
+            .request(r#"{"type": "subscribe", "filter": {"events": ["vote_cast"], "proposal_ids": [7]}}"#)
+            .await;
+        let expected = SubscriptionFilter {
+            events: vec![EventKind::VoteCast],
+            proposal_ids: vec![7],
+        };
+        assert_eq!(frame, ServerFrame::Subscribed { filter: expected });
+        assert_eq!(client.request(r#"{"type": "unsubscribe"}"#).await, ServerFrame::Unsubscribed);
+        assert_eq!(client.request(r#"{"type": "ping"}"#).await, ServerFrame::Pong);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_get_error_frames() {
+        let mut client = TestClient::connect();
+
+        for bad in [
+            "not json",
+            r#"{"type": "subscribe", "filter": {"events": ["proposal_deleted"]}}"#,
+            r#"{"type": "subscribe", "filter": {"proposal": 7}}"#,
+            r#"{"type": "teleport"}"#,
+            r#"{"type": "unsubscribe"}"#,
+        ] {
+            assert!(matches!(client.request(bad).await, ServerFrame::Error { .. }), "{}", bad);
+        }
+
+        let too_many: Vec<u64> = (0..=MAX_FILTER_PROPOSALS as u64).collect();
+        let request = serde_json::json!({ "type": "subscribe", "filter": { "proposal_ids": too_many } });
+        assert!(matches!(client.request(&request.to_string()).await, ServerFrame::Error { .. }));
+
+        // Still usable after the errors
+        assert!(matches!(client.request(r#"{"type": "subscribe"}"#).await, ServerFrame::Subscribed { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keepalive_pings_responsive_clients_and_drops_silent_ones() {
+        let mut client = TestClient::connect();
+
+        for _ in 0..3 {
+            tokio::time::advance(KEEPALIVE_INTERVAL).await;
+            assert!(matches!(client.incoming.next().await, Some(Message::Ping(_))));
+            client.send(Message::Pong(Default::default()));
+        }
+
+        // Silent from here on: pinged once more, then closed
+        tokio::time::advance(KEEPALIVE_INTERVAL).await;
+        assert!(matches!(client.incoming.next().await, Some(Message::Ping(_))));
+        tokio::time::advance(KEEPALIVE_INTERVAL).await;
+        assert!(matches!(client.incoming.next().await, Some(Message::Close(None))));
+        assert!(client.incoming.next().await.is_none());
+    }
+
+    fn event(kind: EventKind, proposal_id: u64) -> SocketEvent {
+        SocketEvent { kind, proposal_id }
+    }
+
+    #[test]
+    fn test_connection_over_limit_refused() {
+        let hub = SocketHub::new(2, 8);
+        let first = hub.connect().unwrap();
+        let _second = hub.connect().unwrap();
+
+        let refused = hub.connect().err().unwrap();
+        assert_eq!(refused.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hub.active_connections(), 2);
+        assert_eq!(hub.active_gauge.get(), 2);
+
+        drop(first);
+        assert_eq!(hub.active_connections(), 1);
+        assert!(hub.connect().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_dropped_without_blocking_others() {
+        let hub = SocketHub::new(10, 2);
+        let mut slow = hub.connect().unwrap();
+        let mut fast = hub.connect().unwrap();
+
+        for id in 0..5 {
+            hub.broadcast(event(EventKind::VoteCast, id));
+            assert_eq!(fast.next_event().await.unwrap().proposal_id, id);
+        }
+
+        // The slow connection gets what fit in its queue, then is cut off
+        assert_eq!(slow.next_event().await.unwrap().proposal_id, 0);
+        assert_eq!(slow.next_event().await.unwrap().proposal_id, 1);
+        assert!(slow.next_event().await.is_none());
+        assert_eq!(hub.broadcast(event(EventKind::VoteCast, 5)), 1);
+    }
+
+    #[tokio::test]
+    async fn test_events_forwarded_by_subscription() {
+        let hub = SocketHub::new(10, 8);
+        let mut client = TestClient::connect_to(&hub);
+        client.request(r#"{"type": "subscribe", "filter": {"proposal_ids": [7]}}"#).await;
+
+        hub.broadcast(event(EventKind::VoteCast, 3));
+        hub.broadcast(event(EventKind::VoteCast, 7));
+        match client.incoming.next().await {
+            Some(Message::Text(text)) => assert_eq!(
+                serde_json::from_str::<ServerFrame>(text.as_str()).unwrap(),
+                ServerFrame::Event {
+                    event: EventKind::VoteCast,
+                    proposal_id: 7
+                }
+            ),
+            other => panic!("Expected an event frame, got {:?}", other),
+        }
+    }
+}
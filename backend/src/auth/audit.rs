@@ -0,0 +1,115 @@
+use crate::utils::clock::SharedClock;
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+
+/// Most records kept regardless of age; the oldest are dropped first
+pub const MAX_SIGNED_MESSAGE_RECORDS: usize = 10_000;
+
+/// A message a wallet signed to sign in, kept for dispute resolution.
+/// The signature itself is never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedMessageRecord {
+    pub address: String, // EIP-55 checksummed, as recovered from the signature
+    pub message: String,
+    pub authenticated_at: DateTime<Utc>,
+    pub source_ip: Option<IpAddr>,
+}
+
+/// Signed sign-in messages of successful authentications, dropped once
+/// older than the retention period
+#[derive(Clone)]
+pub struct SignedMessageAudit {
+    records: Arc<RwLock<VecDeque<SignedMessageRecord>>>,
+    retention: Duration,
+    clock: SharedClock,
+}
+
+impl SignedMessageAudit {
+    pub fn new(retention: Duration, clock: SharedClock) -> Self {
+        Self {
+            records: Arc::new(RwLock::new(VecDeque::new())),
+            retention,
+            clock,
+        }
+    }
+
+    pub fn record(&self, address: Address, message: &str, source_ip: Option<IpAddr>) {
+        let now = self.clock.now();
+        let mut records = self.records.write().unwrap();
+        records.push_back(SignedMessageRecord {
+            address: ethers::utils::to_checksum(&address, None),
+            message: message.to_string(),
+            authenticated_at: now,
+            source_ip,
+        });
+        while records.len() > MAX_SIGNED_MESSAGE_RECORDS {
+            records.pop_front();
+        }
+        self.prune(&mut records, now);
+    }
+
+    /// Records within retention, newest first, optionally for one address
+    pub fn records(&self, address: Option<Address>) -> Vec<SignedMessageRecord> {
+        let now = self.clock.now();
+        let mut records = self.records.write().unwrap();
+        self.prune(&mut records, now);
+
+        let address = address.map(|address| ethers::utils::to_checksum(&address, None));
+        records
+            .iter()
+            .rev()
+            .filter(|record| address.as_ref().is_none_or(|address| record.address == *address))
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Records are appended in time order, so expired ones are at the front
+    fn prune(&self, records: &mut VecDeque<SignedMessageRecord>, now: DateTime<Utc>) {
+        while records
+            .front()
+            .is_some_and(|record| now - record.authenticated_at > self.retention)
+        {
+            records.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+
+    #[test]
+    fn test_records_expire_after_retention() {
+        let clock = MockClock::default();
+        let audit = SignedMessageAudit::new(Duration::days(7), Arc::new(clock.clone()));
+        let (alice, bob) = (Address::random(), Address::random());
+
+        audit.record(alice, "first", None);
+        clock.advance(Duration::days(5));
+        audit.record(bob, "second", Some("203.0.113.7".parse().unwrap()));
+
+        let records = audit.records(None);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "second");
+        assert_eq!(audit.records(Some(alice))[0].message, "first");
+
+        clock.advance(Duration::days(3));
+        let records = audit.records(None);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].address, ethers::utils::to_checksum(&bob, None));
+        assert!(audit.records(Some(alice)).is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::blockchain::client::SomniaClient;
+use crate::utils::clock::SharedClock;
+use crate::utils::errors::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use ethers::abi::{encode, Token};
+use ethers::types::{Address, Bytes, TransactionRequest, H256};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Selector of `isValidSignature(bytes32,bytes)`, which is also the value a
+/// contract returns for a signature it accepts
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// How long an accepted contract signature is trusted without asking again
+pub const DEFAULT_CONTRACT_SIGNATURE_TTL_SECONDS: i64 = 60;
+
+/// Checks signatures of smart contract wallets, which sign under EIP-1271
+/// rather than with a key
+#[async_trait]
+pub trait ContractSignatureValidator: Send + Sync {
+    /// Whether `contract` accepts `signature` over `hash`
+    async fn is_valid_signature(&self, contract: Address, hash: H256, signature: &[u8]) -> Result<bool>;
+}
+
+#[async_trait]
+impl ContractSignatureValidator for SomniaClient {
+    async fn is_valid_signature(&self, contract: Address, hash: H256, signature: &[u8]) -> Result<bool> {
+        let arguments = encode(&[Token::FixedBytes(hash.as_bytes().to_vec()), Token::Bytes(signature.to_vec())]);
+        let data = [&EIP1271_MAGIC_VALUE[..], &arguments[..]].concat();
+        let tx = TransactionRequest::new().to(contract).data(Bytes::from(data));
+
+        // Addresses without code return nothing and reverts mean rejection
+        let simulation = self.simulate_call(&tx.into()).await?;
+        Ok(simulation.success && simulation.return_data.get(..4) == Some(&EIP1271_MAGIC_VALUE[..]))
+    }
+}
+
+type CacheKey = (Address, H256, Vec<u8>);
+
+/// EIP-1271 verification with accepted signatures cached for a short TTL,
+/// since each check is a contract call. Rejections are never cached.
+#[derive(Clone)]
+pub struct ContractSignatureVerifier {
+    validator: Arc<dyn ContractSignatureValidator>,
+    accepted: Arc<RwLock<HashMap<CacheKey, DateTime<Utc>>>>,
+    ttl: Duration,
+    clock: SharedClock,
+}
+
+impl ContractSignatureVerifier {
+    pub fn new(validator: Arc<dyn ContractSignatureValidator>, clock: SharedClock) -> Self {
+        Self {
+            validator,
+            accepted: Arc::new(RwLock::new(HashMap::new())),
+            ttl: Duration::seconds(DEFAULT_CONTRACT_SIGNATURE_TTL_SECONDS),
+            clock,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub async fn verify(&self, contract: Address, hash: H256, signature: &[u8]) -> Result<bool> {
+        let key = (contract, hash, signature.to_vec());
+        let now = self.clock.now();
+        let cached = self.accepted.read().unwrap().get(&key).copied();
+        if cached.is_some_and(|accepted_at| now < accepted_at + self.ttl) {
+            return Ok(true);
+        }
+
+        let valid = self.validator.is_valid_signature(contract, hash, signature).await?;
+        let mut accepted = self.accepted.write().unwrap();
+        // Drop lapsed entries so the cache only holds signatures within the TTL
+        accepted.retain(|_, accepted_at| now < *accepted_at + self.ttl);
+        if valid {
+            accepted.insert(key, now);
+        } else {
+            accepted.remove(&key);
+        }
+        Ok(valid)
+    }
+
+    /// Forget every accepted signature of `contract`, e.g. when one of its
+    /// sessions is revoked
+    pub fn invalidate(&self, contract: Address) {
+        self.accepted.write().unwrap().retain(|(address, _, _), _| *address != contract);
+    }
+}
+
+#[async_trait]
+impl ContractSignatureValidator for ContractSignatureVerifier {
+    async fn is_valid_signature(&self, contract: Address, hash: H256, signature: &[u8]) -> Result<bool> {
+        self.verify(contract, hash, signature).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::simulation::{CallSimulation, CallSimulator};
+    use crate::config::Config;
+    use crate::utils::clock::MockClock;
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Wallet contract accepting one signature, counting how often it is asked
+    #[derive(Default)]
+    struct MockWallet {
+        accepted: Vec<u8>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ContractSignatureValidator for MockWallet {
+        async fn is_valid_signature(&self, _contract: Address, _hash: H256, signature: &[u8]) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(signature == self.accepted.as_slice())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accepted_signature_cached_until_ttl() {
+        let wallet = Arc::new(MockWallet {
+            accepted: vec![0xaa; 70],
+            ..Default::default()
+        });
+        let clock = MockClock::default();
+        let verifier = ContractSignatureVerifier::new(wallet.clone(), Arc::new(clock.clone()));
+        let contract = Address::random();
+        let hash = H256::random();
+
+        assert!(verifier.verify(contract, hash, &[0xaa; 70]).await.unwrap());
+        assert!(verifier.verify(contract, hash, &[0xaa; 70]).await.unwrap());
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 1);
+
+        clock.advance(Duration::seconds(DEFAULT_CONTRACT_SIGNATURE_TTL_SECONDS));
+        assert!(verifier.verify(contract, hash, &[0xaa; 70]).await.unwrap());
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 2);
+
+        verifier.invalidate(contract);
+        assert!(verifier.verify(contract, hash, &[0xaa; 70]).await.unwrap());
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_lapsed_signatures_pruned() {
+        let wallet = Arc::new(MockWallet {
+            accepted: vec![0xaa; 70],
+            ..Default::default()
+        });
+        let clock = MockClock::default();
+        let verifier = ContractSignatureVerifier::new(wallet, Arc::new(clock.clone()));
+
+        for _ in 0..3 {
+            assert!(verifier.verify(Address::random(), H256::random(), &[0xaa; 70]).await.unwrap());
+        }
+        assert_eq!(verifier.accepted.read().unwrap().len(), 3);
+
+        clock.advance(Duration::seconds(DEFAULT_CONTRACT_SIGNATURE_TTL_SECONDS));
+        assert!(verifier.verify(Address::random(), H256::random(), &[0xaa; 70]).await.unwrap());
+        assert_eq!(verifier.accepted.read().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejections_are_not_cached() {
+        let wallet = Arc::new(MockWallet::default());
+        let verifier = ContractSignatureVerifier::new(wallet.clone(), Arc::new(MockClock::default()));
+        let (contract, hash) = (Address::random(), H256::random());
+
+        assert!(!verifier.verify(contract, hash, &[0xbb; 65]).await.unwrap());
+        assert!(!verifier.verify(contract, hash, &[0xbb; 65]).await.unwrap());
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// Contract answering `isValidSignature` with the magic value
+    struct MagicValueContract;
+
+    #[async_trait]
+    impl CallSimulator for MagicValueContract {
+        async fn simulate(&self, tx: &TypedTransaction) -> Result<CallSimulation> {
+            assert_eq!(tx.data().unwrap().get(..4), Some(&EIP1271_MAGIC_VALUE[..]));
+            let mut word = [0u8; 32];
+            word[..4].copy_from_slice(&EIP1271_MAGIC_VALUE);
+            Ok(CallSimulation::succeeded(Bytes::from(word.to_vec())))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_checks_magic_value() {
+        let config = Config::default();
+        let client = SomniaClient::mock(&config).with_simulator(Arc::new(MagicValueContract));
+        assert!(client.is_valid_signature(Address::random(), H256::random(), &[0x01; 65]).await.unwrap());
+
+        let unconnected = SomniaClient::mock(&config);
+        assert!(unconnected.is_valid_signature(Address::random(), H256::random(), &[0x01; 65]).await.is_err());
+    }
+
+    /// An externally owned account: calls to it succeed with no return data
+    struct NoCode;
+
+    #[async_trait]
+    impl CallSimulator for NoCode {
+        async fn simulate(&self, _tx: &TypedTransaction) -> Result<CallSimulation> {
+            Ok(CallSimulation::succeeded(Bytes::new()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_address_without_code_is_no_contract_wallet() {
+        let config = Config::default();
+        let client = SomniaClient::mock(&config).with_simulator(Arc::new(NoCode));
+        assert!(!client.is_valid_signature(Address::random(), H256::random(), &[0x01; 65]).await.unwrap());
+    }
+}
@@ -1,13 +1,16 @@
-use crate::auth::wallet_auth::WalletAuthService;
+use crate::auth::response_signing::{ResponseSigner, SERVER_SIGNATURE_HEADER};
+use crate::auth::wallet_auth::{AuthToken, WalletAuthService};
 use crate::utils::errors::GovernanceError;
 use axum::{
+    body::Body,
     extract::{Request, State},
-    http::{HeaderValue, StatusCode},
+    http::{header, HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use ethers::core::types::Address;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
@@ -31,28 +34,18 @@ pub async fn require_auth(
     State(auth_service): State<WalletAuthService>,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, String)> {
+) -> Result<Response, GovernanceError> {
     // Extract token from Authorization header
     let auth_header = request
         .headers()
         .get("authorization")
         .and_then(|header| header.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                "Missing Authorization header".to_string(),
-            )
-        })?;
+        .ok_or_else(|| GovernanceError::unauthorized("Missing Authorization header"))?;
 
     // Extract Bearer token
     let token = auth_header
         .strip_prefix("Bearer ")
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                "Invalid Authorization header format".to_string(),
-            )
-        })?;
+        .ok_or_else(|| GovernanceError::unauthorized("Invalid Authorization header format"))?;
 
     // Verify token
     let auth_token = auth_service
@@ -60,17 +53,13 @@ pub async fn require_auth(
         .await
         .map_err(|e| {
             tracing::error!("Token verification error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Token verification failed".to_string(),
-            )
+            GovernanceError::Internal(anyhow::anyhow!("Token verification failed"))
         })?
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                "Invalid or expired token".to_string(),
-            )
-        })?;
+        .ok_or_else(|| GovernanceError::unauthorized("Invalid or expired token"))?;
+    // Tokens confirming, say, a delegation are not sign-ins
+    if !auth_token.is_session() {
+        return Err(GovernanceError::unauthorized("Token was not issued for sign-in"));
+    }
 
     // Create authenticated user and add to request extensions
     let authenticated_user = AuthenticatedUser::new(auth_token.address, token.to_string());
@@ -92,7 +81,8 @@ pub async fn optional_auth(
         .and_then(|header| header.to_str().ok())
     {
         if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            if let Ok(Some(auth_token)) = auth_service.verify_token(token).await {
+            let session = auth_service.verify_token(token).await.ok().flatten().filter(AuthToken::is_session);
+            if let Some(auth_token) = session {
                 let authenticated_user = AuthenticatedUser::new(auth_token.address, token.to_string());
                 request.extensions_mut().insert(authenticated_user);
             }
@@ -250,6 +240,71 @@ pub async fn security_headers(
     response
 }
 
+/// Largest error body rewrapped by `envelope_errors`; longer ones are replaced
+/// by the status text
+const MAX_ENVELOPED_ERROR_BYTES: usize = 4096;
+
+/// Wrap error responses that don't come from `GovernanceError`, such as
+/// extractor rejections, oversized bodies and unmatched routes, in the same
+/// `ApiResponse` envelope, keeping their status
+pub async fn envelope_errors(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let message = match axum::body::to_bytes(body, MAX_ENVELOPED_ERROR_BYTES).await {
+        Ok(bytes) if !bytes.is_empty() => String::from_utf8_lossy(&bytes).into_owned(),
+        _ => status.canonical_reason().unwrap_or("Request failed").to_string(),
+    };
+
+    let body = match serde_json::to_vec(&ApiResponse::<()>::error(message)) {
+        Ok(body) => body,
+        Err(_) => return status.into_response(),
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Signed-response middleware - appends the server's signature over the body
+pub async fn sign_response(
+    State(signer): State<Arc<ResponseSigner>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for signing: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    match signer.sign(&bytes) {
+        Ok(signature) => {
+            if let Ok(value) = HeaderValue::from_str(&signature) {
+                parts.headers.insert(SERVER_SIGNATURE_HEADER, value);
+            }
+        }
+        Err(e) => tracing::error!("Failed to sign response: {}", e),
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
 /// Helper function to get authenticated user from request
 pub fn get_authenticated_user(request: &Request) -> Result<&AuthenticatedUser, GovernanceError> {
     request
@@ -330,4 +385,87 @@ mod tests {
         assert!(empty_response.data.is_none());
         assert!(empty_response.error.is_none());
     }
+
+    #[tokio::test]
+    async fn test_only_sign_in_tokens_pass_require_auth() {
+        use crate::auth::wallet_auth::AuthRequest;
+        use crate::config::Config;
+        use axum::{routing::get, Router};
+        use ethers::signers::{LocalWallet, Signer};
+        use tower::ServiceExt;
+
+        let mut config = Config::default();
+        config.auth.message_templates.insert("delegation".to_string(), "Delegate: {nonce}".to_string());
+        let auth_service = WalletAuthService::in_memory(Arc::new(config));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        let mut tokens = Vec::new();
+        for purpose in ["delegation", "login"] {
+            let challenge = auth_service
+                .create_challenge_with_purpose(&address, None, Some(purpose))
+                .await
+                .unwrap();
+            let signature = wallet.sign_message(&challenge.message).await.unwrap();
+            let response = auth_service
+                .authenticate(AuthRequest {
+                    address: address.clone(),
+                    message: challenge.message,
+                    signature: format!("0x{}", hex::encode(signature.to_vec())),
+                    scheme: None,
+                    purpose: Some(purpose.to_string()),
+                })
+                .await
+                .unwrap();
+            tokens.push(response.token.unwrap());
+        }
+
+        let app = Router::new()
+            .route("/", get(|| async { "protected" }))
+            .route_layer(axum::middleware::from_fn_with_state(auth_service, require_auth));
+        let status = |token: String| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .uri("/")
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap();
+                app.oneshot(request).await.unwrap().status()
+            }
+        };
+
+        assert_eq!(status(tokens[0].clone()).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(tokens[1].clone()).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_sign_response_header() {
+        use crate::auth::response_signing::verify_response_signature;
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let signer = Arc::new(ResponseSigner::random());
+        let app = Router::new()
+            .route("/", get(|| async { "signed body" }))
+            .layer(axum::middleware::from_fn_with_state(signer.clone(), sign_response));
+
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let signature = response
+            .headers()
+            .get(SERVER_SIGNATURE_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        assert_eq!(&body[..], b"signed body");
+        assert!(verify_response_signature(&body, &signature, &signer.address()));
+        assert!(!verify_response_signature(b"tampered body", &signature, &signer.address()));
+    }
 }
\ No newline at end of file
@@ -1,3 +1,9 @@
 pub mod wallet_auth;
+pub mod audit;
+pub mod contract_signatures;
 pub mod signature_verification;
-pub mod middleware;
\ No newline at end of file
+pub mod middleware;
+pub mod response_signing;
+pub mod security;
+pub mod rate_limit;
+pub mod token_store;
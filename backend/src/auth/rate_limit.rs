@@ -0,0 +1,93 @@
+use crate::auth::security::SourceIp;
+use crate::utils::clock::{system_clock, Clock, SharedClock};
+use crate::utils::errors::GovernanceError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+/// Fixed-window request limiter keyed by client IP, or by any other key.
+/// Clients whose IP can't be determined share a single bucket.
+#[derive(Clone)]
+pub struct RateLimiter<K = Option<IpAddr>> {
+    max_requests: u32,
+    window: Duration,
+    windows: Arc<Mutex<HashMap<K, (DateTime<Utc>, u32)>>>,
+    clock: SharedClock,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Count a request from `key`, returning false once its window is exhausted
+    pub fn check(&self, key: K) -> bool {
+        let now = self.clock.now();
+        let mut windows = self.windows.lock().unwrap();
+
+        // Drop stale windows so the map stays bounded by recent clients
+        windows.retain(|_, (started, _)| now - *started < self.window);
+
+        let (_, count) = windows.entry(key).or_insert((now, 0));
+        if *count >= self.max_requests {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+/// Reject requests over the limiter's budget with 429
+pub async fn rate_limit_by_ip(
+    State(limiter): State<RateLimiter>,
+    SourceIp(ip): SourceIp,
+    request: Request,
+    next: Next,
+) -> Result<Response, GovernanceError> {
+    if !limiter.check(ip) {
+        tracing::warn!(target: "security", source_ip = ?ip, "Rate limit exceeded");
+        return Err(GovernanceError::RateLimited);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+
+    #[test]
+    fn test_limit_resets_after_window() {
+        let clock = MockClock::default();
+        let limiter = RateLimiter::new(2, Duration::minutes(1)).with_clock(Arc::new(clock.clone()));
+        let ip: Option<IpAddr> = Some("203.0.113.7".parse().unwrap());
+
+        assert!(limiter.check(ip));
+        assert!(limiter.check(ip));
+        assert!(!limiter.check(ip));
+
+        // Other clients have their own budget
+        assert!(limiter.check(Some("198.51.100.1".parse().unwrap())));
+
+        clock.advance(Duration::seconds(61));
+        assert!(limiter.check(ip));
+    }
+}
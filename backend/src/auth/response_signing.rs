@@ -0,0 +1,122 @@
+use crate::utils::errors::{GovernanceError, Result};
+use ethers::core::types::{Address, Signature};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::utils::hash_message;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Header carrying the server's signature over the response body
+pub const SERVER_SIGNATURE_HEADER: &str = "x-server-signature";
+
+/// Signs response bodies with a server key so clients can verify data
+/// originated from this backend.
+///
+/// Signatures follow EIP-191 (`personal_sign`) over the raw body bytes, so any
+/// standard Ethereum library can recover the signer and compare it with the
+/// published server address.
+#[derive(Debug, Clone)]
+pub struct ResponseSigner {
+    wallet: LocalWallet,
+}
+
+impl ResponseSigner {
+    /// Create a signer from a hex-encoded secp256k1 private key
+    pub fn from_hex(private_key: &str) -> Result<Self> {
+        let wallet = LocalWallet::from_str(private_key.strip_prefix("0x").unwrap_or(private_key))
+            .map_err(|e| GovernanceError::invalid_signature(format!("Invalid server signing key: {}", e)))?;
+
+        Ok(Self { wallet })
+    }
+
+    /// Create a signer with a freshly generated key (not stable across restarts)
+    pub fn random() -> Self {
+        let key_bytes: [u8; 32] = rand::random();
+        let wallet = LocalWallet::from_bytes(&key_bytes).expect("32 random bytes form a valid key");
+
+        Self { wallet }
+    }
+
+    /// Address clients should recover from a valid response signature
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    /// Sign a response body, returning a 0x-prefixed 65-byte signature
+    pub fn sign(&self, body: &[u8]) -> Result<String> {
+        let signature = self
+            .wallet
+            .sign_hash(hash_message(body))
+            .map_err(|e| GovernanceError::invalid_signature(format!("Failed to sign response: {}", e)))?;
+
+        Ok(format!("0x{}", hex::encode(signature.to_vec())))
+    }
+
+    /// Public description of the verification key
+    pub fn verification_key(&self) -> ServerVerificationKey {
+        ServerVerificationKey {
+            address: format!("{:?}", self.address()),
+            scheme: "eip191-secp256k1".to_string(),
+            header: SERVER_SIGNATURE_HEADER.to_string(),
+        }
+    }
+}
+
+/// Published verification key for signed responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerVerificationKey {
+    pub address: String,
+    pub scheme: String,
+    pub header: String,
+}
+
+/// Check that a response body was signed by the expected server address
+pub fn verify_response_signature(body: &[u8], signature: &str, expected_signer: &Address) -> bool {
+    let signature = match Signature::from_str(signature.strip_prefix("0x").unwrap_or(signature)) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    signature
+        .recover(body)
+        .map(|recovered| recovered == *expected_signer)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[test]
+    fn test_signature_verifies_against_published_key() {
+        let signer = ResponseSigner::from_hex(TEST_KEY).unwrap();
+        let body = br#"{"success":true,"data":{"proposal_id":1}}"#;
+
+        let signature = signer.sign(body).unwrap();
+        let published: Address = signer.verification_key().address.parse().unwrap();
+
+        assert!(verify_response_signature(body, &signature, &published));
+    }
+
+    #[test]
+    fn test_signature_fails_on_tampered_body() {
+        let signer = ResponseSigner::from_hex(TEST_KEY).unwrap();
+        let body = br#"{"success":true,"data":{"yes_votes":"100"}}"#;
+        let tampered = br#"{"success":true,"data":{"yes_votes":"999"}}"#;
+
+        let signature = signer.sign(body).unwrap();
+
+        assert!(!verify_response_signature(tampered, &signature, &signer.address()));
+    }
+
+    #[test]
+    fn test_invalid_key_rejected() {
+        assert!(ResponseSigner::from_hex("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_random_signers_differ() {
+        assert_ne!(ResponseSigner::random().address(), ResponseSigner::random().address());
+    }
+}
@@ -0,0 +1,301 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use prometheus::{IntCounterVec, Opts, Registry};
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Why an authentication attempt was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthFailureReason {
+    InvalidAddress,
+    NoChallenge,
+    ChallengeExpired,
+    MessageMismatch,
+    InvalidSignature,
+    MalformedSignature,
+    DomainMismatch,
+    PurposeMismatch,
+    AddressMismatch,
+    ChainMismatch,
+    MessageExpired,
+    MessageNotYetValid,
+}
+
+impl AuthFailureReason {
+    /// Stable code used in logs, metric labels and API responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthFailureReason::InvalidAddress => "invalid_address",
+            AuthFailureReason::NoChallenge => "no_challenge",
+            AuthFailureReason::ChallengeExpired => "challenge_expired",
+            AuthFailureReason::MessageMismatch => "message_mismatch",
+            AuthFailureReason::InvalidSignature => "invalid_signature",
+            AuthFailureReason::MalformedSignature => "malformed_signature",
+            AuthFailureReason::DomainMismatch => "domain_mismatch",
+            AuthFailureReason::PurposeMismatch => "purpose_mismatch",
+            AuthFailureReason::AddressMismatch => "address_mismatch",
+            AuthFailureReason::ChainMismatch => "chain_mismatch",
+            AuthFailureReason::MessageExpired => "message_expired",
+            AuthFailureReason::MessageNotYetValid => "message_not_yet_valid",
+        }
+    }
+}
+
+/// Security-event log and per-reason counter for failed authentications
+#[derive(Clone)]
+pub struct AuthFailureMetrics {
+    failures: IntCounterVec,
+}
+
+impl AuthFailureMetrics {
+    pub fn new() -> Self {
+        let failures = IntCounterVec::new(
+            Opts::new("auth_failures_total", "Failed authentication attempts by reason"),
+            &["reason"],
+        )
+        .expect("valid metric definition");
+
+        Self { failures }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.failures.clone()))
+    }
+
+    /// Emit a structured security event and bump the reason's counter.
+    /// The signature is deliberately never logged.
+    pub fn record(&self, reason: AuthFailureReason, address: &str, source_ip: Option<IpAddr>) {
+        self.failures.with_label_values(&[reason.code()]).inc();
+
+        tracing::warn!(
+            target: "security",
+            event = "auth_failure",
+            reason = reason.code(),
+            address = %address,
+            source_ip = %source_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string()),
+            "Authentication failed"
+        );
+    }
+
+    pub fn count(&self, reason: AuthFailureReason) -> u64 {
+        self.failures.with_label_values(&[reason.code()]).get()
+    }
+}
+
+impl Default for AuthFailureMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which in-memory auth map an entry was removed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthStore {
+    Challenges,
+    Sessions,
+}
+
+impl AuthStore {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuthStore::Challenges => "challenges",
+            AuthStore::Sessions => "sessions",
+        }
+    }
+}
+
+/// Entries dropped from the challenge and session maps, by expiry sweeps
+/// and by evictions when a map is over capacity
+#[derive(Clone)]
+pub struct AuthStoreMetrics {
+    expired: IntCounterVec,
+    evicted: IntCounterVec,
+}
+
+impl AuthStoreMetrics {
+    pub fn new() -> Self {
+        let expired = IntCounterVec::new(
+            Opts::new("auth_entries_expired_total", "Expired auth entries removed by cleanup"),
+            &["store"],
+        )
+        .expect("valid metric definition");
+        let evicted = IntCounterVec::new(
+            Opts::new("auth_entries_evicted_total", "Auth entries evicted to stay within capacity"),
+            &["store"],
+        )
+        .expect("valid metric definition");
+
+        Self { expired, evicted }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.expired.clone()))?;
+        registry.register(Box::new(self.evicted.clone()))
+    }
+
+    pub fn record_expired(&self, store: AuthStore, count: usize) {
+        self.expired.with_label_values(&[store.label()]).inc_by(count as u64);
+    }
+
+    pub fn record_evicted(&self, store: AuthStore, count: usize) {
+        self.evicted.with_label_values(&[store.label()]).inc_by(count as u64);
+    }
+
+    pub fn expired(&self, store: AuthStore) -> u64 {
+        self.expired.with_label_values(&[store.label()]).get()
+    }
+
+    pub fn evicted(&self, store: AuthStore) -> u64 {
+        self.evicted.with_label_values(&[store.label()]).get()
+    }
+}
+
+impl Default for AuthStoreMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Proxies whose `X-Forwarded-For` and `X-Real-IP` headers `SourceIp`
+/// believes, from `server.trusted_proxies`. Added to requests as an
+/// extension; without it, no proxy is trusted.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<HashSet<IpAddr>>);
+
+impl TrustedProxies {
+    pub fn new(proxies: impl IntoIterator<Item = IpAddr>) -> Self {
+        Self(Arc::new(proxies.into_iter().collect()))
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.contains(&ip)
+    }
+}
+
+/// Client IP: the socket peer, unless the peer is a trusted proxy, in which
+/// case the nearest untrusted `X-Forwarded-For` hop, then `X-Real-IP`.
+/// Headers from anyone else are ignored, as clients can set them freely.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceIp(pub Option<IpAddr>);
+
+impl<S: Send + Sync> FromRequestParts<S> for SourceIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted = parts.extensions.get::<TrustedProxies>().cloned().unwrap_or_default();
+
+        let ip = match peer {
+            Some(peer) if trusted.contains(peer) => forwarded_client(parts, &trusted).or(Some(peer)),
+            peer => peer,
+        };
+
+        Ok(SourceIp(ip))
+    }
+}
+
+/// The client a trusted proxy forwarded for. `X-Forwarded-For` is read from
+/// the right, as each proxy appends the peer it saw: the first hop that isn't
+/// one of our proxies is the client. Earlier hops could be forged.
+fn forwarded_client(parts: &Parts, trusted: &TrustedProxies) -> Option<IpAddr> {
+    let header = |name: &str| parts.headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(forwarded) = header("x-forwarded-for") {
+        for hop in forwarded.rsplit(',') {
+            let ip = hop.trim().parse::<IpAddr>().ok()?;
+            if !trusted.contains(ip) {
+                return Some(ip);
+            }
+        }
+    }
+    header("x-real-ip").and_then(|value| value.trim().parse().ok())
+}
+
+/// Domain the client is authenticating to: the host of the `Origin` header,
+/// falling back to `Host` for non-browser clients.
+#[derive(Debug, Clone)]
+pub struct RequestDomain(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestDomain {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = |name: &str| parts.headers.get(name).and_then(|value| value.to_str().ok());
+
+        let domain = header("origin")
+            .filter(|origin| *origin != "null")
+            .or_else(|| header("host"))
+            .and_then(normalize_domain);
+
+        Ok(RequestDomain(domain))
+    }
+}
+
+/// Reduce an origin (`https://app.example:8443`) or host header to a
+/// lowercase `host[:port]`
+pub fn normalize_domain(value: &str) -> Option<String> {
+    let value = value.trim();
+    let without_scheme = value.split_once("://").map_or(value, |(_, rest)| rest);
+    let domain = without_scheme.split('/').next().unwrap_or_default().to_lowercase();
+
+    if domain.is_empty() {
+        None
+    } else {
+        Some(domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn source_ip(peer: &str, forwarded_for: Option<&str>, trusted: &[&str]) -> Option<IpAddr> {
+        let mut request = axum::http::Request::builder();
+        if let Some(forwarded_for) = forwarded_for {
+            request = request.header("x-forwarded-for", forwarded_for);
+        }
+        let mut request = request.body(()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 443)));
+        request
+            .extensions_mut()
+            .insert(TrustedProxies::new(trusted.iter().map(|ip| ip.parse().unwrap())));
+
+        let (mut parts, _) = request.into_parts();
+        SourceIp::from_request_parts(&mut parts, &()).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_for_only_believed_from_trusted_proxies() {
+        let ip = |value: &str| Some(value.parse::<IpAddr>().unwrap());
+
+        // A client claiming to be someone else
+        assert_eq!(source_ip("203.0.113.7", Some("198.51.100.1"), &[]).await, ip("203.0.113.7"));
+        assert_eq!(source_ip("203.0.113.7", Some("198.51.100.1"), &["10.0.0.1"]).await, ip("203.0.113.7"));
+
+        // Behind our proxies, the nearest hop they didn't add is the client
+        assert_eq!(source_ip("10.0.0.1", Some("198.51.100.1"), &["10.0.0.1"]).await, ip("198.51.100.1"));
+        let chain = Some("192.0.2.66, 198.51.100.1, 10.0.0.2");
+        assert_eq!(source_ip("10.0.0.1", chain, &["10.0.0.1", "10.0.0.2"]).await, ip("198.51.100.1"));
+        assert_eq!(source_ip("10.0.0.1", None, &["10.0.0.1"]).await, ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(
+            normalize_domain("https://Governance.Somnia.Network").as_deref(),
+            Some("governance.somnia.network")
+        );
+        assert_eq!(normalize_domain("localhost:3000").as_deref(), Some("localhost:3000"));
+        assert_eq!(normalize_domain("http://evil.example/path").as_deref(), Some("evil.example"));
+        assert_eq!(normalize_domain(""), None);
+    }
+}
@@ -1,9 +1,28 @@
+use crate::auth::contract_signatures::ContractSignatureValidator;
+use crate::auth::security::normalize_domain;
 use crate::utils::errors::{GovernanceError, Result};
-use ethers::core::types::Address;
+use chrono::{DateTime, Utc};
+use ethers::core::types::{Address, H256};
 use ethers::utils::hash_message;
 use secp256k1::{ecdsa::RecoverableSignature, Message, Secp256k1};
 use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Scheme used when neither the request nor the config picks one
+pub const DEFAULT_SIGNATURE_SCHEME: &str = "ethereum";
+
+/// A way of recovering the account that signed a message, so wallets other
+/// than Ethereum's secp256k1 keys can authenticate
+pub trait SignatureScheme: Send + Sync {
+    /// Name requests and config select the scheme by
+    fn name(&self) -> &str;
+
+    /// Account that signed `message`, given the wallet's encoded signature
+    fn recover(&self, message: &str, signature: &str) -> Result<Address>;
+}
 
 #[derive(Debug, Clone)]
 pub struct SignatureVerifier {
@@ -23,6 +42,13 @@ impl SignatureVerifier {
         message: &str,
         signature: &str,
     ) -> Result<Address> {
+        // Hash the message using Ethereum's signing scheme
+        self.recover_signer(hash_message(message), signature)
+    }
+
+    /// Recover the address that signed a precomputed digest, such as an
+    /// EIP-712 typed-data hash
+    pub fn recover_signer(&self, digest: H256, signature: &str) -> Result<Address> {
         // Parse signature
         let signature_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
             .map_err(|_| GovernanceError::invalid_signature("Invalid hex signature"))?;
@@ -31,7 +57,11 @@ impl SignatureVerifier {
             return Err(GovernanceError::invalid_signature("Signature must be 65 bytes"));
         }
 
-        let recovery_id = signature_bytes[64];
+        // Wallets encode v as 27/28 (EIP-191); secp256k1 expects 0/1
+        let recovery_id = match signature_bytes[64] {
+            v @ 27..=28 => v - 27,
+            v => v,
+        };
         let signature_data = &signature_bytes[0..64];
 
         // Create recoverable signature
@@ -40,9 +70,7 @@ impl SignatureVerifier {
         let signature = RecoverableSignature::from_compact(signature_data, recovery_id)
             .map_err(|_| GovernanceError::invalid_signature("Invalid signature format"))?;
 
-        // Hash the message using Ethereum's signing scheme
-        let message_hash = hash_message(message);
-        let message = Message::from_digest(message_hash.as_fixed_bytes().clone());
+        let message = Message::from_digest(digest.to_fixed_bytes());
 
         // Recover public key
         let public_key = self.secp.recover_ecdsa(message, &signature)
@@ -65,6 +93,27 @@ impl SignatureVerifier {
         Ok(recovered_address == *expected_address)
     }
 
+    /// Verify a signature by `address` over `message_hash` that may come from a
+    /// smart contract wallet. A matching ECDSA signer is accepted outright;
+    /// otherwise the address is asked through `provider` under EIP-1271, which
+    /// rejects for addresses without code.
+    pub async fn verify_erc1271(
+        &self,
+        address: Address,
+        message_hash: H256,
+        signature: &str,
+        provider: &dyn ContractSignatureValidator,
+    ) -> Result<bool> {
+        if self.recover_signer(message_hash, signature).is_ok_and(|signer| signer == address) {
+            return Ok(true);
+        }
+
+        // Contract wallets may use signatures of any length, not just 65 bytes
+        let signature_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+            .map_err(|_| GovernanceError::invalid_signature("Invalid hex signature"))?;
+        provider.is_valid_signature(address, message_hash, &signature_bytes).await
+    }
+
     /// Convert a secp256k1 public key to an Ethereum address
     fn public_key_to_address(&self, public_key: &secp256k1::PublicKey) -> Address {
         let public_key_bytes = public_key.serialize_uncompressed();
@@ -121,6 +170,61 @@ impl Default for SignatureVerifier {
     }
 }
 
+/// EIP-191 personal messages signed with secp256k1 and recovered to a
+/// Keccak-derived address
+impl SignatureScheme for SignatureVerifier {
+    fn name(&self) -> &str {
+        DEFAULT_SIGNATURE_SCHEME
+    }
+
+    fn recover(&self, message: &str, signature: &str) -> Result<Address> {
+        self.verify_signature(message, signature)
+    }
+}
+
+/// Registered signature schemes by name, and the one used when a request
+/// doesn't name any
+#[derive(Clone)]
+pub struct SignatureSchemes {
+    schemes: HashMap<String, Arc<dyn SignatureScheme>>,
+    default: String,
+}
+
+impl SignatureSchemes {
+    /// Only the Ethereum scheme, which is also the default
+    pub fn new() -> Self {
+        Self {
+            schemes: HashMap::new(),
+            default: DEFAULT_SIGNATURE_SCHEME.to_string(),
+        }
+        .with_scheme(Arc::new(SignatureVerifier::new()))
+    }
+
+    pub fn with_scheme(mut self, scheme: Arc<dyn SignatureScheme>) -> Self {
+        self.schemes.insert(scheme.name().to_string(), scheme);
+        self
+    }
+
+    pub fn with_default(mut self, name: &str) -> Self {
+        self.default = name.to_string();
+        self
+    }
+
+    /// The scheme called `name`, or the default one
+    pub fn get(&self, name: Option<&str>) -> Result<&Arc<dyn SignatureScheme>> {
+        let name = name.unwrap_or(&self.default);
+        self.schemes
+            .get(name)
+            .ok_or_else(|| GovernanceError::invalid_request(format!("Unsupported signature scheme: {}", name)))
+    }
+}
+
+impl Default for SignatureSchemes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Utility functions for address validation
 pub fn is_valid_ethereum_address(address: &str) -> bool {
     if let Ok(_) = Address::from_str(address) {
@@ -135,6 +239,148 @@ pub fn normalize_address(address: &str) -> Result<Address> {
         .map_err(|_| GovernanceError::invalid_signature("Invalid address format"))
 }
 
+/// End of a SIWE message's first line, after the domain
+const SIWE_PREAMBLE: &str = " wants you to sign in with your Ethereum account:";
+
+/// A Sign-In with Ethereum (EIP-4361) message, as standard wallet UIs build
+/// them around a server-issued nonce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage {
+    pub domain: String,
+    pub address: Address,
+    pub statement: Option<String>,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+/// Why a well-formed SIWE message can't be accepted
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SiweValidationError {
+    #[error("Message is for domain {found}, expected {expected}")]
+    DomainMismatch { expected: String, found: String },
+    #[error("Message is for chain {found}, expected {expected}")]
+    ChainMismatch { expected: u64, found: u64 },
+    #[error("Message expired at {0}")]
+    Expired(DateTime<Utc>),
+    #[error("Message is not valid before {0}")]
+    NotYetValid(DateTime<Utc>),
+}
+
+impl SiweMessage {
+    /// Parse an EIP-4361 message. Optional fields may be left out, but the
+    /// ones present must come in the order the EIP lays out.
+    pub fn parse(message: &str) -> Result<Self> {
+        let invalid = |reason: &str| GovernanceError::invalid_signature(format!("Invalid SIWE message: {}", reason));
+        let timestamp = |value: String| {
+            DateTime::parse_from_rfc3339(&value)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| invalid(&format!("malformed timestamp {}", value)))
+        };
+        let mut lines = message.lines().peekable();
+
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(SIWE_PREAMBLE))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| invalid("missing sign-in preamble"))?
+            .to_string();
+        let address = lines
+            .next()
+            .and_then(|line| Address::from_str(line.trim()).ok())
+            .ok_or_else(|| invalid("missing or malformed address"))?;
+
+        // The statement is optional and set off by blank lines
+        while lines.next_if(|line| line.is_empty()).is_some() {}
+        let statement = lines.next_if(|line| !line.starts_with("URI: ")).map(str::to_string);
+        while lines.next_if(|line| line.is_empty()).is_some() {}
+
+        let mut field = |name: &str| {
+            let prefix = format!("{}: ", name);
+            lines
+                .next_if(|line| line.starts_with(&prefix))
+                .map(|line| line[prefix.len()..].to_string())
+        };
+        let uri = field("URI").ok_or_else(|| invalid("missing URI"))?;
+        let version = field("Version").ok_or_else(|| invalid("missing version"))?;
+        let chain_id = field("Chain ID")
+            .and_then(|chain_id| chain_id.parse().ok())
+            .ok_or_else(|| invalid("missing or malformed chain ID"))?;
+        let nonce = field("Nonce")
+            .filter(|nonce| nonce.len() >= 8 && nonce.chars().all(|c| c.is_ascii_alphanumeric()))
+            .ok_or_else(|| invalid("missing or malformed nonce"))?;
+        let issued_at = timestamp(field("Issued At").ok_or_else(|| invalid("missing issued-at time"))?)?;
+        let expiration_time = field("Expiration Time").map(timestamp).transpose()?;
+        let not_before = field("Not Before").map(timestamp).transpose()?;
+        let request_id = field("Request ID");
+
+        let mut resources = Vec::new();
+        if lines.next_if(|line| *line == "Resources:").is_some() {
+            while let Some(resource) = lines.next_if(|line| line.starts_with("- ")) {
+                resources.push(resource[2..].to_string());
+            }
+        }
+        if lines.next().is_some() {
+            return Err(invalid("unexpected content after the last field"));
+        }
+        if version != "1" {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+
+        Ok(Self {
+            domain,
+            address,
+            statement,
+            uri,
+            version,
+            chain_id,
+            nonce,
+            issued_at,
+            expiration_time,
+            not_before,
+            request_id,
+            resources,
+        })
+    }
+
+    /// Check the message was made for `expected_domain`, when there is one,
+    /// and `expected_chain_id`, and that it is valid at `now`
+    pub fn validate(
+        &self,
+        expected_domain: Option<&str>,
+        expected_chain_id: u64,
+        now: DateTime<Utc>,
+    ) -> std::result::Result<(), SiweValidationError> {
+        if let Some(expected) = expected_domain.and_then(normalize_domain) {
+            if normalize_domain(&self.domain).as_deref() != Some(expected.as_str()) {
+                return Err(SiweValidationError::DomainMismatch {
+                    expected,
+                    found: self.domain.clone(),
+                });
+            }
+        }
+        if self.chain_id != expected_chain_id {
+            return Err(SiweValidationError::ChainMismatch {
+                expected: expected_chain_id,
+                found: self.chain_id,
+            });
+        }
+        if let Some(expiration_time) = self.expiration_time.filter(|expiration_time| *expiration_time <= now) {
+            return Err(SiweValidationError::Expired(expiration_time));
+        }
+        if let Some(not_before) = self.not_before.filter(|not_before| *not_before > now) {
+            return Err(SiweValidationError::NotYetValid(not_before));
+        }
+        Ok(())
+    }
+}
+
 /// Authentication message templates
 pub struct AuthMessageTemplates;
 
@@ -224,4 +470,161 @@ mod tests {
         assert!(!is_valid_ethereum_address("invalid_address"));
         assert!(!is_valid_ethereum_address("0x123")); // too short
     }
-}
\ No newline at end of file
+
+    /// Treats the signature as the signer's address, standing in for a
+    /// non-secp256k1 wallet type
+    struct EchoScheme;
+
+    impl SignatureScheme for EchoScheme {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn recover(&self, _message: &str, signature: &str) -> Result<Address> {
+            normalize_address(signature)
+        }
+    }
+
+    #[test]
+    fn test_schemes_dispatch_by_name() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let wallet = LocalWallet::from_bytes(&[7u8; 32]).unwrap();
+        let message = "Sign this message to authenticate with Somnia Governance Engine: 1234567890abcdef";
+        let signature = wallet.sign_hash(hash_message(message)).unwrap();
+        let signature = format!("0x{}", hex::encode(signature.to_vec()));
+        let stand_in = Address::random();
+
+        let schemes = SignatureSchemes::new().with_scheme(Arc::new(EchoScheme));
+        let ethereum = schemes.get(None).unwrap();
+        assert_eq!(ethereum.name(), DEFAULT_SIGNATURE_SCHEME);
+        assert_eq!(ethereum.recover(message, &signature).unwrap(), wallet.address());
+
+        let echo = schemes.get(Some("echo")).unwrap();
+        assert_eq!(echo.recover(message, &format!("{:?}", stand_in)).unwrap(), stand_in);
+        assert_eq!(schemes.clone().with_default("echo").get(None).unwrap().name(), "echo");
+
+        assert!(matches!(schemes.get(Some("ed25519")), Err(GovernanceError::InvalidRequest(_))));
+    }
+
+    /// Contract wallet answering `isValidSignature` with the magic value
+    /// when it has code, or with nothing like an externally owned account
+    struct WalletCode(bool);
+
+    #[async_trait::async_trait]
+    impl crate::blockchain::simulation::CallSimulator for WalletCode {
+        async fn simulate(
+            &self,
+            _tx: &ethers::types::transaction::eip2718::TypedTransaction,
+        ) -> Result<crate::blockchain::simulation::CallSimulation> {
+            use crate::auth::contract_signatures::EIP1271_MAGIC_VALUE;
+            use crate::blockchain::simulation::CallSimulation;
+
+            if !self.0 {
+                return Ok(CallSimulation::succeeded(Default::default()));
+            }
+            let mut word = [0u8; 32];
+            word[..4].copy_from_slice(&EIP1271_MAGIC_VALUE);
+            Ok(CallSimulation::succeeded(word.to_vec().into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_erc1271() {
+        use crate::blockchain::client::SomniaClient;
+        use crate::config::Config;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let config = Config::default();
+        let contract = SomniaClient::mock(&config).with_simulator(Arc::new(WalletCode(true)));
+        let no_code = SomniaClient::mock(&config).with_simulator(Arc::new(WalletCode(false)));
+        let verifier = SignatureVerifier::new();
+        let hash = hash_message("Sign this message to authenticate with Somnia Governance Engine: 1234567890abcdef");
+
+        // Safe-style signatures need not be 65 bytes
+        let safe = Address::random();
+        assert!(verifier.verify_erc1271(safe, hash, "0x0102", &contract).await.unwrap());
+        assert!(!verifier.verify_erc1271(safe, hash, "0x0102", &no_code).await.unwrap());
+
+        let wallet = LocalWallet::from_bytes(&[7u8; 32]).unwrap();
+        let signature = format!("0x{}", hex::encode(wallet.sign_hash(hash).unwrap().to_vec()));
+        assert!(verifier.verify_erc1271(wallet.address(), hash, &signature, &no_code).await.unwrap());
+        assert!(!verifier.verify_erc1271(Address::random(), hash, &signature, &no_code).await.unwrap());
+    }
+
+    const SIWE: &str = "app.example.org wants you to sign in with your Ethereum account:
+0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1
+
+Sign in to vote on governance proposals.
+
+URI: https://app.example.org/login
+Version: 1
+Chain ID: 1337
+Nonce: 1234567890abcdef
+Issued At: 2026-01-01T12:00:00Z
+Expiration Time: 2026-01-01T12:10:00Z
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq";
+
+    #[test]
+    fn test_siwe_message_parsing() {
+        let message = SiweMessage::parse(SIWE).unwrap();
+        assert_eq!(message.domain, "app.example.org");
+        assert_eq!(message.address, normalize_address("0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1").unwrap());
+        assert_eq!(message.statement.as_deref(), Some("Sign in to vote on governance proposals."));
+        assert_eq!(message.uri, "https://app.example.org/login");
+        assert_eq!(message.chain_id, 1337);
+        assert_eq!(message.nonce, "1234567890abcdef");
+        assert_eq!(message.issued_at.to_rfc3339(), "2026-01-01T12:00:00+00:00");
+        assert!(message.expiration_time.is_some());
+        assert_eq!(message.not_before, None);
+        assert_eq!(message.resources.len(), 1);
+
+        // Without a statement or any optional field
+        let minimal = SIWE
+            .replace("Sign in to vote on governance proposals.\n\n", "")
+            .split("\nExpiration Time")
+            .next()
+            .unwrap()
+            .to_string();
+        let message = SiweMessage::parse(&minimal).unwrap();
+        assert_eq!(message.statement, None);
+        assert_eq!(message.expiration_time, None);
+
+        assert!(SiweMessage::parse(AuthMessageTemplates::DEFAULT).is_err());
+        assert!(SiweMessage::parse(&SIWE.replace("Version: 1", "Version: 2")).is_err());
+        assert!(SiweMessage::parse(&SIWE.replace("Nonce: 1234567890abcdef", "Nonce: abc")).is_err());
+        assert!(SiweMessage::parse(&SIWE.replace("Chain ID: 1337\n", "")).is_err());
+        assert!(SiweMessage::parse(&format!("{}\nextra", SIWE)).is_err());
+    }
+
+    #[test]
+    fn test_siwe_message_validation() {
+        let message = SiweMessage::parse(SIWE).unwrap();
+        let issued_at = message.issued_at;
+
+        assert_eq!(message.validate(Some("https://APP.example.org"), 1337, issued_at), Ok(()));
+        assert_eq!(message.validate(None, 1337, issued_at), Ok(()));
+        assert!(matches!(
+            message.validate(Some("evil.example"), 1337, issued_at),
+            Err(SiweValidationError::DomainMismatch { .. })
+        ));
+        assert_eq!(
+            message.validate(None, 1, issued_at),
+            Err(SiweValidationError::ChainMismatch {
+                expected: 1,
+                found: 1337
+            })
+        );
+        let expired_at = issued_at + chrono::Duration::minutes(10);
+        assert_eq!(message.validate(None, 1337, expired_at), Err(SiweValidationError::Expired(expired_at)));
+
+        let not_before = SiweMessage {
+            not_before: Some(expired_at),
+            expiration_time: None,
+            ..message
+        };
+        assert_eq!(not_before.validate(None, 1337, issued_at), Err(SiweValidationError::NotYetValid(expired_at)));
+    }
+
+}
@@ -0,0 +1,670 @@
+use crate::auth::wallet_auth::{AuthChallenge, AuthToken};
+use crate::config::{TokenStoreConfig, DEFAULT_REDIS_POOL_SIZE};
+use crate::utils::errors::{GovernanceError, Result};
+use async_trait::async_trait;
+use chrono::Duration;
+use deadpool_redis::redis::{self, AsyncCommands};
+use ethers::types::Address;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Where `WalletAuthService` keeps outstanding challenges, by address, and
+/// sessions, by token. Entries are inserted with the time they stay valid;
+/// stores that expire entries themselves make the service's sweeps
+/// unnecessary, but not its capacity limits.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Store `challenge` under its address, replacing any outstanding one
+    async fn insert_challenge(&self, challenge: AuthChallenge, ttl: Duration) -> Result<()>;
+    async fn get_challenge(&self, address: &Address) -> Result<Option<AuthChallenge>>;
+    /// Returns whether a challenge was removed
+    async fn remove_challenge(&self, address: &Address) -> Result<bool>;
+    /// Remove the address's challenge only while it is still the one with
+    /// `nonce`, in one step, so that of concurrent sign-ins with it only one
+    /// consumes it. Returns whether it was removed.
+    async fn remove_challenge_if(&self, address: &Address, nonce: &str) -> Result<bool>;
+
+    async fn insert_token(&self, token_id: &str, token: AuthToken, ttl: Duration) -> Result<()>;
+    async fn get_token(&self, token_id: &str) -> Result<Option<AuthToken>>;
+    /// Returns the removed session, if there was one
+    async fn remove_token(&self, token_id: &str) -> Result<Option<AuthToken>>;
+    /// Replace a session that still exists, in one step, so a revocation
+    /// racing a renewal isn't undone. Returns whether it was replaced.
+    async fn renew_token(&self, token_id: &str, token: AuthToken, ttl: Duration) -> Result<bool>;
+
+    async fn challenges(&self) -> Result<Vec<AuthChallenge>>;
+    async fn tokens(&self) -> Result<Vec<(String, AuthToken)>>;
+
+    async fn challenge_count(&self) -> Result<usize> {
+        Ok(self.challenges().await?.len())
+    }
+
+    async fn token_count(&self) -> Result<usize> {
+        Ok(self.tokens().await?.len())
+    }
+
+    /// Whether entries disappear by themselves once their TTL passes
+    fn expires_entries(&self) -> bool {
+        false
+    }
+
+    /// Drop challenges `keep` rejects. Returns how many were dropped.
+    async fn retain_challenges(&self, keep: &(dyn Fn(&AuthChallenge) -> bool + Send + Sync)) -> Result<usize> {
+        let mut dropped = 0;
+        for challenge in self.challenges().await? {
+            if !keep(&challenge) && self.remove_challenge(&challenge.address).await? {
+                dropped += 1;
+            }
+        }
+        Ok(dropped)
+    }
+
+    /// Drop sessions `keep` rejects. Returns how many were dropped.
+    async fn retain_tokens(&self, keep: &(dyn Fn(&str, &AuthToken) -> bool + Send + Sync)) -> Result<usize> {
+        let mut dropped = 0;
+        for (token_id, token) in self.tokens().await? {
+            if !keep(&token_id, &token) && self.remove_token(&token_id).await?.is_some() {
+                dropped += 1;
+            }
+        }
+        Ok(dropped)
+    }
+
+    /// Drop the oldest challenges until at most `max` remain, sparing the one
+    /// for `keep`. Returns how many were dropped.
+    async fn evict_challenges(&self, max: usize, keep: &Address) -> Result<usize> {
+        let excess = self.challenge_count().await?.saturating_sub(max);
+        if excess == 0 {
+            return Ok(0);
+        }
+
+        let mut oldest: Vec<_> = self
+            .challenges()
+            .await?
+            .into_iter()
+            .filter(|challenge| challenge.address != *keep)
+            .map(|challenge| (challenge.created_at, challenge.address))
+            .collect();
+        oldest.sort_unstable();
+        let mut evicted = 0;
+        for (_, address) in oldest.into_iter().take(excess) {
+            if self.remove_challenge(&address).await? {
+                evicted += 1;
+            }
+        }
+        Ok(evicted)
+    }
+
+    /// Drop the oldest sessions until at most `max` remain, sparing `keep`.
+    /// Returns how many were dropped.
+    async fn evict_tokens(&self, max: usize, keep: &str) -> Result<usize> {
+        let excess = self.token_count().await?.saturating_sub(max);
+        if excess == 0 {
+            return Ok(0);
+        }
+
+        let mut oldest: Vec<_> = self
+            .tokens()
+            .await?
+            .into_iter()
+            .filter(|(token_id, _)| token_id.as_str() != keep)
+            .map(|(token_id, token)| (token.issued_at, token_id))
+            .collect();
+        oldest.sort_unstable();
+        let mut evicted = 0;
+        for (_, token_id) in oldest.into_iter().take(excess) {
+            if self.remove_token(&token_id).await?.is_some() {
+                evicted += 1;
+            }
+        }
+        Ok(evicted)
+    }
+}
+
+/// The store `config` selects
+pub fn token_store(config: &TokenStoreConfig) -> Result<Box<dyn TokenStore>> {
+    Ok(match config {
+        TokenStoreConfig::Memory => Box::new(MemoryTokenStore::new()),
+        TokenStoreConfig::Redis { redis_url, pool_size } => Box::new(RedisTokenStore::new(
+            redis_url,
+            pool_size.unwrap_or(DEFAULT_REDIS_POOL_SIZE),
+        )?),
+    })
+}
+
+/// `TokenStore` in process memory. Everyone is signed out on restart, and
+/// instances behind a load balancer don't share sessions. TTLs are left to
+/// the service's sweeps.
+#[derive(Default)]
+pub struct MemoryTokenStore {
+    challenges: RwLock<HashMap<Address, AuthChallenge>>,
+    tokens: RwLock<HashMap<String, AuthToken>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn insert_challenge(&self, challenge: AuthChallenge, _ttl: Duration) -> Result<()> {
+        self.challenges.write().await.insert(challenge.address, challenge);
+        Ok(())
+    }
+
+    async fn get_challenge(&self, address: &Address) -> Result<Option<AuthChallenge>> {
+        Ok(self.challenges.read().await.get(address).cloned())
+    }
+
+    async fn remove_challenge(&self, address: &Address) -> Result<bool> {
+        Ok(self.challenges.write().await.remove(address).is_some())
+    }
+
+    async fn remove_challenge_if(&self, address: &Address, nonce: &str) -> Result<bool> {
+        let mut challenges = self.challenges.write().await;
+        if !challenges.get(address).is_some_and(|challenge| challenge.nonce == nonce) {
+            return Ok(false);
+        }
+        challenges.remove(address);
+        Ok(true)
+    }
+
+    async fn insert_token(&self, token_id: &str, token: AuthToken, _ttl: Duration) -> Result<()> {
+        self.tokens.write().await.insert(token_id.to_string(), token);
+        Ok(())
+    }
+
+    async fn get_token(&self, token_id: &str) -> Result<Option<AuthToken>> {
+        Ok(self.tokens.read().await.get(token_id).cloned())
+    }
+
+    async fn remove_token(&self, token_id: &str) -> Result<Option<AuthToken>> {
+        Ok(self.tokens.write().await.remove(token_id))
+    }
+
+    async fn renew_token(&self, token_id: &str, token: AuthToken, _ttl: Duration) -> Result<bool> {
+        match self.tokens.write().await.get_mut(token_id) {
+            Some(existing) => {
+                *existing = token;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn challenges(&self) -> Result<Vec<AuthChallenge>> {
+        Ok(self.challenges.read().await.values().cloned().collect())
+    }
+
+    async fn tokens(&self) -> Result<Vec<(String, AuthToken)>> {
+        Ok(self
+            .tokens
+            .read()
+            .await
+            .iter()
+            .map(|(token_id, token)| (token_id.clone(), token.clone()))
+            .collect())
+    }
+
+    async fn challenge_count(&self) -> Result<usize> {
+        Ok(self.challenges.read().await.len())
+    }
+
+    async fn token_count(&self) -> Result<usize> {
+        Ok(self.tokens.read().await.len())
+    }
+
+    async fn retain_challenges(&self, keep: &(dyn Fn(&AuthChallenge) -> bool + Send + Sync)) -> Result<usize> {
+        let mut challenges = self.challenges.write().await;
+        let initial_count = challenges.len();
+        challenges.retain(|_, challenge| keep(challenge));
+        Ok(initial_count - challenges.len())
+    }
+
+    async fn retain_tokens(&self, keep: &(dyn Fn(&str, &AuthToken) -> bool + Send + Sync)) -> Result<usize> {
+        let mut tokens = self.tokens.write().await;
+        let initial_count = tokens.len();
+        tokens.retain(|token_id, token| keep(token_id, token));
+        Ok(initial_count - tokens.len())
+    }
+}
+
+const CHALLENGE_PREFIX: &str = "auth:challenge:";
+const TOKEN_PREFIX: &str = "auth:token:";
+const CHALLENGE_INDEX: &str = "auth:index:challenges";
+const TOKEN_INDEX: &str = "auth:index:tokens";
+
+/// Replace a key only while it exists, moving its index entry to the new
+/// expiry. KEYS: entry, index; ARGV: value, TTL seconds, expiry score, member.
+const RENEW_SCRIPT: &str = r"
+if redis.call('SET', KEYS[1], ARGV[1], 'XX', 'EX', ARGV[2]) then
+    redis.call('ZADD', KEYS[2], 'XX', ARGV[3], ARGV[4])
+    return 1
+end
+return 0
+";
+
+/// Delete a challenge only while it still has the given nonce, with its index
+/// entry. KEYS: entry, index; ARGV: nonce, member.
+const REMOVE_CHALLENGE_SCRIPT: &str = r"
+local value = redis.call('GET', KEYS[1])
+if value and cjson.decode(value).nonce == ARGV[1] then
+    redis.call('DEL', KEYS[1])
+    redis.call('ZREM', KEYS[2], ARGV[2])
+    return 1
+end
+return 0
+";
+
+/// `TokenStore` in Redis, as JSON under `auth:challenge:<address>` and
+/// `auth:token:<token>`. Sessions survive restarts and are shared by every
+/// instance; keys expire with their entries. Sorted sets scored by expiry
+/// index each kind of entry, so capacity limits evict those closest to
+/// expiring without scanning the keyspace.
+#[derive(Clone)]
+pub struct RedisTokenStore {
+    pool: deadpool_redis::Pool,
+}
+
+impl RedisTokenStore {
+    pub fn new(redis_url: &str, pool_size: usize) -> Result<Self> {
+        let mut config = deadpool_redis::Config::from_url(redis_url);
+        config.pool = Some(deadpool_redis::PoolConfig::new(pool_size.max(1)));
+        let pool = config
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .map_err(|e| GovernanceError::Internal(anyhow::anyhow!("Invalid Redis configuration: {}", e)))?;
+        Ok(Self { pool })
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| GovernanceError::Internal(anyhow::anyhow!("Redis unavailable: {}", e)))
+    }
+
+    async fn put<T: Serialize + Sync>(
+        &self,
+        index: &str,
+        member: &str,
+        key: String,
+        value: &T,
+        ttl: Duration,
+    ) -> Result<()> {
+        let value = serde_json::to_vec(value)?;
+        let seconds = ttl_seconds(ttl);
+        let mut connection = self.connection().await?;
+        redis::pipe()
+            .atomic()
+            .set_ex(key, value, seconds)
+            .ignore()
+            .zadd(index, member, expiry_score(seconds))
+            .ignore()
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, key: String) -> Result<Option<T>> {
+        let mut connection = self.connection().await?;
+        let value: Option<Vec<u8>> = connection.get(key).await.map_err(redis_error)?;
+        Ok(value.map(|value| serde_json::from_slice(&value)).transpose()?)
+    }
+
+    /// GETDEL, so only one caller ever receives the value
+    async fn take<T: DeserializeOwned>(&self, index: &str, member: &str, key: String) -> Result<Option<T>> {
+        let mut connection = self.connection().await?;
+        let (value,): (Option<Vec<u8>>,) = redis::pipe()
+            .atomic()
+            .get_del(key)
+            .zrem(index, member)
+            .ignore()
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(value.map(|value| serde_json::from_slice(&value)).transpose()?)
+    }
+
+    /// Live entries in `index`, after dropping members whose keys expired
+    async fn live_count(&self, index: &str) -> Result<usize> {
+        let mut connection = self.connection().await?;
+        let (count,): (usize,) = redis::pipe()
+            .atomic()
+            .zrembyscore(index, "-inf", chrono::Utc::now().timestamp())
+            .ignore()
+            .zcard(index)
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(count)
+    }
+
+    /// Pop the entries closest to expiry until at most `max` remain in
+    /// `index`, sparing `keep`. ZPOPMIN hands each member to one caller, so
+    /// instances evicting at once don't overshoot.
+    async fn evict(&self, index: &str, prefix: &str, max: usize, keep: &str) -> Result<usize> {
+        let excess = self.live_count(index).await?.saturating_sub(max);
+        if excess == 0 {
+            return Ok(0);
+        }
+
+        let mut connection = self.connection().await?;
+        let popped: Vec<(String, f64)> = connection.zpopmin(index, excess as isize).await.map_err(redis_error)?;
+        let mut keys = Vec::new();
+        for (member, score) in popped {
+            if member == keep {
+                connection.zadd::<_, _, _, ()>(index, member, score).await.map_err(redis_error)?;
+            } else {
+                keys.push(format!("{}{}", prefix, member));
+            }
+        }
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        connection.del(keys).await.map_err(redis_error)
+    }
+
+    /// Entries under `prefix`, with the prefix stripped from their keys.
+    /// Uses SCAN, so it doesn't block the server on large keyspaces.
+    async fn scan<T: DeserializeOwned>(&self, prefix: &str) -> Result<Vec<(String, T)>> {
+        let mut connection = self.connection().await?;
+        let mut keys: Vec<String> = Vec::new();
+        {
+            let mut iter = connection
+                .scan_match::<_, String>(format!("{}*", prefix))
+                .await
+                .map_err(redis_error)?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        // Keys that expired since the scan come back empty
+        keys.into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| Some((key, value?)))
+            .map(|(key, value)| Ok((key[prefix.len()..].to_string(), serde_json::from_slice(&value)?)))
+            .collect::<Result<Vec<_>>>()
+    }
+}
+
+fn redis_error(error: redis::RedisError) -> GovernanceError {
+    GovernanceError::Internal(anyhow::anyhow!("Redis error: {}", error))
+}
+
+/// SETEX refuses a zero TTL; an entry about to lapse gets a second
+fn ttl_seconds(ttl: Duration) -> u64 {
+    ttl.num_seconds().max(1) as u64
+}
+
+/// Index score of an entry expiring `seconds` from now: its expiry in Unix
+/// seconds
+fn expiry_score(seconds: u64) -> i64 {
+    chrono::Utc::now().timestamp() + seconds as i64
+}
+
+fn challenge_member(address: &Address) -> String {
+    format!("{:?}", address)
+}
+
+fn challenge_key(address: &Address) -> String {
+    format!("{}{}", CHALLENGE_PREFIX, challenge_member(address))
+}
+
+fn token_key(token_id: &str) -> String {
+    format!("{}{}", TOKEN_PREFIX, token_id)
+}
+
+#[async_trait]
+impl TokenStore for RedisTokenStore {
+    async fn insert_challenge(&self, challenge: AuthChallenge, ttl: Duration) -> Result<()> {
+        let member = challenge_member(&challenge.address);
+        self.put(CHALLENGE_INDEX, &member, challenge_key(&challenge.address), &challenge, ttl)
+            .await
+    }
+
+    async fn get_challenge(&self, address: &Address) -> Result<Option<AuthChallenge>> {
+        self.fetch(challenge_key(address)).await
+    }
+
+    async fn remove_challenge(&self, address: &Address) -> Result<bool> {
+        let mut connection = self.connection().await?;
+        let (removed,): (usize,) = redis::pipe()
+            .atomic()
+            .del(challenge_key(address))
+            .zrem(CHALLENGE_INDEX, challenge_member(address))
+            .ignore()
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(removed > 0)
+    }
+
+    async fn remove_challenge_if(&self, address: &Address, nonce: &str) -> Result<bool> {
+        let mut connection = self.connection().await?;
+        let removed: i64 = redis::Script::new(REMOVE_CHALLENGE_SCRIPT)
+            .key(challenge_key(address))
+            .key(CHALLENGE_INDEX)
+            .arg(nonce)
+            .arg(challenge_member(address))
+            .invoke_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(removed == 1)
+    }
+
+    async fn insert_token(&self, token_id: &str, token: AuthToken, ttl: Duration) -> Result<()> {
+        self.put(TOKEN_INDEX, token_id, token_key(token_id), &token, ttl).await
+    }
+
+    async fn get_token(&self, token_id: &str) -> Result<Option<AuthToken>> {
+        self.fetch(token_key(token_id)).await
+    }
+
+    async fn remove_token(&self, token_id: &str) -> Result<Option<AuthToken>> {
+        self.take(TOKEN_INDEX, token_id, token_key(token_id)).await
+    }
+
+    async fn renew_token(&self, token_id: &str, token: AuthToken, ttl: Duration) -> Result<bool> {
+        let value = serde_json::to_vec(&token)?;
+        let seconds = ttl_seconds(ttl);
+        let mut connection = self.connection().await?;
+        let renewed: i64 = redis::Script::new(RENEW_SCRIPT)
+            .key(token_key(token_id))
+            .key(TOKEN_INDEX)
+            .arg(value)
+            .arg(seconds)
+            .arg(expiry_score(seconds))
+            .arg(token_id)
+            .invoke_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(renewed == 1)
+    }
+
+    async fn challenges(&self) -> Result<Vec<AuthChallenge>> {
+        Ok(self
+            .scan(CHALLENGE_PREFIX)
+            .await?
+            .into_iter()
+            .map(|(_, challenge)| challenge)
+            .collect())
+    }
+
+    async fn tokens(&self) -> Result<Vec<(String, AuthToken)>> {
+        self.scan(TOKEN_PREFIX).await
+    }
+
+    async fn challenge_count(&self) -> Result<usize> {
+        self.live_count(CHALLENGE_INDEX).await
+    }
+
+    async fn token_count(&self) -> Result<usize> {
+        self.live_count(TOKEN_INDEX).await
+    }
+
+    fn expires_entries(&self) -> bool {
+        true
+    }
+
+    async fn evict_challenges(&self, max: usize, keep: &Address) -> Result<usize> {
+        self.evict(CHALLENGE_INDEX, CHALLENGE_PREFIX, max, &challenge_member(keep))
+            .await
+    }
+
+    async fn evict_tokens(&self, max: usize, keep: &str) -> Result<usize> {
+        self.evict(TOKEN_INDEX, TOKEN_PREFIX, max, keep).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn token(address: Address) -> AuthToken {
+        let now = Utc::now();
+        AuthToken {
+            address,
+            issued_at: now,
+            expires_at: now + Duration::hours(1),
+            nonce: "1234567890abcdef".to_string(),
+            purpose: "login".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_retains_by_predicate() {
+        let store = MemoryTokenStore::new();
+        let (kept, dropped) = (Address::random(), Address::random());
+        store.insert_token("a", token(kept), Duration::hours(1)).await.unwrap();
+        store.insert_token("b", token(dropped), Duration::hours(1)).await.unwrap();
+
+        assert_eq!(store.retain_tokens(&|_, token| token.address == kept).await.unwrap(), 1);
+        assert_eq!(store.token_count().await.unwrap(), 1);
+        assert_eq!(store.remove_token("a").await.unwrap().map(|token| token.address), Some(kept));
+        assert!(store.get_token("a").await.unwrap().is_none());
+        assert!(!store.expires_entries());
+    }
+
+    fn challenge(address: Address, created_at: chrono::DateTime<Utc>) -> AuthChallenge {
+        AuthChallenge {
+            nonce: "1234567890abcdef".to_string(),
+            message: "Sign in: 1234567890abcdef".to_string(),
+            address,
+            domain: None,
+            purpose: "login".to_string(),
+            created_at,
+            expires_at: created_at + Duration::minutes(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_removes_challenge_once_and_renews_only_live_tokens() {
+        let store = MemoryTokenStore::new();
+        let address = Address::random();
+        let issued = challenge(address, Utc::now());
+        store.insert_challenge(issued.clone(), Duration::minutes(5)).await.unwrap();
+        assert!(!store.remove_challenge_if(&address, "ffffffffffffffff").await.unwrap());
+        assert!(store.remove_challenge_if(&address, &issued.nonce).await.unwrap());
+        assert!(!store.remove_challenge_if(&address, &issued.nonce).await.unwrap());
+
+        store.insert_token("a", token(address), Duration::hours(1)).await.unwrap();
+        assert!(store.renew_token("a", token(address), Duration::hours(2)).await.unwrap());
+        store.remove_token("a").await.unwrap();
+        assert!(!store.renew_token("a", token(address), Duration::hours(2)).await.unwrap());
+        assert!(store.get_token("a").await.unwrap().is_none());
+    }
+
+    /// Redis at `REDIS_TEST_URL`, emptied of auth keys. Tests using it are
+    /// skipped when the variable isn't set.
+    async fn redis_store() -> Option<RedisTokenStore> {
+        let url = std::env::var("REDIS_TEST_URL").ok()?;
+        let store = RedisTokenStore::new(&url, 2).unwrap();
+        let mut connection = store.connection().await.unwrap();
+        let keys: Vec<String> = connection.keys("auth:*").await.unwrap();
+        if !keys.is_empty() {
+            connection.del::<_, ()>(keys).await.unwrap();
+        }
+        Some(store)
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_round_trip_and_revoke() {
+        let Some(store) = redis_store().await else {
+            return;
+        };
+        let address = Address::random();
+        store.insert_challenge(challenge(address, Utc::now()), Duration::minutes(5)).await.unwrap();
+        assert_eq!(store.get_challenge(&address).await.unwrap().map(|c| c.address), Some(address));
+        assert_eq!(store.challenge_count().await.unwrap(), 1);
+        let nonce = store.get_challenge(&address).await.unwrap().unwrap().nonce;
+        assert!(!store.remove_challenge_if(&address, "ffffffffffffffff").await.unwrap());
+        assert!(store.remove_challenge_if(&address, &nonce).await.unwrap());
+        assert!(!store.remove_challenge_if(&address, &nonce).await.unwrap());
+        assert_eq!(store.challenge_count().await.unwrap(), 0);
+
+        store.insert_token("a", token(address), Duration::hours(1)).await.unwrap();
+        assert_eq!(store.tokens().await.unwrap().len(), 1);
+        assert!(store.renew_token("a", token(address), Duration::hours(2)).await.unwrap());
+        assert_eq!(store.remove_token("a").await.unwrap().map(|t| t.address), Some(address));
+        // A renewal racing the revocation doesn't bring the session back
+        assert!(!store.renew_token("a", token(address), Duration::hours(2)).await.unwrap());
+        assert!(store.get_token("a").await.unwrap().is_none());
+        assert_eq!(store.token_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_expires_entries() {
+        let Some(store) = redis_store().await else {
+            return;
+        };
+        store.insert_token("a", token(Address::random()), Duration::seconds(1)).await.unwrap();
+        assert_eq!(store.token_count().await.unwrap(), 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+        assert!(store.get_token("a").await.unwrap().is_none());
+        assert_eq!(store.token_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_evicts_over_capacity() {
+        let Some(store) = redis_store().await else {
+            return;
+        };
+        let addresses: Vec<Address> = (0..4).map(|_| Address::random()).collect();
+        for (i, address) in addresses.iter().enumerate() {
+            let ttl = Duration::minutes(5 + i as i64);
+            store.insert_challenge(challenge(*address, Utc::now()), ttl).await.unwrap();
+        }
+
+        assert_eq!(store.evict_challenges(2, &addresses[3]).await.unwrap(), 2);
+        assert_eq!(store.challenge_count().await.unwrap(), 2);
+        assert!(store.get_challenge(&addresses[0]).await.unwrap().is_none());
+        assert!(store.get_challenge(&addresses[3]).await.unwrap().is_some());
+        assert_eq!(store.evict_challenges(2, &addresses[3]).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_store_selected_by_config() {
+        let redis = TokenStoreConfig::Redis {
+            redis_url: "redis://127.0.0.1:6379".to_string(),
+            pool_size: None,
+        };
+        // Connections are made lazily, so building the pool needs no server
+        assert!(token_store(&redis).unwrap().expires_entries());
+        assert!(!token_store(&TokenStoreConfig::Memory).unwrap().expires_entries());
+    }
+}
@@ -1,18 +1,28 @@
-use crate::auth::signature_verification::{SignatureVerifier, normalize_address};
-use crate::config::Config;
-use crate::utils::errors::Result;
+use crate::auth::audit::{SignedMessageAudit, SignedMessageRecord};
+use crate::auth::contract_signatures::ContractSignatureVerifier;
+use crate::auth::security::{normalize_domain, AuthFailureMetrics, AuthFailureReason, AuthStore, AuthStoreMetrics};
+use crate::auth::signature_verification::{
+    normalize_address, SignatureScheme, SignatureSchemes, SignatureVerifier, SiweMessage, SiweValidationError,
+    DEFAULT_SIGNATURE_SCHEME,
+};
+use crate::auth::token_store::{MemoryTokenStore, TokenStore};
+use crate::config::{Config, DEFAULT_AUTH_PURPOSE};
+use crate::utils::clock::{system_clock, Clock, SharedClock};
+use crate::utils::errors::{GovernanceError, Result};
 use chrono::{DateTime, Duration, Utc};
 use ethers::core::types::Address;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthChallenge {
     pub nonce: String,
     pub message: String,
     pub address: Address,
+    #[serde(default)]
+    pub domain: Option<String>, // Domain the message was issued for, if known
+    pub purpose: String, // what signing the message authorizes, e.g. "login" or "delegation"
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
 }
@@ -23,6 +33,21 @@ pub struct AuthToken {
     pub issued_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub nonce: String,
+    #[serde(default = "default_purpose")]
+    pub purpose: String, // purpose of the challenge it was issued for; only "login" tokens are sessions
+}
+
+impl AuthToken {
+    /// Whether the token came from a sign-in, rather than from a signature
+    /// confirming some other action, and so may authorize API requests
+    pub fn is_session(&self) -> bool {
+        self.purpose == DEFAULT_AUTH_PURPOSE
+    }
+}
+
+/// Tokens stored before purposes existed were all sign-ins
+fn default_purpose() -> String {
+    DEFAULT_AUTH_PURPOSE.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,11 +55,33 @@ pub struct AuthRequest {
     pub address: String,
     pub message: String,
     pub signature: String,
+    #[serde(default)]
+    pub scheme: Option<String>, // signature scheme; the configured default if omitted
+    #[serde(default)]
+    pub purpose: Option<String>, // purpose the challenge must have been issued for; "login" if omitted
+}
+
+/// Standalone signature check, outside the challenge flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureCheckRequest {
+    pub address: String,
+    pub message: String,
+    pub signature: String,
+    #[serde(default)]
+    pub scheme: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureCheck {
+    pub verified: bool,
+    pub recovered_address: String, // EIP-55 checksummed
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChallengeRequest {
     pub address: String,
+    #[serde(default)]
+    pub purpose: Option<String>, // one of the configured message template purposes; "login" if omitted
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +91,23 @@ pub struct ChallengeResponse {
     pub expires_at: DateTime<Utc>,
 }
 
+/// Exact message the wallet must sign for the outstanding challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeMessage {
+    pub address: Address,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Current session details for a bearer token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub address: String, // EIP-55 checksummed
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub remaining_ttl_seconds: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub success: bool,
@@ -51,53 +115,130 @@ pub struct AuthResponse {
     pub address: Option<Address>,
     pub expires_at: Option<DateTime<Utc>>,
     pub error: Option<String>,
+    pub error_code: Option<String>, // AuthFailureReason code
 }
 
 #[derive(Clone)]
 pub struct WalletAuthService {
     verifier: SignatureVerifier,
-    challenges: Arc<RwLock<HashMap<Address, AuthChallenge>>>,
-    tokens: Arc<RwLock<HashMap<String, AuthToken>>>,
+    signature_schemes: SignatureSchemes,
+    store: Arc<dyn TokenStore>,
     config: Arc<Config>,
+    clock: SharedClock,
+    failure_metrics: AuthFailureMetrics,
+    store_metrics: AuthStoreMetrics,
+    contract_signatures: Option<ContractSignatureVerifier>,
+    signed_messages: SignedMessageAudit,
 }
 
 impl WalletAuthService {
-    pub fn new(config: Arc<Config>) -> Self {
+    /// Service keeping challenges and sessions in `store`
+    pub fn new(config: Arc<Config>, store: Box<dyn TokenStore>) -> Self {
+        let clock = system_clock();
+        let signed_messages = SignedMessageAudit::new(config.auth.signed_message_retention(), clock.clone());
+        let default_scheme = config.auth.signature_scheme.as_deref().unwrap_or(DEFAULT_SIGNATURE_SCHEME);
         Self {
             verifier: SignatureVerifier::new(),
-            challenges: Arc::new(RwLock::new(HashMap::new())),
-            tokens: Arc::new(RwLock::new(HashMap::new())),
+            signature_schemes: SignatureSchemes::new().with_default(default_scheme),
+            store: Arc::from(store),
             config,
+            clock,
+            failure_metrics: AuthFailureMetrics::new(),
+            store_metrics: AuthStoreMetrics::new(),
+            contract_signatures: None,
+            signed_messages,
         }
     }
 
+    /// Service keeping challenges and sessions in process memory
+    pub fn in_memory(config: Arc<Config>) -> Self {
+        Self::new(config, Box::new(MemoryTokenStore::new()))
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.signed_messages = SignedMessageAudit::new(self.config.auth.signed_message_retention(), clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// Also accept EIP-1271 signatures from smart contract wallets, for
+    /// addresses whose signature doesn't recover by ECDSA
+    pub fn with_contract_signatures(mut self, verifier: ContractSignatureVerifier) -> Self {
+        self.contract_signatures = Some(verifier);
+        self
+    }
+
+    /// Accept signatures under `scheme`, for requests naming it or, when it
+    /// is the configured default, for requests naming none
+    pub fn with_signature_scheme(mut self, scheme: Arc<dyn SignatureScheme>) -> Self {
+        self.signature_schemes = self.signature_schemes.with_scheme(scheme);
+        self
+    }
+
     /// Generate a new authentication challenge for an address
     pub async fn create_challenge(&self, address: &str) -> Result<ChallengeResponse> {
+        self.create_challenge_for(address, None).await
+    }
+
+    /// `create_challenge`, binding the message to the domain the user is
+    /// signing in to so it can't be replayed against another site
+    pub async fn create_challenge_for(&self, address: &str, domain: Option<&str>) -> Result<ChallengeResponse> {
+        self.create_challenge_with_purpose(address, domain, None).await
+    }
+
+    /// `create_challenge_for`, with a message stating what the signature is
+    /// for. `authenticate` only accepts it for the same purpose, so a
+    /// signature collected for one action can't be used for another.
+    pub async fn create_challenge_with_purpose(
+        &self,
+        address: &str,
+        domain: Option<&str>,
+        purpose: Option<&str>,
+    ) -> Result<ChallengeResponse> {
         // Validate and normalize address
         let address = normalize_address(address)?;
 
+        let purpose = purpose.unwrap_or(DEFAULT_AUTH_PURPOSE);
+        let template = self
+            .config
+            .auth
+            .message_template_for(purpose)
+            .ok_or_else(|| GovernanceError::invalid_request(format!("Unknown challenge purpose: {}", purpose)))?;
+
+        let domain = domain.and_then(normalize_domain);
+        if let Some(domain) = &domain {
+            if !self.is_allowed_domain(domain) {
+                return Err(GovernanceError::invalid_request(format!("Origin {} is not allowed", domain)));
+            }
+        }
+
         // Generate nonce and create message
         let nonce = SignatureVerifier::generate_nonce();
-        let message = self.verifier.create_sign_message(&nonce, &self.config.auth.message_template);
+        let message = self.sign_message(template, &nonce, domain.as_deref(), purpose);
 
         // Validate message
         self.verifier.validate_message(&message)?;
 
         // Create challenge
-        let expires_at = Utc::now() + Duration::seconds(self.config.auth.signature_ttl as i64);
+        let now = self.clock.now();
+        let expires_at = now + Duration::seconds(self.config.auth.signature_ttl as i64);
         let challenge = AuthChallenge {
             nonce: nonce.clone(),
             message: message.clone(),
             address,
-            created_at: Utc::now(),
+            domain,
+            purpose: purpose.to_string(),
+            created_at: now,
             expires_at,
         };
 
-        // Store challenge
-        self.challenges.write().await.insert(address, challenge);
+        // Store challenge, for as long as `authenticate` would accept it
+        let ttl = expires_at + self.config.auth.clock_skew_tolerance() - now;
+        self.store.insert_challenge(challenge, ttl).await?;
 
-        // Clean up expired challenges
+        // Clean up expired challenges, then make room if still over capacity
         self.cleanup_expired_challenges().await;
+        self.evict_excess_challenges(address).await;
 
         Ok(ChallengeResponse {
             challenge: nonce,
@@ -106,86 +247,175 @@ impl WalletAuthService {
         })
     }
 
+    /// Render `template`. `{domain}` and `{purpose}` are substituted where the
+    /// template places them; otherwise the domain is stated up front and the
+    /// purpose at the end.
+    fn sign_message(&self, template: &str, nonce: &str, domain: Option<&str>, purpose: &str) -> String {
+        let mut message = self.verifier.create_sign_message(nonce, template);
+        if template.contains("{purpose}") {
+            message = message.replace("{purpose}", purpose);
+        } else {
+            message = format!("{}\n\nPurpose: {}", message, purpose);
+        }
+
+        match domain {
+            Some(domain) if template.contains("{domain}") => message.replace("{domain}", domain),
+            Some(domain) => format!("{} wants you to sign in with your Ethereum account.\n\n{}", domain, message),
+            None => message.replace("{domain}", ""),
+        }
+    }
+
+    /// Whether `domain` appears in the server's CORS allowlist. An empty
+    /// allowlist accepts any domain.
+    fn is_allowed_domain(&self, domain: &str) -> bool {
+        let allowed = &self.config.server.allowed_origins;
+        allowed.is_empty()
+            || allowed
+                .iter()
+                .any(|origin| normalize_domain(origin).as_deref() == Some(domain))
+    }
+
+    /// The outstanding, unexpired challenge message for an address. This is the
+    /// same string `authenticate` compares against, byte for byte.
+    pub async fn challenge_message(&self, address: &str) -> Result<Option<ChallengeMessage>> {
+        let address = normalize_address(address)?;
+        let now = self.clock.now();
+
+        Ok(self
+            .store
+            .get_challenge(&address)
+            .await?
+            .filter(|challenge| !self.is_expired(challenge, now))
+            .map(|challenge| ChallengeMessage {
+                address,
+                message: challenge.message.clone(),
+                expires_at: challenge.expires_at,
+            }))
+    }
+
+    /// Drop the outstanding challenge for an address, e.g. when the user
+    /// dismisses their wallet prompt. Returns whether one was removed.
+    pub async fn cancel_challenge(&self, address: &str) -> Result<bool> {
+        let address = normalize_address(address)?;
+        let removed = self.store.remove_challenge(&address).await?;
+        if removed {
+            tracing::debug!("Challenge cancelled for {:?}", address);
+        }
+        Ok(removed)
+    }
+
     /// Verify signature and create authentication token
     pub async fn authenticate(&self, auth_request: AuthRequest) -> Result<AuthResponse> {
+        self.authenticate_from(auth_request, None, None).await
+    }
+
+    /// `authenticate`, attributing failures to `source_ip` in security logs.
+    /// `domain` must match the domain the challenge was issued for.
+    pub async fn authenticate_from(
+        &self,
+        auth_request: AuthRequest,
+        source_ip: Option<IpAddr>,
+        domain: Option<&str>,
+    ) -> Result<AuthResponse> {
+        let reject = |reason, error: &str| self.reject(reason, &auth_request.address, source_ip, error);
+
         // Validate address format
         let address = match normalize_address(&auth_request.address) {
             Ok(addr) => addr,
-            Err(_) => {
-                return Ok(AuthResponse {
-                    success: false,
-                    token: None,
-                    address: None,
-                    expires_at: None,
-                    error: Some("Invalid address format".to_string()),
-                });
-            }
+            Err(_) => return Ok(reject(AuthFailureReason::InvalidAddress, "Invalid address format")),
         };
 
-        // Get stored challenge
-        let challenge = match self.challenges.read().await.get(&address).cloned() {
+        let scheme = self.signature_schemes.get(auth_request.scheme.as_deref())?;
+
+        // Get stored challenge. It is consumed only once the attempt verifies,
+        // so nobody can burn another address's challenge with a bad signature.
+        let challenge = match self.store.get_challenge(&address).await? {
             Some(challenge) => challenge,
             None => {
-                return Ok(AuthResponse {
-                    success: false,
-                    token: None,
-                    address: None,
-                    expires_at: None,
-                    error: Some("No challenge found for this address".to_string()),
-                });
+                return Ok(reject(AuthFailureReason::NoChallenge, "No challenge found for this address"));
             }
         };
 
         // Check if challenge has expired
-        if Utc::now() > challenge.expires_at {
-            // Remove expired challenge
-            self.challenges.write().await.remove(&address);
-            return Ok(AuthResponse {
-                success: false,
-                token: None,
-                address: None,
-                expires_at: None,
-                error: Some("Challenge expired".to_string()),
-            });
-        }
-
-        // Verify message matches challenge
-        if auth_request.message != challenge.message {
-            return Ok(AuthResponse {
-                success: false,
-                token: None,
-                address: None,
-                expires_at: None,
-                error: Some("Message does not match challenge".to_string()),
-            });
+        if self.is_expired(&challenge, self.clock.now()) {
+            self.store.remove_challenge_if(&address, &challenge.nonce).await?;
+            return Ok(reject(AuthFailureReason::ChallengeExpired, "Challenge expired"));
+        }
+
+        // Either the message issued with the challenge, or a Sign-In with
+        // Ethereum message a wallet UI built around the challenge nonce
+        let siwe = match SiweMessage::parse(&auth_request.message) {
+            Ok(siwe) if siwe.nonce == challenge.nonce => Some(siwe),
+            _ if auth_request.message == challenge.message => None,
+            _ => return Ok(reject(AuthFailureReason::MessageMismatch, "Message does not match challenge")),
+        };
+
+        // Nor one signed for, say, a delegation as a sign-in
+        let purpose = auth_request.purpose.as_deref().unwrap_or(DEFAULT_AUTH_PURPOSE);
+        if challenge.purpose != purpose {
+            return Ok(reject(AuthFailureReason::PurposeMismatch, "Challenge was issued for a different purpose"));
+        }
+        // A SIWE message only carries the nonce, so it signs in unless it
+        // states the challenge's other purpose itself
+        if siwe.as_ref().is_some_and(|siwe| !siwe_states_purpose(siwe, &challenge.purpose)) {
+            return Ok(reject(AuthFailureReason::PurposeMismatch, "Message does not state the challenge's purpose"));
+        }
+
+        // A message signed for one site must not be usable from another
+        if challenge.domain != domain.and_then(normalize_domain) {
+            return Ok(reject(AuthFailureReason::DomainMismatch, "Challenge was issued for a different domain"));
+        }
+
+        if let Some(siwe) = &siwe {
+            if let Some((reason, error)) = self.check_siwe(siwe, address, challenge.domain.as_deref()) {
+                return Ok(reject(reason, &error));
+            }
         }
 
         // Verify signature
-        match self.verifier.verify_signature_for_address(
-            &auth_request.message,
-            &auth_request.signature,
-            &address,
-        ) {
+        let mut verification = scheme
+            .recover(&auth_request.message, &auth_request.signature)
+            .map(|signer| signer == address);
+        // EIP-1271 contract wallets only exist alongside Ethereum signatures
+        if !matches!(verification, Ok(true))
+            && scheme.name() == DEFAULT_SIGNATURE_SCHEME
+            && self.verify_contract_signature(&auth_request, address).await
+        {
+            verification = Ok(true);
+        }
+
+        match verification {
             Ok(true) => {
+                // Of concurrent attempts with the challenge, here or on another
+                // instance, only the one that removes it gets a token
+                if !self.store.remove_challenge_if(&address, &challenge.nonce).await? {
+                    return Ok(reject(AuthFailureReason::NoChallenge, "Challenge was already used"));
+                }
+
                 // Signature is valid, create token
                 let token_id = uuid::Uuid::new_v4().to_string();
-                let expires_at = Utc::now() + Duration::hours(24); // 24 hour token
+                let issued_at = self.clock.now();
+                let expires_at = issued_at + Duration::seconds(self.config.auth.session_ttl as i64);
 
                 let auth_token = AuthToken {
                     address,
-                    issued_at: Utc::now(),
+                    issued_at,
                     expires_at,
                     nonce: challenge.nonce,
+                    purpose: challenge.purpose,
                 };
 
                 // Store token
-                self.tokens.write().await.insert(token_id.clone(), auth_token);
-
-                // Remove used challenge
-                self.challenges.write().await.remove(&address);
+                let ttl = expires_at - issued_at;
+                self.store.insert_token(&token_id, auth_token, ttl).await?;
 
-                // Clean up expired tokens
+                // Clean up expired tokens, then make room if still over capacity
                 self.cleanup_expired_tokens().await;
+                self.evict_excess_tokens(&token_id).await;
+
+                if self.config.auth.record_signed_messages {
+                    self.signed_messages.record(address, &auth_request.message, source_ip);
+                }
 
                 tracing::info!("User authenticated successfully: {:?}", address);
 
@@ -195,114 +425,296 @@ impl WalletAuthService {
                     address: Some(address),
                     expires_at: Some(expires_at),
                     error: None,
+                    error_code: None,
                 })
             }
-            Ok(false) => Ok(AuthResponse {
-                success: false,
-                token: None,
-                address: None,
-                expires_at: None,
-                error: Some("Invalid signature".to_string()),
-            }),
-            Err(e) => Ok(AuthResponse {
-                success: false,
-                token: None,
-                address: None,
-                expires_at: None,
-                error: Some(format!("Signature verification failed: {}", e)),
-            }),
-        }
-    }
-
-    /// Verify an authentication token
+            Ok(false) => Ok(reject(AuthFailureReason::InvalidSignature, "Invalid signature")),
+            Err(e) => Ok(reject(
+                AuthFailureReason::MalformedSignature,
+                &format!("Signature verification failed: {}", e),
+            )),
+        }
+    }
+
+    /// Why a SIWE message can't sign `address` in, if it can't: it must name
+    /// that address, this chain, and the challenge's domain (or, without one,
+    /// an allowed domain), and be valid now
+    fn check_siwe(
+        &self,
+        siwe: &SiweMessage,
+        address: Address,
+        domain: Option<&str>,
+    ) -> Option<(AuthFailureReason, String)> {
+        if siwe.address != address {
+            return Some((AuthFailureReason::AddressMismatch, "Message is for a different address".to_string()));
+        }
+        if domain.is_none() && !normalize_domain(&siwe.domain).is_some_and(|domain| self.is_allowed_domain(&domain)) {
+            return Some((AuthFailureReason::DomainMismatch, format!("Origin {} is not allowed", siwe.domain)));
+        }
+
+        let error = siwe
+            .validate(domain, self.config.blockchain.chain_id, self.clock.now())
+            .err()?;
+        let reason = match error {
+            SiweValidationError::DomainMismatch { .. } => AuthFailureReason::DomainMismatch,
+            SiweValidationError::ChainMismatch { .. } => AuthFailureReason::ChainMismatch,
+            SiweValidationError::Expired(_) => AuthFailureReason::MessageExpired,
+            SiweValidationError::NotYetValid(_) => AuthFailureReason::MessageNotYetValid,
+        };
+        Some((reason, error.to_string()))
+    }
+
+    /// Whether `address` is a contract wallet accepting the request's signature
+    async fn verify_contract_signature(&self, auth_request: &AuthRequest, address: Address) -> bool {
+        let Some(contract_signatures) = &self.contract_signatures else {
+            return false;
+        };
+        let hash = ethers::utils::hash_message(&auth_request.message);
+        match self
+            .verifier
+            .verify_erc1271(address, hash, &auth_request.signature, contract_signatures)
+            .await
+        {
+            Ok(valid) => valid,
+            Err(e) => {
+                tracing::debug!("EIP-1271 check for {:?} failed: {}", address, e);
+                false
+            }
+        }
+    }
+
+    /// Check that `signature` over `message` recovers to `address` without
+    /// issuing a token. Malformed input is an invalid request, not a mismatch.
+    pub fn check_signature(&self, request: &SignatureCheckRequest) -> Result<SignatureCheck> {
+        let address = normalize_address(&request.address)
+            .map_err(|_| GovernanceError::invalid_request("Invalid address format"))?;
+
+        let recovered = self
+            .signature_schemes
+            .get(request.scheme.as_deref())?
+            .recover(&request.message, &request.signature)
+            .map_err(|e| GovernanceError::invalid_request(e.to_string()))?;
+
+        Ok(SignatureCheck {
+            verified: recovered == address,
+            recovered_address: ethers::utils::to_checksum(&recovered, None),
+        })
+    }
+
+    /// Log and count a failed attempt and build the failure response
+    fn reject(
+        &self,
+        reason: AuthFailureReason,
+        address: &str,
+        source_ip: Option<IpAddr>,
+        error: &str,
+    ) -> AuthResponse {
+        // Cap client-supplied input before it reaches the logs
+        let address: String = address.chars().take(64).collect();
+        self.failure_metrics.record(reason, &address, source_ip);
+
+        AuthResponse {
+            success: false,
+            token: None,
+            address: None,
+            expires_at: None,
+            error: Some(error.to_string()),
+            error_code: Some(reason.code().to_string()),
+        }
+    }
+
+    pub fn failure_metrics(&self) -> &AuthFailureMetrics {
+        &self.failure_metrics
+    }
+
+    pub fn store_metrics(&self) -> &AuthStoreMetrics {
+        &self.store_metrics
+    }
+
+    /// Recorded sign-in messages, newest first. Empty unless
+    /// `auth.record_signed_messages` is enabled.
+    pub fn signed_messages(&self, address: Option<&str>) -> Result<Vec<SignedMessageRecord>> {
+        let address = address.map(normalize_address).transpose()?;
+        Ok(self.signed_messages.records(address))
+    }
+
+    /// Verify an authentication token. With sliding sessions enabled, a token
+    /// used within the renewal window of its expiry is extended by the session
+    /// TTL, but never past `session_max_lifetime` from issuance.
     pub async fn verify_token(&self, token: &str) -> Result<Option<AuthToken>> {
-        let tokens = self.tokens.read().await;
-        
-        if let Some(auth_token) = tokens.get(token) {
-            if Utc::now() <= auth_token.expires_at {
-                Ok(Some(auth_token.clone()))
-            } else {
-                // Token expired
-                Ok(None)
+        let now = self.clock.now();
+        let auth = &self.config.auth;
+
+        let Some(mut auth_token) = self.store.get_token(token).await?.filter(|t| now <= t.expires_at) else {
+            return Ok(None);
+        };
+        if !auth.sliding_sessions {
+            return Ok(Some(auth_token));
+        }
+
+        if auth_token.expires_at - now <= Duration::seconds(auth.session_renewal_window as i64) {
+            let max_expiry = auth_token.issued_at + Duration::seconds(auth.session_max_lifetime as i64);
+            let renewed = (now + Duration::seconds(auth.session_ttl as i64)).min(max_expiry);
+            if renewed > auth_token.expires_at {
+                auth_token.expires_at = renewed;
+                // Revoked since it was read
+                if !self.store.renew_token(token, auth_token.clone(), renewed - now).await? {
+                    return Ok(None);
+                }
+                tracing::debug!("Session renewed for {:?} until {}", auth_token.address, renewed);
             }
-        } else {
-            Ok(None)
         }
+
+        Ok(Some(auth_token))
+    }
+
+    /// Session details for a valid token, `None` if unknown or expired
+    pub async fn session_info(&self, token: &str) -> Result<Option<SessionInfo>> {
+        let now = self.clock.now();
+        Ok(self.verify_token(token).await?.map(|auth_token| SessionInfo {
+            address: ethers::utils::to_checksum(&auth_token.address, None),
+            issued_at: auth_token.issued_at,
+            expires_at: auth_token.expires_at,
+            remaining_ttl_seconds: (auth_token.expires_at - now).num_seconds().max(0),
+        }))
     }
 
     /// Revoke an authentication token
     pub async fn revoke_token(&self, token: &str) -> Result<bool> {
-        let removed = self.tokens.write().await.remove(token).is_some();
-        if removed {
+        let removed = self.store.remove_token(token).await?;
+        if let Some(auth_token) = &removed {
+            if let Some(contract_signatures) = &self.contract_signatures {
+                contract_signatures.invalidate(auth_token.address);
+            }
             tracing::info!("Token revoked: {}", token);
         }
-        Ok(removed)
+        Ok(removed.is_some())
     }
 
     /// Get all active tokens for an address (for debugging/admin)
-    pub async fn get_tokens_for_address(&self, address: &Address) -> Vec<String> {
-        let tokens = self.tokens.read().await;
-        tokens
-            .iter()
-            .filter(|(_, token)| token.address == *address && Utc::now() <= token.expires_at)
-            .map(|(token_id, _)| token_id.clone())
-            .collect()
+    pub async fn get_tokens_for_address(&self, address: &Address) -> Result<Vec<String>> {
+        let now = self.clock.now();
+        Ok(self
+            .store
+            .tokens()
+            .await?
+            .into_iter()
+            .filter(|(_, token)| token.address == *address && now <= token.expires_at)
+            .map(|(token_id, _)| token_id)
+            .collect())
+    }
+
+    /// Challenges stay valid for `auth.clock_skew_tolerance` past their
+    /// expiry, so a client whose clock runs behind isn't rejected at the edge
+    fn is_expired(&self, challenge: &AuthChallenge, now: DateTime<Utc>) -> bool {
+        now > challenge.expires_at + self.config.auth.clock_skew_tolerance()
     }
 
-    /// Clean up expired challenges
+    /// Clean up expired challenges. Stores that expire entries themselves
+    /// need no sweeping.
     async fn cleanup_expired_challenges(&self) {
-        let now = Utc::now();
-        let mut challenges = self.challenges.write().await;
-        let initial_count = challenges.len();
-        
-        challenges.retain(|_, challenge| now <= challenge.expires_at);
-        
-        let removed_count = initial_count - challenges.len();
-        if removed_count > 0 {
-            tracing::debug!("Cleaned up {} expired challenges", removed_count);
+        if self.store.expires_entries() {
+            return;
+        }
+        let now = self.clock.now();
+        let tolerance = self.config.auth.clock_skew_tolerance();
+        match self.store.retain_challenges(&|challenge| now <= challenge.expires_at + tolerance).await {
+            Ok(0) => {}
+            Ok(removed_count) => {
+                self.store_metrics.record_expired(AuthStore::Challenges, removed_count);
+                tracing::debug!("Cleaned up {} expired challenges", removed_count);
+            }
+            Err(e) => tracing::warn!("Failed to clean up expired challenges: {}", e),
+        }
+    }
+
+    /// Drop the oldest challenges beyond `auth.max_challenges`, sparing the
+    /// one just issued to `keep`, so memory stays bounded between sweeps
+    async fn evict_excess_challenges(&self, keep: Address) {
+        match self.store.evict_challenges(self.config.auth.max_challenges(), &keep).await {
+            Ok(0) => {}
+            Ok(evicted) => {
+                self.store_metrics.record_evicted(AuthStore::Challenges, evicted);
+                tracing::warn!("Challenge capacity reached; evicted {} oldest challenges", evicted);
+            }
+            Err(e) => tracing::warn!("Failed to evict excess challenges: {}", e),
         }
     }
 
-    /// Clean up expired tokens
+    /// Clean up expired tokens. Stores that expire entries themselves need
+    /// no sweeping.
     async fn cleanup_expired_tokens(&self) {
-        let now = Utc::now();
-        let mut tokens = self.tokens.write().await;
-        let initial_count = tokens.len();
-        
-        tokens.retain(|_, token| now <= token.expires_at);
-        
-        let removed_count = initial_count - tokens.len();
-        if removed_count > 0 {
-            tracing::debug!("Cleaned up {} expired tokens", removed_count);
+        if self.store.expires_entries() {
+            return;
+        }
+        let now = self.clock.now();
+        match self.store.retain_tokens(&|_, token| now <= token.expires_at).await {
+            Ok(0) => {}
+            Ok(removed_count) => {
+                self.store_metrics.record_expired(AuthStore::Sessions, removed_count);
+                tracing::debug!("Cleaned up {} expired tokens", removed_count);
+            }
+            Err(e) => tracing::warn!("Failed to clean up expired tokens: {}", e),
+        }
+    }
+
+    /// Drop the oldest sessions beyond `auth.max_sessions`, sparing the one
+    /// just issued as `keep`
+    async fn evict_excess_tokens(&self, keep: &str) {
+        match self.store.evict_tokens(self.config.auth.max_sessions(), keep).await {
+            Ok(0) => {}
+            Ok(evicted) => {
+                self.store_metrics.record_evicted(AuthStore::Sessions, evicted);
+                tracing::warn!("Session capacity reached; evicted {} oldest sessions", evicted);
+            }
+            Err(e) => tracing::warn!("Failed to evict excess sessions: {}", e),
         }
     }
 
     /// Get authentication statistics
-    pub async fn get_stats(&self) -> AuthStats {
-        let challenges = self.challenges.read().await;
-        let tokens = self.tokens.read().await;
-        
-        AuthStats {
+    pub async fn get_stats(&self) -> Result<AuthStats> {
+        let challenges = self.store.challenges().await?;
+        let tokens = self.store.tokens().await?;
+
+        Ok(AuthStats {
             active_challenges: challenges.len(),
             active_tokens: tokens.len(),
-            total_addresses: challenges.keys().chain(tokens.values().map(|t| &t.address)).collect::<std::collections::HashSet<_>>().len(),
-        }
+            total_addresses: challenges
+                .iter()
+                .map(|challenge| challenge.address)
+                .chain(tokens.iter().map(|(_, token)| token.address))
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+        })
     }
 
-    /// Start background cleanup task
-    pub fn start_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
+    /// Start background cleanup task, every `auth.cleanup_interval` seconds.
+    /// `None` with stores that expire entries themselves, such as Redis.
+    pub fn start_cleanup_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        if self.store.expires_entries() {
+            return None;
+        }
         let service = self.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(300)); // 5 minutes
+        let period = self.config.auth.cleanup_interval();
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
             loop {
                 interval.tick().await;
                 service.cleanup_expired_challenges().await;
                 service.cleanup_expired_tokens().await;
             }
-        })
+        }))
     }
 }
 
+/// Whether a SIWE message was signed for `purpose`: sign-ins always, other
+/// purposes only when named in its statement or listed among its resources
+fn siwe_states_purpose(siwe: &SiweMessage, purpose: &str) -> bool {
+    purpose == DEFAULT_AUTH_PURPOSE
+        || siwe.statement.as_deref().is_some_and(|statement| statement.contains(purpose))
+        || siwe.resources.iter().any(|resource| resource == purpose)
+}
+
 #[derive(Debug, Serialize)]
 pub struct AuthStats {
     pub active_challenges: usize,
@@ -317,7 +729,7 @@ mod tests {
     #[tokio::test]
     async fn test_challenge_creation() {
         let config = Arc::new(Config::default());
-        let auth_service = WalletAuthService::new(config);
+        let auth_service = WalletAuthService::in_memory(config);
         
         let address = "0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1";
         let challenge = auth_service.create_challenge(address).await.unwrap();
@@ -330,7 +742,7 @@ mod tests {
     #[tokio::test]
     async fn test_invalid_address() {
         let config = Arc::new(Config::default());
-        let auth_service = WalletAuthService::new(config);
+        let auth_service = WalletAuthService::in_memory(config);
         
         let result = auth_service.create_challenge("invalid_address").await;
         assert!(result.is_err());
@@ -339,12 +751,14 @@ mod tests {
     #[tokio::test]
     async fn test_authentication_without_challenge() {
         let config = Arc::new(Config::default());
-        let auth_service = WalletAuthService::new(config);
+        let auth_service = WalletAuthService::in_memory(config);
         
         let auth_request = AuthRequest {
             address: "0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1".to_string(),
             message: "Test message".to_string(),
             signature: "0x".to_string() + &"a".repeat(130),
+            scheme: None,
+            purpose: None,
         };
         
         let response = auth_service.authenticate(auth_request).await.unwrap();
@@ -355,21 +769,750 @@ mod tests {
     #[tokio::test]
     async fn test_token_verification() {
         let config = Arc::new(Config::default());
-        let auth_service = WalletAuthService::new(config);
+        let auth_service = WalletAuthService::in_memory(config);
         
         // Non-existent token
         let result = auth_service.verify_token("non_existent_token").await.unwrap();
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_challenge_expires_with_mock_clock() {
+        use crate::utils::clock::{Clock, MockClock};
+
+        let config = Arc::new(Config::default());
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(config.clone()).with_clock(Arc::new(clock.clone()));
+
+        let address = "0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1";
+        let challenge = auth_service.create_challenge(address).await.unwrap();
+        assert_eq!(
+            challenge.expires_at,
+            clock.now() + Duration::seconds(config.auth.signature_ttl as i64)
+        );
+
+        clock.advance(Duration::seconds(config.auth.signature_ttl as i64 + 1) + config.auth.clock_skew_tolerance());
+
+        let response = auth_service
+            .authenticate(AuthRequest {
+                address: address.to_string(),
+                message: challenge.message,
+                signature: "0x".to_string() + &"a".repeat(130),
+                scheme: None,
+                purpose: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("Challenge expired"));
+        assert_eq!(auth_service.get_stats().await.unwrap().active_challenges, 0);
+    }
+
+    #[tokio::test]
+    async fn test_challenge_accepted_within_clock_skew_tolerance() {
+        use crate::utils::clock::MockClock;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mut config = Config::default();
+        config.auth.clock_skew_tolerance = Some(30);
+        let ttl = config.auth.signature_ttl as i64;
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config)).with_clock(Arc::new(clock.clone()));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        let sign_in_after = |elapsed: i64| {
+            let auth_service = auth_service.clone();
+            let wallet = wallet.clone();
+            let address = address.clone();
+            let clock = clock.clone();
+            async move {
+                let challenge = auth_service.create_challenge(&address).await.unwrap();
+                let signature = wallet.sign_message(&challenge.message).await.unwrap();
+                clock.advance(Duration::seconds(elapsed));
+                auth_service
+                    .authenticate(AuthRequest {
+                        address,
+                        message: challenge.message,
+                        signature: format!("0x{}", hex::encode(signature.to_vec())),
+                        scheme: None,
+                        purpose: None,
+                    })
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Expired 20s ago by the server's clock, within the tolerance
+        assert!(sign_in_after(ttl + 20).await.success);
+
+        let response = sign_in_after(ttl + 31).await;
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("Challenge expired"));
+    }
+
+    #[tokio::test]
+    async fn test_contract_wallet_signature_cached_until_revoked() {
+        use crate::auth::contract_signatures::ContractSignatureValidator;
+        use ethers::types::H256;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Wallet contract accepting any 70-byte signature
+        #[derive(Default)]
+        struct SmartWallet {
+            calls: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl ContractSignatureValidator for SmartWallet {
+            async fn is_valid_signature(&self, _contract: Address, _hash: H256, signature: &[u8]) -> Result<bool> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                Ok(signature.len() == 70)
+            }
+        }
+
+        let wallet = Arc::new(SmartWallet::default());
+        let clock = system_clock();
+        let auth_service = WalletAuthService::in_memory(Arc::new(Config::default()))
+            .with_clock(clock.clone())
+            .with_contract_signatures(ContractSignatureVerifier::new(wallet.clone(), clock));
+        let address = "0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1";
+        let signature = format!("0x{}", "ab".repeat(70));
+
+        // Sign the same challenge message again after each sign-in
+        async fn sign_in(auth_service: &WalletAuthService, challenge: &AuthChallenge, signature: &str) -> AuthResponse {
+            auth_service
+                .store
+                .insert_challenge(challenge.clone(), Duration::minutes(5))
+                .await
+                .unwrap();
+            auth_service
+                .authenticate(AuthRequest {
+                    address: format!("{:?}", challenge.address),
+                    message: challenge.message.clone(),
+                    signature: signature.to_string(),
+                    scheme: None,
+                    purpose: None,
+                })
+                .await
+                .unwrap()
+        }
+        auth_service.create_challenge(address).await.unwrap();
+        let challenge = auth_service.store.challenges().await.unwrap().pop().unwrap();
+
+        let first = sign_in(&auth_service, &challenge, &signature).await;
+        assert!(first.success);
+        assert!(sign_in(&auth_service, &challenge, &signature).await.success);
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 1);
+
+        assert!(auth_service.revoke_token(&first.token.unwrap()).await.unwrap());
+        assert!(sign_in(&auth_service, &challenge, &signature).await.success);
+        assert_eq!(wallet.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempt_leaves_challenge_usable_once() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let auth_service = WalletAuthService::in_memory(Arc::new(Config::default()));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+        let challenge = auth_service.create_challenge(&address).await.unwrap();
+        let attempt = |signature: String| AuthRequest {
+            address: address.clone(),
+            message: challenge.message.clone(),
+            signature,
+            scheme: None,
+            purpose: None,
+        };
+
+        // Anyone can post a bad signature for the address; it burns nothing
+        for _ in 0..3 {
+            let response = auth_service.authenticate(attempt("0x".to_string() + &"a".repeat(130))).await.unwrap();
+            assert!(!response.success);
+        }
+
+        let signature = wallet.sign_message(&challenge.message).await.unwrap();
+        let signature = format!("0x{}", hex::encode(signature.to_vec()));
+        assert!(auth_service.authenticate(attempt(signature.clone())).await.unwrap().success);
+
+        // The verified attempt consumed it
+        let replay = auth_service.authenticate(attempt(signature)).await.unwrap();
+        assert_eq!(replay.error_code.as_deref(), Some("no_challenge"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_challenge_cannot_authenticate() {
+        let auth_service = WalletAuthService::in_memory(Arc::new(Config::default()));
+        let address = "0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1";
+        let challenge = auth_service.create_challenge(address).await.unwrap();
+
+        assert!(auth_service.cancel_challenge(&address.to_lowercase()).await.unwrap());
+        assert!(!auth_service.cancel_challenge(address).await.unwrap());
+        assert!(auth_service.challenge_message(address).await.unwrap().is_none());
+        assert_eq!(auth_service.get_stats().await.unwrap().active_challenges, 0);
+
+        let response = auth_service
+            .authenticate(AuthRequest {
+                address: address.to_string(),
+                message: challenge.message,
+                signature: "0x".to_string() + &"a".repeat(130),
+                scheme: None,
+                purpose: None,
+            })
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("No challenge found for this address"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cleanup_task_runs_on_configured_interval() {
+        use crate::utils::clock::MockClock;
+
+        let defaults = Config::default();
+        assert_eq!(defaults.auth.cleanup_interval(), std::time::Duration::from_secs(300));
+        assert_eq!(defaults.ipfs.cache_cleanup_interval(), std::time::Duration::from_secs(300));
+
+        let mut config = Config::default();
+        config.auth.cleanup_interval = Some(30);
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config.clone())).with_clock(Arc::new(clock.clone()));
+        assert!(auth_service.start_cleanup_task().is_some());
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        auth_service.create_challenge("0x742d35Cc6634C0532925a3b8D5c1b9E9C4F5e5A1").await.unwrap();
+        clock.advance(Duration::seconds(config.auth.signature_ttl as i64 + 1) + config.auth.clock_skew_tolerance());
+
+        // Expired, but only swept on the next tick
+        tokio::time::sleep(std::time::Duration::from_secs(28)).await;
+        assert_eq!(auth_service.get_stats().await.unwrap().active_challenges, 1);
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        assert_eq!(auth_service.get_stats().await.unwrap().active_challenges, 0);
+        // Redis expires keys itself, so there is nothing to sweep
+        let redis = crate::auth::token_store::RedisTokenStore::new("redis://127.0.0.1:6379", 1).unwrap();
+        let auth_service = WalletAuthService::new(Arc::new(config), Box::new(redis));
+        assert!(auth_service.start_cleanup_task().is_none());
+    }
+
     #[tokio::test]
     async fn test_stats() {
         let config = Arc::new(Config::default());
-        let auth_service = WalletAuthService::new(config);
+        let auth_service = WalletAuthService::in_memory(config);
         
-        let stats = auth_service.get_stats().await;
+        let stats = auth_service.get_stats().await.unwrap();
         assert_eq!(stats.active_challenges, 0);
         assert_eq!(stats.active_tokens, 0);
         assert_eq!(stats.total_addresses, 0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_failed_authentications_are_counted_by_reason() {
+        use crate::utils::clock::MockClock;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let config = Arc::new(Config::default());
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(config.clone()).with_clock(Arc::new(clock.clone()));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+        let source_ip = Some("203.0.113.7".parse().unwrap());
+
+        let attempt = |address: &str, message: &str, signature: String| AuthRequest {
+            address: address.to_string(),
+            message: message.to_string(),
+            signature,
+            scheme: None,
+            purpose: None,
+        };
+        let junk_signature = || "0x".to_string() + &"a".repeat(130);
+
+        let assert_rejected = |response: AuthResponse, reason: AuthFailureReason| {
+            assert!(!response.success);
+            assert_eq!(response.error_code.as_deref(), Some(reason.code()));
+            assert_eq!(auth_service.failure_metrics().count(reason), 1, "{}", reason.code());
+        };
+
+        let attempts = [
+            (attempt("not-an-address", "msg", junk_signature()), AuthFailureReason::InvalidAddress),
+            (attempt(&address, "msg", junk_signature()), AuthFailureReason::NoChallenge),
+        ];
+        for (request, reason) in attempts {
+            assert_rejected(auth_service.authenticate_from(request, source_ip, None).await.unwrap(), reason);
+        }
+
+        // A failed attempt leaves the challenge in place
+        let other_wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        for reason in [
+            AuthFailureReason::MessageMismatch,
+            AuthFailureReason::InvalidSignature,
+            AuthFailureReason::MalformedSignature,
+        ] {
+            let challenge = auth_service.create_challenge(&address).await.unwrap();
+            let request = match reason {
+                AuthFailureReason::MessageMismatch => attempt(&address, "other message", junk_signature()),
+                AuthFailureReason::InvalidSignature => {
+                    let foreign = other_wallet.sign_message(&challenge.message).await.unwrap();
+                    attempt(&address, &challenge.message, format!("0x{}", hex::encode(foreign.to_vec())))
+                }
+                _ => attempt(&address, &challenge.message, "0x1234".to_string()),
+            };
+            assert_rejected(auth_service.authenticate_from(request, source_ip, None).await.unwrap(), reason);
+            let outstanding = auth_service.challenge_message(&address).await.unwrap().unwrap();
+            assert_eq!(outstanding.message, challenge.message);
+        }
+
+        let challenge = auth_service.create_challenge(&address).await.unwrap();
+        clock.advance(Duration::seconds(config.auth.signature_ttl as i64 + 1) + config.auth.clock_skew_tolerance());
+        let response = auth_service
+            .authenticate_from(attempt(&address, &challenge.message, junk_signature()), source_ip, None)
+            .await
+            .unwrap();
+        assert_eq!(response.error_code.as_deref(), Some("challenge_expired"));
+        assert_eq!(auth_service.failure_metrics().count(AuthFailureReason::ChallengeExpired), 1);
+    }
+
+    #[tokio::test]
+    async fn test_challenge_is_bound_to_requesting_domain() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mut config = Config::default();
+        config.server.allowed_origins = vec![
+            "https://governance.somnia.network".to_string(),
+            "https://evil.example".to_string(),
+        ];
+        let auth_service = WalletAuthService::in_memory(Arc::new(config));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        let challenge = auth_service
+            .create_challenge_for(&address, Some("https://governance.somnia.network"))
+            .await
+            .unwrap();
+        assert!(challenge.message.starts_with("governance.somnia.network wants you to sign in"));
+
+        let signature = wallet.sign_message(&challenge.message).await.unwrap();
+        let request = AuthRequest {
+            address: address.clone(),
+            message: challenge.message.clone(),
+            signature: format!("0x{}", hex::encode(signature.to_vec())),
+            scheme: None,
+            purpose: None,
+        };
+
+        // Replayed from another site, which also spends the challenge
+        let response = auth_service
+            .authenticate_from(request.clone(), None, Some("https://evil.example"))
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error_code.as_deref(), Some("domain_mismatch"));
+        let response = auth_service
+            .authenticate_from(request, None, Some("https://governance.somnia.network"))
+            .await
+            .unwrap();
+        assert_eq!(response.error_code.as_deref(), Some("no_challenge"));
+
+        let challenge = auth_service
+            .create_challenge_for(&address, Some("https://governance.somnia.network"))
+            .await
+            .unwrap();
+        let signature = wallet.sign_message(&challenge.message).await.unwrap();
+        let response = auth_service
+            .authenticate_from(
+                AuthRequest {
+                    address: address.clone(),
+                    message: challenge.message,
+                    signature: format!("0x{}", hex::encode(signature.to_vec())),
+                    scheme: None,
+                    purpose: None,
+                },
+                None,
+                Some("https://governance.somnia.network"),
+            )
+            .await
+            .unwrap();
+        assert!(response.success);
+
+        // Origins outside the allowlist can't obtain a challenge at all
+        assert!(auth_service
+            .create_challenge_for(&address, Some("https://phish.example"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_challenge_is_bound_to_requested_purpose() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mut config = Config::default();
+        config.auth.message_templates.insert(
+            "delegation".to_string(),
+            "Sign to delegate your Somnia voting power ({purpose}): {nonce}".to_string(),
+        );
+        let auth_service = WalletAuthService::in_memory(Arc::new(config));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        let signed = |purpose: Option<&str>| {
+            let auth_service = auth_service.clone();
+            let wallet = wallet.clone();
+            let address = address.clone();
+            let purpose = purpose.map(str::to_string);
+            async move {
+                let challenge = auth_service
+                    .create_challenge_with_purpose(&address, None, purpose.as_deref())
+                    .await
+                    .unwrap();
+                let signature = wallet.sign_message(&challenge.message).await.unwrap();
+                AuthRequest {
+                    address,
+                    message: challenge.message,
+                    signature: format!("0x{}", hex::encode(signature.to_vec())),
+                    scheme: None,
+                    purpose,
+                }
+            }
+        };
+
+        // A delegation signature presented as a sign-in
+        let request = signed(Some("delegation")).await;
+        assert!(request.message.starts_with("Sign to delegate your Somnia voting power (delegation)"));
+        let response = auth_service
+            .authenticate(AuthRequest {
+                purpose: Some("login".to_string()),
+                ..request.clone()
+            })
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error_code.as_deref(), Some("purpose_mismatch"));
+        let response = auth_service.authenticate(signed(Some("delegation")).await).await.unwrap();
+        let token = auth_service.verify_token(&response.token.unwrap()).await.unwrap().unwrap();
+        assert!(!token.is_session());
+
+        // A sign-in signature presented for a delegation
+        let request = signed(None).await;
+        assert!(request.message.ends_with("Purpose: login"));
+        let response = auth_service
+            .authenticate(AuthRequest {
+                purpose: Some("delegation".to_string()),
+                ..request
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.error_code.as_deref(), Some("purpose_mismatch"));
+        assert!(auth_service.authenticate(signed(None).await).await.unwrap().success);
+
+        assert!(auth_service
+            .create_challenge_with_purpose(&address, None, Some("withdrawal"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sliding_session_renews_until_max_lifetime() {
+        use crate::utils::clock::MockClock;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mut config = Config::default();
+        config.auth.sliding_sessions = true;
+        config.auth.session_ttl = 3600;
+        config.auth.session_renewal_window = 1800;
+        config.auth.session_max_lifetime = 7200;
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config)).with_clock(Arc::new(clock.clone()));
+
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+        let challenge = auth_service.create_challenge(&address).await.unwrap();
+        let signature = wallet.sign_message(&challenge.message).await.unwrap();
+        let token = auth_service
+            .authenticate(AuthRequest {
+                address,
+                message: challenge.message,
+                signature: format!("0x{}", hex::encode(signature.to_vec())),
+                scheme: None,
+                purpose: None,
+            })
+            .await
+            .unwrap()
+            .token
+            .unwrap();
+        let issued_at = clock.now();
+        let expiry = |t: AuthToken| t.expires_at - issued_at;
+
+        // Outside the renewal window: unchanged
+        clock.advance(Duration::seconds(100));
+        let verified = auth_service.verify_token(&token).await.unwrap().unwrap();
+        assert_eq!(expiry(verified), Duration::seconds(3600));
+
+        // Used within the window: extended by the TTL from now
+        clock.advance(Duration::seconds(1900));
+        let verified = auth_service.verify_token(&token).await.unwrap().unwrap();
+        assert_eq!(expiry(verified), Duration::seconds(5600));
+
+        // Renewal is capped at the absolute max lifetime
+        clock.advance(Duration::seconds(2000));
+        let verified = auth_service.verify_token(&token).await.unwrap().unwrap();
+        assert_eq!(expiry(verified), Duration::seconds(7200));
+
+        clock.advance(Duration::seconds(3300));
+        assert!(auth_service.verify_token(&token).await.unwrap().is_none());
+    }
+
+    async fn sign_in_with_wallet(auth_service: &WalletAuthService) -> Address {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+        let challenge = auth_service.create_challenge(&address).await.unwrap();
+        let signature = wallet.sign_message(&challenge.message).await.unwrap();
+        let response = auth_service
+            .authenticate_from(
+                AuthRequest {
+                    address,
+                    message: challenge.message,
+                    signature: format!("0x{}", hex::encode(signature.to_vec())),
+                    scheme: None,
+                    purpose: None,
+                },
+                Some("203.0.113.7".parse().unwrap()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(response.success);
+        wallet.address()
+    }
+
+    #[tokio::test]
+    async fn test_signed_message_recorded_when_enabled() {
+        let mut config = Config::default();
+        config.auth.record_signed_messages = true;
+        let auth_service = WalletAuthService::in_memory(Arc::new(config));
+
+        let address = sign_in_with_wallet(&auth_service).await;
+        sign_in_with_wallet(&auth_service).await;
+
+        let records = auth_service.signed_messages(Some(&format!("{:?}", address))).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].address, ethers::utils::to_checksum(&address, None));
+        assert!(records[0].message.starts_with("Sign this message to authenticate"));
+        assert_eq!(records[0].source_ip, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(auth_service.signed_messages(None).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_signed_message_not_recorded_by_default() {
+        let auth_service = WalletAuthService::in_memory(Arc::new(Config::default()));
+        sign_in_with_wallet(&auth_service).await;
+        assert!(auth_service.signed_messages(None).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_selects_signature_scheme() {
+        /// Accepts a signature equal to the message reversed, from any address
+        struct ReversedScheme(Address);
+
+        impl SignatureScheme for ReversedScheme {
+            fn name(&self) -> &str {
+                "reversed"
+            }
+
+            fn recover(&self, message: &str, signature: &str) -> Result<Address> {
+                if signature.chars().eq(message.chars().rev()) {
+                    Ok(self.0)
+                } else {
+                    Err(GovernanceError::invalid_signature("Not reversed"))
+                }
+            }
+        }
+
+        let address = Address::random();
+        let auth_service = WalletAuthService::in_memory(Arc::new(Config::default()))
+            .with_signature_scheme(Arc::new(ReversedScheme(address)));
+        let request = |scheme: Option<&str>| {
+            let auth_service = auth_service.clone();
+            let scheme = scheme.map(str::to_string);
+            async move {
+                let challenge = auth_service.create_challenge(&format!("{:?}", address)).await.unwrap();
+                AuthRequest {
+                    address: format!("{:?}", address),
+                    signature: challenge.message.chars().rev().collect(),
+                    message: challenge.message,
+                    scheme,
+                    purpose: None,
+                }
+            }
+        };
+
+        // The Ethereum default can't make sense of it
+        assert!(!auth_service.authenticate(request(None).await).await.unwrap().success);
+        assert!(auth_service.authenticate(request(Some("unknown")).await).await.is_err());
+        assert!(auth_service.authenticate(request(Some("reversed")).await).await.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_challenges_over_capacity_evict_oldest() {
+        use crate::utils::clock::MockClock;
+
+        let mut config = Config::default();
+        config.auth.max_challenges = Some(3);
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config)).with_clock(Arc::new(clock.clone()));
+
+        let addresses: Vec<String> = (0..5).map(|_| format!("{:?}", Address::random())).collect();
+        for address in &addresses {
+            auth_service.create_challenge(address).await.unwrap();
+            clock.advance(Duration::seconds(1));
+        }
+
+        assert_eq!(auth_service.get_stats().await.unwrap().active_challenges, 3);
+        assert_eq!(auth_service.store_metrics().evicted(AuthStore::Challenges), 2);
+        for (i, address) in addresses.iter().enumerate() {
+            let outstanding = auth_service.challenge_message(address).await.unwrap().is_some();
+            assert_eq!(outstanding, i >= 2, "challenge {}", i);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sessions_over_capacity_evict_oldest() {
+        use crate::utils::clock::MockClock;
+
+        let mut config = Config::default();
+        config.auth.max_sessions = Some(2);
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config)).with_clock(Arc::new(clock.clone()));
+
+        let mut addresses = Vec::new();
+        for _ in 0..3 {
+            addresses.push(sign_in_with_wallet(&auth_service).await);
+            clock.advance(Duration::seconds(1));
+        }
+
+        assert_eq!(auth_service.get_stats().await.unwrap().active_tokens, 2);
+        assert!(auth_service.get_tokens_for_address(&addresses[0]).await.unwrap().is_empty());
+        assert_eq!(auth_service.get_tokens_for_address(&addresses[2]).await.unwrap().len(), 1);
+        assert_eq!(auth_service.store_metrics().evicted(AuthStore::Sessions), 1);
+        assert_eq!(auth_service.store_metrics().expired(AuthStore::Sessions), 0);
+    }
+
+    #[tokio::test]
+    async fn test_siwe_message_signs_in_on_configured_chain() {
+        use crate::utils::clock::MockClock;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let config = Config::default();
+        let chain_id = config.blockchain.chain_id;
+        let clock = MockClock::default();
+        let auth_service = WalletAuthService::in_memory(Arc::new(config)).with_clock(Arc::new(clock.clone()));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        // What a standard wallet UI would build around the challenge nonce
+        let siwe = |signer: Address, chain_id: u64, nonce: &str, valid_for: i64| {
+            let issued_at = clock.now();
+            format!(
+                "app.example.org wants you to sign in with your Ethereum account:\n{}\n\n\
+                 Sign in to Somnia governance.\n\n\
+                 URI: https://app.example.org\nVersion: 1\nChain ID: {}\nNonce: {}\nIssued At: {}\nExpiration Time: {}",
+                ethers::utils::to_checksum(&signer, None),
+                chain_id,
+                nonce,
+                issued_at.to_rfc3339(),
+                (issued_at + Duration::seconds(valid_for)).to_rfc3339(),
+            )
+        };
+        let sign_in = |message: String| {
+            let auth_service = auth_service.clone();
+            let wallet = wallet.clone();
+            let address = address.clone();
+            async move {
+                let signature = wallet.sign_message(&message).await.unwrap();
+                auth_service
+                    .authenticate(AuthRequest {
+                        address,
+                        message,
+                        signature: format!("0x{}", hex::encode(signature.to_vec())),
+                        scheme: None,
+                        purpose: None,
+                    })
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Each attempt gets a fresh nonce
+        let nonce = || async { auth_service.create_challenge(&address).await.unwrap().challenge };
+        let response = sign_in(siwe(wallet.address(), 1, &nonce().await, 600)).await;
+        assert_eq!(response.error_code.as_deref(), Some("chain_mismatch"));
+        let response = sign_in(siwe(Address::random(), chain_id, &nonce().await, 600)).await;
+        assert_eq!(response.error_code.as_deref(), Some("address_mismatch"));
+        let response = sign_in(siwe(wallet.address(), chain_id, &nonce().await, 0)).await;
+        assert_eq!(response.error_code.as_deref(), Some("message_expired"));
+        nonce().await;
+        let response = sign_in(siwe(wallet.address(), chain_id, "0000000000000000", 600)).await;
+        assert_eq!(response.error_code.as_deref(), Some("message_mismatch"));
+
+        let response = sign_in(siwe(wallet.address(), chain_id, &nonce().await, 600)).await;
+        assert!(response.success, "{:?}", response.error);
+        assert_eq!(response.address, Some(wallet.address()));
+    }
+
+    #[tokio::test]
+    async fn test_siwe_message_must_state_non_login_purpose() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mut config = Config::default();
+        config.auth.message_templates.insert("delegation".to_string(), "Delegate: {nonce}".to_string());
+        let chain_id = config.blockchain.chain_id;
+        let auth_service = WalletAuthService::in_memory(Arc::new(config));
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let address = format!("{:?}", wallet.address());
+
+        let attempt = |resources: &'static str| {
+            let auth_service = auth_service.clone();
+            let wallet = wallet.clone();
+            let address = address.clone();
+            async move {
+                let challenge = auth_service
+                    .create_challenge_with_purpose(&address, None, Some("delegation"))
+                    .await
+                    .unwrap();
+                let message = format!(
+                    "app.example.org wants you to sign in with your Ethereum account:\n{}\n\n\
+                     Sign in to Somnia governance.\n\n\
+                     URI: https://app.example.org\nVersion: 1\nChain ID: {}\nNonce: {}\nIssued At: {}{}",
+                    ethers::utils::to_checksum(&wallet.address(), None),
+                    chain_id,
+                    challenge.challenge,
+                    Utc::now().to_rfc3339(),
+                    resources,
+                );
+                let signature = wallet.sign_message(&message).await.unwrap();
+                auth_service
+                    .authenticate(AuthRequest {
+                        address,
+                        message,
+                        signature: format!("0x{}", hex::encode(signature.to_vec())),
+                        scheme: None,
+                        purpose: Some("delegation".to_string()),
+                    })
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // A plain sign-in message carrying the nonce confirms no delegation
+        let response = attempt("").await;
+        assert_eq!(response.error_code.as_deref(), Some("purpose_mismatch"));
+
+        let response = attempt("\nResources:\n- delegation").await;
+        assert!(response.success, "{:?}", response.error);
+        let token = auth_service.verify_token(&response.token.unwrap()).await.unwrap().unwrap();
+        assert_eq!(token.purpose, "delegation");
+        assert!(!token.is_session());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::blockchain::contracts::*;
+use crate::blockchain::node_info::{ContractCodeSource, NodeInfoSource, RpcNodeInfo};
+use crate::blockchain::simulation::{CallSimulation, CallSimulator};
 use crate::config::Config;
 use crate::utils::errors::{GovernanceError, Result};
+use crate::utils::retry::{retry_with_decision, RetryPolicy};
 use ethers::prelude::*;
 use ethers::types::transaction::eip2718::TypedTransaction;
 use std::sync::Arc;
@@ -8,8 +11,13 @@ use tokio::sync::RwLock;
 
 #[derive(Clone)]
 pub struct SomniaClient {
-    provider: Arc<Provider<Ws>>,
+    provider: Option<Arc<Provider<Ws>>>,
+    simulator: Option<Arc<dyn CallSimulator>>,
+    node_info: Option<Arc<dyn NodeInfoSource>>,
+    code: Option<Arc<dyn ContractCodeSource>>,
     chain_id: u64,
+    rpc_batch_size: usize,
+    rate_limit_retry: RetryPolicy,
     governance_hub: Arc<dyn GovernanceHubContract + Send + Sync>,
     simple_voting: Arc<dyn SimpleVotingContract + Send + Sync>,
     contract_addresses: ContractAddresses,
@@ -40,7 +48,7 @@ pub enum EventType {
 pub enum ContractEvent {
     ProposalCreated(ProposalCreatedEvent),
     VoteCast(VoteCastEvent),
-    ProposalExecuted { proposal_id: u64, executor: Address },
+    ProposalExecuted { contract: Address, proposal_id: u64, executor: Address },
 }
 
 impl SomniaClient {
@@ -48,14 +56,7 @@ impl SomniaClient {
         // For now, we'll use mock implementations
         // In production, this would connect to actual Somnia network
         
-        let contract_addresses = ContractAddresses {
-            governance_hub: config.blockchain.contracts.governance_hub
-                .as_ref()
-                .and_then(|addr| addr.parse().ok()),
-            simple_voting: config.blockchain.contracts.simple_voting
-                .as_ref()
-                .and_then(|addr| addr.parse().ok()),
-        };
+        let contract_addresses = Self::contract_addresses_from_config(config);
 
         // Create mock provider for now
         let provider = Self::create_mock_provider(&config.blockchain.rpc_url).await?;
@@ -66,8 +67,16 @@ impl SomniaClient {
         let simple_voting = factory.create_mock_simple_voting();
 
         Ok(Self {
-            provider,
+            simulator: Some(provider.clone()),
+            node_info: Some(provider.clone()),
+            code: Some(provider.clone()),
+            provider: Some(provider),
             chain_id: config.blockchain.chain_id,
+            rpc_batch_size: config.blockchain.rpc_batch_size.max(1),
+            rate_limit_retry: RetryPolicy {
+                max_delay: config.blockchain.rate_limit_max_delay(),
+                ..RetryPolicy::default()
+            },
             governance_hub,
             simple_voting,
             contract_addresses,
@@ -75,6 +84,92 @@ impl SomniaClient {
         })
     }
 
+    /// Build a client around the given contract implementations without an RPC provider.
+    /// Provider-backed calls (block number, receipts, gas) return an error.
+    pub fn with_contracts(
+        config: &Config,
+        governance_hub: Arc<dyn GovernanceHubContract + Send + Sync>,
+        simple_voting: Arc<dyn SimpleVotingContract + Send + Sync>,
+    ) -> Self {
+        Self {
+            provider: None,
+            simulator: None,
+            node_info: None,
+            code: None,
+            chain_id: config.blockchain.chain_id,
+            rpc_batch_size: config.blockchain.rpc_batch_size.max(1),
+            rate_limit_retry: RetryPolicy {
+                max_delay: config.blockchain.rate_limit_max_delay(),
+                ..RetryPolicy::default()
+            },
+            governance_hub,
+            simple_voting,
+            contract_addresses: Self::contract_addresses_from_config(config),
+            event_subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Client backed by the in-memory mock contracts
+    pub fn mock(config: &Config) -> Self {
+        let factory = crate::blockchain::contracts::ContractFactory::new();
+        Self::with_contracts(
+            config,
+            factory.create_mock_governance_hub(),
+            factory.create_mock_simple_voting(),
+        )
+    }
+
+    /// Run simulated calls through `simulator` instead of the RPC provider
+    pub fn with_simulator(mut self, simulator: Arc<dyn CallSimulator>) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    /// Read node versions from `source` instead of the RPC provider
+    pub fn with_node_info(mut self, source: Arc<dyn NodeInfoSource>) -> Self {
+        self.node_info = Some(source);
+        self
+    }
+
+    /// Read contract code from `source` instead of the RPC provider
+    pub fn with_code_source(mut self, source: Arc<dyn ContractCodeSource>) -> Self {
+        self.code = Some(source);
+        self
+    }
+
+    /// How provider reads turned away by the provider's rate limit are retried
+    pub fn with_rate_limit_retry(mut self, policy: RetryPolicy) -> Self {
+        self.rate_limit_retry = policy;
+        self
+    }
+
+    fn contract_addresses_from_config(config: &Config) -> ContractAddresses {
+        ContractAddresses {
+            governance_hub: config.blockchain.contracts.governance_hub
+                .as_ref()
+                .and_then(|addr| addr.parse().ok()),
+            simple_voting: config.blockchain.contracts.simple_voting
+                .as_ref()
+                .and_then(|addr| addr.parse().ok()),
+        }
+    }
+
+    /// Run a provider read, backing off and retrying while the provider
+    /// rate-limits it. Other failures are returned as they are.
+    async fn rate_limited<T, F, Fut>(&self, read: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        retry_with_decision(&self.rate_limit_retry, read, GovernanceError::rate_limit_retry).await
+    }
+
+    fn provider(&self) -> Result<&Arc<Provider<Ws>>> {
+        self.provider
+            .as_ref()
+            .ok_or_else(|| GovernanceError::Internal(anyhow::anyhow!("No RPC provider connected")))
+    }
+
     async fn create_mock_provider(_rpc_url: &str) -> Result<Arc<Provider<Ws>>> {
         // For development, we'll create a mock provider
         // In production, this would connect to actual Somnia WebSocket endpoint
@@ -109,24 +204,73 @@ impl SomniaClient {
             .await
     }
 
+    pub async fn estimate_create_proposal_gas(
+        &self,
+        ipfs_hash: &str,
+        voting_duration: u64,
+        proposal_type: u8,
+    ) -> Result<U256> {
+        self.governance_hub
+            .estimate_create_proposal_gas(ipfs_hash, U256::from(voting_duration), proposal_type)
+            .await
+    }
+
     pub async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData> {
         self.governance_hub.get_proposal(proposal_id).await
     }
 
+    /// Fetch several proposals, in batches of `rpc_batch_size` when the
+    /// contract binding supports it and one call per proposal otherwise.
+    /// Results are returned in the order of `proposal_ids`. Only the mock
+    /// binding batches so far; there is no JSON-RPC batch transport yet.
+    pub async fn get_proposals(&self, proposal_ids: &[u64]) -> Result<Vec<ProposalData>> {
+        if !self.governance_hub.supports_batching() {
+            let mut proposals = Vec::with_capacity(proposal_ids.len());
+            for &proposal_id in proposal_ids {
+                proposals.push(self.governance_hub.get_proposal(proposal_id).await?);
+            }
+            return Ok(proposals);
+        }
+
+        let mut proposals = Vec::with_capacity(proposal_ids.len());
+        for chunk in proposal_ids.chunks(self.rpc_batch_size) {
+            proposals.extend(self.governance_hub.get_proposals_batch(chunk).await?);
+        }
+        Ok(proposals)
+    }
+
+    pub async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt> {
+        self.governance_hub.execute_proposal(proposal_id).await
+    }
+
     pub async fn get_proposal_count(&self) -> Result<u64> {
         self.governance_hub.get_proposal_count().await
     }
 
     pub async fn get_active_proposals(&self) -> Result<Vec<ProposalData>> {
-        self.governance_hub
+        let mut proposals = self
+            .governance_hub
             .get_proposals_by_status(ProposalStatus::Active)
-            .await
+            .await?;
+        // Don't rely on every binding honouring the ordering contract
+        proposals.sort_by_key(|p| p.id);
+        Ok(proposals)
     }
 
     pub async fn get_user_voting_power(&self, user: Address) -> Result<U256> {
         self.governance_hub.get_user_voting_power(user).await
     }
 
+    /// Historical voting power from the token's checkpoints. Zero before the
+    /// address held any power; an error for blocks past the chain head.
+    pub async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256> {
+        self.governance_hub.get_voting_power_at(user, block).await
+    }
+
+    pub async fn get_total_voting_power(&self) -> Result<U256> {
+        self.governance_hub.get_total_voting_power().await
+    }
+
     // Simple Voting methods
     pub async fn cast_vote(
         &self,
@@ -155,27 +299,74 @@ impl SomniaClient {
         self.simple_voting.get_vote_tally(proposal_id).await
     }
 
+    pub async fn estimate_cast_vote_gas(&self, proposal_id: u64, voter: Address, choice: u8) -> Result<U256> {
+        self.simple_voting.estimate_cast_vote_gas(proposal_id, voter, choice).await
+    }
+
+    pub async fn submit_vote_aggregate(&self, proposal_id: u64, tally: &AggregateTally) -> Result<TransactionReceipt> {
+        self.simple_voting.submit_vote_aggregate(proposal_id, tally).await
+    }
+
     // Provider methods
     pub async fn get_block_number(&self) -> Result<u64> {
-        self.provider
-            .get_block_number()
-            .await
-            .map(|n| n.as_u64())
-            .map_err(GovernanceError::Blockchain)
+        let provider = self.provider()?;
+        self.rate_limited(|| async {
+            provider
+                .get_block_number()
+                .await
+                .map(|n| n.as_u64())
+                .map_err(GovernanceError::from)
+        })
+        .await
     }
 
     pub async fn get_transaction_receipt(&self, tx_hash: H256) -> Result<Option<TransactionReceipt>> {
-        self.provider
-            .get_transaction_receipt(tx_hash)
-            .await
-            .map_err(GovernanceError::Blockchain)
+        let provider = self.provider()?;
+        self.rate_limited(|| async {
+            provider
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(GovernanceError::from)
+        })
+        .await
     }
 
     pub async fn estimate_gas(&self, tx: &TypedTransaction) -> Result<U256> {
-        self.provider
-            .estimate_gas(tx, None)
-            .await
-            .map_err(GovernanceError::Blockchain)
+        let provider = self.provider()?;
+        self.rate_limited(|| async {
+            provider
+                .estimate_gas(tx, None)
+                .await
+                .map_err(GovernanceError::from)
+        })
+        .await
+    }
+
+    /// Run `tx` with `eth_call` against current state without sending it
+    pub async fn simulate_call(&self, tx: &TypedTransaction) -> Result<CallSimulation> {
+        let simulator = self
+            .simulator
+            .as_ref()
+            .ok_or_else(|| GovernanceError::Internal(anyhow::anyhow!("No RPC provider connected")))?;
+        self.rate_limited(|| simulator.simulate(tx)).await
+    }
+
+    /// Client and network versions reported by the RPC node
+    pub async fn node_info(&self) -> Result<RpcNodeInfo> {
+        let source = self
+            .node_info
+            .as_ref()
+            .ok_or_else(|| GovernanceError::Internal(anyhow::anyhow!("No RPC provider connected")))?;
+        self.rate_limited(|| source.node_info()).await
+    }
+
+    /// Bytecode deployed at `address`, empty if there is none
+    pub async fn contract_code(&self, address: Address) -> Result<Bytes> {
+        let source = self
+            .code
+            .as_ref()
+            .ok_or_else(|| GovernanceError::Internal(anyhow::anyhow!("No RPC provider connected")))?;
+        self.rate_limited(|| source.code_at(address)).await
     }
 
     pub fn chain_id(&self) -> u64 {
@@ -262,6 +453,141 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_mock_client_without_provider() {
+        let config = Config::default();
+        let client = SomniaClient::mock(&config);
+
+        client.create_proposal("QmTest123".to_string(), 86400, 0).await.unwrap();
+        assert_eq!(client.get_proposal_count().await.unwrap(), 1);
+        assert!(client.get_block_number().await.is_err());
+    }
+
+    /// Hub that counts round-trips and can disable batching
+    struct CountingHub {
+        inner: MockGovernanceHub,
+        batching: bool,
+        single_calls: std::sync::atomic::AtomicUsize,
+        batch_calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CountingHub {
+        fn new(batching: bool) -> Self {
+            Self {
+                inner: MockGovernanceHub::new(),
+                batching,
+                single_calls: Default::default(),
+                batch_calls: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl GovernanceHubContract for CountingHub {
+        async fn create_proposal(&self, ipfs_hash: String, voting_duration: U256, proposal_type: u8) -> Result<TransactionReceipt> {
+            self.inner.create_proposal(ipfs_hash, voting_duration, proposal_type).await
+        }
+
+        async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData> {
+            self.single_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.get_proposal(proposal_id).await
+        }
+
+        fn supports_batching(&self) -> bool {
+            self.batching
+        }
+
+        async fn get_proposals_batch(&self, proposal_ids: &[u64]) -> Result<Vec<ProposalData>> {
+            self.batch_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.get_proposals_batch(proposal_ids).await
+        }
+
+        async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt> {
+            self.inner.execute_proposal(proposal_id).await
+        }
+
+        async fn get_proposal_count(&self) -> Result<u64> {
+            self.inner.get_proposal_count().await
+        }
+
+        async fn get_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<ProposalData>> {
+            self.inner.get_proposals_by_status(status).await
+        }
+
+        async fn get_user_voting_power(&self, user: Address) -> Result<U256> {
+            self.inner.get_user_voting_power(user).await
+        }
+
+        async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256> {
+            self.inner.get_voting_power_at(user, block).await
+        }
+    }
+
+    async fn client_with_proposals(hub: Arc<CountingHub>, count: u64) -> SomniaClient {
+        let config = Config::default();
+        let client = SomniaClient::with_contracts(&config, hub, Arc::new(MockSimpleVoting::new()));
+        for _ in 0..count {
+            client.create_proposal("QmTest123".to_string(), 86400, 0).await.unwrap();
+        }
+        client
+    }
+
+    #[tokio::test]
+    async fn test_proposal_list_uses_single_batch() {
+        let hub = Arc::new(CountingHub::new(true));
+        let client = client_with_proposals(hub.clone(), 20).await;
+        let ids: Vec<u64> = (1..=20).rev().collect();
+
+        let proposals = client.get_proposals(&ids).await.unwrap();
+        assert_eq!(proposals.iter().map(|p| p.id).collect::<Vec<_>>(), ids);
+        assert_eq!(hub.batch_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(hub.single_calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_list_splits_by_batch_size() {
+        let mut config = Config::default();
+        config.blockchain.rpc_batch_size = 8;
+        let hub = Arc::new(CountingHub::new(true));
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        for _ in 0..20 {
+            client.create_proposal("QmTest123".to_string(), 86400, 0).await.unwrap();
+        }
+
+        let ids: Vec<u64> = (1..=20).collect();
+        assert_eq!(client.get_proposals(&ids).await.unwrap().len(), 20);
+        assert_eq!(hub.batch_calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_list_falls_back_to_sequential() {
+        let hub = Arc::new(CountingHub::new(false));
+        let client = client_with_proposals(hub.clone(), 20).await;
+        let ids: Vec<u64> = (1..=20).collect();
+
+        let proposals = client.get_proposals(&ids).await.unwrap();
+        assert_eq!(proposals.len(), 20);
+        assert_eq!(hub.batch_calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(hub.single_calls.load(std::sync::atomic::Ordering::SeqCst), 20);
+        assert!(client.get_proposals(&[21]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_voting_power_at_old_block_differs_from_current() {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let user = Address::random();
+
+        hub.set_voting_power(user, U256::from(100));
+        let old_block = hub.mine_blocks(5);
+        hub.set_voting_power(user, U256::from(900));
+
+        assert_eq!(client.get_voting_power_at(user, old_block).await.unwrap(), U256::from(100));
+        assert_eq!(client.get_user_voting_power(user).await.unwrap(), U256::from(900));
+        assert_eq!(client.get_voting_power_at(user, old_block - 10).await.unwrap(), U256::zero());
+    }
+
     #[tokio::test]
     async fn test_contract_interactions() {
         let config = Config::default();
@@ -283,6 +609,48 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_reads_back_off_and_retry() {
+        use ethers::providers::{JsonRpcError, MockResponse};
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        let config = Config::default();
+        let (provider, mock) = Provider::mocked();
+        let client = SomniaClient::mock(&config).with_code_source(Arc::new(provider));
+        let too_many_requests = |data| {
+            MockResponse::Error(JsonRpcError {
+                code: 429,
+                message: "Too Many Requests".to_string(),
+                data,
+            })
+        };
+
+        // Mock responses are served last-pushed first
+        let code = Bytes::from(vec![0x60, 0x80]);
+        mock.push(code.clone()).unwrap();
+        mock.push_response(too_many_requests(None));
+        mock.push_response(too_many_requests(Some(serde_json::json!({ "retry_after": 3 }))));
+
+        let started = Instant::now();
+        assert_eq!(client.contract_code(Address::random()).await.unwrap(), code);
+        // The provider's 3s, then the policy's first backoff of at most 200ms
+        assert!(started.elapsed() >= Duration::from_secs(3));
+        assert!(started.elapsed() <= Duration::from_millis(3200));
+
+        // Asked to wait longer than the policy allows, the error is surfaced
+        mock.push_response(too_many_requests(Some(serde_json::json!({ "retry_after": 60 }))));
+        let error = client.contract_code(Address::random()).await.unwrap_err();
+        assert!(matches!(
+            error,
+            GovernanceError::RpcRateLimited {
+                retry_after: Some(delay),
+                ..
+            } if delay == Duration::from_secs(60)
+        ));
+        assert_eq!(error.status_code(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    }
 }
 
 // Helper functions for address and transaction handling
@@ -19,15 +19,18 @@ pub struct ProposalData {
     pub total_votes: U256,
     pub yes_votes: U256,
     pub no_votes: U256,
+    #[serde(default)]
+    pub total_voting_power: U256, // Total supply at the snapshot block
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProposalStatus {
     Pending = 0,
     Active = 1,
     Passed = 2,
     Rejected = 3,
     Executed = 4,
+    Canceled = 5,
 }
 
 impl From<u8> for ProposalStatus {
@@ -38,6 +41,7 @@ impl From<u8> for ProposalStatus {
             2 => ProposalStatus::Passed,
             3 => ProposalStatus::Rejected,
             4 => ProposalStatus::Executed,
+            5 => ProposalStatus::Canceled,
             _ => ProposalStatus::Pending,
         }
     }
@@ -53,8 +57,19 @@ pub struct VoteData {
     pub ipfs_hash: Option<String>,
 }
 
+/// Tally of off-chain signed votes, posted on-chain in one transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateTally {
+    pub yes_votes: U256,
+    pub no_votes: U256,
+    pub abstain_votes: U256,
+    pub voter_count: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoteCastEvent {
+    /// Governance contract that emitted the event
+    pub contract: Address,
     pub proposal_id: u64,
     pub voter: Address,
     pub choice: u8,
@@ -65,6 +80,8 @@ pub struct VoteCastEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposalCreatedEvent {
+    /// Governance contract that emitted the event
+    pub contract: Address,
     pub proposal_id: u64,
     pub proposer: Address,
     pub ipfs_hash: String,
@@ -73,6 +90,84 @@ pub struct ProposalCreatedEvent {
     pub proposal_type: u8,
 }
 
+impl ProposalCreatedEvent {
+    /// Signature emitted by the deployed GovernanceHub
+    pub const SIGNATURE: &'static str = "ProposalCreated(uint256,address,string,uint256,uint256,uint256,uint8)";
+    /// Signature declared by IGovernanceHub, which omits the snapshot block
+    pub const INTERFACE_SIGNATURE: &'static str = "ProposalCreated(uint256,address,string,uint256,uint256,uint8)";
+
+    /// Id of the proposal a creation receipt's `ProposalCreated` log announces.
+    /// The id is the first indexed argument, so it is read from `topics[1]`.
+    pub fn proposal_id_from_receipt(receipt: &TransactionReceipt) -> Option<u64> {
+        let signatures = [Self::SIGNATURE, Self::INTERFACE_SIGNATURE].map(|s| H256::from(ethers::utils::keccak256(s)));
+        receipt
+            .logs
+            .iter()
+            .filter(|log| log.topics.first().is_some_and(|topic| signatures.contains(topic)))
+            .find_map(|log| log.topics.get(1))
+            .and_then(|id| {
+                let id = U256::from_big_endian(id.as_bytes());
+                (id <= U256::from(u64::MAX)).then(|| id.as_u64())
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionOutcome {
+    /// The execution transaction and its target call succeeded
+    Succeeded,
+    /// The execution transaction was mined but the target call reverted
+    Reverted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionEvent {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Outcome of executing a proposal, captured from the execution receipt.
+/// Receipts don't carry call return data, so emitted events are recorded instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionResult {
+    pub proposal_id: u64,
+    pub transaction_hash: H256,
+    pub block_number: Option<u64>,
+    pub outcome: ExecutionOutcome,
+    pub gas_used: Option<U256>,
+    pub events: Vec<ExecutionEvent>,
+    pub executed_at: u64,
+}
+
+impl ExecutionResult {
+    pub fn from_receipt(proposal_id: u64, receipt: &TransactionReceipt, executed_at: u64) -> Self {
+        let outcome = match receipt.status {
+            Some(status) if status == U64::from(1) => ExecutionOutcome::Succeeded,
+            _ => ExecutionOutcome::Reverted,
+        };
+
+        Self {
+            proposal_id,
+            transaction_hash: receipt.transaction_hash,
+            block_number: receipt.block_number.map(|n| n.as_u64()),
+            outcome,
+            gas_used: receipt.gas_used,
+            events: receipt
+                .logs
+                .iter()
+                .map(|log| ExecutionEvent {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone(),
+                })
+                .collect(),
+            executed_at,
+        }
+    }
+}
+
 // Trait definitions for contract interactions
 #[async_trait]
 pub trait GovernanceHubContract {
@@ -83,10 +178,41 @@ pub trait GovernanceHubContract {
         proposal_type: u8,
     ) -> Result<TransactionReceipt>;
 
+    /// Gas `create_proposal` would spend, without submitting anything
+    async fn estimate_create_proposal_gas(
+        &self,
+        _ipfs_hash: &str,
+        _voting_duration: U256,
+        _proposal_type: u8,
+    ) -> Result<U256> {
+        Err(GovernanceError::Internal(anyhow::anyhow!("Gas estimation not supported")))
+    }
+
     async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData>;
+    async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt>;
+
+    /// Whether `get_proposals_batch` can fetch several proposals in one
+    /// round-trip. No binding batches over HTTP yet; only the mock does.
+    fn supports_batching(&self) -> bool {
+        false
+    }
+
+    /// Fetch several proposals in one round-trip, in the order given
+    async fn get_proposals_batch(&self, _proposal_ids: &[u64]) -> Result<Vec<ProposalData>> {
+        Err(GovernanceError::Internal(anyhow::anyhow!("Batched proposal reads not supported")))
+    }
+
     async fn get_proposal_count(&self) -> Result<u64>;
+    /// Proposals with the given status, in ascending id order
     async fn get_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<ProposalData>>;
     async fn get_user_voting_power(&self, user: Address) -> Result<U256>;
+    /// Voting power at a past block (`getPastVotes`); errors for blocks beyond head
+    async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256>;
+
+    /// Total voting power a proposal created now would snapshot
+    async fn get_total_voting_power(&self) -> Result<U256> {
+        Err(GovernanceError::Internal(anyhow::anyhow!("Total voting power lookup not supported")))
+    }
 }
 
 #[async_trait]
@@ -102,21 +228,63 @@ pub trait SimpleVotingContract {
     async fn get_proposal_votes(&self, proposal_id: u64) -> Result<Vec<VoteData>>;
     async fn has_voted(&self, proposal_id: u64, voter: Address) -> Result<bool>;
     async fn get_vote_tally(&self, proposal_id: u64) -> Result<(U256, U256, U256)>; // (yes, no, abstain)
+
+    /// Gas `voter` would spend casting `choice`, without submitting anything
+    async fn estimate_cast_vote_gas(&self, _proposal_id: u64, _voter: Address, _choice: u8) -> Result<U256> {
+        Err(GovernanceError::Internal(anyhow::anyhow!("Gas estimation not supported")))
+    }
+
+    /// Record the aggregate result of off-chain signed votes
+    async fn submit_vote_aggregate(&self, _proposal_id: u64, _tally: &AggregateTally) -> Result<TransactionReceipt> {
+        Err(GovernanceError::Internal(anyhow::anyhow!("Signed vote aggregation not supported")))
+    }
 }
 
 // Mock implementations for testing (will be replaced with real contract calls)
 pub struct MockGovernanceHub {
     pub proposals: std::sync::Mutex<std::collections::HashMap<u64, ProposalData>>,
     pub next_id: std::sync::Mutex<u64>,
+    pub block_number: std::sync::Mutex<u64>,
+    /// When set, executions are mined with the target call reverted (status 0)
+    pub revert_executions: std::sync::atomic::AtomicBool,
+    /// Per-address (block, power) checkpoints in block order
+    pub power_checkpoints: std::sync::Mutex<std::collections::HashMap<Address, Vec<(u64, U256)>>>,
+    /// Total power snapshotted onto new proposals
+    pub total_supply: std::sync::Mutex<U256>,
 }
 
 impl MockGovernanceHub {
+    /// Voting power of addresses without recorded checkpoints
+    pub const DEFAULT_VOTING_POWER: u64 = 1000;
+    pub const DEFAULT_TOTAL_SUPPLY: u64 = 100_000;
+    /// Gas reported for every proposal creation estimate
+    pub const CREATE_PROPOSAL_GAS: u64 = 180_000;
+
     pub fn new() -> Self {
         Self {
             proposals: std::sync::Mutex::new(std::collections::HashMap::new()),
             next_id: std::sync::Mutex::new(1),
+            block_number: std::sync::Mutex::new(1000),
+            revert_executions: std::sync::atomic::AtomicBool::new(false),
+            power_checkpoints: std::sync::Mutex::new(std::collections::HashMap::new()),
+            total_supply: std::sync::Mutex::new(U256::from(Self::DEFAULT_TOTAL_SUPPLY)),
         }
     }
+
+    /// Record `power` for `user` from the current block onwards
+    pub fn set_voting_power(&self, user: Address, power: U256) {
+        let block = *self.block_number.lock().unwrap();
+        let mut checkpoints = self.power_checkpoints.lock().unwrap();
+        let history = checkpoints.entry(user).or_default();
+        history.retain(|(at, _)| *at != block);
+        history.push((block, power));
+    }
+
+    pub fn mine_blocks(&self, count: u64) -> u64 {
+        let mut block_number = self.block_number.lock().unwrap();
+        *block_number += count;
+        *block_number
+    }
 }
 
 #[async_trait]
@@ -127,6 +295,9 @@ impl GovernanceHubContract for MockGovernanceHub {
         voting_duration: U256,
         proposal_type: u8,
     ) -> Result<TransactionReceipt> {
+        // Allocate and insert under the proposals lock so the count never
+        // includes an id that isn't stored yet. Lock order: proposals, next_id.
+        let mut proposals = self.proposals.lock().unwrap();
         let mut next_id = self.next_id.lock().unwrap();
         let proposal_id = *next_id;
         *next_id += 1;
@@ -143,23 +314,33 @@ impl GovernanceHubContract for MockGovernanceHub {
             total_votes: U256::zero(),
             yes_votes: U256::zero(),
             no_votes: U256::zero(),
+            total_voting_power: *self.total_supply.lock().unwrap(),
         };
 
-        let mut proposals = self.proposals.lock().unwrap();
         proposals.insert(proposal_id, proposal);
+        drop(next_id);
+        drop(proposals);
 
         // Mock transaction receipt
         Ok(TransactionReceipt {
             transaction_hash: H256::random(),
             transaction_index: U64::from(0),
             block_hash: Some(H256::random()),
-            block_number: Some(U64::from(1000)),
+            block_number: Some(U64::from(*self.block_number.lock().unwrap())),
             from: Address::zero(),
             to: Some(Address::random()),
             cumulative_gas_used: U256::from(100000),
             gas_used: Some(U256::from(50000)),
             contract_address: None,
-            logs: vec![],
+            logs: vec![Log {
+                address: Address::zero(),
+                topics: vec![
+                    H256::from(ethers::utils::keccak256(ProposalCreatedEvent::SIGNATURE)),
+                    H256::from_low_u64_be(proposal_id),
+                    H256::zero(), // proposer
+                ],
+                ..Default::default()
+            }],
             status: Some(U64::from(1)),
             root: None,
             logs_bloom: Bloom::default(),
@@ -169,6 +350,15 @@ impl GovernanceHubContract for MockGovernanceHub {
         })
     }
 
+    async fn estimate_create_proposal_gas(
+        &self,
+        _ipfs_hash: &str,
+        _voting_duration: U256,
+        _proposal_type: u8,
+    ) -> Result<U256> {
+        Ok(U256::from(Self::CREATE_PROPOSAL_GAS))
+    }
+
     async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData> {
         let proposals = self.proposals.lock().unwrap();
         proposals
@@ -177,35 +367,120 @@ impl GovernanceHubContract for MockGovernanceHub {
             .ok_or_else(|| GovernanceError::ProposalNotFound { proposal_id })
     }
 
+    async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt> {
+        let mut proposals = self.proposals.lock().unwrap();
+        let proposal = proposals
+            .get_mut(&proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+
+        let reverted = self.revert_executions.load(std::sync::atomic::Ordering::SeqCst);
+        let logs = if reverted {
+            vec![]
+        } else {
+            proposal.status = ProposalStatus::Executed;
+            vec![Log {
+                address: Address::zero(),
+                topics: vec![
+                    H256::from(ethers::utils::keccak256("ProposalExecuted(uint256,address)")),
+                    H256::from_low_u64_be(proposal_id),
+                ],
+                ..Default::default()
+            }]
+        };
+
+        Ok(TransactionReceipt {
+            transaction_hash: H256::random(),
+            block_number: Some(U64::from(*self.block_number.lock().unwrap())),
+            gas_used: Some(U256::from(60000)),
+            logs,
+            status: Some(U64::from(if reverted { 0 } else { 1 })),
+            ..Default::default()
+        })
+    }
+
+    // Everything is in memory, so any batch is a single round-trip
+    fn supports_batching(&self) -> bool {
+        true
+    }
+
+    async fn get_proposals_batch(&self, proposal_ids: &[u64]) -> Result<Vec<ProposalData>> {
+        let proposals = self.proposals.lock().unwrap();
+        proposal_ids
+            .iter()
+            .map(|&proposal_id| {
+                proposals
+                    .get(&proposal_id)
+                    .cloned()
+                    .ok_or(GovernanceError::ProposalNotFound { proposal_id })
+            })
+            .collect()
+    }
+
     async fn get_proposal_count(&self) -> Result<u64> {
+        let _proposals = self.proposals.lock().unwrap();
         let next_id = self.next_id.lock().unwrap();
         Ok(*next_id - 1)
     }
 
     async fn get_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<ProposalData>> {
         let proposals = self.proposals.lock().unwrap();
-        let filtered: Vec<ProposalData> = proposals
+        let mut filtered: Vec<ProposalData> = proposals
             .values()
-            .filter(|p| std::mem::discriminant(&p.status) == std::mem::discriminant(&status))
+            .filter(|p| p.status == status)
             .cloned()
             .collect();
+        // HashMap iteration order is arbitrary; keep results stable for pagination
+        filtered.sort_by_key(|p| p.id);
         Ok(filtered)
     }
 
-    async fn get_user_voting_power(&self, _user: Address) -> Result<U256> {
-        // Mock voting power
-        Ok(U256::from(1000))
+    async fn get_user_voting_power(&self, user: Address) -> Result<U256> {
+        let block = *self.block_number.lock().unwrap();
+        self.get_voting_power_at(user, block).await
+    }
+
+    async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256> {
+        let head = *self.block_number.lock().unwrap();
+        if block > head {
+            return Err(GovernanceError::invalid_request(format!(
+                "Block {} is beyond the current head {}",
+                block, head
+            )));
+        }
+
+        let checkpoints = self.power_checkpoints.lock().unwrap();
+        let power = match checkpoints.get(&user) {
+            // Latest checkpoint at or before the block; zero before the first one
+            Some(history) => history
+                .iter()
+                .rev()
+                .find(|(at, _)| *at <= block)
+                .map(|(_, power)| *power)
+                .unwrap_or_default(),
+            None => U256::from(Self::DEFAULT_VOTING_POWER),
+        };
+        Ok(power)
+    }
+
+    async fn get_total_voting_power(&self) -> Result<U256> {
+        Ok(*self.total_supply.lock().unwrap())
     }
 }
 
 pub struct MockSimpleVoting {
     pub votes: std::sync::Mutex<std::collections::HashMap<(u64, Address), VoteData>>,
+    /// Aggregates posted per proposal; the contract accepts one each
+    pub aggregates: std::sync::Mutex<std::collections::HashMap<u64, AggregateTally>>,
 }
 
 impl MockSimpleVoting {
+    /// Gas reported for every vote, estimated or cast
+    pub const CAST_VOTE_GAS: u64 = 40_000;
+
     pub fn new() -> Self {
         Self {
             votes: std::sync::Mutex::new(std::collections::HashMap::new()),
+            aggregates: std::sync::Mutex::new(std::collections::HashMap::new()),
         }
     }
 }
@@ -240,7 +515,7 @@ impl SimpleVotingContract for MockSimpleVoting {
             from: voter,
             to: Some(Address::random()),
             cumulative_gas_used: U256::from(80000),
-            gas_used: Some(U256::from(40000)),
+            gas_used: Some(U256::from(Self::CAST_VOTE_GAS)),
             contract_address: None,
             logs: vec![],
             status: Some(U64::from(1)),
@@ -289,6 +564,29 @@ impl SimpleVotingContract for MockSimpleVoting {
 
         Ok((yes_votes, no_votes, abstain_votes))
     }
+
+    async fn estimate_cast_vote_gas(&self, _proposal_id: u64, _voter: Address, _choice: u8) -> Result<U256> {
+        Ok(U256::from(Self::CAST_VOTE_GAS))
+    }
+
+    async fn submit_vote_aggregate(&self, proposal_id: u64, tally: &AggregateTally) -> Result<TransactionReceipt> {
+        let mut aggregates = self.aggregates.lock().unwrap();
+        if aggregates.contains_key(&proposal_id) {
+            return Err(GovernanceError::invalid_request(format!(
+                "Aggregate already recorded for proposal {}",
+                proposal_id
+            )));
+        }
+        aggregates.insert(proposal_id, tally.clone());
+
+        Ok(TransactionReceipt {
+            transaction_hash: H256::random(),
+            block_number: Some(U64::from(1001)),
+            gas_used: Some(U256::from(Self::CAST_VOTE_GAS)),
+            status: Some(U64::from(1)),
+            ..Default::default()
+        })
+    }
 }
 
 // Contract factory for creating contract instances
@@ -348,6 +646,24 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[tokio::test]
+    async fn test_proposal_id_read_from_created_event() {
+        let hub = MockGovernanceHub::new();
+        hub.create_proposal("QmFirst".to_string(), U256::from(86400), 0).await.unwrap();
+        let receipt = hub.create_proposal("QmSecond".to_string(), U256::from(86400), 0).await.unwrap();
+        assert_eq!(ProposalCreatedEvent::proposal_id_from_receipt(&receipt), Some(2));
+
+        // Unrelated logs are skipped
+        let unrelated = TransactionReceipt {
+            logs: vec![Log {
+                topics: vec![H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)")), H256::zero()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(ProposalCreatedEvent::proposal_id_from_receipt(&unrelated), None);
+    }
+
     #[tokio::test]
     async fn test_mock_simple_voting() {
         let voting = MockSimpleVoting::new();
@@ -364,4 +680,66 @@ mod tests {
         assert_eq!(tally.1, U256::zero()); // no votes
         assert_eq!(tally.2, U256::zero()); // abstain votes
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_mock_voting_power_history() {
+        let hub = MockGovernanceHub::new();
+        let user = Address::random();
+        let before = hub.mine_blocks(0);
+
+        hub.mine_blocks(10);
+        hub.set_voting_power(user, U256::from(500));
+        let old_block = hub.mine_blocks(10);
+        hub.set_voting_power(user, U256::from(2000));
+        let head = hub.mine_blocks(1);
+
+        assert_eq!(hub.get_voting_power_at(user, before).await.unwrap(), U256::zero());
+        assert_eq!(hub.get_voting_power_at(user, old_block).await.unwrap(), U256::from(500));
+        assert_eq!(hub.get_user_voting_power(user).await.unwrap(), U256::from(2000));
+        assert!(hub.get_voting_power_at(user, head + 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_proposals_by_status_ordered_by_id() {
+        let hub = MockGovernanceHub::new();
+        for _ in 0..12 {
+            hub.create_proposal("QmTest123".to_string(), U256::from(86400), 0).await.unwrap();
+        }
+
+        let expected: Vec<u64> = (1..=12).collect();
+        for _ in 0..5 {
+            let ids: Vec<u64> = hub
+                .get_proposals_by_status(ProposalStatus::Active)
+                .await
+                .unwrap()
+                .iter()
+                .map(|p| p.id)
+                .collect();
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_creation_allocates_ids_without_gaps() {
+        let hub = Arc::new(MockGovernanceHub::new());
+        let creators: Vec<_> = (0..64)
+            .map(|_| {
+                let hub = hub.clone();
+                tokio::spawn(async move {
+                    hub.create_proposal("QmTest123".to_string(), U256::from(86400), 0).await.unwrap();
+                    let count = hub.get_proposal_count().await.unwrap();
+                    // Every counted id must already be readable
+                    hub.get_proposal(count).await.unwrap();
+                })
+            })
+            .collect();
+        for creator in creators {
+            creator.await.unwrap();
+        }
+
+        assert_eq!(hub.get_proposal_count().await.unwrap(), 64);
+        let mut ids: Vec<u64> = hub.proposals.lock().unwrap().keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, (1..=64).collect::<Vec<_>>());
+    }
+}
@@ -9,7 +9,7 @@ use tokio::sync::broadcast;
 pub enum ContractEvent {
     ProposalCreated(ProposalCreatedEvent),
     VoteCast(VoteCastEvent),
-    ProposalExecuted { proposal_id: u64, executor: Address },
+    ProposalExecuted { contract: Address, proposal_id: u64, executor: Address },
 }
 
 pub struct EventProcessor {
@@ -54,7 +54,7 @@ impl EventProcessor {
         self.provider
             .get_logs(&filter)
             .await
-            .map_err(GovernanceError::Blockchain)
+            .map_err(GovernanceError::from)
     }
 }
 
@@ -66,6 +66,7 @@ fn parse_proposal_created_event(log: &Log) -> Result<ProposalCreatedEvent> {
     let proposer = log.address; // Would be decoded from log.topics
     
     Ok(ProposalCreatedEvent {
+        contract: log.address,
         proposal_id,
         proposer,
         ipfs_hash: "QmMock123".to_string(), // Would be decoded from log.data
@@ -80,6 +81,7 @@ fn parse_vote_cast_event(log: &Log) -> Result<VoteCastEvent> {
     // For now, we'll create a mock event
     
     Ok(VoteCastEvent {
+        contract: log.address,
         proposal_id: 1u64, // Would be decoded from log.topics
         voter: log.address, // Would be decoded from log.topics
         choice: 1u8, // Would be decoded from log.data
@@ -93,7 +95,7 @@ fn parse_vote_cast_event(log: &Log) -> Result<VoteCastEvent> {
 pub trait EventHandler: Send + Sync {
     fn handle_proposal_created(&self, event: &ProposalCreatedEvent);
     fn handle_vote_cast(&self, event: &VoteCastEvent);
-    fn handle_proposal_executed(&self, proposal_id: u64, executor: Address);
+    fn handle_proposal_executed(&self, contract: Address, proposal_id: u64, executor: Address);
 }
 
 // Default event handler that logs events
@@ -119,9 +121,10 @@ impl EventHandler for LoggingEventHandler {
         );
     }
 
-    fn handle_proposal_executed(&self, proposal_id: u64, executor: Address) {
+    fn handle_proposal_executed(&self, contract: Address, proposal_id: u64, executor: Address) {
         tracing::info!(
-            "Proposal executed: ID={}, Executor={:?}",
+            "Proposal executed: Contract={:?}, ID={}, Executor={:?}",
+            contract,
             proposal_id,
             executor
         );
@@ -161,9 +164,13 @@ impl EventAggregator {
                         handler.handle_vote_cast(e);
                     }
                 }
-                ContractEvent::ProposalExecuted { proposal_id, executor } => {
+                ContractEvent::ProposalExecuted {
+                    contract,
+                    proposal_id,
+                    executor,
+                } => {
                     for handler in &self.handlers {
-                        handler.handle_proposal_executed(proposal_id, executor);
+                        handler.handle_proposal_executed(contract, proposal_id, executor);
                     }
                 }
             }
@@ -180,6 +187,7 @@ mod tests {
         let handler = LoggingEventHandler;
         
         let proposal_event = ProposalCreatedEvent {
+            contract: Address::zero(),
             proposal_id: 1,
             proposer: Address::zero(),
             ipfs_hash: "QmTest123".to_string(),
@@ -189,6 +197,7 @@ mod tests {
         };
         
         let vote_event = VoteCastEvent {
+            contract: Address::zero(),
             proposal_id: 1,
             voter: Address::zero(),
             choice: 1,
@@ -200,6 +209,6 @@ mod tests {
         // These should not panic
         handler.handle_proposal_created(&proposal_event);
         handler.handle_vote_cast(&vote_event);
-        handler.handle_proposal_executed(1, Address::zero());
+        handler.handle_proposal_executed(Address::zero(), 1, Address::zero());
     }
 }
\ No newline at end of file
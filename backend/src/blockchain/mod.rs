@@ -1,4 +1,8 @@
+pub mod callbacks;
 pub mod client;
 pub mod contracts;
 pub mod events;
+pub mod node_info;
+pub mod rate_limit;
+pub mod simulation;
 pub mod transactions;
\ No newline at end of file
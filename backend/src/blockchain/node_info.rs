@@ -0,0 +1,46 @@
+use crate::utils::errors::{GovernanceError, Result};
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, Middleware, Provider};
+use ethers::types::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+
+/// What the connected RPC node reports about itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcNodeInfo {
+    /// `web3_clientVersion`, e.g. `Geth/v1.13.5-stable/linux-amd64/go1.21.4`
+    pub client_version: String,
+    /// `net_version`, the network id
+    pub net_version: String,
+}
+
+/// Source of `RpcNodeInfo`, for compatibility diagnostics
+#[async_trait]
+pub trait NodeInfoSource: Send + Sync {
+    async fn node_info(&self) -> Result<RpcNodeInfo>;
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> NodeInfoSource for Provider<P> {
+    async fn node_info(&self) -> Result<RpcNodeInfo> {
+        let client_version = self.client_version().await.map_err(GovernanceError::from)?;
+        let net_version = self.get_net_version().await.map_err(GovernanceError::from)?;
+        Ok(RpcNodeInfo {
+            client_version,
+            net_version,
+        })
+    }
+}
+
+/// Source of deployed bytecode, to confirm configured contracts exist
+#[async_trait]
+pub trait ContractCodeSource: Send + Sync {
+    /// Code at `address`; empty for accounts without code
+    async fn code_at(&self, address: Address) -> Result<Bytes>;
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> ContractCodeSource for Provider<P> {
+    async fn code_at(&self, address: Address) -> Result<Bytes> {
+        self.get_code(address, None).await.map_err(GovernanceError::from)
+    }
+}
@@ -0,0 +1,112 @@
+use ethers::providers::{ProviderError, RpcError};
+use serde_json::Value;
+use std::time::Duration;
+
+/// JSON-RPC error codes providers use for requests over quota: HTTP's own
+/// 429, and EIP-1474's "limit exceeded"
+const RATE_LIMIT_CODES: [i64; 2] = [429, -32005];
+
+/// Longest delay hint taken at face value. Anything longer is read as this,
+/// far past any sensible retry limit, so the caller gives up.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(86_400);
+
+/// A provider turning a request away because the caller is over its quota
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRateLimit {
+    /// How long the provider asked us to wait, if it said
+    pub retry_after: Option<Duration>,
+}
+
+impl RpcRateLimit {
+    /// Recognize a rate-limit rejection among provider errors. Providers
+    /// report these as JSON-RPC errors, as bare HTTP 429s, or as a non-JSON
+    /// "Too Many Requests" body the client fails to parse. Only the first
+    /// carries a delay: the HTTP transport drops response headers, so a bare
+    /// 429's `Retry-After` is never seen and the retry policy's backoff applies.
+    pub fn detect(error: &ProviderError) -> Option<Self> {
+        if let Some(response) = error.as_error_response() {
+            let message = response.message.to_lowercase();
+            if RATE_LIMIT_CODES.contains(&response.code) || mentions_rate_limit(&message) {
+                return Some(Self {
+                    retry_after: response.data.as_ref().and_then(retry_after),
+                });
+            }
+            return None;
+        }
+
+        if let ProviderError::HTTPError(e) = error {
+            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                return Some(Self { retry_after: None });
+            }
+        }
+
+        mentions_rate_limit(&error.to_string().to_lowercase()).then_some(Self { retry_after: None })
+    }
+}
+
+fn mentions_rate_limit(message: &str) -> bool {
+    ["too many requests", "rate limit", "rate-limit", "exceeded its throughput limit"]
+        .iter()
+        .any(|phrase| message.contains(phrase))
+}
+
+/// Delay hint in a rate-limit error's data: a `retry_after` in seconds, the
+/// form Infura uses (`rate.backoff_seconds`), or a bare number of seconds
+fn retry_after(data: &Value) -> Option<Duration> {
+    let seconds = data
+        .get("retry_after")
+        .or_else(|| data.get("retryAfter"))
+        .or_else(|| data.pointer("/rate/backoff_seconds"))
+        .unwrap_or(data);
+    match seconds {
+        Value::Number(seconds) => seconds.as_f64(),
+        Value::String(seconds) => seconds.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|seconds| !seconds.is_nan() && *seconds >= 0.0)
+    .map(|seconds| Duration::try_from_secs_f64(seconds).map_or(MAX_RETRY_AFTER, |delay| delay.min(MAX_RETRY_AFTER)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{JsonRpcClient, JsonRpcError, MockProvider, MockResponse};
+
+    async fn provider_error(code: i64, message: &str, data: Option<Value>) -> ProviderError {
+        let mock = MockProvider::new();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code,
+            message: message.to_string(),
+            data,
+        }));
+        let result: Result<u64, _> = mock.request("eth_blockNumber", ()).await;
+        result.unwrap_err().into()
+    }
+
+    #[tokio::test]
+    async fn test_detects_rate_limits_and_their_delay() {
+        let error = provider_error(429, "Too Many Requests", Some(serde_json::json!({ "retry_after": 2 }))).await;
+        assert_eq!(
+            RpcRateLimit::detect(&error),
+            Some(RpcRateLimit {
+                retry_after: Some(Duration::from_secs(2))
+            })
+        );
+
+        let infura = serde_json::json!({ "rate": { "backoff_seconds": 1.5 } });
+        let error = provider_error(-32005, "project ID request rate exceeded", Some(infura)).await;
+        assert_eq!(RpcRateLimit::detect(&error).unwrap().retry_after, Some(Duration::from_millis(1500)));
+
+        let error = provider_error(-32000, "Your app has exceeded its rate limit", None).await;
+        assert_eq!(RpcRateLimit::detect(&error), Some(RpcRateLimit { retry_after: None }));
+
+        // Hints too large for a Duration are capped rather than trusted
+        for huge in [serde_json::json!({ "retry_after": 1e20 }), serde_json::json!("1e400")] {
+            let error = provider_error(429, "Too Many Requests", Some(huge)).await;
+            assert_eq!(RpcRateLimit::detect(&error).unwrap().retry_after, Some(MAX_RETRY_AFTER));
+        }
+
+        let error = provider_error(-32000, "header not found", None).await;
+        assert_eq!(RpcRateLimit::detect(&error), None);
+    }
+}
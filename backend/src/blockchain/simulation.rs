@@ -0,0 +1,119 @@
+use crate::utils::errors::{GovernanceError, Result};
+use async_trait::async_trait;
+use ethers::abi::{decode, ParamType, Token};
+use ethers::providers::{JsonRpcClient, Middleware, Provider, RpcError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// `Error(string)`, emitted by `require` and `revert("...")`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// `Panic(uint256)`, emitted on assertion failures and arithmetic errors
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Outcome of running a call against current state without sending it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSimulation {
+    pub success: bool,
+    /// Return data on success, revert data on failure
+    pub return_data: Bytes,
+    /// Decoded `Error(string)` or `Panic(uint256)` reason, when reverted
+    pub revert_reason: Option<String>,
+}
+
+impl CallSimulation {
+    pub fn succeeded(return_data: Bytes) -> Self {
+        Self {
+            success: true,
+            return_data,
+            revert_reason: None,
+        }
+    }
+
+    pub fn reverted(revert_data: Bytes) -> Self {
+        Self {
+            success: false,
+            revert_reason: decode_revert_reason(&revert_data),
+            return_data: revert_data,
+        }
+    }
+}
+
+/// Human-readable reason from standard Solidity revert data
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let (selector, payload) = (data.get(..4)?, data.get(4..)?);
+    if selector == ERROR_SELECTOR {
+        match decode(&[ParamType::String], payload).ok()?.pop()? {
+            Token::String(reason) => Some(reason),
+            _ => None,
+        }
+    } else if selector == PANIC_SELECTOR {
+        match decode(&[ParamType::Uint(256)], payload).ok()?.pop()? {
+            Token::Uint(code) => Some(format!("panic code {:#x}", code)),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Runs calls with `eth_call`, without broadcasting anything
+#[async_trait]
+pub trait CallSimulator: Send + Sync {
+    async fn simulate(&self, tx: &TypedTransaction) -> Result<CallSimulation>;
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> CallSimulator for Provider<P> {
+    async fn simulate(&self, tx: &TypedTransaction) -> Result<CallSimulation> {
+        match self.call(tx, None).await {
+            Ok(return_data) => Ok(CallSimulation::succeeded(return_data)),
+            Err(e) => match e.as_error_response().and_then(|response| response.as_revert_data()) {
+                Some(revert_data) => Ok(CallSimulation::reverted(revert_data)),
+                None => Err(e.into()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+    use ethers::providers::{JsonRpcError, MockResponse};
+    use ethers::types::{Address, TransactionRequest};
+
+    fn call() -> TypedTransaction {
+        TransactionRequest::new().to(Address::random()).data(vec![0xde, 0xad, 0xbe, 0xef]).into()
+    }
+
+    #[tokio::test]
+    async fn test_successful_call_returns_data() {
+        let (provider, mock) = Provider::mocked();
+        let returned = Bytes::from(encode(&[Token::Uint(42.into())]));
+        mock.push(returned.clone()).unwrap();
+
+        let simulation = provider.simulate(&call()).await.unwrap();
+        assert_eq!(simulation, CallSimulation::succeeded(returned));
+    }
+
+    #[tokio::test]
+    async fn test_revert_reason_decoded() {
+        let (provider, mock) = Provider::mocked();
+        let revert_data = [&ERROR_SELECTOR[..], &encode(&[Token::String("Not authorized".to_string())])].concat();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: 3,
+            message: "execution reverted: Not authorized".to_string(),
+            data: Some(serde_json::json!(Bytes::from(revert_data.clone()))),
+        }));
+
+        let simulation = provider.simulate(&call()).await.unwrap();
+        assert!(!simulation.success);
+        assert_eq!(simulation.revert_reason.as_deref(), Some("Not authorized"));
+        assert_eq!(simulation.return_data, Bytes::from(revert_data));
+
+        let panic = [&PANIC_SELECTOR[..], &encode(&[Token::Uint(0x11.into())])].concat();
+        assert_eq!(decode_revert_reason(&panic).as_deref(), Some("panic code 0x11"));
+        assert_eq!(decode_revert_reason(&[0x01, 0x02]), None);
+    }
+}
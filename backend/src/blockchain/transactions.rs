@@ -1,6 +1,8 @@
+use crate::blockchain::callbacks::{validate_callback_url, CallbackDispatcher, CallbackStatus, TransactionCallback};
 use crate::utils::errors::{GovernanceError, Result};
 use ethers::prelude::*;
 use ethers::types::transaction::eip2718::TypedTransaction;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -8,21 +10,25 @@ use tokio::sync::RwLock;
 #[derive(Debug, Clone)]
 pub struct TransactionManager {
     provider: Arc<Provider<Ws>>,
-    pending_transactions: Arc<RwLock<HashMap<H256, PendingTransaction>>>,
+    pending_transactions: PendingTransactions,
     gas_oracle: GasOracle,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingTransaction {
     pub hash: H256,
+    pub origin: Address, // user the transaction was submitted for, not necessarily its signer
     pub transaction_type: TransactionType,
     pub submitted_at: chrono::DateTime<chrono::Utc>,
     pub confirmations_required: u64,
     pub current_confirmations: u64,
     pub max_wait_time: std::time::Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>, // told the outcome once the transaction settles
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum TransactionType {
     CreateProposal { ipfs_hash: String },
     CastVote { proposal_id: u64, choice: u8 },
@@ -36,25 +42,163 @@ pub struct GasOracle {
     max_fee_per_gas: U256,
 }
 
+/// How often tracked transactions are swept
+pub const PENDING_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Transactions submitted and not yet cleaned up, shared between the
+/// `TransactionManager` tracking them and the API reporting on them
+#[derive(Clone, Default)]
+pub struct PendingTransactions {
+    transactions: Arc<RwLock<HashMap<H256, PendingTransaction>>>,
+    callbacks: Option<CallbackDispatcher>,
+}
+
+impl std::fmt::Debug for PendingTransactions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingTransactions")
+            .field("callbacks", &self.callbacks.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PendingTransactions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send callbacks for settled transactions that asked for one
+    pub fn with_callbacks(mut self, dispatcher: CallbackDispatcher) -> Self {
+        self.callbacks = Some(dispatcher);
+        self
+    }
+
+    pub fn callbacks_enabled(&self) -> bool {
+        self.callbacks.is_some()
+    }
+
+    pub async fn track(&self, transaction: PendingTransaction) {
+        self.transactions.write().await.insert(transaction.hash, transaction);
+    }
+
+    pub async fn get(&self, tx_hash: H256) -> Option<PendingTransaction> {
+        self.transactions.read().await.get(&tx_hash).cloned()
+    }
+
+    pub async fn set_confirmations(&self, tx_hash: H256, confirmations: u64) {
+        if let Some(pending) = self.transactions.write().await.get_mut(&tx_hash) {
+            pending.current_confirmations = confirmations;
+        }
+    }
+
+    /// Record the outcome of a tracked transaction from its receipt: a
+    /// success counts `confirmations`, a failure stops tracking it. Either
+    /// way its callback, if any, is told. Returns the outcome, or `None` for
+    /// transactions not being tracked.
+    pub async fn settle(&self, receipt: TransactionReceipt, confirmations: u64) -> Option<CallbackStatus> {
+        let tx_hash = receipt.transaction_hash;
+        let (status, transaction) = if receipt.status == Some(U64::from(1)) {
+            let mut transactions = self.transactions.write().await;
+            let transaction = transactions.get_mut(&tx_hash)?;
+            transaction.current_confirmations = confirmations;
+            (CallbackStatus::Confirmed, transaction.clone())
+        } else {
+            (CallbackStatus::Failed, self.transactions.write().await.remove(&tx_hash)?)
+        };
+
+        if let (Some(callbacks), Some(url)) = (&self.callbacks, &transaction.callback_url) {
+            let block_number = receipt.block_number.map(|block| block.as_u64());
+            callbacks
+                .dispatch(url, &TransactionCallback::new(&transaction, status, block_number))
+                .await;
+        }
+        Some(status)
+    }
+
+    /// Transactions submitted for `origin` that still lack the confirmations
+    /// they require, oldest first
+    pub async fn for_origin(&self, origin: Address) -> Vec<PendingTransaction> {
+        let mut pending: Vec<_> = self
+            .transactions
+            .read()
+            .await
+            .values()
+            .filter(|tx| tx.origin == origin && tx.current_confirmations < tx.confirmations_required)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|tx| tx.submitted_at);
+        pending
+    }
+
+    /// Forget transactions submitted before `cutoff`. Returns how many remain.
+    pub async fn retain_since(&self, cutoff: chrono::DateTime<chrono::Utc>) -> usize {
+        let mut transactions = self.transactions.write().await;
+        transactions.retain(|_, tx| tx.submitted_at > cutoff);
+        transactions.len()
+    }
+
+    /// Start the background sweep forgetting transactions older than
+    /// `max_age`, every `period`
+    pub fn start_cleanup_task(
+        &self,
+        period: std::time::Duration,
+        max_age: chrono::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let pending = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let remaining = pending.retain_since(chrono::Utc::now() - max_age).await;
+                tracing::debug!("Cleaned up old transactions, {} remaining", remaining);
+            }
+        })
+    }
+
+    pub async fn len(&self) -> usize {
+        self.transactions.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
 impl TransactionManager {
     pub fn new(provider: Arc<Provider<Ws>>) -> Self {
         Self {
             provider,
-            pending_transactions: Arc::new(RwLock::new(HashMap::new())),
+            pending_transactions: PendingTransactions::new(),
             gas_oracle: GasOracle::default(),
         }
     }
 
+    /// Track submissions in `pending`, e.g. the tracker the API reports from
+    pub fn with_pending_transactions(mut self, pending: PendingTransactions) -> Self {
+        self.pending_transactions = pending;
+        self
+    }
+
+    /// Submit `tx` on behalf of `origin`, the user it is attributed to.
+    /// `callback_url` is sent a signed `TransactionCallback` once it settles.
     pub async fn submit_transaction(
         &self,
         tx: TypedTransaction,
         transaction_type: TransactionType,
+        origin: Address,
+        callback_url: Option<String>,
     ) -> Result<H256> {
+        if let Some(url) = &callback_url {
+            if !self.pending_transactions.callbacks_enabled() {
+                return Err(GovernanceError::invalid_request("Transaction callbacks are not enabled"));
+            }
+            validate_callback_url(url)?;
+        }
+
         // Estimate gas
         let gas_estimate = self.provider
             .estimate_gas(&tx, None)
             .await
-            .map_err(GovernanceError::Blockchain)?;
+            .map_err(GovernanceError::from)?;
 
         // Set gas parameters
         let mut tx = tx;
@@ -67,24 +211,23 @@ impl TransactionManager {
         let pending_tx = self.provider
             .send_transaction(tx, None)
             .await
-            .map_err(GovernanceError::Blockchain)?;
+            .map_err(GovernanceError::from)?;
 
         let tx_hash = pending_tx.tx_hash();
 
         // Track the transaction
         let pending = PendingTransaction {
             hash: tx_hash,
+            origin,
             transaction_type,
             submitted_at: chrono::Utc::now(),
             confirmations_required: 1, // Somnia has fast finality
             current_confirmations: 0,
             max_wait_time: std::time::Duration::from_secs(30),
+            callback_url,
         };
 
-        self.pending_transactions
-            .write()
-            .await
-            .insert(tx_hash, pending);
+        self.pending_transactions.track(pending).await;
 
         tracing::info!("Submitted transaction: {:?}", tx_hash);
         Ok(tx_hash)
@@ -102,22 +245,14 @@ impl TransactionManager {
                 if let Some(receipt) = self.provider
                     .get_transaction_receipt(tx_hash)
                     .await
-                    .map_err(GovernanceError::Blockchain)?
+                    .map_err(GovernanceError::from)?
                 {
-                    if receipt.status == Some(U64::from(1)) {
-                        // Update pending transaction
-                        if let Some(pending) = self.pending_transactions
-                            .write()
-                            .await
-                            .get_mut(&tx_hash)
-                        {
-                            pending.current_confirmations = confirmations;
-                        }
-
-                        return Ok(receipt);
-                    } else {
+                    // Update pending transaction and tell its callback
+                    self.pending_transactions.settle(receipt.clone(), confirmations).await;
+                    if receipt.status != Some(U64::from(1)) {
                         return Err(GovernanceError::ipfs("Transaction failed"));
                     }
+                    return Ok(receipt);
                 }
 
                 tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -129,12 +264,12 @@ impl TransactionManager {
 
     pub async fn get_transaction_status(&self, tx_hash: H256) -> Result<TransactionStatus> {
         // Check if it's a pending transaction we're tracking
-        if let Some(_pending) = self.pending_transactions.read().await.get(&tx_hash) {
+        if let Some(_pending) = self.pending_transactions.get(tx_hash).await {
             // Check for receipt
             if let Some(receipt) = self.provider
                 .get_transaction_receipt(tx_hash)
                 .await
-                .map_err(GovernanceError::Blockchain)?
+                .map_err(GovernanceError::from)?
             {
                 if receipt.status == Some(U64::from(1)) {
                     return Ok(TransactionStatus::Confirmed(receipt));
@@ -142,7 +277,7 @@ impl TransactionManager {
                     return Ok(TransactionStatus::Failed(receipt));
                 }
             } else {
-                return Ok(TransactionStatus::Pending(_pending.clone()));
+                return Ok(TransactionStatus::Pending(_pending));
             }
         }
 
@@ -150,7 +285,7 @@ impl TransactionManager {
         match self.provider
             .get_transaction_receipt(tx_hash)
             .await
-            .map_err(GovernanceError::Blockchain)?
+            .map_err(GovernanceError::from)?
         {
             Some(receipt) => {
                 if receipt.status == Some(U64::from(1)) {
@@ -200,14 +335,17 @@ impl TransactionManager {
     pub async fn cleanup_old_transactions(&self) {
         let cutoff = chrono::Utc::now() - chrono::Duration::hours(1);
         
-        let mut pending = self.pending_transactions.write().await;
-        pending.retain(|_, tx| tx.submitted_at > cutoff);
+        let remaining = self.pending_transactions.retain_since(cutoff).await;
         
-        tracing::debug!("Cleaned up old transactions, {} remaining", pending.len());
+        tracing::debug!("Cleaned up old transactions, {} remaining", remaining);
     }
 
     pub async fn get_pending_count(&self) -> usize {
-        self.pending_transactions.read().await.len()
+        self.pending_transactions.len().await
+    }
+
+    pub fn pending_transactions(&self) -> &PendingTransactions {
+        &self.pending_transactions
     }
 }
 
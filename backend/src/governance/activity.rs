@@ -0,0 +1,172 @@
+use crate::governance::delegation::DelegationRegistry;
+use crate::indexer::content_indexer::ContentIndexer;
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+/// One way an address took part in a proposal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "interaction", rename_all = "snake_case")]
+pub enum Interaction {
+    Created,
+    Voted { choice: u8, power: U256 },
+    /// A delegate the address's power currently flows to voted
+    DelegateVoted { delegate: Address, choice: u8, power: U256 },
+    Watched,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub proposal_id: u64,
+    /// Contract of the proposal; omitted for the primary one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Address>,
+    #[serde(flatten)]
+    pub interaction: Interaction,
+    pub timestamp: u64,
+}
+
+/// Every indexed interaction of `address` with a proposal, most recent
+/// first: proposals it created (at their start), its votes, votes of the
+/// delegates its power flows to, and proposals it watches. Delegated votes
+/// follow the current delegation chain.
+pub fn activity_feed(
+    indexer: &ContentIndexer,
+    delegations: &DelegationRegistry,
+    address: Address,
+) -> Vec<ActivityEntry> {
+    let mut entries: Vec<ActivityEntry> = indexer
+        .proposals_by(address)
+        .into_iter()
+        .map(|proposal| ActivityEntry {
+            proposal_id: proposal.id,
+            contract: proposal.contract,
+            interaction: Interaction::Created,
+            timestamp: proposal.start_time,
+        })
+        .collect();
+
+    let mut voters = vec![address];
+    voters.extend(delegations.delegate_chain(address));
+    entries.extend(indexer.votes_by(&voters).into_iter().map(|vote| ActivityEntry {
+        proposal_id: vote.proposal_id,
+        contract: vote.contract,
+        interaction: match vote.voter == address {
+            true => Interaction::Voted {
+                choice: vote.choice,
+                power: vote.power,
+            },
+            false => Interaction::DelegateVoted {
+                delegate: vote.voter,
+                choice: vote.choice,
+                power: vote.power,
+            },
+        },
+        timestamp: vote.timestamp,
+    }));
+
+    entries.extend(indexer.watched_by(address).into_iter().map(|(proposal_id, since)| ActivityEntry {
+        proposal_id,
+        contract: None,
+        interaction: Interaction::Watched,
+        timestamp: since,
+    }));
+
+    // Stable, so same-second entries keep a fixed order across pages
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::contracts::ProposalStatus;
+    use crate::indexer::content_indexer::{IndexedProposal, IndexedVote};
+    use crate::ipfs::content_types::NotificationSettings;
+
+    fn proposal(id: u64, proposer: Address, start_time: u64) -> IndexedProposal {
+        IndexedProposal {
+            id,
+            contract: None,
+            proposer,
+            ipfs_hash: format!("QmProposal{}", id),
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time,
+            end_time: start_time + 86_400,
+            supersedes: None,
+            total_voting_power: U256::from(10_000),
+            tie_extended: false,
+        }
+    }
+
+    fn vote(proposal_id: u64, voter: Address, choice: u8, timestamp: u64) -> IndexedVote {
+        IndexedVote {
+            proposal_id,
+            contract: None,
+            voter,
+            choice,
+            power: U256::from(100),
+            timestamp,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_feed_merges_interactions_newest_first() {
+        let indexer = ContentIndexer::new();
+        let delegations = DelegationRegistry::new();
+        let (user, delegate, stranger) = (Address::random(), Address::random(), Address::random());
+        delegations.set_delegate(user, delegate);
+
+        indexer.index_proposal(proposal(1, user, 1000));
+        indexer.index_proposal(proposal(2, stranger, 1100));
+        indexer.index_proposal(proposal(3, stranger, 1200));
+        indexer.index_vote(vote(2, user, 1, 1500));
+        indexer.index_vote(vote(3, delegate, 0, 1600));
+        indexer.index_vote(vote(3, stranger, 1, 1700));
+        indexer.watch(3, user, NotificationSettings::default(), 1300);
+        indexer.watch(2, stranger, NotificationSettings::default(), 1400);
+
+        let feed = activity_feed(&indexer, &delegations, user);
+        let summary: Vec<(u64, Interaction, u64)> = feed
+            .into_iter()
+            .map(|entry| (entry.proposal_id, entry.interaction, entry.timestamp))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    3,
+                    Interaction::DelegateVoted {
+                        delegate,
+                        choice: 0,
+                        power: U256::from(100)
+                    },
+                    1600
+                ),
+                (
+                    2,
+                    Interaction::Voted {
+                        choice: 1,
+                        power: U256::from(100)
+                    },
+                    1500
+                ),
+                (3, Interaction::Watched, 1300),
+                (1, Interaction::Created, 1000),
+            ]
+        );
+
+        // Watching again with new settings keeps the original start
+        indexer.watch(3, user, NotificationSettings::default(), 1900);
+        let feed = activity_feed(&indexer, &delegations, user);
+        assert!(feed.contains(&ActivityEntry {
+            proposal_id: 3,
+            contract: None,
+            interaction: Interaction::Watched,
+            timestamp: 1300,
+        }));
+        assert!(activity_feed(&indexer, &delegations, Address::random()).is_empty());
+    }
+}
@@ -1,2 +1,397 @@
-// Placeholder for governance analytics logic
-pub struct AnalyticsManager;
\ No newline at end of file
+use crate::blockchain::contracts::ProposalStatus;
+use crate::config::ProposalRules;
+use crate::indexer::content_indexer::{IndexedProposal, IndexedVote};
+use crate::utils::errors::{GovernanceError, Result};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TIMELINE_BUCKET_SECONDS: u64 = 3600; // 1 hour
+pub const DEFAULT_TRENDING_WINDOW_SECONDS: u64 = 86_400; // 1 day
+const MAX_TIMELINE_BUCKETS: u64 = 1000;
+const BASIS_POINTS: u64 = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub start: u64,
+    pub end: u64,
+    pub yes_votes: U256,
+    pub no_votes: U256,
+    pub abstain_votes: U256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteTimeline {
+    pub proposal_id: u64,
+    pub bucket_seconds: u64,
+    pub buckets: Vec<TimelineBucket>,
+}
+
+/// Build cumulative yes/no/abstain power per time bucket across the voting window
+pub fn build_vote_timeline(
+    proposal: &IndexedProposal,
+    votes: &[IndexedVote],
+    bucket_seconds: u64,
+) -> Result<VoteTimeline> {
+    if bucket_seconds == 0 {
+        return Err(GovernanceError::invalid_request("Bucket size must be greater than zero"));
+    }
+
+    let window = proposal.end_time.saturating_sub(proposal.start_time);
+    let bucket_count = window.div_ceil(bucket_seconds).max(1);
+    if bucket_count > MAX_TIMELINE_BUCKETS {
+        return Err(GovernanceError::invalid_request(format!(
+            "Bucket size too small: at most {} buckets allowed",
+            MAX_TIMELINE_BUCKETS
+        )));
+    }
+
+    // Per-bucket power deltas: (yes, no, abstain)
+    let mut deltas = vec![(U256::zero(), U256::zero(), U256::zero()); bucket_count as usize];
+    for vote in votes {
+        let offset = vote.timestamp.saturating_sub(proposal.start_time);
+        let index = (offset / bucket_seconds).min(bucket_count - 1) as usize;
+        match vote.choice {
+            0 => deltas[index].1 += vote.power,
+            1 => deltas[index].0 += vote.power,
+            2 => deltas[index].2 += vote.power,
+            _ => {}
+        }
+    }
+
+    let mut buckets = Vec::with_capacity(deltas.len());
+    let (mut yes, mut no, mut abstain) = (U256::zero(), U256::zero(), U256::zero());
+    for (index, (yes_delta, no_delta, abstain_delta)) in deltas.into_iter().enumerate() {
+        yes += yes_delta;
+        no += no_delta;
+        abstain += abstain_delta;
+
+        let start = proposal.start_time + index as u64 * bucket_seconds;
+        buckets.push(TimelineBucket {
+            start,
+            end: (start + bucket_seconds).min(proposal.end_time.max(start)),
+            yes_votes: yes,
+            no_votes: no,
+            abstain_votes: abstain,
+        });
+    }
+
+    Ok(VoteTimeline {
+        proposal_id: proposal.id,
+        bucket_seconds,
+        buckets,
+    })
+}
+
+/// How reachable quorum looks from past turnout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuorumOutlook {
+    /// At least half of past proposals drew enough turnout
+    Likely,
+    /// Some, but fewer than half, did
+    Uncertain,
+    /// None did, or quorum exceeds total power
+    Unlikely,
+    /// No closed proposals to judge by
+    Unknown,
+}
+
+/// Estimate of whether a new proposal can meet the configured quorum, with
+/// the figures behind it. Rates are in basis points of total voting power.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumFeasibility {
+    pub total_voting_power: U256,
+    pub required_quorum: U256,
+    pub quorum_bps: u64,
+    /// Closed proposals the estimate is drawn from
+    pub sample_size: usize,
+    pub average_turnout_bps: u64,
+    pub peak_turnout_bps: u64,
+    /// Power expected to vote at the average turnout
+    pub expected_participation: U256,
+    /// Share of past proposals whose turnout would have met this quorum
+    pub historical_success_bps: u64,
+    pub outlook: QuorumOutlook,
+    pub warning: Option<String>,
+}
+
+impl QuorumFeasibility {
+    pub fn estimate(total_voting_power: U256, rules: &ProposalRules, turnout_bps: &[u64]) -> Self {
+        let quorum_bps = if rules.quorum_denominator == 0 {
+            0
+        } else {
+            rules.quorum_numerator.saturating_mul(BASIS_POINTS) / rules.quorum_denominator
+        };
+        let sample_size = turnout_bps.len();
+        let average_turnout_bps = match sample_size {
+            0 => 0,
+            n => turnout_bps.iter().sum::<u64>() / n as u64,
+        };
+        let peak_turnout_bps = turnout_bps.iter().copied().max().unwrap_or(0);
+        let successes = turnout_bps.iter().filter(|&&turnout| turnout >= quorum_bps).count();
+        let historical_success_bps = match sample_size {
+            0 => 0,
+            n => successes as u64 * BASIS_POINTS / n as u64,
+        };
+
+        let (outlook, warning) = if quorum_bps > BASIS_POINTS {
+            (
+                QuorumOutlook::Unlikely,
+                Some("Quorum exceeds the total voting power".to_string()),
+            )
+        } else if sample_size == 0 {
+            (QuorumOutlook::Unknown, None)
+        } else if historical_success_bps * 2 >= BASIS_POINTS {
+            (QuorumOutlook::Likely, None)
+        } else if successes > 0 {
+            (
+                QuorumOutlook::Uncertain,
+                Some(format!("Only {} of {} past proposals reached quorum turnout", successes, sample_size)),
+            )
+        } else {
+            (
+                QuorumOutlook::Unlikely,
+                Some(format!(
+                    "No past proposal reached quorum turnout of {} bps; the highest was {} bps",
+                    quorum_bps, peak_turnout_bps
+                )),
+            )
+        };
+
+        Self {
+            total_voting_power,
+            required_quorum: rules.required_quorum(total_voting_power),
+            quorum_bps,
+            sample_size,
+            average_turnout_bps,
+            peak_turnout_bps,
+            expected_participation: total_voting_power * average_turnout_bps / BASIS_POINTS,
+            historical_success_bps,
+            outlook,
+            warning,
+        }
+    }
+}
+
+/// Power voted as basis points of the proposal's snapshot total. `None` for
+/// canceled proposals and those without a snapshot.
+pub fn turnout_bps(proposal: &IndexedProposal, votes: &[IndexedVote]) -> Option<u64> {
+    if proposal.status == ProposalStatus::Canceled || proposal.total_voting_power.is_zero() {
+        return None;
+    }
+    let participation = votes.iter().fold(U256::zero(), |sum, vote| sum + vote.power);
+    let turnout = participation * BASIS_POINTS / proposal.total_voting_power;
+    Some(turnout.min(U256::from(BASIS_POINTS)).as_u64())
+}
+
+/// Labels of yes/no/abstain choices, indexed by choice
+pub const BINARY_CHOICE_LABELS: [&str; 3] = ["no", "yes", "abstain"];
+
+/// One choice's share of a proposal's votes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceShare {
+    pub choice: u8,
+    pub label: String,
+    pub voter_count: usize,
+    pub power: U256,
+    /// Share of voters, in basis points
+    pub count_bps: u64,
+    /// Share of power, in basis points
+    pub power_bps: u64,
+}
+
+/// How votes and power split across a proposal's choices, without naming
+/// any voter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteDistribution {
+    pub proposal_id: u64,
+    pub voter_count: usize,
+    pub total_power: U256,
+    pub choices: Vec<ChoiceShare>,
+    /// Gini coefficient of power across voters, in basis points: 0 when
+    /// every voter has equal power, nearing 10000 as a few voters hold it all
+    pub concentration_bps: u64,
+}
+
+/// Split `votes` across the choices named by `labels`. A vote counts
+/// towards the choice it was recorded against; weighted votes spread their
+/// power by weight. Votes for choices outside `labels` are left out.
+pub fn build_vote_distribution(proposal_id: u64, labels: &[String], votes: &[IndexedVote]) -> VoteDistribution {
+    let mut choices: Vec<ChoiceShare> = labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| ChoiceShare {
+            choice: index as u8,
+            label: label.clone(),
+            voter_count: 0,
+            power: U256::zero(),
+            count_bps: 0,
+            power_bps: 0,
+        })
+        .collect();
+
+    let counted: Vec<&IndexedVote> = votes.iter().filter(|vote| (vote.choice as usize) < choices.len()).collect();
+    for vote in &counted {
+        choices[vote.choice as usize].voter_count += 1;
+        if vote.weights.is_empty() {
+            choices[vote.choice as usize].power += vote.power;
+        } else {
+            for (share, weight) in choices.iter_mut().zip(&vote.weights) {
+                share.power += *weight;
+            }
+        }
+    }
+
+    let voter_count = counted.len();
+    let total_power = choices.iter().fold(U256::zero(), |sum, share| sum + share.power);
+    for share in &mut choices {
+        if voter_count > 0 {
+            share.count_bps = share.voter_count as u64 * BASIS_POINTS / voter_count as u64;
+        }
+        if !total_power.is_zero() {
+            share.power_bps = (share.power * BASIS_POINTS / total_power).as_u64();
+        }
+    }
+
+    let mut powers: Vec<U256> = counted.iter().map(|vote| vote.power).collect();
+    VoteDistribution {
+        proposal_id,
+        voter_count,
+        total_power,
+        choices,
+        concentration_bps: gini_bps(&mut powers),
+    }
+}
+
+/// Gini coefficient in basis points, from the sorted form
+/// `(2 Σ i·x_i) / (n Σ x) - (n + 1) / n` with `i` counting from 1
+fn gini_bps(powers: &mut [U256]) -> u64 {
+    let total = powers.iter().fold(U256::zero(), |sum, power| sum + *power);
+    if powers.is_empty() || total.is_zero() {
+        return 0;
+    }
+
+    powers.sort();
+    let n = U256::from(powers.len());
+    let ranked = powers
+        .iter()
+        .enumerate()
+        .fold(U256::zero(), |sum, (index, power)| sum + *power * U256::from(index + 1));
+    let numerator = (ranked * 2).saturating_sub((n + 1) * total);
+    (numerator * BASIS_POINTS / (n * total)).as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+
+    fn proposal(start_time: u64, end_time: u64) -> IndexedProposal {
+        IndexedProposal {
+            id: 1,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash: "QmTest123".to_string(),
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time,
+            end_time,
+            supersedes: None,
+            total_voting_power: U256::zero(),
+            tie_extended: false,
+        }
+    }
+
+    fn vote(choice: u8, power: u64, timestamp: u64) -> IndexedVote {
+        IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter: Address::random(),
+            choice,
+            power: U256::from(power),
+            timestamp,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_timeline_is_cumulative() {
+        let votes = vec![vote(1, 100, 1000), vote(0, 40, 4000), vote(2, 10, 7500), vote(1, 5, 9999)];
+        let timeline = build_vote_timeline(&proposal(0, 10000), &votes, 2500).unwrap();
+
+        assert_eq!(timeline.buckets.len(), 4);
+        for pair in timeline.buckets.windows(2) {
+            assert!(pair[1].yes_votes >= pair[0].yes_votes);
+            assert!(pair[1].no_votes >= pair[0].no_votes);
+            assert!(pair[1].abstain_votes >= pair[0].abstain_votes);
+        }
+
+        let last = timeline.buckets.last().unwrap();
+        assert_eq!(last.yes_votes, U256::from(105));
+        assert_eq!(last.no_votes, U256::from(40));
+        assert_eq!(last.abstain_votes, U256::from(10));
+    }
+
+    #[test]
+    fn test_votes_land_in_correct_bucket() {
+        let votes = vec![vote(1, 7, 3599), vote(0, 3, 3600)];
+        let timeline = build_vote_timeline(&proposal(0, 7200), &votes, 3600).unwrap();
+
+        assert_eq!(timeline.buckets[0].yes_votes, U256::from(7));
+        assert_eq!(timeline.buckets[0].no_votes, U256::zero());
+        assert_eq!(timeline.buckets[1].no_votes, U256::from(3));
+        assert_eq!(timeline.buckets[1].start, 3600);
+    }
+
+    #[test]
+    fn test_empty_votes_produce_empty_buckets() {
+        let timeline = build_vote_timeline(&proposal(1000, 11000), &[], 1000).unwrap();
+
+        assert_eq!(timeline.buckets.len(), 10);
+        assert!(timeline.buckets.iter().all(|b| b.yes_votes.is_zero() && b.no_votes.is_zero()));
+        assert_eq!(timeline.buckets[0].start, 1000);
+        assert_eq!(timeline.buckets[9].end, 11000);
+    }
+
+    #[test]
+    fn test_rejects_invalid_bucket_size() {
+        assert!(build_vote_timeline(&proposal(0, 10000), &[], 0).is_err());
+        assert!(build_vote_timeline(&proposal(0, 100000), &[], 1).is_err());
+    }
+
+    fn labels() -> Vec<String> {
+        BINARY_CHOICE_LABELS.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn test_distribution_histograms_sum_to_totals() {
+        let votes = vec![vote(1, 100, 0), vote(1, 50, 0), vote(0, 30, 0), vote(2, 20, 0), vote(7, 999, 0)];
+        let distribution = build_vote_distribution(1, &labels(), &votes);
+
+        assert_eq!(distribution.voter_count, 4);
+        assert_eq!(distribution.total_power, U256::from(200));
+        let counts: usize = distribution.choices.iter().map(|share| share.voter_count).sum();
+        let power = distribution.choices.iter().fold(U256::zero(), |sum, share| sum + share.power);
+        assert_eq!(counts, distribution.voter_count);
+        assert_eq!(power, distribution.total_power);
+
+        let yes = &distribution.choices[1];
+        assert_eq!((yes.label.as_str(), yes.voter_count, yes.count_bps, yes.power_bps), ("yes", 2, 5000, 7500));
+        assert!(distribution.choices.iter().map(|share| share.power_bps).sum::<u64>() <= BASIS_POINTS);
+    }
+
+    #[test]
+    fn test_concentration_higher_when_whales_dominate() {
+        let even: Vec<_> = (0..10).map(|i| vote(i % 2, 100, 0)).collect();
+        let mut whale: Vec<_> = (0..9).map(|_| vote(0, 1, 0)).collect();
+        whale.push(vote(1, 10_000, 0));
+
+        let even = build_vote_distribution(1, &labels(), &even);
+        let whale = build_vote_distribution(1, &labels(), &whale);
+
+        assert_eq!(even.concentration_bps, 0);
+        assert!(whale.concentration_bps > 8000, "{}", whale.concentration_bps);
+        assert_eq!(build_vote_distribution(1, &labels(), &[]).concentration_bps, 0);
+    }
+}
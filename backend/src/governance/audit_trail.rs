@@ -0,0 +1,106 @@
+use crate::blockchain::contracts::{ExecutionOutcome, ExecutionResult, ProposalStatus};
+use crate::indexer::content_indexer::{IndexedProposal, IndexedVote};
+use ethers::types::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// Something that happened to a proposal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProposalEvent {
+    Created { ipfs_hash: String },
+    VoteCast { choice: u8, power: U256 },
+    StatusChanged { from: ProposalStatus, to: ProposalStatus },
+    Finalized { status: ProposalStatus },
+    Executed { outcome: ExecutionOutcome },
+}
+
+/// One step of a proposal's history. `actor` is the address that caused it,
+/// `None` for the service's own settlement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub actor: Option<Address>,
+    pub transaction_hash: Option<H256>,
+    #[serde(flatten)]
+    pub event: ProposalEvent,
+}
+
+/// Lifecycle events the engine takes part in, per proposal, in the order
+/// they happened
+#[derive(Clone, Default)]
+pub struct ProposalAuditLog {
+    entries: Arc<RwLock<BTreeMap<u64, Vec<AuditEntry>>>>,
+}
+
+impl ProposalAuditLog {
+    pub fn record(&self, proposal_id: u64, entry: AuditEntry) {
+        self.entries.write().unwrap().entry(proposal_id).or_default().push(entry);
+    }
+
+    pub fn entries(&self, proposal_id: u64) -> Vec<AuditEntry> {
+        self.entries.read().unwrap().get(&proposal_id).cloned().unwrap_or_default()
+    }
+}
+
+/// Chronological trail of a proposal: the logged entries, plus what the
+/// indexer learned from chain events without going through the engine,
+/// such as votes cast directly against the contract. Those carry no
+/// transaction hash.
+pub fn audit_trail(
+    logged: Vec<AuditEntry>,
+    proposal: &IndexedProposal,
+    votes: &[IndexedVote],
+    execution: Option<&ExecutionResult>,
+) -> Vec<AuditEntry> {
+    let mut trail = logged;
+
+    if !trail.iter().any(|entry| matches!(entry.event, ProposalEvent::Created { .. })) {
+        trail.insert(
+            0,
+            AuditEntry {
+                timestamp: proposal.start_time,
+                actor: Some(proposal.proposer),
+                transaction_hash: None,
+                event: ProposalEvent::Created {
+                    ipfs_hash: proposal.ipfs_hash.clone(),
+                },
+            },
+        );
+    }
+
+    let logged_voters: HashSet<Address> = trail
+        .iter()
+        .filter(|entry| matches!(entry.event, ProposalEvent::VoteCast { .. }))
+        .filter_map(|entry| entry.actor)
+        .collect();
+    for vote in votes.iter().filter(|vote| !logged_voters.contains(&vote.voter)) {
+        trail.push(AuditEntry {
+            timestamp: vote.timestamp,
+            actor: Some(vote.voter),
+            transaction_hash: None,
+            event: ProposalEvent::VoteCast {
+                choice: vote.choice,
+                power: vote.power,
+            },
+        });
+    }
+
+    if let Some(execution) = execution {
+        if !trail.iter().any(|entry| matches!(entry.event, ProposalEvent::Executed { .. })) {
+            trail.push(AuditEntry {
+                timestamp: execution.executed_at,
+                actor: None,
+                transaction_hash: Some(execution.transaction_hash),
+                event: ProposalEvent::Executed {
+                    outcome: execution.outcome,
+                },
+            });
+        }
+    }
+
+    // Stable, so entries at the same second keep the order they were logged in
+    trail.sort_by_key(|entry| entry.timestamp);
+    trail
+}
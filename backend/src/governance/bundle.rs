@@ -0,0 +1,286 @@
+use crate::governance::engine::GovernanceEngine;
+use crate::governance::proposals::{ProposalDetail, ProposalVotes};
+use crate::ipfs::client::IpfsClient;
+use crate::utils::errors::Result;
+use axum::body::Bytes;
+use futures::channel::mpsc;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::{Component, Path};
+use std::sync::{Arc, Mutex};
+use zip::write::{SimpleFileOptions, StreamWriter};
+use zip::{CompressionMethod, ZipWriter};
+
+/// Zip chunks buffered ahead of a slow client
+const BUNDLE_CHANNEL_CAPACITY: usize = 4;
+
+/// Index of a proposal bundle, written last as `manifest.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub proposal_id: u64,
+    pub entries: Vec<String>,
+    /// Entries that could not be fetched and were left out
+    pub errors: Vec<BundleFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFailure {
+    pub entry: String,
+    pub error: String,
+}
+
+/// Zip bytes of a proposal bundle, produced as the archive is written
+pub type BundleStream = mpsc::Receiver<io::Result<Bytes>>;
+
+/// Stream a zip of the proposal's detail, each attachment and its votes.
+/// Missing proposals fail up front; attachments that can't be fetched are
+/// listed in the manifest instead of aborting the download.
+pub async fn proposal_bundle(engine: &GovernanceEngine, proposal_id: u64) -> Result<BundleStream> {
+    let detail = engine.get_proposal_detail(proposal_id).await?;
+    let votes = engine.proposal_votes(proposal_id, None).await?;
+    let ipfs = engine.ipfs_client().clone();
+
+    let (tx, rx) = mpsc::channel(BUNDLE_CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut errors = tx.clone();
+        if let Err(e) = write_bundle(tx, &ipfs, detail, votes).await {
+            tracing::warn!("Bundle for proposal {} failed: {}", proposal_id, e);
+            let _ = errors.send(Err(e)).await;
+        }
+    });
+
+    Ok(rx)
+}
+
+async fn write_bundle(
+    tx: mpsc::Sender<io::Result<Bytes>>,
+    ipfs: &IpfsClient,
+    detail: ProposalDetail,
+    votes: ProposalVotes,
+) -> io::Result<()> {
+    let mut bundle = BundleWriter::new(tx, detail.id);
+
+    bundle.add_json("proposal.json", &detail)?;
+    if !bundle.flush().await {
+        return Ok(());
+    }
+
+    for hash in &detail.metadata.attachments {
+        let entry = format!("attachments/{}", hash);
+        if enclosed_name(&entry).is_none() {
+            bundle.manifest.errors.push(BundleFailure {
+                entry,
+                error: "Attachment name escapes the bundle".to_string(),
+            });
+            continue;
+        }
+        match ipfs.get_bytes(hash).await {
+            Ok(bytes) => bundle.add(&entry, &bytes)?,
+            Err(e) => bundle.manifest.errors.push(BundleFailure {
+                entry,
+                error: e.to_string(),
+            }),
+        }
+        if !bundle.flush().await {
+            return Ok(());
+        }
+    }
+
+    bundle.add_json("votes.json", &votes)?;
+    bundle.finish().await
+}
+
+/// `name` if it stays inside the archive when extracted: relative, with no
+/// `..` segments, backslashes or NUL bytes. Stricter than what
+/// `ZipFile::enclosed_name` accepts on the reading side, so every entry
+/// written here passes it.
+fn enclosed_name(name: &str) -> Option<&str> {
+    let path = Path::new(name);
+    let normal = path.components().all(|component| matches!(component, Component::Normal(_)));
+    (normal && !name.is_empty() && !name.contains(['\0', '\\'])).then_some(name)
+}
+
+/// Collects what the zip writer produces so it can be sent entry by entry
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct BundleWriter {
+    zip: ZipWriter<StreamWriter<SharedBuffer>>,
+    buffer: SharedBuffer,
+    tx: mpsc::Sender<io::Result<Bytes>>,
+    manifest: BundleManifest,
+}
+
+impl BundleWriter {
+    fn new(tx: mpsc::Sender<io::Result<Bytes>>, proposal_id: u64) -> Self {
+        let buffer = SharedBuffer::default();
+        Self {
+            zip: ZipWriter::new_stream(buffer.clone()),
+            buffer,
+            tx,
+            manifest: BundleManifest {
+                proposal_id,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn add(&mut self, name: &str, bytes: &[u8]) -> io::Result<()> {
+        if enclosed_name(name).is_none() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unsafe entry name {:?}", name)));
+        }
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        self.zip.start_file(name, options).map_err(io::Error::other)?;
+        self.zip.write_all(bytes)?;
+        self.manifest.entries.push(name.to_string());
+        Ok(())
+    }
+
+    fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> io::Result<()> {
+        let bytes = serde_json::to_vec_pretty(value)?;
+        self.add(name, &bytes)
+    }
+
+    /// Send what has been written so far. `false` once the client has gone.
+    async fn flush(&mut self) -> bool {
+        let chunk = self.buffer.take();
+        chunk.is_empty() || self.tx.send(Ok(Bytes::from(chunk))).await.is_ok()
+    }
+
+    async fn finish(mut self) -> io::Result<()> {
+        let manifest = std::mem::take(&mut self.manifest);
+        self.add_json("manifest.json", &manifest)?;
+        self.zip.finish().map_err(io::Error::other)?;
+        self.flush().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::client::SomniaClient;
+    use crate::config::Config;
+    use crate::ipfs::content_types::{ProposalIPFSContent, ProposalMetadata};
+    use ethers::types::Address;
+    use futures::StreamExt;
+    use std::io::{Cursor, Read};
+
+    async fn bundle_archive(attachments: Vec<String>, engine: &GovernanceEngine) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let content = ProposalIPFSContent {
+            title: "Bundled Proposal".to_string(),
+            description: "Proposal with attachments.".to_string(),
+            metadata: ProposalMetadata {
+                attachments,
+                ..Default::default()
+            },
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+        engine.cast_vote(Address::random(), proposal.id, 1, None).await.unwrap();
+
+        let chunks: Vec<_> = proposal_bundle(engine, proposal.id).await.unwrap().collect().await;
+        let bytes: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.unwrap().to_vec()).collect();
+        zip::ZipArchive::new(Cursor::new(bytes)).unwrap()
+    }
+
+    fn read_manifest(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>) -> BundleManifest {
+        let mut json = String::new();
+        archive.by_name("manifest.json").unwrap().read_to_string(&mut json).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    async fn mock_engine() -> GovernanceEngine {
+        let config = Config::default();
+        GovernanceEngine::new(SomniaClient::mock(&config), IpfsClient::in_memory(&config))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bundle_contains_proposal_attachments_and_votes() {
+        let engine = mock_engine().await;
+        let attachment = engine
+            .ipfs_client()
+            .add_json(&serde_json::json!({ "budget": 1200 }))
+            .await
+            .unwrap();
+
+        let mut archive = bundle_archive(vec![attachment.clone()], &engine).await;
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                format!("attachments/{}", attachment),
+                "manifest.json".to_string(),
+                "proposal.json".to_string(),
+                "votes.json".to_string(),
+            ]
+        );
+
+        let mut votes = String::new();
+        archive.by_name("votes.json").unwrap().read_to_string(&mut votes).unwrap();
+        let votes: serde_json::Value = serde_json::from_str(&votes).unwrap();
+        assert_eq!(votes["votes"].as_array().unwrap().len(), 1);
+        assert!(read_manifest(&mut archive).errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_attachment_noted_in_manifest() {
+        let engine = mock_engine().await;
+        let missing = "Qm".to_string() + &"0".repeat(44);
+
+        let mut archive = bundle_archive(vec![missing.clone()], &engine).await;
+        assert!(archive.by_name("proposal.json").is_ok());
+        assert!(archive.by_name(&format!("attachments/{}", missing)).is_err());
+
+        let manifest = read_manifest(&mut archive);
+        assert_eq!(manifest.errors.len(), 1);
+        assert_eq!(manifest.errors[0].entry, format!("attachments/{}", missing));
+        assert!(manifest.entries.contains(&"votes.json".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_entry_names_stay_inside_the_archive() {
+        for name in ["../x", "attachments/../../x", "/etc/passwd", "./x", "a\\..\\x", "a\0b", ""] {
+            assert_eq!(enclosed_name(name), None, "{:?}", name);
+        }
+
+        let (tx, rx) = mpsc::channel(BUNDLE_CHANNEL_CAPACITY);
+        let mut bundle = BundleWriter::new(tx, 1);
+        let error = bundle.add("attachments/../../x", b"escape").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        bundle.add("attachments/QmSafe", b"safe").unwrap();
+        bundle.finish().await.unwrap();
+
+        let chunks: Vec<_> = rx.collect().await;
+        let bytes: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.unwrap().to_vec()).collect();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+        for index in 0..archive.len() {
+            assert!(archive.by_index(index).unwrap().enclosed_name().is_some());
+        }
+        assert!(read_manifest(&mut archive).entries.iter().all(|entry| !entry.contains("..")));
+    }
+}
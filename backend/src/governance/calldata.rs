@@ -0,0 +1,209 @@
+use crate::ipfs::content_types::ExecutionData;
+use crate::utils::errors::{GovernanceError, Result};
+use ethers::abi::{Function, HumanReadableParser, Param, Token};
+use ethers::types::I256;
+use serde::{Deserialize, Serialize};
+
+/// A proposal's execution call in human terms, for voters. Calldata that
+/// doesn't fit the stated signature is reported rather than failing the
+/// proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExecutionCall {
+    Decoded(DecodedCall),
+    Undecodable { function_signature: String, error: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedCall {
+    pub function: String,
+    pub parameters: Vec<DecodedParameter>,
+    /// The call as one line, e.g. `transfer(to: 0x..., amount: 1000)`
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedParameter {
+    /// Parameter name, when the signature gives one
+    pub name: Option<String>,
+    /// Solidity type, e.g. `uint256`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
+
+pub fn decode_execution_call(execution: &ExecutionData) -> ExecutionCall {
+    match decode_call(&execution.function_signature, &execution.call_data) {
+        Ok(call) => ExecutionCall::Decoded(call),
+        Err(e) => ExecutionCall::Undecodable {
+            function_signature: execution.function_signature.clone(),
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Decode hex `call_data` against `signature`, such as
+/// `transfer(address to, uint256 amount)` or `transfer(address,uint256)`.
+/// The calldata must start with the signature's selector.
+pub fn decode_call(signature: &str, call_data: &str) -> Result<DecodedCall> {
+    let function = parse_signature(signature)?;
+
+    let call_data = call_data.trim();
+    let call_data = hex::decode(call_data.strip_prefix("0x").unwrap_or(call_data))
+        .map_err(|_| GovernanceError::invalid_request("Execution call data must be hex encoded"))?;
+    let Some((selector, arguments)) = call_data.split_first_chunk::<4>() else {
+        return Err(GovernanceError::invalid_request("Execution call data is shorter than a function selector"));
+    };
+    if *selector != function.short_signature() {
+        return Err(GovernanceError::invalid_request(format!(
+            "Execution call data selector 0x{} does not match {}",
+            hex::encode(selector),
+            function.signature()
+        )));
+    }
+
+    let tokens = function.decode_input(arguments).map_err(|e| {
+        GovernanceError::invalid_request(format!("Execution call data does not match {}: {}", function.signature(), e))
+    })?;
+    let parameters = decoded_parameters(&function.inputs, &tokens);
+
+    let arguments: Vec<String> = parameters
+        .iter()
+        .map(|parameter| match &parameter.name {
+            Some(name) => format!("{}: {}", name, parameter.value),
+            None => parameter.value.clone(),
+        })
+        .collect();
+    Ok(DecodedCall {
+        summary: format!("{}({})", function.name, arguments.join(", ")),
+        function: function.name,
+        parameters,
+    })
+}
+
+/// Decode what a call to `signature` returned, when the signature declares
+/// its outputs, such as `balanceOf(address) returns (uint256)`. `None` if it
+/// declares none, since there is nothing to decode against.
+pub fn decode_return_data(signature: &str, return_data: &[u8]) -> Result<Option<Vec<DecodedParameter>>> {
+    let function = parse_signature(signature)?;
+    if function.outputs.is_empty() {
+        return Ok(None);
+    }
+
+    let tokens = function.decode_output(return_data).map_err(|e| {
+        GovernanceError::invalid_request(format!("Return data does not match {}: {}", function.signature(), e))
+    })?;
+    Ok(Some(decoded_parameters(&function.outputs, &tokens)))
+}
+
+fn decoded_parameters(params: &[Param], tokens: &[Token]) -> Vec<DecodedParameter> {
+    params
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| DecodedParameter {
+            name: (!param.name.is_empty()).then(|| param.name.clone()),
+            kind: param.kind.to_string(),
+            value: format_token(token),
+        })
+        .collect()
+}
+
+fn parse_signature(signature: &str) -> Result<Function> {
+    let signature = signature.trim();
+    let signature = match signature.strip_prefix("function ") {
+        Some(_) => signature.to_string(),
+        None => format!("function {}", signature),
+    };
+    HumanReadableParser::parse_function(&signature)
+        .map_err(|e| GovernanceError::invalid_request(format!("Invalid function signature: {}", e)))
+}
+
+/// Render a value the way a voter would read it: decimal numbers, checksummed
+/// addresses and hex bytes
+fn format_token(token: &Token) -> String {
+    let list = |tokens: &[Token]| tokens.iter().map(format_token).collect::<Vec<_>>().join(", ");
+    match token {
+        Token::Address(address) => ethers::utils::to_checksum(address, None),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{:?}", value),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Array(tokens) | Token::FixedArray(tokens) => format!("[{}]", list(tokens)),
+        Token::Tuple(tokens) => format!("({})", list(tokens)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER: &str = "0xa9059cbb\
+        000000000000000000000000000000000000000000000000000000000000dead\
+        00000000000000000000000000000000000000000000000000000000000003e8";
+
+    #[test]
+    fn test_decodes_named_parameters() {
+        let call = decode_call("transfer(address to, uint256 amount)", TRANSFER).unwrap();
+
+        assert_eq!(call.function, "transfer");
+        assert_eq!(
+            call.parameters,
+            vec![
+                DecodedParameter {
+                    name: Some("to".to_string()),
+                    kind: "address".to_string(),
+                    value: "0x000000000000000000000000000000000000dEaD".to_string(),
+                },
+                DecodedParameter {
+                    name: Some("amount".to_string()),
+                    kind: "uint256".to_string(),
+                    value: "1000".to_string(),
+                },
+            ]
+        );
+        assert_eq!(call.summary, "transfer(to: 0x000000000000000000000000000000000000dEaD, amount: 1000)");
+
+        // Unnamed parameters decode the same, without names
+        let call = decode_call("transfer(address,uint256)", TRANSFER).unwrap();
+        assert_eq!(call.summary, "transfer(0x000000000000000000000000000000000000dEaD, 1000)");
+    }
+
+    #[test]
+    fn test_mismatched_calldata_is_reported() {
+        let undecodable = |signature: &str, call_data: &str| {
+            let execution = ExecutionData {
+                target_contract: "0x000000000000000000000000000000000000dEaD".to_string(),
+                function_signature: signature.to_string(),
+                call_data: call_data.to_string(),
+                value: "0".to_string(),
+            };
+            match decode_execution_call(&execution) {
+                ExecutionCall::Undecodable { error, .. } => error,
+                ExecutionCall::Decoded(call) => panic!("decoded {}", call.summary),
+            }
+        };
+
+        assert!(undecodable("approve(address spender, uint256 amount)", TRANSFER).contains("selector"));
+        // Right selector, arguments cut short
+        assert!(undecodable("transfer(address to, uint256 amount)", &TRANSFER[..74]).contains("does not match"));
+        assert!(undecodable("transfer(address to, uint256 amount)", "0xa905").contains("shorter"));
+        assert!(undecodable("transfer(address to, uint256 amount)", "not hex").contains("hex"));
+        assert!(undecodable("transfer(address to", TRANSFER).contains("Invalid function signature"));
+    }
+
+    #[test]
+    fn test_decodes_declared_return_values() {
+        let returned = ethers::abi::encode(&[Token::Bool(true), Token::Uint(1000.into())]);
+        let outputs = decode_return_data("transfer(address to, uint256 amount) returns (bool ok, uint256)", &returned)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            outputs.iter().map(|output| (output.name.as_deref(), output.value.as_str())).collect::<Vec<_>>(),
+            vec![(Some("ok"), "true"), (None, "1000")]
+        );
+
+        assert_eq!(decode_return_data("transfer(address,uint256)", &returned).unwrap(), None);
+        assert!(decode_return_data("transfer(address,uint256) returns (bool)", &[0x01]).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::governance::voting::PowerSource;
+use crate::utils::helpers::PaginatedResponse;
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+
+/// Delegator -> delegate edges, mirroring on-chain delegation
+#[derive(Clone, Default)]
+pub struct DelegationRegistry {
+    delegations: Arc<RwLock<HashMap<Address, Address>>>,
+}
+
+impl DelegationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `delegator` delegates its power to `delegate`.
+    /// Returns false for self-delegation, which is a no-op.
+    pub fn set_delegate(&self, delegator: Address, delegate: Address) -> bool {
+        if delegator == delegate {
+            return false;
+        }
+
+        self.delegations.write().unwrap().insert(delegator, delegate);
+        true
+    }
+
+    pub fn remove_delegate(&self, delegator: Address) -> Option<Address> {
+        self.delegations.write().unwrap().remove(&delegator)
+    }
+
+    pub fn delegate_of(&self, delegator: Address) -> Option<Address> {
+        self.delegations.read().unwrap().get(&delegator).copied()
+    }
+
+    /// Addresses delegating directly to `delegate`
+    pub fn direct_delegators(&self, delegate: Address) -> Vec<Address> {
+        let mut delegators: Vec<Address> = self
+            .delegations
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, to)| **to == delegate)
+            .map(|(from, _)| *from)
+            .collect();
+        delegators.sort();
+        delegators
+    }
+
+    /// Every address whose power flows to `delegate`, following delegation
+    /// chains (A -> B -> delegate). Cycles are cut at the first revisit.
+    pub fn transitive_delegators(&self, delegate: Address) -> Vec<Address> {
+        let delegations = self.delegations.read().unwrap();
+        let mut visited = HashSet::from([delegate]);
+        let mut queue = VecDeque::from([delegate]);
+        let mut delegators = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            for (from, to) in delegations.iter() {
+                if *to == current && visited.insert(*from) {
+                    delegators.push(*from);
+                    queue.push_back(*from);
+                }
+            }
+        }
+
+        delegators.sort();
+        delegators
+    }
+
+    /// Addresses `delegator`'s power flows through, nearest first
+    /// (delegator -> A -> B gives [A, B]). Cycles are cut at the first revisit.
+    pub fn delegate_chain(&self, delegator: Address) -> Vec<Address> {
+        let delegations = self.delegations.read().unwrap();
+        let mut visited = HashSet::from([delegator]);
+        let mut chain = Vec::new();
+        let mut current = delegator;
+        while let Some(&delegate) = delegations.get(&current) {
+            if !visited.insert(delegate) {
+                break;
+            }
+            chain.push(delegate);
+            current = delegate;
+        }
+        chain
+    }
+}
+
+/// Apply the per-delegate cap to received power, returning the counted
+/// amount and whether the cap was hit
+pub fn clamp_delegated_power(received: U256, cap: Option<U256>) -> (U256, bool) {
+    match cap {
+        Some(cap) if received > cap => (cap, true),
+        _ => (received, false),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationDirection {
+    /// Addresses delegating to the subject
+    Incoming,
+    /// The address the subject delegates to
+    Outgoing,
+}
+
+/// One delegation edge and the power currently flowing along it, including
+/// power delegated on to the delegator from further up the chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationEntry {
+    pub address: Address,
+    pub power: U256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationListing {
+    pub address: Address,
+    pub direction: DelegationDirection,
+    /// Sum over this page's entries; a delegate's total across pages is its
+    /// `DelegateStats::received_power`
+    pub page_power: U256,
+    #[serde(flatten)]
+    pub entries: PaginatedResponse<DelegationEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegateStats {
+    pub delegate: Address,
+    pub own_power: U256,
+    pub delegator_count: usize,
+    pub received_power: U256,
+    pub counted_power: U256,
+    pub effective_power: U256,
+    pub cap: Option<U256>,
+    pub cap_reached: bool,
+    pub warning: Option<String>,
+    pub power_source: PowerSource,
+}
+
+impl DelegateStats {
+    pub fn new(
+        delegate: Address,
+        own_power: U256,
+        delegator_count: usize,
+        received_power: U256,
+        cap: Option<U256>,
+    ) -> Self {
+        let (counted_power, cap_reached) = clamp_delegated_power(received_power, cap);
+        let warning = cap_reached.then(|| {
+            format!(
+                "Delegated power {} exceeds the per-delegate cap; only {} is counted",
+                received_power, counted_power
+            )
+        });
+
+        Self {
+            delegate,
+            own_power,
+            delegator_count,
+            received_power,
+            counted_power,
+            effective_power: own_power + counted_power,
+            cap,
+            cap_reached,
+            warning,
+            power_source: PowerSource::Live,
+        }
+    }
+
+    pub fn with_power_source(mut self, power_source: PowerSource) -> Self {
+        self.power_source = power_source;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitive_delegators_handle_chains_and_cycles() {
+        let registry = DelegationRegistry::new();
+        let (a, b, c, d) = (Address::random(), Address::random(), Address::random(), Address::random());
+
+        registry.set_delegate(a, b);
+        registry.set_delegate(b, c);
+        registry.set_delegate(d, c);
+        assert!(!registry.set_delegate(c, c));
+
+        let mut expected = vec![a, b, d];
+        expected.sort();
+        assert_eq!(registry.transitive_delegators(c), expected);
+        assert_eq!(registry.direct_delegators(b), vec![a]);
+
+        // A cycle must terminate
+        registry.set_delegate(c, a);
+        assert_eq!(registry.transitive_delegators(c).len(), 3);
+    }
+
+    #[test]
+    fn test_clamp_delegated_power() {
+        let cap = Some(U256::from(1500));
+        assert_eq!(clamp_delegated_power(U256::from(2000), cap), (U256::from(1500), true));
+        assert_eq!(clamp_delegated_power(U256::from(1000), cap), (U256::from(1000), false));
+        assert_eq!(clamp_delegated_power(U256::from(2000), None), (U256::from(2000), false));
+    }
+
+    #[test]
+    fn test_stats_warn_when_capped() {
+        let stats = DelegateStats::new(Address::zero(), U256::from(10), 2, U256::from(50), Some(U256::from(20)));
+        assert_eq!(stats.effective_power, U256::from(30));
+        assert!(stats.cap_reached);
+        assert!(stats.warning.is_some());
+
+        let stats = DelegateStats::new(Address::zero(), U256::from(10), 2, U256::from(5), Some(U256::from(20)));
+        assert_eq!(stats.effective_power, U256::from(15));
+        assert!(stats.warning.is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::ipfs::content_types::ProposalIPFSContent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use std::collections::BTreeSet;
+
+/// What changed between two content versions of a proposal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalDiff {
+    pub proposal_id: u64,
+    pub from: String,
+    pub to: String,
+    /// Changed fields other than the description, e.g. `title` or `metadata.tags`
+    pub fields: Vec<FieldChange>,
+    /// Line diff of the description; empty when it is unchanged
+    pub description: Vec<DescriptionLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineChange {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DescriptionLine {
+    pub change: LineChange,
+    pub text: String,
+}
+
+/// Field-level diff of two versions of a proposal's content
+pub fn diff_proposal_content(
+    proposal_id: u64,
+    from_hash: &str,
+    from: &ProposalIPFSContent,
+    to_hash: &str,
+    to: &ProposalIPFSContent,
+) -> ProposalDiff {
+    let mut fields = Vec::new();
+    if from.title != to.title {
+        fields.push(FieldChange {
+            field: "title".to_string(),
+            from: Value::String(from.title.clone()),
+            to: Value::String(to.title.clone()),
+        });
+    }
+    fields.extend(diff_metadata(
+        serde_json::to_value(&from.metadata).unwrap_or_default(),
+        serde_json::to_value(&to.metadata).unwrap_or_default(),
+    ));
+
+    let description = if from.description == to.description {
+        Vec::new()
+    } else {
+        diff_lines(&from.description, &to.description)
+    };
+
+    ProposalDiff {
+        proposal_id,
+        from: from_hash.to_string(),
+        to: to_hash.to_string(),
+        fields,
+        description,
+    }
+}
+
+fn diff_metadata(from: Value, to: Value) -> Vec<FieldChange> {
+    let (Value::Object(from), Value::Object(to)) = (from, to) else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = from.get(key).cloned().unwrap_or(Value::Null);
+            let after = to.get(key).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| FieldChange {
+                field: format!("metadata.{}", key),
+                from: before,
+                to: after,
+            })
+        })
+        .collect()
+}
+
+fn diff_lines(from: &str, to: &str) -> Vec<DescriptionLine> {
+    TextDiff::from_lines(from, to)
+        .iter_all_changes()
+        .map(|change| DescriptionLine {
+            change: match change.tag() {
+                ChangeTag::Equal => LineChange::Unchanged,
+                ChangeTag::Insert => LineChange::Added,
+                ChangeTag::Delete => LineChange::Removed,
+            },
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipfs::content_types::ProposalMetadata;
+
+    fn content(title: &str, description: &str) -> ProposalIPFSContent {
+        ProposalIPFSContent {
+            title: title.to_string(),
+            description: description.to_string(),
+            metadata: ProposalMetadata::default(),
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_reports_changed_title_and_description_lines() {
+        let from = content("Fund the grants pool", "Summary\nSend 100 SOMI to grants\nReview in March");
+        let mut to = content("Fund the grants pool (amended)", "Summary\nSend 250 SOMI to grants\nReview in March");
+        to.metadata.tags = vec!["treasury".to_string()];
+
+        let diff = diff_proposal_content(7, "QmFrom", &from, "QmTo", &to);
+
+        assert_eq!(
+            diff.fields,
+            vec![
+                FieldChange {
+                    field: "title".to_string(),
+                    from: "Fund the grants pool".into(),
+                    to: "Fund the grants pool (amended)".into(),
+                },
+                FieldChange {
+                    field: "metadata.tags".to_string(),
+                    from: serde_json::json!([]),
+                    to: serde_json::json!(["treasury"]),
+                },
+            ]
+        );
+
+        let line = |change, text: &str| DescriptionLine {
+            change,
+            text: text.to_string(),
+        };
+        assert_eq!(
+            diff.description,
+            vec![
+                line(LineChange::Unchanged, "Summary"),
+                line(LineChange::Removed, "Send 100 SOMI to grants"),
+                line(LineChange::Added, "Send 250 SOMI to grants"),
+                line(LineChange::Unchanged, "Review in March"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical_versions_have_no_changes() {
+        let version = content("Same", "Unchanged text");
+        let diff = diff_proposal_content(1, "QmA", &version, "QmB", &version);
+        assert!(diff.fields.is_empty());
+        assert!(diff.description.is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+use crate::config::{DEFAULT_DRAFT_EXPIRY_WARNING, DEFAULT_DRAFT_TTL};
+use crate::ipfs::content_types::{ProposalIPFSContent, ProposalMetadata};
+use crate::storage::kv::{MemoryKvStore, SharedKvStore};
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::utils::errors::{GovernanceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Period of the background sweep dropping expired drafts
+pub const DRAFT_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Editable part of a proposal draft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftContent {
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub metadata: ProposalMetadata,
+}
+
+impl DraftContent {
+    /// The content a proposal submitted from this draft at `created_at` would carry
+    pub fn into_proposal_content(self, created_at: DateTime<Utc>) -> ProposalIPFSContent {
+        ProposalIPFSContent {
+            title: self.title,
+            description: self.description,
+            metadata: self.metadata,
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalDraft {
+    pub id: String,
+    pub author: Address,
+    #[serde(flatten)]
+    pub content: DraftContent,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A draft as returned to its author. `expiring_soon` is set once the draft
+/// is within the expiry warning window, so clients can prompt an edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftView {
+    #[serde(flatten)]
+    pub draft: ProposalDraft,
+    pub expires_at: DateTime<Utc>,
+    pub expiring_soon: bool,
+}
+
+/// Proposal drafts by author, kept in a `KvStore` under
+/// `draft/<author>/<id>`. A draft expires `ttl` after its last edit; each
+/// edit starts the TTL over.
+#[derive(Clone)]
+pub struct DraftStore {
+    store: SharedKvStore,
+    clock: SharedClock,
+    ttl: Duration,
+    expiry_warning: Duration,
+}
+
+impl DraftStore {
+    pub fn new(store: SharedKvStore) -> Self {
+        Self {
+            store,
+            clock: system_clock(),
+            ttl: Duration::seconds(DEFAULT_DRAFT_TTL as i64),
+            expiry_warning: Duration::seconds(DEFAULT_DRAFT_EXPIRY_WARNING as i64),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration, expiry_warning: Duration) -> Self {
+        self.ttl = ttl;
+        self.expiry_warning = expiry_warning;
+        self
+    }
+
+    pub fn create(&self, author: Address, content: DraftContent) -> Result<DraftView> {
+        let now = self.clock.now();
+        let draft = ProposalDraft {
+            id: uuid::Uuid::new_v4().to_string(),
+            author,
+            content,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save(&draft)?;
+        Ok(self.view(draft))
+    }
+
+    /// Replace a draft's content, restarting its TTL
+    pub fn update(&self, author: Address, id: &str, content: DraftContent) -> Result<DraftView> {
+        let mut draft = self.load(author, id)?.ok_or_else(|| draft_not_found(id))?;
+        draft.content = content;
+        draft.updated_at = self.clock.now();
+        self.save(&draft)?;
+        Ok(self.view(draft))
+    }
+
+    pub fn get(&self, author: Address, id: &str) -> Result<Option<DraftView>> {
+        Ok(self.load(author, id)?.map(|draft| self.view(draft)))
+    }
+
+    /// The author's live drafts, most recently edited first
+    pub fn list(&self, author: Address) -> Result<Vec<DraftView>> {
+        let mut drafts = self
+            .store
+            .list(&format!("draft/{:?}/", author))?
+            .into_iter()
+            .map(|(_, value)| decode(&value))
+            .collect::<Result<Vec<_>>>()?;
+        drafts.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(drafts.into_iter().map(|draft| self.view(draft)).collect())
+    }
+
+    /// Returns whether the draft existed
+    pub fn delete(&self, author: Address, id: &str) -> Result<bool> {
+        self.store.delete(&key(author, id))
+    }
+
+    /// Drop drafts past their TTL, along with any other expired entries in
+    /// the shared store. Returns how many were dropped.
+    pub fn purge_expired(&self) -> Result<usize> {
+        self.store.purge_expired()
+    }
+
+    /// Start the background sweep of expired drafts, every `period`
+    pub fn start_purge_task(&self, period: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let drafts = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match drafts.purge_expired() {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::debug!("Purged {} expired proposal drafts", purged),
+                    Err(e) => tracing::warn!("Failed to purge expired proposal drafts: {}", e),
+                }
+            }
+        })
+    }
+
+    fn load(&self, author: Address, id: &str) -> Result<Option<ProposalDraft>> {
+        self.store.get(&key(author, id))?.map(|value| decode(&value)).transpose()
+    }
+
+    fn save(&self, draft: &ProposalDraft) -> Result<()> {
+        let value = serde_json::to_vec(draft)?;
+        self.store.put(&key(draft.author, &draft.id), &value, Some(self.ttl))
+    }
+
+    fn view(&self, draft: ProposalDraft) -> DraftView {
+        let expires_at = draft.updated_at + self.ttl;
+        DraftView {
+            expiring_soon: expires_at - self.clock.now() <= self.expiry_warning,
+            expires_at,
+            draft,
+        }
+    }
+}
+
+impl Default for DraftStore {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryKvStore::new()))
+    }
+}
+
+fn key(author: Address, id: &str) -> String {
+    format!("draft/{:?}/{}", author, id)
+}
+
+fn decode(value: &[u8]) -> Result<ProposalDraft> {
+    Ok(serde_json::from_slice(value)?)
+}
+
+pub fn draft_not_found(id: &str) -> GovernanceError {
+    GovernanceError::not_found(format!("Draft {} not found", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+
+    fn content(title: &str) -> DraftContent {
+        DraftContent {
+            title: title.to_string(),
+            description: "Work in progress".to_string(),
+            metadata: ProposalMetadata::default(),
+        }
+    }
+
+    fn drafts(clock: &MockClock) -> DraftStore {
+        let clock: SharedClock = Arc::new(clock.clone());
+        DraftStore::new(Arc::new(MemoryKvStore::new().with_clock(clock.clone())))
+            .with_clock(clock)
+            .with_ttl(Duration::days(7), Duration::days(1))
+    }
+
+    #[test]
+    fn test_draft_older_than_ttl_is_purged() {
+        let clock = MockClock::default();
+        let drafts = drafts(&clock);
+        let author = Address::random();
+        let draft = drafts.create(author, content("Grants round")).unwrap();
+
+        clock.advance(Duration::days(7) - Duration::seconds(1));
+        assert!(drafts.get(author, &draft.draft.id).unwrap().is_some());
+        assert_eq!(drafts.purge_expired().unwrap(), 0);
+
+        clock.advance(Duration::seconds(1));
+        assert_eq!(drafts.purge_expired().unwrap(), 1);
+        assert!(drafts.get(author, &draft.draft.id).unwrap().is_none());
+        assert!(drafts.list(author).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_editing_a_draft_resets_its_ttl() {
+        let clock = MockClock::default();
+        let drafts = drafts(&clock);
+        let author = Address::random();
+        let created = drafts.create(author, content("Grants round")).unwrap();
+
+        clock.advance(Duration::days(6));
+        let edited = drafts.update(author, &created.draft.id, content("Grants round, revised")).unwrap();
+        assert_eq!(edited.expires_at, created.expires_at + Duration::days(6));
+
+        clock.advance(Duration::days(6));
+        assert_eq!(drafts.purge_expired().unwrap(), 0);
+        let fetched = drafts.get(author, &created.draft.id).unwrap().unwrap();
+        assert_eq!(fetched.draft.content.title, "Grants round, revised");
+
+        // Drafts are only reachable by their author
+        assert!(drafts.update(Address::random(), &created.draft.id, content("Hijacked")).is_err());
+    }
+
+    #[test]
+    fn test_near_expiry_warning_within_window() {
+        let clock = MockClock::default();
+        let drafts = drafts(&clock);
+        let author = Address::random();
+        let draft = drafts.create(author, content("Grants round")).unwrap();
+        assert!(!draft.expiring_soon);
+
+        clock.advance(Duration::days(6) - Duration::seconds(1));
+        assert!(!drafts.get(author, &draft.draft.id).unwrap().unwrap().expiring_soon);
+
+        clock.advance(Duration::seconds(1));
+        assert!(drafts.get(author, &draft.draft.id).unwrap().unwrap().expiring_soon);
+        assert!(drafts.list(author).unwrap()[0].expiring_soon);
+    }
+}
@@ -1,12 +1,76 @@
+use crate::auth::signature_verification::SignatureVerifier;
+use crate::blockchain::callbacks::validate_callback_url;
 use crate::blockchain::client::SomniaClient;
+use crate::blockchain::contracts::{ExecutionOutcome, ExecutionResult, ProposalCreatedEvent, ProposalStatus};
+use crate::blockchain::transactions::{PendingTransaction, PendingTransactions, TransactionType};
+use crate::config::{GovernanceConfig, TiePolicy, VotingPowerFallback, ZeroPowerVotes};
+use crate::governance::activity::{activity_feed, ActivityEntry};
+use crate::governance::analytics::{
+    build_vote_distribution, turnout_bps, QuorumFeasibility, VoteDistribution, BINARY_CHOICE_LABELS,
+};
+use crate::governance::audit_trail::{audit_trail, AuditEntry, ProposalAuditLog, ProposalEvent};
+use crate::governance::calldata::decode_return_data;
+use crate::governance::delegation::{
+    DelegateStats, DelegationDirection, DelegationEntry, DelegationListing, DelegationRegistry,
+};
+use crate::governance::diff::{diff_proposal_content, ProposalDiff};
+use crate::governance::drafts::DraftContent;
+use crate::governance::moderation::{
+    moderator_for, ContentModerator, FlaggedVote, ModerationQueue, ModerationVerdict, NoopModerator,
+};
+use crate::governance::participation::{Notifier, ParticipationAlert, ParticipationMonitor};
+use crate::governance::proposal_limits::ProposalRateLimiter;
+use crate::governance::proposals::{
+    tally_binary, BinaryVerdict, ExecutionSimulation, GovernanceCapabilities, HypotheticalVote, ProposalDetail,
+    ProposalEstimate, ProposalFinalization, ProposalListEntry, ProposalResults, ProposalStatusEntry,
+    ProposalStatusSummary, ProposalVotes, VotingDurationOptions, WhatIfTally, MAX_STATUS_BATCH,
+};
+use crate::governance::receipts::{FinalizedVotes, VoteCommitments, VoteInclusionProof, VoteSetCommitment};
+use crate::governance::signed_votes::{
+    tally_signed_votes, vote_domain, SignedVote, SignedVoteAggregate, SignedVoteStore, VoteMessage,
+};
+use crate::governance::voting::{
+    CastVoteOutcome, PendingVotes, PowerSnapshots, PowerSource, PreflightCheck, SnapshotBlocks, VotePreflight,
+};
+use crate::governance::watch::{WatchDispatcher, WatchNotifier};
+use crate::indexer::content_indexer::{ContentIndexer, IndexedProposal, IndexedVote, ProposalKey, TrendingProposal};
 use crate::ipfs::client::IpfsClient;
-use crate::utils::errors::Result;
+use crate::ipfs::content_types::{NotificationSettings, ProposalIPFSContent, ProposalType, VoteChoice};
+use crate::ipfs::validation::{normalize_title, validate_proposal_content};
+use crate::storage::kv::SharedKvStore;
+use crate::utils::clock::{system_clock, Clock, SharedClock};
+use crate::utils::errors::{GovernanceError, Result};
+use crate::utils::helpers::{PaginatedResponse, PaginationParams};
+use crate::utils::validation::{
+    validate_voting_duration, MAX_VOTING_DURATION, MIN_REMAINING_VOTING_TIME, MIN_VOTING_DURATION,
+};
+use ethers::types::transaction::eip712::EIP712Domain;
+use ethers::types::{Address, Bytes, TransactionReceipt, TransactionRequest, H256, U256};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct GovernanceEngine {
     blockchain_client: Arc<SomniaClient>,
     ipfs_client: Arc<IpfsClient>,
+    indexer: ContentIndexer,
+    pending_votes: PendingVotes,
+    power_snapshots: PowerSnapshots,
+    snapshot_blocks: SnapshotBlocks,
+    delegations: DelegationRegistry,
+    participation: ParticipationMonitor,
+    watches: WatchDispatcher,
+    moderator: Arc<dyn ContentModerator>,
+    moderation_queue: ModerationQueue,
+    vote_commitments: VoteCommitments,
+    signed_votes: SignedVoteStore,
+    signature_verifier: SignatureVerifier,
+    proposal_limiter: ProposalRateLimiter,
+    audit_log: ProposalAuditLog,
+    /// Submitted transactions, and the callbacks waiting on them
+    pending_transactions: PendingTransactions,
+    config: GovernanceConfig,
+    clock: SharedClock,
 }
 
 impl GovernanceEngine {
@@ -17,9 +81,74 @@ impl GovernanceEngine {
         Ok(Self {
             blockchain_client: Arc::new(blockchain_client),
             ipfs_client: Arc::new(ipfs_client),
+            indexer: ContentIndexer::new(),
+            pending_votes: PendingVotes::new(),
+            power_snapshots: PowerSnapshots::default(),
+            snapshot_blocks: SnapshotBlocks::default(),
+            delegations: DelegationRegistry::new(),
+            participation: ParticipationMonitor::default(),
+            watches: WatchDispatcher::default(),
+            moderator: Arc::new(NoopModerator),
+            moderation_queue: ModerationQueue::default(),
+            vote_commitments: VoteCommitments::default(),
+            signed_votes: SignedVoteStore::default(),
+            signature_verifier: SignatureVerifier::new(),
+            proposal_limiter: ProposalRateLimiter::default(),
+            audit_log: ProposalAuditLog::default(),
+            pending_transactions: PendingTransactions::new(),
+            config: GovernanceConfig::default(),
+            clock: system_clock(),
         })
     }
 
+    /// Replace the default indexer, e.g. with one shared with an event aggregator
+    pub fn with_indexer(mut self, indexer: ContentIndexer) -> Self {
+        self.indexer = indexer;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Apply `config`, including the moderator its moderation rules describe
+    pub fn with_config(mut self, config: GovernanceConfig) -> Self {
+        self.moderator = moderator_for(&config.moderation);
+        self.config = config;
+        self
+    }
+
+    /// Review vote content with a custom moderator instead of the configured one
+    pub fn with_moderator(mut self, moderator: Arc<dyn ContentModerator>) -> Self {
+        self.moderator = moderator;
+        self
+    }
+
+    /// Keep signed votes in `store` instead of process memory
+    pub fn with_kv_store(mut self, store: SharedKvStore) -> Self {
+        self.signed_votes = SignedVoteStore::new(store);
+        self
+    }
+
+    /// Send low-participation alerts somewhere other than the service log
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.participation = ParticipationMonitor::new(notifier);
+        self
+    }
+
+    /// Send notifications to proposal watchers somewhere other than the service log
+    pub fn with_watch_notifier(mut self, notifier: Arc<dyn WatchNotifier>) -> Self {
+        self.watches = WatchDispatcher::new(notifier);
+        self
+    }
+
+    /// Track submissions in `pending`, e.g. the tracker the API reports from
+    pub fn with_pending_transactions(mut self, pending: PendingTransactions) -> Self {
+        self.pending_transactions = pending;
+        self
+    }
+
     pub fn blockchain_client(&self) -> &Arc<SomniaClient> {
         &self.blockchain_client
     }
@@ -27,4 +156,3507 @@ impl GovernanceEngine {
     pub fn ipfs_client(&self) -> &Arc<IpfsClient> {
         &self.ipfs_client
     }
-}
\ No newline at end of file
+
+    pub fn indexer(&self) -> &ContentIndexer {
+        &self.indexer
+    }
+
+    pub fn delegations(&self) -> &DelegationRegistry {
+        &self.delegations
+    }
+
+    pub fn config(&self) -> &GovernanceConfig {
+        &self.config
+    }
+
+    /// Votes accepted with flagged content, for admin review
+    pub fn moderation_queue(&self) -> &ModerationQueue {
+        &self.moderation_queue
+    }
+
+    pub fn participation_monitor(&self) -> &ParticipationMonitor {
+        &self.participation
+    }
+
+    /// Flag active proposals closing within the configured alert window
+    /// without enough participation for quorum, publishing them to the
+    /// participation monitor. Returns nothing when alerts are disabled.
+    pub fn check_participation(&self) -> Vec<ParticipationAlert> {
+        let Some(window) = self.config.participation_alert_window else {
+            return Vec::new();
+        };
+
+        let now = self.clock.timestamp();
+        let alerts: Vec<_> = self
+            .indexer
+            .proposals()
+            .iter()
+            .filter_map(|proposal| {
+                let votes = self.indexer.get_votes(proposal.id);
+                ParticipationAlert::evaluate(proposal, &votes, &self.config.proposal_rules, now, window)
+            })
+            .collect();
+
+        self.participation.record(&alerts);
+        alerts
+    }
+
+    /// Start background participation checks and watcher deadline
+    /// reminders, once a minute
+    pub fn start_participation_task(&self) -> tokio::task::JoinHandle<()> {
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                engine.backfill_voting_power().await;
+                engine.check_participation();
+                engine.remind_watchers();
+            }
+        })
+    }
+
+    /// Follow a proposal's status changes and deadline as `watcher`, within
+    /// `settings`. Returns whether the watch is new.
+    pub fn watch_proposal(&self, watcher: Address, proposal_id: u64, settings: NotificationSettings) -> Result<bool> {
+        if self.indexer.get_proposal(proposal_id).is_none() {
+            return Err(GovernanceError::ProposalNotFound { proposal_id });
+        }
+        Ok(self.indexer.watch(proposal_id, watcher, settings, self.clock.timestamp()))
+    }
+
+    /// Stop following a proposal. Returns whether `watcher` was following it.
+    pub fn unwatch_proposal(&self, watcher: Address, proposal_id: u64) -> bool {
+        self.indexer.unwatch(proposal_id, watcher)
+    }
+
+    /// Remind watchers of proposals closing within the reminder window.
+    /// Returns how many reminders went out.
+    pub fn remind_watchers(&self) -> usize {
+        let window = self.config.watch_reminder_window();
+        self.watches.deadline_reminders(&self.indexer, self.clock.timestamp(), window)
+    }
+
+    /// Move a proposal to `status`, notifying its watchers if that changed it
+    fn set_status(&self, proposal_id: u64, status: ProposalStatus) {
+        let previous = self.indexer.get_proposal(proposal_id).map(|proposal| proposal.status);
+        self.indexer.update_status(proposal_id, status);
+        if let Some(previous) = previous.filter(|previous| *previous != status) {
+            self.log_event(proposal_id, None, None, ProposalEvent::StatusChanged { from: previous, to: status });
+            self.watches.status_changed(&self.indexer, proposal_id, status);
+        }
+    }
+
+    fn log_event(
+        &self,
+        proposal_id: u64,
+        actor: Option<Address>,
+        transaction_hash: Option<H256>,
+        event: ProposalEvent,
+    ) {
+        self.audit_log.record(
+            proposal_id,
+            AuditEntry {
+                timestamp: self.clock.timestamp(),
+                actor,
+                transaction_hash,
+                event,
+            },
+        );
+    }
+
+    /// Everything that happened to a proposal, oldest first: creation, votes
+    /// with their transactions, status changes, finalization and execution
+    pub fn proposal_audit_trail(&self, proposal_id: u64) -> Result<Vec<AuditEntry>> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        Ok(audit_trail(
+            self.audit_log.entries(proposal_id),
+            &proposal,
+            &self.indexer.get_votes(proposal_id),
+            self.indexer.get_execution(proposal_id).as_ref(),
+        ))
+    }
+
+    /// Own and delegated power for an address, with the per-delegate cap applied
+    pub async fn delegate_stats(&self, delegate: Address) -> Result<DelegateStats> {
+        self.delegate_stats_at(delegate, None).await
+    }
+
+    /// `delegate_stats` with power as of `block`, or live power when `None`
+    async fn delegate_stats_at(&self, delegate: Address, block: Option<u64>) -> Result<DelegateStats> {
+        let mut power_source = match block {
+            Some(_) => PowerSource::Snapshot,
+            None => PowerSource::Live,
+        };
+
+        // Power delegated away no longer counts for the delegator
+        let own_power = match self.delegations.delegate_of(delegate) {
+            Some(_) => U256::zero(),
+            None => self.lookup_power_at(delegate, block, &mut power_source).await?,
+        };
+
+        let delegators = self.delegations.transitive_delegators(delegate);
+        let mut received_power = U256::zero();
+        for delegator in &delegators {
+            received_power += self.lookup_power_at(*delegator, block, &mut power_source).await?;
+        }
+
+        Ok(DelegateStats::new(
+            delegate,
+            own_power,
+            delegators.len(),
+            received_power,
+            self.config.max_delegated_power(),
+        )
+        .with_power_source(power_source))
+    }
+
+    /// Power as of `block` without any fallback, or `lookup_power` when `None`
+    async fn lookup_power_at(&self, address: Address, block: Option<u64>, source: &mut PowerSource) -> Result<U256> {
+        match block {
+            Some(block) => self.blockchain_client.get_voting_power_at(address, block).await,
+            None => self.lookup_power(address, source).await,
+        }
+    }
+
+    /// Power `voter` votes on `proposal_id` with: as of the proposal's
+    /// snapshot block under `snapshot_voting`, live otherwise. Proposals not
+    /// created through the engine have no recorded snapshot and use live power.
+    async fn voting_stats(&self, voter: Address, proposal_id: u64) -> Result<DelegateStats> {
+        let block = match self.config.snapshot_voting {
+            true => self.snapshot_blocks.get(proposal_id),
+            false => None,
+        };
+        self.delegate_stats_at(voter, block).await
+    }
+
+    /// Votes without power are turned away before they cost gas, unless
+    /// they are accepted as signal votes
+    fn check_has_power(&self, proposal_id: u64, stats: &DelegateStats) -> Result<()> {
+        if !stats.effective_power.is_zero() || self.config.zero_power_votes == ZeroPowerVotes::Signal {
+            return Ok(());
+        }
+        match (stats.power_source, self.snapshot_blocks.get(proposal_id)) {
+            // The voter may well hold power now, just not at the snapshot
+            (PowerSource::Snapshot, Some(block)) => Err(GovernanceError::NoSnapshotPower { proposal_id, block }),
+            _ => Err(GovernanceError::InsufficientVotingPower {
+                required: 1,
+                available: 0,
+            }),
+        }
+    }
+
+    /// Live voting power, falling back to the last known value when the
+    /// lookup fails and the config allows it. Marks `source` on fallback.
+    async fn lookup_power(&self, address: Address, source: &mut PowerSource) -> Result<U256> {
+        match self.blockchain_client.get_user_voting_power(address).await {
+            Ok(power) => {
+                self.power_snapshots.record(address, power);
+                Ok(power)
+            }
+            Err(e) => match (self.config.voting_power_fallback, self.power_snapshots.get(address)) {
+                (VotingPowerFallback::LastKnown, Some(power)) => {
+                    tracing::warn!("Voting power lookup for {:?} failed, using last known value: {}", address, e);
+                    *source = PowerSource::LastKnown;
+                    Ok(power)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Who delegates to `address` (incoming) or whom it delegates to
+    /// (outgoing), with the power each edge currently carries. Power is only
+    /// looked up for the requested page.
+    pub async fn list_delegations(
+        &self,
+        address: Address,
+        direction: DelegationDirection,
+        pagination: &PaginationParams,
+    ) -> Result<DelegationListing> {
+        // (counterparty, address whose power flows along the edge)
+        let edges: Vec<(Address, Address)> = match direction {
+            DelegationDirection::Incoming => self
+                .delegations
+                .direct_delegators(address)
+                .into_iter()
+                .map(|delegator| (delegator, delegator))
+                .collect(),
+            DelegationDirection::Outgoing => self
+                .delegations
+                .delegate_of(address)
+                .map(|delegate| (delegate, address))
+                .into_iter()
+                .collect(),
+        };
+
+        let total = edges.len() as u64;
+        let page = edges
+            .into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .map(|(counterparty, source)| async move {
+                let power = self.power_through(source).await?;
+                Ok::<_, GovernanceError>(DelegationEntry {
+                    address: counterparty,
+                    power,
+                })
+            });
+        let entries = futures::future::try_join_all(page).await?;
+        let page_power = entries.iter().fold(U256::zero(), |sum, entry| sum + entry.power);
+
+        Ok(DelegationListing {
+            address,
+            direction,
+            page_power,
+            entries: PaginatedResponse::new(entries, pagination.page(), pagination.limit(), total),
+        })
+    }
+
+    /// Power `delegator` passes on: its own plus everything delegated to it
+    async fn power_through(&self, delegator: Address) -> Result<U256> {
+        let mut source = PowerSource::Live;
+        let mut power = self.lookup_power(delegator, &mut source).await?;
+        for upstream in self.delegations.transitive_delegators(delegator) {
+            power += self.lookup_power(upstream, &mut source).await?;
+        }
+        Ok(power)
+    }
+
+    /// Power counted for a vote from `voter`
+    pub async fn effective_voting_power(&self, voter: Address) -> Result<U256> {
+        Ok(self.delegate_stats(voter).await?.effective_power)
+    }
+
+    /// Validate and pin proposal content, create the proposal on-chain and index it
+    pub async fn create_proposal(
+        &self,
+        proposer: Address,
+        mut content: ProposalIPFSContent,
+        voting_duration: u64,
+    ) -> Result<IndexedProposal> {
+        self.validate_new_proposal(proposer, &mut content, voting_duration).await?;
+
+        let ipfs_hash = self.ipfs_client.add_proposal_content(&content).await?;
+        let proposal_type: u8 = content.metadata.proposal_type.into();
+        let supersedes = content.metadata.supersedes;
+
+        let receipt = self
+            .blockchain_client
+            .create_proposal(ipfs_hash.clone(), voting_duration, proposal_type)
+            .await?;
+        let transaction_type = TransactionType::CreateProposal {
+            ipfs_hash: ipfs_hash.clone(),
+        };
+        self.settle_transaction(&receipt, proposer, transaction_type, None).await;
+
+        // Another proposal may land between our transaction and any follow-up
+        // read, so the id comes from the receipt rather than the proposal count
+        let proposal_id = ProposalCreatedEvent::proposal_id_from_receipt(&receipt).ok_or_else(|| {
+            GovernanceError::Internal(anyhow::anyhow!(
+                "Proposal creation {:?} emitted no ProposalCreated event",
+                receipt.transaction_hash
+            ))
+        })?;
+        let data = self.blockchain_client.get_proposal(proposal_id).await?;
+
+        let proposal = IndexedProposal {
+            id: proposal_id,
+            contract: None,
+            proposer,
+            ipfs_hash,
+            proposal_type,
+            status: data.status,
+            start_time: data.start_time.as_u64(),
+            end_time: data.end_time.as_u64(),
+            supersedes,
+            total_voting_power: data.total_voting_power,
+            tie_extended: false,
+        };
+        self.indexer.index_proposal(proposal.clone());
+        self.proposal_limiter.record(proposer, self.clock.timestamp());
+        if let Some(block) = receipt.block_number {
+            self.snapshot_blocks.record(proposal_id, block.as_u64());
+        }
+        self.log_event(
+            proposal_id,
+            Some(proposer),
+            Some(receipt.transaction_hash),
+            ProposalEvent::Created {
+                ipfs_hash: proposal.ipfs_hash.clone(),
+            },
+        );
+
+        if let Some(target) = supersedes {
+            self.apply_supersession(target);
+        }
+
+        tracing::info!("Created proposal {} by {:?}", proposal_id, proposer);
+        self.check_created_end_time(&proposal)?;
+        Ok(proposal)
+    }
+
+    /// Dry run of `create_proposal` for a draft: validate it as a proposal
+    /// submitted now, then work out its CID and the creation gas. Nothing is
+    /// pinned or submitted.
+    pub async fn estimate_draft(
+        &self,
+        proposer: Address,
+        draft: DraftContent,
+        voting_duration: u64,
+    ) -> Result<ProposalEstimate> {
+        let mut content = draft.into_proposal_content(self.clock.now());
+        self.validate_new_proposal(proposer, &mut content, voting_duration).await?;
+
+        let ipfs_hash = self.ipfs_client.content_hash(&content).await?;
+        let proposal_type: u8 = content.metadata.proposal_type.into();
+        let estimated_gas = match self
+            .blockchain_client
+            .estimate_create_proposal_gas(&ipfs_hash, voting_duration, proposal_type)
+            .await
+        {
+            Ok(gas) => Some(gas),
+            Err(e) => {
+                tracing::warn!("Proposal creation gas estimate failed: {}", e);
+                None
+            }
+        };
+
+        Ok(ProposalEstimate {
+            ipfs_hash,
+            voting_duration,
+            estimated_gas,
+        })
+    }
+
+    /// Every check `create_proposal` makes before touching IPFS or the chain.
+    /// Normalizes `content` as it goes.
+    async fn validate_new_proposal(
+        &self,
+        proposer: Address,
+        content: &mut ProposalIPFSContent,
+        voting_duration: u64,
+    ) -> Result<()> {
+        self.check_proposal_rate(proposer)?;
+        self.validate_duration(voting_duration)?;
+        validate_proposal_content(content)?;
+        self.validate_option_count(content)?;
+        if !self.config.may_propose(&content.metadata.category, proposer) {
+            return Err(GovernanceError::ProposerNotAllowed {
+                category: content.metadata.category.clone(),
+                proposer: format!("{:?}", proposer),
+            });
+        }
+        if self.config.unique_titles {
+            self.validate_unique_title(content).await?;
+        }
+        if self.config.verify_attachments {
+            self.validate_attachments_available(content).await?;
+        }
+        if let Some(target) = content.metadata.supersedes {
+            self.validate_supersession(target)?;
+        }
+        Ok(())
+    }
+
+    /// The contract sets a new proposal's times itself. One that has already
+    /// closed, or is about to, points at a misconfigured duration or a clock
+    /// out of step with the chain. The proposal stays indexed as the chain
+    /// has it, so the creator gets a conflict naming it rather than a
+    /// server error that invites a retry and a duplicate.
+    fn check_created_end_time(&self, proposal: &IndexedProposal) -> Result<()> {
+        let now = self.clock.timestamp();
+        if proposal.end_time >= now.saturating_add(MIN_REMAINING_VOTING_TIME) {
+            return Ok(());
+        }
+
+        tracing::error!(
+            "Proposal {} was created ending at {}, less than {}s after now ({})",
+            proposal.id,
+            proposal.end_time,
+            MIN_REMAINING_VOTING_TIME,
+            now
+        );
+        Err(GovernanceError::ProposalEndsTooSoon {
+            proposal_id: proposal.id,
+            end_time: proposal.end_time,
+            now,
+            min_remaining: MIN_REMAINING_VOTING_TIME,
+        })
+    }
+
+    /// Every attachment must resolve on IPFS, not just be a well-formed CID
+    async fn validate_attachments_available(&self, content: &ProposalIPFSContent) -> Result<()> {
+        for attachment in &content.metadata.attachments {
+            if !self.ipfs_client.is_available(attachment).await {
+                return Err(GovernanceError::invalid_request(format!(
+                    "Attachment {} is not available on IPFS",
+                    attachment
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse a proposer who already created `max_proposals_per_address`
+    /// proposals within the rate window, however much power they hold
+    fn check_proposal_rate(&self, proposer: Address) -> Result<()> {
+        let Some(limit) = self.config.max_proposals_per_address else {
+            return Ok(());
+        };
+        let window = self.config.proposal_rate_window();
+        match self.proposal_limiter.retry_after(proposer, limit, window, self.clock.timestamp()) {
+            Some(retry_after) => Err(GovernanceError::ProposalCooldown {
+                proposer: format!("{:?}", proposer),
+                retry_after,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Voting durations proposers may choose from under the current config
+    pub fn duration_options(&self) -> VotingDurationOptions {
+        VotingDurationOptions {
+            presets: self.config.duration_presets.clone(),
+            presets_only: self.config.presets_only,
+            min_duration: MIN_VOTING_DURATION,
+            max_duration: MAX_VOTING_DURATION,
+        }
+    }
+
+    pub fn capabilities(&self) -> GovernanceCapabilities {
+        let config = &self.config;
+        GovernanceCapabilities {
+            proposal_types: ProposalType::ALL.to_vec(),
+            option_limits: config.option_limits,
+            voting_durations: self.duration_options(),
+            quorum: config.proposal_rules,
+            tie_policy: config.tie_policy,
+            max_delegated_power: config.max_delegated_power(),
+            relayed_votes: !config.trusted_relayers.is_empty(),
+            vote_moderation: config.moderation.is_enabled(),
+            unique_titles: config.unique_titles,
+            gated_categories: config.category_proposers.keys().cloned().collect(),
+        }
+    }
+
+    /// In presets-only mode the duration must be a listed preset; otherwise
+    /// it only has to be within the general bounds
+    fn validate_duration(&self, voting_duration: u64) -> Result<()> {
+        if self.config.presets_only {
+            if !self.config.duration_presets.contains(&voting_duration) {
+                return Err(GovernanceError::invalid_request(format!(
+                    "Voting duration {}s is not one of the allowed presets {:?}",
+                    voting_duration, self.config.duration_presets
+                )));
+            }
+            return Ok(());
+        }
+
+        validate_voting_duration(voting_duration).map_err(|e| {
+            GovernanceError::invalid_request(format!("Invalid voting duration {}s: {}", voting_duration, e.code))
+        })
+    }
+
+    /// Option-based proposals must offer between `option_limits.min_options`
+    /// and `option_limits.max_options` options
+    fn validate_option_count(&self, content: &ProposalIPFSContent) -> Result<()> {
+        let metadata = &content.metadata;
+        if !metadata.proposal_type.uses_options() {
+            return Ok(());
+        }
+
+        let limits = self.config.option_limits;
+        let count = metadata.options.len();
+        if count < limits.min_options || count > limits.max_options {
+            return Err(GovernanceError::invalid_request(format!(
+                "{:?} proposals need between {} and {} options, got {}",
+                metadata.proposal_type, limits.min_options, limits.max_options, count
+            )));
+        }
+        Ok(())
+    }
+
+    /// No proposal still open for voting in the same category (matched
+    /// case-insensitively) may have the same normalized title. Proposals whose
+    /// content can't be fetched are skipped rather than blocking creation.
+    async fn validate_unique_title(&self, content: &ProposalIPFSContent) -> Result<()> {
+        let now = self.clock.timestamp();
+        let category = content.metadata.category.trim().to_lowercase();
+        let title = normalize_title(&content.title);
+
+        let open = self
+            .indexer
+            .proposals()
+            .into_iter()
+            .filter(|p| p.status == ProposalStatus::Active && now < p.end_time);
+        for proposal in open {
+            let existing = match self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await {
+                Ok(existing) => existing,
+                Err(e) => {
+                    tracing::debug!("Skipping title check against proposal {}: {}", proposal.id, e);
+                    continue;
+                }
+            };
+            let same_category = existing.metadata.category.trim().to_lowercase() == category;
+            if same_category && normalize_title(&existing.title) == title {
+                return Err(GovernanceError::DuplicateTitle {
+                    proposal_id: proposal.id,
+                    category: content.metadata.category.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// A superseded proposal must be indexed and its own chain of
+    /// `supersedes` links must not loop back on itself.
+    fn validate_supersession(&self, target: u64) -> Result<()> {
+        let mut visited = HashSet::new();
+        let mut current = Some(target);
+
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                return Err(GovernanceError::invalid_request(format!(
+                    "Supersession of proposal {} forms a cycle",
+                    target
+                )));
+            }
+
+            let proposal = self.indexer.get_proposal(id).ok_or_else(|| {
+                GovernanceError::invalid_request(format!("Superseded proposal {} does not exist", id))
+            })?;
+            current = proposal.supersedes;
+        }
+
+        Ok(())
+    }
+
+    fn apply_supersession(&self, target: u64) {
+        if !self.config.auto_cancel_superseded {
+            return;
+        }
+
+        if let Some(proposal) = self.indexer.get_proposal(target) {
+            if proposal.status == ProposalStatus::Active {
+                self.set_status(target, ProposalStatus::Canceled);
+                tracing::info!("Canceled proposal {} after it was superseded", target);
+            }
+        }
+    }
+
+    /// Execute a proposal on-chain and record whether the target call succeeded
+    pub async fn execute_proposal(&self, proposal_id: u64) -> Result<ExecutionResult> {
+        let proposer = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?
+            .proposer;
+
+        let receipt = self.blockchain_client.execute_proposal(proposal_id).await?;
+        // Attributed to the proposer, whoever triggered it
+        self.settle_transaction(&receipt, proposer, TransactionType::ExecuteProposal { proposal_id }, None)
+            .await;
+        let result = ExecutionResult::from_receipt(proposal_id, &receipt, self.clock.timestamp());
+        self.log_event(
+            proposal_id,
+            None,
+            Some(result.transaction_hash),
+            ProposalEvent::Executed { outcome: result.outcome },
+        );
+
+        match result.outcome {
+            ExecutionOutcome::Succeeded => {
+                self.set_status(proposal_id, ProposalStatus::Executed);
+                tracing::info!("Executed proposal {}", proposal_id);
+            }
+            ExecutionOutcome::Reverted => {
+                tracing::warn!("Execution of proposal {} reverted in {:?}", proposal_id, result.transaction_hash);
+            }
+        }
+
+        self.indexer.record_execution(result.clone());
+        Ok(result)
+    }
+
+    /// Proposal with its content and current tally
+    pub async fn get_proposal_detail(&self, proposal_id: u64) -> Result<ProposalDetail> {
+        self.get_scoped_proposal_detail(ProposalKey::primary(proposal_id)).await
+    }
+
+    /// Key for proposal `proposal_id` of `contract`. The configured governance
+    /// hub is the primary contract, so its proposals keep bare ids.
+    pub fn proposal_key(&self, contract: Address, proposal_id: u64) -> ProposalKey {
+        if self.blockchain_client.contract_addresses().governance_hub == Some(contract) {
+            ProposalKey::primary(proposal_id)
+        } else {
+            ProposalKey::scoped(contract, proposal_id)
+        }
+    }
+
+    /// Indexed proposal `key`, with its snapshot's total voting power read
+    /// from the hub if the index lacks it, as `ProposalCreated` events don't
+    /// carry it. Only the primary contract's hub can be asked; a failed
+    /// lookup leaves the power at zero for the next attempt.
+    async fn proposal_with_voting_power(&self, key: ProposalKey) -> Result<IndexedProposal> {
+        let mut proposal = self
+            .indexer
+            .get_scoped_proposal(key)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id: key.id })?;
+        if proposal.total_voting_power.is_zero() && key.contract.is_none() {
+            match self.blockchain_client.get_proposal(key.id).await {
+                Ok(data) => {
+                    proposal.total_voting_power = data.total_voting_power;
+                    self.indexer.set_total_voting_power(key, data.total_voting_power);
+                }
+                Err(e) => tracing::warn!("Failed to read the snapshot power of proposal {}: {}", key.id, e),
+            }
+        }
+        Ok(proposal)
+    }
+
+    /// Fill in the snapshot voting power of open primary proposals indexed
+    /// without it, so participation checks see their real quorum
+    pub async fn backfill_voting_power(&self) {
+        let missing = self
+            .indexer
+            .proposals()
+            .into_iter()
+            .filter(|proposal| proposal.status == ProposalStatus::Active && proposal.total_voting_power.is_zero());
+        for proposal in missing {
+            let _ = self.proposal_with_voting_power(proposal.key()).await;
+        }
+    }
+
+    /// Proposal detail for a proposal of any indexed governance contract
+    pub async fn get_scoped_proposal_detail(&self, key: ProposalKey) -> Result<ProposalDetail> {
+        let proposal = self.proposal_with_voting_power(key).await?;
+
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        let votes = self.indexer.get_scoped_votes(key);
+        // Supersession links are only tracked within the primary contract
+        let superseded_by = match key.contract {
+            None => self.indexer.superseded_by(key.id),
+            Some(_) => Vec::new(),
+        };
+
+        Ok(ProposalDetail::new(&proposal, content, &votes)
+            .with_rules(&self.config.proposal_rules)
+            .with_superseded_by(superseded_by))
+    }
+
+    /// Dry-run the proposal's execution call with `eth_call` against current
+    /// state, as the governance hub would send it. Nothing is executed.
+    pub async fn simulate_execution(&self, proposal_id: u64) -> Result<ExecutionSimulation> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        let execution = content.metadata.execution_data.ok_or_else(|| {
+            GovernanceError::invalid_request(format!("Proposal {} has no execution data", proposal_id))
+        })?;
+
+        let target: Address = execution
+            .target_contract
+            .parse()
+            .map_err(|_| GovernanceError::invalid_request("Invalid execution target address"))?;
+        let call_data = execution.call_data.strip_prefix("0x").unwrap_or(&execution.call_data);
+        let call_data = hex::decode(call_data)
+            .map_err(|_| GovernanceError::invalid_request("Execution call data must be hex encoded"))?;
+        let value = U256::from_dec_str(&execution.value)
+            .map_err(|_| GovernanceError::invalid_request("Execution value must be a decimal amount"))?;
+
+        let mut tx = TransactionRequest::new().to(target).data(Bytes::from(call_data)).value(value);
+        if let Some(hub) = self.blockchain_client.contract_addresses().governance_hub {
+            tx = tx.from(hub);
+        }
+        let simulation = self.blockchain_client.simulate_call(&tx.into()).await?;
+
+        // Return data that doesn't fit the declared outputs is still shown raw
+        let decoded_return = if simulation.success {
+            decode_return_data(&execution.function_signature, &simulation.return_data).unwrap_or_else(|e| {
+                tracing::debug!("Proposal {} simulation output not decoded: {}", proposal_id, e);
+                None
+            })
+        } else {
+            None
+        };
+
+        Ok(ExecutionSimulation {
+            proposal_id,
+            target_contract: target,
+            function_signature: execution.function_signature,
+            success: simulation.success,
+            return_data: simulation.return_data,
+            decoded_return,
+            revert_reason: simulation.revert_reason,
+        })
+    }
+
+    /// Status and tally for each id, looked up concurrently. Unknown ids get
+    /// an error entry rather than failing the batch.
+    pub async fn proposal_statuses(&self, ids: &[u64]) -> Result<BTreeMap<u64, ProposalStatusEntry>> {
+        if ids.len() > MAX_STATUS_BATCH {
+            return Err(GovernanceError::invalid_request(format!(
+                "At most {} proposals per status batch, got {}",
+                MAX_STATUS_BATCH,
+                ids.len()
+            )));
+        }
+
+        let lookups = ids.iter().map(|&proposal_id| async move {
+            let entry = match self.proposal_status(proposal_id) {
+                Ok(summary) => ProposalStatusEntry::Found(summary),
+                Err(e) => ProposalStatusEntry::Failed { error: e.to_string() },
+            };
+            (proposal_id, entry)
+        });
+
+        Ok(futures::future::join_all(lookups).await.into_iter().collect())
+    }
+
+    fn proposal_status(&self, proposal_id: u64) -> Result<ProposalStatusSummary> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        let votes = self.indexer.get_votes(proposal_id);
+        Ok(ProposalStatusSummary::new(&proposal, &votes, &self.config.proposal_rules))
+    }
+
+    /// Primary contract proposals, newest first, with their content. A page
+    /// never fails over content IPFS can't serve right now; those entries
+    /// are returned with just their on-chain fields.
+    pub async fn list_proposals(&self, pagination: &PaginationParams) -> Result<PaginatedResponse<ProposalListEntry>> {
+        let proposals = self.indexer.proposals();
+        let total = proposals.len() as u64;
+        let page = proposals
+            .into_iter()
+            .rev()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .map(|proposal| async move {
+                let content = match self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await {
+                    Ok(content) => Some(content),
+                    Err(e) => {
+                        tracing::warn!("Content of proposal {} unavailable: {}", proposal.id, e);
+                        None
+                    }
+                };
+                ProposalListEntry {
+                    proposal,
+                    content_available: content.is_some(),
+                    content,
+                }
+            });
+
+        let entries = futures::future::join_all(page).await;
+        Ok(PaginatedResponse::new(entries, pagination.page(), pagination.limit(), total))
+    }
+
+    /// `address`'s interactions with proposals, most recent first
+    pub fn activity_feed(&self, address: Address, pagination: &PaginationParams) -> PaginatedResponse<ActivityEntry> {
+        let entries = activity_feed(&self.indexer, &self.delegations, address);
+        let total = entries.len() as u64;
+        let page = entries
+            .into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .collect();
+        PaginatedResponse::new(page, pagination.page(), pagination.limit(), total)
+    }
+
+    /// Field-level diff between two content versions of a proposal, e.g. an
+    /// amendment and the proposal it supersedes
+    pub async fn proposal_diff(&self, proposal_id: u64, from: &str, to: &str) -> Result<ProposalDiff> {
+        if self.indexer.get_proposal(proposal_id).is_none() {
+            return Err(GovernanceError::ProposalNotFound { proposal_id });
+        }
+        let (from_content, to_content) = futures::future::try_join(
+            self.ipfs_client.get_proposal_content(from),
+            self.ipfs_client.get_proposal_content(to),
+        )
+        .await?;
+        Ok(diff_proposal_content(proposal_id, from, &from_content, to, &to_content))
+    }
+
+    /// Open proposals ranked by votes in the last `window` seconds, then by
+    /// total participation
+    pub fn trending_proposals(
+        &self,
+        window: u64,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<TrendingProposal>> {
+        if window == 0 {
+            return Err(GovernanceError::invalid_request("Trending window must be greater than zero"));
+        }
+
+        let trending = self.indexer.trending(self.clock.timestamp(), window);
+        let total = trending.len() as u64;
+        let page = trending
+            .into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .collect();
+
+        Ok(PaginatedResponse::new(page, pagination.page(), pagination.limit(), total))
+    }
+
+    /// Whether the configured quorum looks reachable for a new proposal,
+    /// judged by current total power and turnout on closed proposals
+    pub async fn quorum_feasibility(&self) -> Result<QuorumFeasibility> {
+        let total_voting_power = self.blockchain_client.get_total_voting_power().await?;
+        let now = self.clock.timestamp();
+        let turnout: Vec<u64> = self
+            .indexer
+            .proposals()
+            .iter()
+            .filter(|proposal| proposal.end_time <= now)
+            .filter_map(|proposal| turnout_bps(proposal, &self.indexer.get_votes(proposal.id)))
+            .collect();
+
+        Ok(QuorumFeasibility::estimate(total_voting_power, &self.config.proposal_rules, &turnout))
+    }
+
+    /// Votes on a proposal as `viewer` may see them. Proposals flagged
+    /// `hide_votes_until_close` expose only the tally and the viewer's own
+    /// vote until `end_time` has passed.
+    pub async fn proposal_votes(&self, proposal_id: u64, viewer: Option<Address>) -> Result<ProposalVotes> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        let mut votes = self.indexer.get_votes(proposal_id);
+        let results = ProposalResults::tally(&content.metadata, &votes);
+
+        let votes_hidden = content.metadata.hide_votes_until_close && self.clock.timestamp() < proposal.end_time;
+        if votes_hidden {
+            votes.retain(|vote| Some(vote.voter) == viewer);
+        }
+
+        Ok(ProposalVotes {
+            proposal_id,
+            results,
+            votes_hidden,
+            votes,
+        })
+    }
+
+    /// Split of votes and power across a proposal's choices. Proposals with
+    /// `hide_votes_until_close` only show it once voting has closed.
+    pub async fn vote_distribution(&self, proposal_id: u64) -> Result<VoteDistribution> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        if content.metadata.hide_votes_until_close && self.clock.timestamp() < proposal.end_time {
+            return Err(GovernanceError::forbidden(format!(
+                "Votes on proposal {} are hidden until voting closes",
+                proposal_id
+            )));
+        }
+
+        let labels: Vec<String> = if content.metadata.proposal_type.uses_options() {
+            content.metadata.options.clone()
+        } else {
+            BINARY_CHOICE_LABELS.iter().map(|label| label.to_string()).collect()
+        };
+        Ok(build_vote_distribution(proposal_id, &labels, &self.indexer.get_votes(proposal_id)))
+    }
+
+    /// Settle a binary proposal once voting has closed, marking it passed or
+    /// rejected in the index. Proposals already settled keep their status.
+    ///
+    /// An exact tie that meets quorum follows `config.tie_policy`. Under
+    /// `TiePolicy::Extend` the first tie reopens voting in the index for
+    /// `period` seconds from now and the proposal stays active; a tie after
+    /// that extension is rejected.
+    pub async fn evaluate_proposal(&self, proposal_id: u64) -> Result<ProposalStatus> {
+        let proposal = self.proposal_with_voting_power(ProposalKey::primary(proposal_id)).await?;
+        if proposal.status != ProposalStatus::Active {
+            return Ok(proposal.status);
+        }
+
+        let now = self.clock.timestamp();
+        if now < proposal.end_time {
+            return Err(GovernanceError::invalid_request(format!(
+                "Proposal {} is still open for voting",
+                proposal_id
+            )));
+        }
+        if ProposalType::from(proposal.proposal_type).uses_options() {
+            return Err(GovernanceError::invalid_request(format!(
+                "Proposal {} chooses among options and has no pass/fail outcome",
+                proposal_id
+            )));
+        }
+
+        let (_, verdict) = self.binary_outcome(&self.indexer.get_votes(proposal_id), proposal.total_voting_power);
+        let status = match verdict {
+            BinaryVerdict::Passed => ProposalStatus::Passed,
+            BinaryVerdict::Rejected => ProposalStatus::Rejected,
+            BinaryVerdict::Tied => match self.config.tie_policy {
+                TiePolicy::Pass => ProposalStatus::Passed,
+                TiePolicy::Reject => ProposalStatus::Rejected,
+                TiePolicy::Extend { period } => {
+                    if self.indexer.extend_for_tie(proposal_id, now + period) {
+                        tracing::info!("Proposal {} tied; voting extended until {}", proposal_id, now + period);
+                        return Ok(ProposalStatus::Active);
+                    }
+                    ProposalStatus::Rejected
+                }
+            },
+        };
+
+        self.set_status(proposal_id, status);
+        Ok(status)
+    }
+
+    /// Tally of binary `votes` and its verdict under the passage rules
+    fn binary_outcome(&self, votes: &[IndexedVote], total_voting_power: U256) -> (ProposalResults, BinaryVerdict) {
+        let results = tally_binary(votes);
+        let ProposalResults::Binary { yes_votes, no_votes, abstain_votes } = results else {
+            unreachable!("tally_binary always produces binary results");
+        };
+        let verdict = BinaryVerdict::evaluate(
+            yes_votes,
+            no_votes,
+            abstain_votes,
+            total_voting_power,
+            &self.config.proposal_rules,
+        );
+        (results, verdict)
+    }
+
+    /// Project an open binary proposal's outcome with `vote` added, judged
+    /// the way `evaluate_proposal` settles it. Records nothing. A voter who
+    /// already voted has that vote replaced by the hypothetical one.
+    pub async fn what_if(&self, proposal_id: u64, vote: HypotheticalVote) -> Result<WhatIfTally> {
+        let proposal = self.proposal_with_voting_power(ProposalKey::primary(proposal_id)).await?;
+        if proposal.status != ProposalStatus::Active {
+            return Err(GovernanceError::invalid_request(format!(
+                "Proposal {} is no longer open for voting",
+                proposal_id
+            )));
+        }
+        if ProposalType::from(proposal.proposal_type).uses_options() {
+            return Err(GovernanceError::invalid_request(format!(
+                "Proposal {} chooses among options and has no pass/fail outcome",
+                proposal_id
+            )));
+        }
+        if vote.choice > 2 {
+            return Err(GovernanceError::invalid_request("Choice must be 0 (no), 1 (yes) or 2 (abstain)"));
+        }
+
+        let power = match (vote.power, vote.voter) {
+            (Some(power), _) => power,
+            (None, Some(voter)) => self.voting_stats(voter, proposal_id).await?.effective_power,
+            (None, None) => {
+                return Err(GovernanceError::invalid_request("A hypothetical vote needs a voter or an amount of power"))
+            }
+        };
+
+        let mut votes = self.indexer.get_votes(proposal_id);
+        let (current, current_verdict) = self.binary_outcome(&votes, proposal.total_voting_power);
+        if let Some(voter) = vote.voter.filter(|voter| votes.iter().any(|existing| existing.voter == *voter)) {
+            // Replacing a hidden vote would reveal it through the change in the tally
+            let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+            if content.metadata.hide_votes_until_close && self.clock.timestamp() < proposal.end_time {
+                return Err(GovernanceError::forbidden(format!(
+                    "Votes on proposal {} are hidden until voting closes",
+                    proposal_id
+                )));
+            }
+            votes.retain(|existing| existing.voter != voter);
+        }
+        votes.push(IndexedVote {
+            proposal_id,
+            contract: proposal.contract,
+            voter: vote.voter.unwrap_or_default(),
+            choice: vote.choice,
+            power,
+            timestamp: self.clock.timestamp(),
+            ipfs_hash: None,
+            weights: Vec::new(),
+        });
+        let (projected, projected_verdict) = self.binary_outcome(&votes, proposal.total_voting_power);
+
+        let projected_status = match projected_verdict {
+            BinaryVerdict::Passed => ProposalStatus::Passed,
+            BinaryVerdict::Rejected => ProposalStatus::Rejected,
+            BinaryVerdict::Tied => match self.config.tie_policy {
+                TiePolicy::Pass => ProposalStatus::Passed,
+                TiePolicy::Reject => ProposalStatus::Rejected,
+                // Voting would be reopened, unless it already was
+                TiePolicy::Extend { .. } if proposal.tie_extended => ProposalStatus::Rejected,
+                TiePolicy::Extend { .. } => ProposalStatus::Active,
+            },
+        };
+
+        Ok(WhatIfTally {
+            proposal_id,
+            choice: vote.choice,
+            power,
+            current,
+            current_verdict,
+            projected,
+            projected_verdict,
+            projected_status,
+        })
+    }
+
+    /// Settle a closed proposal now, for recovery when its deadline passed
+    /// while nothing was settling proposals. Goes through `evaluate_proposal`,
+    /// so the outcome matches regular settlement, and settled proposals are
+    /// left as they are.
+    pub async fn finalize_proposal(&self, proposal_id: u64) -> Result<ProposalFinalization> {
+        let previous_status = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?
+            .status;
+        let status = self.evaluate_proposal(proposal_id).await?;
+
+        let changed = status != previous_status;
+        if changed {
+            self.log_event(proposal_id, None, None, ProposalEvent::Finalized { status });
+            tracing::info!("Proposal {} finalized as {:?}", proposal_id, status);
+        }
+        Ok(ProposalFinalization {
+            proposal_id,
+            previous_status,
+            status,
+            changed,
+        })
+    }
+
+    /// EIP-712 domain voters sign off-chain votes under
+    pub fn vote_signing_domain(&self) -> EIP712Domain {
+        vote_domain(
+            self.blockchain_client.chain_id(),
+            self.blockchain_client.contract_addresses().simple_voting,
+        )
+    }
+
+    /// Accept an EIP-712 signed vote for later aggregation instead of a
+    /// transaction per voter. Each voter may sign one vote per proposal.
+    /// Refused while trusted relayers are configured, as votes must then
+    /// come through one of them.
+    pub async fn submit_signed_vote(
+        &self,
+        proposal_id: u64,
+        voter: Address,
+        choice: u8,
+        signature: String,
+    ) -> Result<SignedVote> {
+        if !self.config.trusted_relayers.is_empty() {
+            return Err(GovernanceError::forbidden("Signed votes must be submitted through a trusted relayer"));
+        }
+        self.accept_signed_vote(proposal_id, voter, choice, signature, None).await
+    }
+
+    /// Accept a signed vote passed on by `relayer`, which must be one of the
+    /// configured trusted relayers
+    pub async fn submit_relayed_vote(
+        &self,
+        relayer: Address,
+        proposal_id: u64,
+        voter: Address,
+        choice: u8,
+        signature: String,
+    ) -> Result<SignedVote> {
+        if !self.config.is_trusted_relayer(relayer) {
+            return Err(GovernanceError::UntrustedRelayer(format!("{:?}", relayer)));
+        }
+        self.accept_signed_vote(proposal_id, voter, choice, signature, Some(relayer)).await
+    }
+
+    async fn accept_signed_vote(
+        &self,
+        proposal_id: u64,
+        voter: Address,
+        choice: u8,
+        signature: String,
+        relayer: Option<Address>,
+    ) -> Result<SignedVote> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        let now = self.clock.timestamp();
+        if proposal.status != ProposalStatus::Active || !(proposal.start_time..proposal.end_time).contains(&now) {
+            return Err(GovernanceError::VotingPeriodEnded { proposal_id });
+        }
+        if ProposalType::from(proposal.proposal_type).uses_options() {
+            return Err(GovernanceError::invalid_request(
+                "Signed votes are only supported on yes/no proposals",
+            ));
+        }
+        self.validate_choice(proposal_id, choice).await?;
+
+        let message = VoteMessage { proposal_id, voter, choice };
+        let signer = self
+            .signature_verifier
+            .recover_signer(message.digest(&self.vote_signing_domain()), &signature)?;
+        if signer != voter {
+            return Err(GovernanceError::invalid_signature("Vote was not signed by the voter"));
+        }
+
+        let duplicate = || GovernanceError::DuplicateVote {
+            proposal_id,
+            voter: format!("{:?}", voter),
+        };
+        if self.blockchain_client.has_voted(proposal_id, voter).await? {
+            return Err(duplicate());
+        }
+
+        let vote = SignedVote {
+            proposal_id,
+            voter,
+            choice,
+            signature,
+            power: self.effective_voting_power(voter).await?,
+            submitted_at: now,
+            relayer,
+        };
+        if !self.signed_votes.insert(&vote)? {
+            return Err(duplicate());
+        }
+
+        Ok(vote)
+    }
+
+    /// Once voting has closed, re-verify the proposal's signed votes and post
+    /// their tally on-chain in one transaction. Voters who also voted on-chain
+    /// are left out, so no one is counted twice. Idempotent: later calls
+    /// return the first aggregate.
+    pub async fn aggregate_signed_votes(&self, proposal_id: u64) -> Result<SignedVoteAggregate> {
+        if let Some(aggregate) = self.signed_votes.aggregate(proposal_id)? {
+            return Ok(aggregate);
+        }
+
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        let now = self.clock.timestamp();
+        if now < proposal.end_time {
+            return Err(GovernanceError::invalid_request(format!(
+                "Signed votes on proposal {} can't be aggregated until voting closes",
+                proposal_id
+            )));
+        }
+
+        let on_chain: HashSet<Address> = self.indexer.get_votes(proposal_id).iter().map(|vote| vote.voter).collect();
+        let (votes, superseded): (Vec<_>, Vec<_>) = self
+            .signed_votes
+            .votes(proposal_id)?
+            .into_iter()
+            .partition(|vote| !on_chain.contains(&vote.voter));
+        let superseded: Vec<Address> = superseded.into_iter().map(|vote| vote.voter).collect();
+        if !superseded.is_empty() {
+            tracing::info!(
+                "{} signed votes on proposal {} superseded by on-chain votes",
+                superseded.len(),
+                proposal_id
+            );
+        }
+
+        let (tally, excluded) = tally_signed_votes(&votes, &self.vote_signing_domain(), &self.signature_verifier);
+        if !excluded.is_empty() {
+            tracing::warn!("Excluded {} signed votes on proposal {} that failed verification", excluded.len(), proposal_id);
+        }
+
+        let receipt = self.blockchain_client.submit_vote_aggregate(proposal_id, &tally).await?;
+        let aggregate = SignedVoteAggregate {
+            proposal_id,
+            tally,
+            excluded,
+            superseded,
+            transaction_hash: receipt.transaction_hash,
+            aggregated_at: now,
+        };
+        self.signed_votes.record_aggregate(&aggregate)?;
+
+        Ok(aggregate)
+    }
+
+    /// Commit to a settled proposal's votes with a Merkle root, publishing the
+    /// commitment to IPFS. Idempotent: later calls return the first commitment.
+    /// Proposals still open, or past their deadline but not yet settled, may
+    /// yet be extended or take votes, so they are refused.
+    pub async fn finalize_votes(&self, proposal_id: u64) -> Result<VoteSetCommitment> {
+        if let Some(commitment) = self.vote_commitments.get(proposal_id) {
+            return Ok(commitment);
+        }
+
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        let now = self.clock.timestamp();
+        let settled = !matches!(proposal.status, ProposalStatus::Pending | ProposalStatus::Active);
+        if !settled || now < proposal.end_time {
+            return Err(GovernanceError::VotesNotFinal { proposal_id });
+        }
+
+        let mut finalized = FinalizedVotes::new(proposal_id, self.indexer.get_votes(proposal_id), now);
+        match self.ipfs_client.add_json(&finalized.commitment).await {
+            Ok(hash) => finalized.commitment.ipfs_hash = Some(hash),
+            Err(e) => tracing::warn!("Failed to publish vote root for proposal {}: {}", proposal_id, e),
+        }
+
+        Ok(self.vote_commitments.insert(finalized))
+    }
+
+    /// Merkle proof that `voter`'s vote is in the proposal's final vote set,
+    /// finalizing the set first if needed
+    pub async fn vote_proof(&self, proposal_id: u64, voter: Address) -> Result<VoteInclusionProof> {
+        self.finalize_votes(proposal_id).await?;
+        self.vote_commitments.proof(proposal_id, voter).ok_or_else(|| {
+            GovernanceError::not_found(format!("{:?} did not vote on proposal {}", voter, proposal_id))
+        })
+    }
+
+    /// Submit a vote, rejecting a second submission from the same voter while
+    /// the first is still unconfirmed.
+    pub async fn cast_vote(
+        &self,
+        voter: Address,
+        proposal_id: u64,
+        choice: u8,
+        ipfs_hash: Option<String>,
+    ) -> Result<CastVoteOutcome> {
+        self.cast_vote_with_callback(voter, proposal_id, choice, ipfs_hash, None).await
+    }
+
+    /// `cast_vote`, sending a signed `TransactionCallback` to `callback_url`
+    /// once the vote's transaction settles
+    pub async fn cast_vote_with_callback(
+        &self,
+        voter: Address,
+        proposal_id: u64,
+        choice: u8,
+        ipfs_hash: Option<String>,
+        callback_url: Option<String>,
+    ) -> Result<CastVoteOutcome> {
+        self.validate_choice(proposal_id, choice).await?;
+        self.submit_vote(voter, proposal_id, choice, Vec::new(), ipfs_hash, callback_url).await
+    }
+
+    /// Submit a vote on a weighted proposal, splitting power across options
+    /// by index. The weights may total at most the voter's effective power;
+    /// only that total counts towards participation.
+    pub async fn cast_weighted_vote(
+        &self,
+        voter: Address,
+        proposal_id: u64,
+        weights: Vec<U256>,
+        ipfs_hash: Option<String>,
+        callback_url: Option<String>,
+    ) -> Result<CastVoteOutcome> {
+        self.validate_weights(proposal_id, &weights).await?;
+
+        // On chain the vote is recorded against its heaviest option
+        let choice = weights
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, weight)| (**weight, std::cmp::Reverse(*index)))
+            .map(|(index, _)| index as u8)
+            .unwrap_or_default();
+
+        self.submit_vote(voter, proposal_id, choice, weights, ipfs_hash, callback_url).await
+    }
+
+    async fn submit_vote(
+        &self,
+        voter: Address,
+        proposal_id: u64,
+        choice: u8,
+        weights: Vec<U256>,
+        ipfs_hash: Option<String>,
+        callback_url: Option<String>,
+    ) -> Result<CastVoteOutcome> {
+        if let Some(url) = &callback_url {
+            if !self.pending_transactions.callbacks_enabled() {
+                return Err(GovernanceError::invalid_request("Transaction callbacks are not enabled"));
+            }
+            validate_callback_url(url)?;
+        }
+
+        let duplicate = || GovernanceError::DuplicateVote {
+            proposal_id,
+            voter: format!("{:?}", voter),
+        };
+
+        // Reserve before any await so concurrent submissions can't both pass
+        let _guard = self
+            .pending_votes
+            .try_reserve(proposal_id, voter)
+            .ok_or_else(duplicate)?;
+
+        if self.blockchain_client.has_voted(proposal_id, voter).await? {
+            return Err(duplicate());
+        }
+
+        let flag = match &ipfs_hash {
+            Some(hash) => self.moderate_vote_content(hash).await?,
+            None => None,
+        };
+
+        let stats = self.voting_stats(voter, proposal_id).await?;
+        self.check_has_power(proposal_id, &stats)?;
+        self.check_min_vote_power(proposal_id, stats.effective_power).await?;
+        let power = if weights.is_empty() {
+            stats.effective_power
+        } else {
+            let distributed = weights
+                .iter()
+                .try_fold(U256::zero(), |total, weight| total.checked_add(*weight));
+            match distributed {
+                Some(total) if total <= stats.effective_power => total,
+                _ => {
+                    return Err(GovernanceError::invalid_request(format!(
+                        "Weights exceed available voting power {}",
+                        stats.effective_power
+                    )))
+                }
+            }
+        };
+
+        let receipt = self
+            .blockchain_client
+            .cast_vote(proposal_id, choice, ipfs_hash.clone())
+            .await?;
+        self.settle_transaction(
+            &receipt,
+            voter,
+            TransactionType::CastVote { proposal_id, choice },
+            callback_url,
+        )
+        .await;
+
+        self.indexer.index_vote(IndexedVote {
+            proposal_id,
+            contract: None,
+            voter,
+            choice,
+            power,
+            timestamp: self.clock.timestamp(),
+            ipfs_hash: ipfs_hash.clone(),
+            weights,
+        });
+        self.log_event(
+            proposal_id,
+            Some(voter),
+            Some(receipt.transaction_hash),
+            ProposalEvent::VoteCast { choice, power },
+        );
+
+        if let (Some(reason), Some(ipfs_hash)) = (flag, ipfs_hash) {
+            self.moderation_queue.record(FlaggedVote {
+                proposal_id,
+                voter,
+                ipfs_hash,
+                reason,
+                flagged_at: self.clock.timestamp(),
+            });
+        }
+
+        Ok(CastVoteOutcome {
+            receipt,
+            power,
+            power_source: stats.power_source,
+        })
+    }
+
+    /// Record a transaction submitted for `origin` with its receipt, telling
+    /// its callback, if any, how it went. The callback is sent in the
+    /// background so a slow receiver doesn't hold up the caller.
+    async fn settle_transaction(
+        &self,
+        receipt: &TransactionReceipt,
+        origin: Address,
+        transaction_type: TransactionType,
+        callback_url: Option<String>,
+    ) {
+        self.pending_transactions
+            .track(PendingTransaction {
+                hash: receipt.transaction_hash,
+                origin,
+                transaction_type,
+                submitted_at: self.clock.now(),
+                confirmations_required: 1,
+                current_confirmations: 0,
+                max_wait_time: std::time::Duration::from_secs(30),
+                callback_url,
+            })
+            .await;
+        let pending = self.pending_transactions.clone();
+        let receipt = receipt.clone();
+        tokio::spawn(async move { pending.settle(receipt, 1).await });
+    }
+
+    /// Dry run of `cast_vote`: whether `voter` could vote `choice` right now,
+    /// the power it would count and the estimated gas. Submits nothing.
+    pub async fn vote_preflight(&self, voter: Address, proposal_id: u64, choice: u8) -> Result<VotePreflight> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+
+        let now = self.clock.timestamp();
+        let active = proposal.status == ProposalStatus::Active && (proposal.start_time..proposal.end_time).contains(&now);
+        let valid_choice = self.validate_choice(proposal_id, choice).await;
+        let already_voted = self.pending_votes.is_pending(proposal_id, voter)
+            || self.blockchain_client.has_voted(proposal_id, voter).await?;
+        let stats = self.voting_stats(voter, proposal_id).await?;
+        let enough_power = self.check_min_vote_power(proposal_id, stats.effective_power).await;
+        let has_power = self.check_has_power(proposal_id, &stats);
+
+        let checks = vec![
+            PreflightCheck::new("proposal_active", active, || {
+                format!("Proposal {} is not open for voting", proposal_id)
+            }),
+            PreflightCheck::new("valid_choice", valid_choice.is_ok(), || {
+                valid_choice.as_ref().err().map(ToString::to_string).unwrap_or_default()
+            }),
+            PreflightCheck::new("not_yet_voted", !already_voted, || {
+                format!("{:?} has already voted on proposal {}", voter, proposal_id)
+            }),
+            PreflightCheck::new("has_voting_power", has_power.is_ok(), || {
+                has_power.as_ref().err().map(ToString::to_string).unwrap_or_default()
+            }),
+            PreflightCheck::new("meets_min_vote_power", enough_power.is_ok(), || {
+                enough_power.as_ref().err().map(ToString::to_string).unwrap_or_default()
+            }),
+        ];
+
+        let estimated_gas = match self.blockchain_client.estimate_cast_vote_gas(proposal_id, voter, choice).await {
+            Ok(gas) => Some(gas),
+            Err(e) => {
+                tracing::warn!("Vote gas estimate failed for proposal {}: {}", proposal_id, e);
+                None
+            }
+        };
+
+        Ok(VotePreflight {
+            proposal_id,
+            voter,
+            choice,
+            eligible: checks.iter().all(|check| check.passed),
+            checks,
+            voting_power: stats.effective_power,
+            power_source: stats.power_source,
+            estimated_gas,
+        })
+    }
+
+    /// Reject choices the proposal can't accept instead of letting them be
+    /// coerced: 0-2 for yes/no/abstain proposals, an option index for
+    /// option-based ones. Proposals not yet indexed are checked as binary.
+    async fn validate_choice(&self, proposal_id: u64, choice: u8) -> Result<()> {
+        match self.indexer.get_proposal(proposal_id) {
+            Some(proposal) if ProposalType::from(proposal.proposal_type).uses_options() => {
+                let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+                let option_count = content.metadata.options.len();
+                if choice as usize >= option_count {
+                    return Err(GovernanceError::invalid_request(format!(
+                        "Invalid choice {}: proposal {} has {} options",
+                        choice, proposal_id, option_count
+                    )));
+                }
+            }
+            _ => {
+                if VoteChoice::from_choice(choice).is_none() {
+                    return Err(GovernanceError::invalid_request(format!(
+                        "Invalid choice {}: expected 0 (no), 1 (yes) or 2 (abstain)",
+                        choice
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run the vote's comment and reasoning past the moderator. Rejections
+    /// block the vote; a flag reason is returned for the caller to record.
+    async fn moderate_vote_content(&self, ipfs_hash: &str) -> Result<Option<String>> {
+        let content = self.ipfs_client.get_vote_content(ipfs_hash).await?;
+
+        let mut flag = None;
+        for text in [&content.comment, &content.reasoning].into_iter().flatten() {
+            match self.moderator.review(text) {
+                ModerationVerdict::Accept => {}
+                ModerationVerdict::Flag(reason) => flag = flag.or(Some(reason)),
+                ModerationVerdict::Reject(reason) => return Err(GovernanceError::ContentRejected(reason)),
+            }
+        }
+
+        Ok(flag)
+    }
+
+    /// Weighted votes need an indexed weighted proposal, at most one weight
+    /// per option and some power actually distributed
+    async fn validate_weights(&self, proposal_id: u64, weights: &[U256]) -> Result<()> {
+        let proposal = self
+            .indexer
+            .get_proposal(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound { proposal_id })?;
+        if ProposalType::from(proposal.proposal_type) != ProposalType::Weighted {
+            return Err(GovernanceError::invalid_request(format!(
+                "Proposal {} does not accept weighted votes",
+                proposal_id
+            )));
+        }
+
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        let option_count = content.metadata.options.len();
+        if weights.len() > option_count {
+            return Err(GovernanceError::invalid_request(format!(
+                "Got {} weights: proposal {} has {} options",
+                weights.len(),
+                proposal_id,
+                option_count
+            )));
+        }
+
+        if weights.iter().all(|weight| weight.is_zero()) {
+            return Err(GovernanceError::invalid_request("Weighted vote distributes no power"));
+        }
+
+        Ok(())
+    }
+
+    /// Proposals may require a minimum effective power to vote, e.g. to keep
+    /// dust accounts out. Proposals not yet indexed have no minimum.
+    async fn check_min_vote_power(&self, proposal_id: u64, effective_power: U256) -> Result<()> {
+        let Some(proposal) = self.indexer.get_proposal(proposal_id) else {
+            return Ok(());
+        };
+
+        let content = self.ipfs_client.get_proposal_content(&proposal.ipfs_hash).await?;
+        match content.metadata.min_vote_power {
+            // Less than a u64 minimum, so the power fits in a u64
+            Some(required) if effective_power < U256::from(required) => {
+                Err(GovernanceError::InsufficientVotingPower {
+                    required,
+                    available: effective_power.as_u64(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn pending_votes(&self) -> &PendingVotes {
+        &self.pending_votes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::contracts::{
+        GovernanceHubContract, MockGovernanceHub, MockSimpleVoting, ProposalData, SimpleVotingContract, VoteData,
+    };
+    use crate::config::Config;
+    use crate::governance::proposals::{tally_binary, ProposalResults};
+    use crate::ipfs::content_types::{ProposalMetadata, ProposalType};
+    use async_trait::async_trait;
+    use ethers::types::{TransactionReceipt, U256, U64};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn proposal_content(proposal_type: ProposalType, options: &[&str]) -> ProposalIPFSContent {
+        ProposalIPFSContent {
+            title: "Test Proposal".to_string(),
+            description: "This is a test proposal description.".to_string(),
+            metadata: ProposalMetadata {
+                proposal_type,
+                options: options.iter().map(|o| o.to_string()).collect(),
+                ..Default::default()
+            },
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn mock_engine() -> GovernanceEngine {
+        let config = Config::default();
+        GovernanceEngine::new(SomniaClient::mock(&config), IpfsClient::in_memory(&config))
+            .await
+            .unwrap()
+    }
+
+    /// Voting contract that takes a while to confirm and counts submissions
+    #[derive(Default)]
+    struct SlowVoting {
+        submissions: AtomicUsize,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl SimpleVotingContract for SlowVoting {
+        async fn cast_vote(&self, _proposal_id: u64, _choice: u8, _ipfs_hash: Option<String>) -> Result<TransactionReceipt> {
+            self.submissions.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            if self.fail {
+                return Err(GovernanceError::Internal(anyhow::anyhow!("Transaction reverted")));
+            }
+            Ok(TransactionReceipt {
+                status: Some(U64::from(1)),
+                ..Default::default()
+            })
+        }
+
+        async fn get_vote(&self, _proposal_id: u64, _voter: Address) -> Result<Option<VoteData>> {
+            Ok(None)
+        }
+
+        async fn get_proposal_votes(&self, _proposal_id: u64) -> Result<Vec<VoteData>> {
+            Ok(vec![])
+        }
+
+        async fn has_voted(&self, _proposal_id: u64, _voter: Address) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn get_vote_tally(&self, _proposal_id: u64) -> Result<(U256, U256, U256)> {
+            Ok((U256::zero(), U256::zero(), U256::zero()))
+        }
+    }
+
+    async fn engine_with(voting: Arc<SlowVoting>) -> GovernanceEngine {
+        let config = Config::default();
+        let client = SomniaClient::with_contracts(&config, Arc::new(MockGovernanceHub::new()), voting);
+        GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_vote_rejected() {
+        let voting = Arc::new(SlowVoting::default());
+        let engine = engine_with(voting.clone()).await;
+        let voter = Address::random();
+
+        let (first, second) = tokio::join!(
+            engine.cast_vote(voter, 1, 1, None),
+            engine.cast_vote(voter, 1, 1, None),
+        );
+
+        assert_eq!(voting.submissions.load(Ordering::SeqCst), 1);
+        assert!(first.is_ok() != second.is_ok());
+        assert!(matches!(
+            first.err().or(second.err()),
+            Some(GovernanceError::DuplicateVote { proposal_id: 1, .. })
+        ));
+        assert!(engine.pending_votes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_guard_cleared_after_failure() {
+        let voting = Arc::new(SlowVoting { fail: true, ..Default::default() });
+        let engine = engine_with(voting.clone()).await;
+        let voter = Address::random();
+
+        assert!(engine.cast_vote(voter, 1, 1, None).await.is_err());
+        assert!(!engine.pending_votes().is_pending(1, voter));
+
+        // A retry after the failed submission is allowed through
+        assert!(engine.cast_vote(voter, 1, 1, None).await.is_err());
+        assert_eq!(voting.submissions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_multi_choice_detail_lists_option_tallies() {
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::MultipleChoice, &["Alpha", "Beta", "Gamma"]);
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        for (choice, power) in [(0u8, 10u64), (2, 7), (0, 3)] {
+            engine.indexer().index_vote(IndexedVote {
+                proposal_id: proposal.id,
+                contract: None,
+                voter: Address::random(),
+                choice,
+                power: U256::from(power),
+                timestamp: proposal.start_time,
+                ipfs_hash: None,
+                weights: Vec::new(),
+            });
+        }
+
+        let detail = engine.get_proposal_detail(proposal.id).await.unwrap();
+        assert_eq!(detail.metadata.options, vec!["Alpha", "Beta", "Gamma"]);
+        match detail.results {
+            ProposalResults::Options { options } => {
+                let tallies: Vec<(String, U256)> = options.into_iter().map(|o| (o.label, o.votes)).collect();
+                assert_eq!(tallies, vec![
+                    ("Alpha".to_string(), U256::from(13)),
+                    ("Beta".to_string(), U256::zero()),
+                    ("Gamma".to_string(), U256::from(7)),
+                ]);
+            }
+            _ => panic!("Expected option results"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_binary_detail_uses_standard_shape() {
+        let engine = mock_engine().await;
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+
+        let detail = engine.get_proposal_detail(proposal.id).await.unwrap();
+        assert!(matches!(detail.results, ProposalResults::Binary { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_option_labels_rejected_at_creation() {
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::MultipleChoice, &["Alpha", "alpha"]);
+
+        assert!(engine.create_proposal(Address::random(), content, 86400).await.is_err());
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 0);
+        assert_eq!(engine.indexer().proposal_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_option_count_bounds_enforced_at_creation() {
+        let engine = mock_engine().await;
+        let labels: Vec<String> = (1..=21).map(|i| format!("Option {}", i)).collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+
+        for options in [&labels[..1], &labels[..21]] {
+            let content = proposal_content(ProposalType::RankedChoice, options);
+            let result = engine.create_proposal(Address::random(), content, 86400).await;
+            assert!(matches!(result, Err(GovernanceError::InvalidRequest(message)) if message.contains("between 2 and 20")));
+        }
+        assert_eq!(engine.indexer().proposal_count(), 0);
+
+        for options in [&labels[..2], &labels[..20]] {
+            let content = proposal_content(ProposalType::RankedChoice, options);
+            engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+        }
+
+        let strict = engine.clone().with_config(GovernanceConfig {
+            option_limits: crate::config::OptionLimits {
+                min_options: 3,
+                max_options: 5,
+            },
+            ..Default::default()
+        });
+        let content = proposal_content(ProposalType::MultipleChoice, &labels[..2]);
+        assert!(strict.create_proposal(Address::random(), content, 86400).await.is_err());
+    }
+
+    async fn tally_with_delegation_cap(cap: Option<&str>) -> U256 {
+        let config = Config::default();
+        let engine = GovernanceEngine::new(SomniaClient::mock(&config), IpfsClient::in_memory(&config))
+            .await
+            .unwrap()
+            .with_config(GovernanceConfig {
+                max_delegated_power: cap.map(str::to_string),
+                ..Default::default()
+            });
+
+        // Mock hub reports 1000 power for every address
+        let delegate = Address::random();
+        engine.delegations().set_delegate(Address::random(), delegate);
+        engine.delegations().set_delegate(Address::random(), delegate);
+
+        engine.cast_vote(delegate, 1, 1, None).await.unwrap();
+
+        match tally_binary(&engine.indexer().get_votes(1)) {
+            ProposalResults::Binary { yes_votes, .. } => yes_votes,
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delegated_power_clamped_to_cap() {
+        assert_eq!(tally_with_delegation_cap(Some("1500")).await, U256::from(2500));
+    }
+
+    #[tokio::test]
+    async fn test_delegated_power_under_cap_counts_fully() {
+        assert_eq!(tally_with_delegation_cap(Some("5000")).await, U256::from(3000));
+        assert_eq!(tally_with_delegation_cap(None).await, U256::from(3000));
+    }
+
+    #[tokio::test]
+    async fn test_delegate_stats_warn_at_cap() {
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            max_delegated_power: Some("500".to_string()),
+            ..Default::default()
+        });
+        let delegate = Address::random();
+        engine.delegations().set_delegate(Address::random(), delegate);
+
+        let stats = engine.delegate_stats(delegate).await.unwrap();
+        assert!(stats.cap_reached);
+        assert_eq!(stats.counted_power, U256::from(500));
+        assert!(stats.warning.is_some());
+    }
+
+    fn superseding_content(target: u64) -> ProposalIPFSContent {
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.supersedes = Some(target);
+        content
+    }
+
+    #[tokio::test]
+    async fn test_superseding_proposal_links_both_directions() {
+        let engine = mock_engine().await;
+        let original = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let amendment = engine
+            .create_proposal(Address::random(), superseding_content(original.id), 86400)
+            .await
+            .unwrap();
+        assert_eq!(amendment.supersedes, Some(original.id));
+
+        let detail = engine.get_proposal_detail(amendment.id).await.unwrap();
+        assert_eq!(detail.supersedes, Some(original.id));
+        assert!(detail.superseded_by.is_empty());
+
+        let detail = engine.get_proposal_detail(original.id).await.unwrap();
+        assert_eq!(detail.superseded_by, vec![amendment.id]);
+        // Auto-cancel is off by default
+        assert_eq!(detail.status, ProposalStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_superseded_proposal_auto_canceled() {
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            auto_cancel_superseded: true,
+            ..Default::default()
+        });
+        let original = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        engine
+            .create_proposal(Address::random(), superseding_content(original.id), 86400)
+            .await
+            .unwrap();
+
+        assert_eq!(engine.indexer().get_proposal(original.id).unwrap().status, ProposalStatus::Canceled);
+    }
+
+    #[tokio::test]
+    async fn test_supersession_rejects_missing_and_cyclic_targets() {
+        let engine = mock_engine().await;
+        assert!(engine
+            .create_proposal(Address::random(), superseding_content(42), 86400)
+            .await
+            .is_err());
+
+        // Two indexed proposals that supersede each other
+        for (id, supersedes) in [(10, 11), (11, 10)] {
+            engine.indexer().index_proposal(IndexedProposal {
+                id,
+                contract: None,
+                proposer: Address::zero(),
+                ipfs_hash: "QmTest123".to_string(),
+                proposal_type: 0,
+                status: ProposalStatus::Active,
+                start_time: 0,
+                end_time: 86400,
+                supersedes: Some(supersedes),
+                total_voting_power: U256::zero(),
+                tie_extended: false,
+            });
+        }
+
+        let result = engine
+            .create_proposal(Address::random(), superseding_content(10), 86400)
+            .await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_binary_choice_rejected() {
+        let engine = mock_engine().await;
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+
+        let result = engine.cast_vote(Address::random(), proposal.id, 3, None).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+        assert!(engine.indexer().get_votes(proposal.id).is_empty());
+
+        for choice in 0..=2 {
+            engine.cast_vote(Address::random(), proposal.id, choice, None).await.unwrap();
+        }
+        assert_eq!(engine.indexer().get_votes(proposal.id).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_multi_choice_vote_bounded_by_options() {
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::MultipleChoice, &["Alpha", "Beta", "Gamma", "Delta"]);
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        engine.cast_vote(Address::random(), proposal.id, 3, None).await.unwrap();
+        let result = engine.cast_vote(Address::random(), proposal.id, 4, None).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+    }
+
+    /// Hub whose voting power lookups fail while `power_down` is set
+    #[derive(Default)]
+    struct FlakyPowerHub {
+        inner: MockGovernanceHub,
+        power_down: AtomicBool,
+    }
+
+    #[async_trait]
+    impl GovernanceHubContract for FlakyPowerHub {
+        async fn create_proposal(&self, ipfs_hash: String, voting_duration: U256, proposal_type: u8) -> Result<TransactionReceipt> {
+            self.inner.create_proposal(ipfs_hash, voting_duration, proposal_type).await
+        }
+
+        async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData> {
+            self.inner.get_proposal(proposal_id).await
+        }
+
+        async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt> {
+            self.inner.execute_proposal(proposal_id).await
+        }
+
+        async fn get_proposal_count(&self) -> Result<u64> {
+            self.inner.get_proposal_count().await
+        }
+
+        async fn get_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<ProposalData>> {
+            self.inner.get_proposals_by_status(status).await
+        }
+
+        async fn get_user_voting_power(&self, user: Address) -> Result<U256> {
+            if self.power_down.load(Ordering::SeqCst) {
+                return Err(GovernanceError::Internal(anyhow::anyhow!("RPC unavailable")));
+            }
+            self.inner.get_user_voting_power(user).await
+        }
+
+        async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256> {
+            self.inner.get_voting_power_at(user, block).await
+        }
+    }
+
+    async fn engine_with_flaky_power(fallback: VotingPowerFallback) -> (GovernanceEngine, Arc<FlakyPowerHub>) {
+        let config = Config::default();
+        let hub = Arc::new(FlakyPowerHub::default());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config))
+            .await
+            .unwrap()
+            .with_config(GovernanceConfig {
+                voting_power_fallback: fallback,
+                ..Default::default()
+            });
+        (engine, hub)
+    }
+
+    #[tokio::test]
+    async fn test_power_outage_fails_closed_by_default() {
+        assert_eq!(GovernanceConfig::default().voting_power_fallback, VotingPowerFallback::FailClosed);
+
+        let (engine, hub) = engine_with_flaky_power(VotingPowerFallback::FailClosed).await;
+        let voter = Address::random();
+        engine.delegate_stats(voter).await.unwrap();
+
+        hub.power_down.store(true, Ordering::SeqCst);
+        assert!(engine.cast_vote(voter, 1, 1, None).await.is_err());
+        assert!(engine.indexer().get_votes(1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_power_outage_uses_last_known_power() {
+        let (engine, hub) = engine_with_flaky_power(VotingPowerFallback::LastKnown).await;
+        let voter = Address::random();
+
+        let outcome = engine.cast_vote(voter, 1, 1, None).await.unwrap();
+        assert_eq!(outcome.power_source, PowerSource::Live);
+
+        hub.power_down.store(true, Ordering::SeqCst);
+        let outcome = engine.cast_vote(voter, 2, 1, None).await.unwrap();
+        assert_eq!(outcome.power_source, PowerSource::LastKnown);
+        assert_eq!(outcome.power, U256::from(1000));
+
+        // Nothing known for a fresh address, so there is nothing to fall back to
+        assert!(engine.cast_vote(Address::random(), 2, 1, None).await.is_err());
+    }
+
+    /// Hub where someone else's proposal lands right after each of ours
+    #[derive(Default)]
+    struct RacingHub {
+        inner: MockGovernanceHub,
+    }
+
+    #[async_trait]
+    impl GovernanceHubContract for RacingHub {
+        async fn create_proposal(&self, ipfs_hash: String, voting_duration: U256, proposal_type: u8) -> Result<TransactionReceipt> {
+            let receipt = self.inner.create_proposal(ipfs_hash, voting_duration, proposal_type).await?;
+            self.inner.create_proposal("QmCompetitor".to_string(), voting_duration, proposal_type).await?;
+            Ok(receipt)
+        }
+
+        async fn get_proposal(&self, proposal_id: u64) -> Result<ProposalData> {
+            self.inner.get_proposal(proposal_id).await
+        }
+
+        async fn execute_proposal(&self, proposal_id: u64) -> Result<TransactionReceipt> {
+            self.inner.execute_proposal(proposal_id).await
+        }
+
+        async fn get_proposal_count(&self) -> Result<u64> {
+            self.inner.get_proposal_count().await
+        }
+
+        async fn get_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<ProposalData>> {
+            self.inner.get_proposals_by_status(status).await
+        }
+
+        async fn get_user_voting_power(&self, user: Address) -> Result<U256> {
+            self.inner.get_user_voting_power(user).await
+        }
+
+        async fn get_voting_power_at(&self, user: Address, block: u64) -> Result<U256> {
+            self.inner.get_voting_power_at(user, block).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_proposal_id_survives_concurrent_creation() {
+        let config = Config::default();
+        let hub = Arc::new(RacingHub::default());
+        let client = SomniaClient::with_contracts(&config, hub, Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        assert_eq!(proposal.id, 1);
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 2);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().ipfs_hash, proposal.ipfs_hash);
+        assert!(engine.indexer().get_proposal(2).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vote_callback_sent_once_vote_settles() {
+        use crate::blockchain::callbacks::{CallbackDispatcher, CallbackSender, CallbackStatus, TransactionCallback};
+
+        #[derive(Default)]
+        struct RecordingSender {
+            sent: Mutex<Vec<(String, Vec<u8>)>>,
+        }
+
+        #[async_trait]
+        impl CallbackSender for RecordingSender {
+            async fn send(&self, url: &str, body: Vec<u8>, _signature: String) -> Result<()> {
+                self.sent.lock().unwrap().push((url.to_string(), body));
+                Ok(())
+            }
+        }
+
+        let engine = mock_engine().await;
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let url = Some("https://bot.example/votes".to_string());
+
+        // Nobody to sign callbacks with
+        let result = engine.cast_vote_with_callback(Address::random(), proposal.id, 1, None, url.clone()).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+
+        let sender = Arc::new(RecordingSender::default());
+        let pending = PendingTransactions::new().with_callbacks(CallbackDispatcher::new(sender.clone(), b"secret", 10));
+        let engine = engine.with_pending_transactions(pending.clone());
+        let internal = Some("http://169.254.169.254/latest".to_string());
+        let result = engine.cast_vote_with_callback(Address::random(), proposal.id, 1, None, internal).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+
+        let voter = Address::random();
+        let outcome = engine.cast_vote_with_callback(voter, proposal.id, 1, None, url).await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            while sender.sent.lock().unwrap().is_empty() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent[0].0, "https://bot.example/votes");
+        let callback: TransactionCallback = serde_json::from_slice(&sent[0].1).unwrap();
+        assert_eq!(callback.transaction_hash, outcome.receipt.transaction_hash);
+        assert_eq!(callback.origin, voter);
+        assert_eq!(callback.status, CallbackStatus::Confirmed);
+        assert_eq!(callback.transaction_type, TransactionType::CastVote { proposal_id: proposal.id, choice: 1 });
+    }
+
+    async fn execution_outcome(revert: bool) -> (ExecutionResult, ProposalStatus) {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        hub.revert_executions.store(revert, Ordering::SeqCst);
+        let client = SomniaClient::with_contracts(&config, hub, Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        engine.execute_proposal(proposal.id).await.unwrap();
+
+        let stored = engine.indexer().get_execution(proposal.id).unwrap();
+        let status = engine.indexer().get_proposal(proposal.id).unwrap().status;
+        (stored, status)
+    }
+
+    #[tokio::test]
+    async fn test_submissions_tracked_for_their_origin() {
+        let pending = PendingTransactions::new();
+        let engine = mock_engine().await.with_pending_transactions(pending.clone());
+        let proposer = Address::random();
+        let proposal = engine
+            .create_proposal(proposer, proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let voter = Address::random();
+        let vote = engine.cast_vote(voter, proposal.id, 1, None).await.unwrap();
+        let execution = engine.execute_proposal(proposal.id).await.unwrap();
+
+        let created = engine.proposal_audit_trail(proposal.id).unwrap()[0].transaction_hash.unwrap();
+        let tracked = pending.get(created).await.unwrap();
+        assert_eq!(tracked.origin, proposer);
+        assert!(matches!(tracked.transaction_type, TransactionType::CreateProposal { .. }));
+
+        let tracked = pending.get(vote.receipt.transaction_hash).await.unwrap();
+        assert_eq!(tracked.origin, voter);
+        assert_eq!(tracked.transaction_type, TransactionType::CastVote { proposal_id: proposal.id, choice: 1 });
+
+        let tracked = pending.get(execution.transaction_hash).await.unwrap();
+        assert_eq!(tracked.origin, proposer);
+        assert_eq!(tracked.transaction_type, TransactionType::ExecuteProposal { proposal_id: proposal.id });
+    }
+
+    #[tokio::test]
+    async fn test_successful_execution_recorded() {
+        let (result, status) = execution_outcome(false).await;
+        assert_eq!(result.outcome, ExecutionOutcome::Succeeded);
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(status, ProposalStatus::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_execution_reported_differently() {
+        let (result, status) = execution_outcome(true).await;
+        assert_eq!(result.outcome, ExecutionOutcome::Reverted);
+        assert!(result.events.is_empty());
+        assert_eq!(status, ProposalStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_private_votes_hidden_until_close() {
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.hide_votes_until_close = true;
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        let (alice, bob) = (Address::random(), Address::random());
+        engine.cast_vote(alice, proposal.id, 1, None).await.unwrap();
+        engine.cast_vote(bob, proposal.id, 0, None).await.unwrap();
+
+        // During voting: full tally, but only the viewer's own vote
+        let seen_by_alice = engine.proposal_votes(proposal.id, Some(alice)).await.unwrap();
+        assert!(seen_by_alice.votes_hidden);
+        assert_eq!(seen_by_alice.votes.len(), 1);
+        assert_eq!(seen_by_alice.votes[0].voter, alice);
+        match seen_by_alice.results {
+            ProposalResults::Binary { yes_votes, no_votes, .. } => {
+                assert_eq!(yes_votes, U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER));
+                assert_eq!(no_votes, U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER));
+            }
+            _ => panic!("Expected binary results"),
+        }
+        assert!(engine.proposal_votes(proposal.id, None).await.unwrap().votes.is_empty());
+
+        clock.advance(chrono::Duration::seconds(86401));
+        let after_close = engine.proposal_votes(proposal.id, None).await.unwrap();
+        assert!(!after_close.votes_hidden);
+        assert_eq!(after_close.votes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_incoming_and_outgoing_delegations() {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+
+        // c -> a -> delegate <- b
+        let (a, b, c, delegate) = (Address::random(), Address::random(), Address::random(), Address::random());
+        for (address, power) in [(a, 100), (b, 20), (c, 3), (delegate, 5000)] {
+            hub.set_voting_power(address, U256::from(power));
+        }
+        engine.delegations().set_delegate(a, delegate);
+        engine.delegations().set_delegate(b, delegate);
+        engine.delegations().set_delegate(c, a);
+
+        let all = PaginationParams { page: None, limit: None };
+        let incoming = engine.list_delegations(delegate, DelegationDirection::Incoming, &all).await.unwrap();
+        assert_eq!(incoming.entries.total, 2);
+        assert_eq!(incoming.page_power, U256::from(123));
+        for entry in &incoming.entries.data {
+            let expected = if entry.address == a { 103 } else { 20 };
+            assert_eq!(entry.power, U256::from(expected));
+        }
+        assert_eq!(
+            incoming.page_power,
+            engine.delegate_stats(delegate).await.unwrap().received_power
+        );
+
+        let second_page = PaginationParams { page: Some(2), limit: Some(1) };
+        let page = engine.list_delegations(delegate, DelegationDirection::Incoming, &second_page).await.unwrap();
+        assert_eq!(page.entries.data.len(), 1);
+        assert_eq!(page.entries.total, 2);
+        assert!(!page.entries.has_next);
+        assert_eq!(page.page_power, page.entries.data[0].power);
+
+        let outgoing = engine.list_delegations(c, DelegationDirection::Outgoing, &all).await.unwrap();
+        assert_eq!(outgoing.entries.data.len(), 1);
+        assert_eq!(outgoing.entries.data[0].address, a);
+        assert_eq!(outgoing.page_power, U256::from(3));
+
+        let outgoing = engine.list_delegations(delegate, DelegationDirection::Outgoing, &all).await.unwrap();
+        assert!(outgoing.entries.data.is_empty());
+        assert_eq!(outgoing.page_power, U256::zero());
+    }
+
+    #[tokio::test]
+    async fn test_participation_alerts_only_below_quorum() {
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone())).with_config(GovernanceConfig {
+            participation_alert_window: Some(3600),
+            ..Default::default()
+        });
+        let now = clock.timestamp();
+        for (id, participation) in [(1, 100u64), (2, 5000)] {
+            engine.indexer().index_proposal(IndexedProposal {
+                id,
+                contract: None,
+                proposer: Address::zero(),
+                ipfs_hash: "QmTest123".to_string(),
+                proposal_type: 0,
+                status: ProposalStatus::Active,
+                start_time: now - 86400,
+                end_time: now + 1800,
+                supersedes: None,
+                total_voting_power: U256::from(10_000),
+                tie_extended: false,
+            });
+            engine.indexer().index_vote(IndexedVote {
+                proposal_id: id,
+                contract: None,
+                voter: Address::random(),
+                choice: 1,
+                power: U256::from(participation),
+                timestamp: now - 60,
+                ipfs_hash: None,
+                weights: Vec::new(),
+            });
+        }
+
+        let alerts = engine.check_participation();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].proposal_id, 1);
+        assert_eq!(alerts[0].shortfall, U256::from(300));
+        assert_eq!(engine.participation_monitor().alerts_total(), 1);
+
+        // Disabled without a configured window
+        let engine = engine.with_config(GovernanceConfig::default());
+        assert!(engine.check_participation().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_flags_voter_who_already_voted() {
+        let engine = mock_engine().await;
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let voter = Address::random();
+
+        let preflight = engine.vote_preflight(voter, proposal.id, 1).await.unwrap();
+        assert!(preflight.eligible);
+        assert_eq!(preflight.voting_power, U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER));
+
+        // A vote from the same voter still awaiting confirmation
+        let _pending = engine.pending_votes().try_reserve(proposal.id, voter).unwrap();
+        let preflight = engine.vote_preflight(voter, proposal.id, 7).await.unwrap();
+        assert!(!preflight.eligible);
+        let failed: Vec<_> = preflight.failed_checks().map(|check| check.name.as_str()).collect();
+        assert_eq!(failed, vec!["valid_choice", "not_yet_voted"]);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_vote_distributes_power_across_options() {
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::Weighted, &["Grants", "Audits", "Events"]);
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        // Mock hub reports 1000 power for every address
+        let weights = vec![U256::from(500), U256::from(300), U256::from(150)];
+        let outcome = engine
+            .cast_weighted_vote(Address::random(), proposal.id, weights, None, None)
+            .await
+            .unwrap();
+        assert_eq!(outcome.power, U256::from(950));
+        engine
+            .cast_weighted_vote(Address::random(), proposal.id, vec![U256::zero(), U256::from(1000)], None, None)
+            .await
+            .unwrap();
+
+        match engine.get_proposal_detail(proposal.id).await.unwrap().results {
+            ProposalResults::Options { options } => {
+                let tallies: Vec<_> = options.iter().map(|option| option.votes.as_u64()).collect();
+                assert_eq!(tallies, vec![500, 1300, 150]);
+            }
+            _ => panic!("Expected option results"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weighted_vote_over_available_power_rejected() {
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::Weighted, &["Grants", "Audits", "Events"]);
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+        let voter = Address::random();
+
+        let weights = vec![U256::from(600), U256::from(300), U256::from(101)];
+        let result = engine.cast_weighted_vote(voter, proposal.id, weights, None, None).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+        assert!(engine.indexer().get_votes(proposal.id).is_empty());
+
+        // Too many weights, or a proposal that isn't weighted
+        let four = vec![U256::from(1); 4];
+        assert!(engine.cast_weighted_vote(voter, proposal.id, four, None, None).await.is_err());
+        let binary = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        assert!(engine.cast_weighted_vote(voter, binary.id, vec![U256::from(1)], None, None).await.is_err());
+    }
+
+    async fn vote_with_comment(engine: &GovernanceEngine, comment: &str) -> String {
+        use crate::ipfs::content_types::{VoteIPFSContent, VoteMetadata};
+
+        let content = VoteIPFSContent {
+            choice: VoteChoice::Yes,
+            comment: Some(comment.to_string()),
+            reasoning: None,
+            metadata: VoteMetadata {
+                voting_power: "1000".to_string(),
+                delegated_votes: None,
+                timestamp: chrono::Utc::now(),
+                version: "1.0".to_string(),
+            },
+            content_type: "vote".to_string(),
+        };
+        engine.ipfs_client().add_vote_content(&content).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_moderator_rejects_banned_words_and_flags_others() {
+        use crate::config::ModerationConfig;
+
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            moderation: ModerationConfig {
+                banned_words: vec!["scam".to_string()],
+                flagged_words: vec!["shill".to_string()],
+                max_length: None,
+            },
+            ..Default::default()
+        });
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+
+        let abusive = vote_with_comment(&engine, "Obvious scam, vote no").await;
+        let result = engine.cast_vote(Address::random(), proposal.id, 0, Some(abusive)).await;
+        assert!(matches!(result, Err(GovernanceError::ContentRejected(_))));
+        assert!(engine.indexer().get_votes(proposal.id).is_empty());
+
+        let clean = vote_with_comment(&engine, "Solid plan, well budgeted").await;
+        engine.cast_vote(Address::random(), proposal.id, 1, Some(clean)).await.unwrap();
+        assert!(engine.moderation_queue().list().is_empty());
+
+        let voter = Address::random();
+        let flagged = vote_with_comment(&engine, "Reads like a shill post").await;
+        engine.cast_vote(voter, proposal.id, 1, Some(flagged.clone())).await.unwrap();
+        assert_eq!(engine.indexer().get_votes(proposal.id).len(), 2);
+        let queue = engine.moderation_queue().list();
+        assert_eq!(queue.len(), 1);
+        assert_eq!((queue[0].voter, queue[0].ipfs_hash.as_str()), (voter, flagged.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_presets_only_duration_enforced() {
+        const WEEK: u64 = 7 * 86400;
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            duration_presets: vec![3 * 86400, WEEK, 2 * WEEK],
+            presets_only: true,
+            ..Default::default()
+        });
+        let content = || proposal_content(ProposalType::Simple, &[]);
+
+        let proposal = engine.create_proposal(Address::random(), content(), WEEK).await.unwrap();
+        assert_eq!(proposal.end_time - proposal.start_time, WEEK);
+
+        let result = engine.create_proposal(Address::random(), content(), 86400).await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+        assert_eq!(engine.duration_options().presets.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_free_form_duration_bounded() {
+        let engine = mock_engine().await;
+        let content = || proposal_content(ProposalType::Simple, &[]);
+
+        assert!(engine.create_proposal(Address::random(), content(), 5 * 3600).await.is_ok());
+        assert!(engine.create_proposal(Address::random(), content(), 60).await.is_err());
+        assert!(engine.create_proposal(Address::random(), content(), 60 * 86400).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vote_proof_verifies_against_stored_root() {
+        use crate::governance::receipts::{verify_proof, vote_leaf};
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let voters: Vec<_> = (0..5).map(|_| Address::random()).collect();
+        for voter in &voters {
+            engine.cast_vote(*voter, proposal.id, 1, None).await.unwrap();
+        }
+
+        // Not final while voting is open, nor once closed until settled
+        assert!(matches!(
+            engine.vote_proof(proposal.id, voters[0]).await,
+            Err(GovernanceError::VotesNotFinal { .. })
+        ));
+        clock.advance(chrono::Duration::seconds(86401));
+        assert!(matches!(
+            engine.finalize_votes(proposal.id).await,
+            Err(GovernanceError::VotesNotFinal { .. })
+        ));
+
+        engine.finalize_proposal(proposal.id).await.unwrap();
+        let commitment = engine.finalize_votes(proposal.id).await.unwrap();
+        assert_eq!(commitment.vote_count, 5);
+        assert!(commitment.ipfs_hash.is_some());
+
+        let receipt = engine.vote_proof(proposal.id, voters[3]).await.unwrap();
+        assert_eq!(receipt.root, commitment.root);
+        assert!(verify_proof(receipt.leaf, &receipt.proof, commitment.root));
+
+        let mut tampered = receipt.vote.clone();
+        tampered.choice = 0;
+        assert!(!verify_proof(vote_leaf(&tampered), &receipt.proof, commitment.root));
+
+        // Late votes don't change the committed set
+        engine.indexer().index_vote(IndexedVote { voter: Address::random(), ..receipt.vote.clone() });
+        assert_eq!(engine.finalize_votes(proposal.id).await.unwrap().root, commitment.root);
+        assert!(matches!(
+            engine.vote_proof(proposal.id, Address::random()).await,
+            Err(GovernanceError::NotFound(_))
+        ));
+    }
+
+    /// Closed binary proposal with yes and no power exactly tied
+    fn index_tied_proposal(engine: &GovernanceEngine, id: u64, now: u64) {
+        engine.indexer().index_proposal(IndexedProposal {
+            id,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash: "QmTest123".to_string(),
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time: now - 86400,
+            end_time: now - 1,
+            supersedes: None,
+            total_voting_power: U256::from(10_000),
+            tie_extended: false,
+        });
+        for choice in [0, 1, 2] {
+            engine.indexer().index_vote(IndexedVote {
+                proposal_id: id,
+                contract: None,
+                voter: Address::random(),
+                choice,
+                power: U256::from(500),
+                timestamp: now - 60,
+                ipfs_hash: None,
+                weights: Vec::new(),
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tie_policy_reject_and_pass() {
+        let engine = mock_engine().await;
+        let now = engine.clock.timestamp();
+
+        index_tied_proposal(&engine, 1, now);
+        assert_eq!(engine.evaluate_proposal(1).await.unwrap(), ProposalStatus::Rejected);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().status, ProposalStatus::Rejected);
+
+        let engine = engine.with_config(GovernanceConfig {
+            tie_policy: TiePolicy::Pass,
+            ..Default::default()
+        });
+        index_tied_proposal(&engine, 2, now);
+        assert_eq!(engine.evaluate_proposal(2).await.unwrap(), ProposalStatus::Passed);
+        // Settled proposals keep their outcome
+        assert_eq!(engine.evaluate_proposal(1).await.unwrap(), ProposalStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_what_if_projects_hypothetical_vote() {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+        let now = engine.clock.timestamp();
+        let content = proposal_content(ProposalType::Simple, &[]);
+        let ipfs_hash = engine.ipfs_client.add_proposal_content(&content).await.unwrap();
+        engine.indexer().index_proposal(IndexedProposal {
+            id: 1,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash,
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time: now - 3600,
+            end_time: now + 86400,
+            supersedes: None,
+            total_voting_power: U256::from(10_000),
+            tie_extended: false,
+        });
+        let holder = Address::random();
+        hub.set_voting_power(holder, U256::from(2000));
+        for (voter, choice, power) in [(holder, 0, 300), (Address::random(), 0, 800), (Address::random(), 1, 500)] {
+            engine.indexer().index_vote(IndexedVote {
+                proposal_id: 1,
+                contract: None,
+                voter,
+                choice,
+                power: U256::from(power),
+                timestamp: now - 60,
+                ipfs_hash: None,
+                weights: Vec::new(),
+            });
+        }
+        let yes = |voter: Option<Address>, power: Option<u64>| HypotheticalVote {
+            voter,
+            power: power.map(U256::from),
+            choice: 1,
+        };
+
+        // Yes 500 against no 1100: 500 more isn't enough, 700 more is
+        let too_small = engine.what_if(1, yes(None, Some(500))).await.unwrap();
+        assert_eq!(too_small.current_verdict, BinaryVerdict::Rejected);
+        assert_eq!(too_small.projected_verdict, BinaryVerdict::Rejected);
+        assert_eq!(too_small.projected_status, ProposalStatus::Rejected);
+
+        let large_enough = engine.what_if(1, yes(None, Some(700))).await.unwrap();
+        assert_eq!(large_enough.current_verdict, BinaryVerdict::Rejected);
+        assert_eq!(large_enough.projected_verdict, BinaryVerdict::Passed);
+        assert_eq!(large_enough.projected_status, ProposalStatus::Passed);
+
+        // The holder's full power replaces their earlier no vote
+        let switched = engine.what_if(1, yes(Some(holder), None)).await.unwrap();
+        assert_eq!(switched.power, U256::from(2000));
+        match switched.projected {
+            ProposalResults::Binary { yes_votes, no_votes, .. } => {
+                assert_eq!(yes_votes, U256::from(2500));
+                assert_eq!(no_votes, U256::from(800));
+            }
+            other => panic!("Expected a binary tally, got {:?}", other),
+        }
+        assert_eq!(switched.projected_verdict, BinaryVerdict::Passed);
+
+        // Nothing was recorded
+        assert_eq!(engine.indexer().get_votes(1).len(), 3);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().status, ProposalStatus::Active);
+        assert!(engine.what_if(1, yes(None, None)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_indexed_proposals_get_snapshot_power() {
+        use crate::blockchain::events::EventHandler;
+
+        let engine = mock_engine().await;
+        let content = proposal_content(ProposalType::Simple, &[]);
+        let ipfs_hash = engine.ipfs_client.add_proposal_content(&content).await.unwrap();
+        let now = engine.clock.timestamp();
+        for proposal_id in [1, 2] {
+            engine.blockchain_client().create_proposal(ipfs_hash.clone(), 86400, 0).await.unwrap();
+            engine.indexer().handle_proposal_created(&ProposalCreatedEvent {
+                contract: Address::zero(),
+                proposal_id,
+                proposer: Address::random(),
+                ipfs_hash: ipfs_hash.clone(),
+                start_time: U256::from(now),
+                end_time: U256::from(now + 86400),
+                proposal_type: 0,
+            });
+        }
+        let snapshot = U256::from(MockGovernanceHub::DEFAULT_TOTAL_SUPPLY);
+        assert!(engine.indexer().get_proposal(1).unwrap().total_voting_power.is_zero());
+
+        // Read on first use, then kept in the index
+        let detail = engine.get_proposal_detail(1).await.unwrap();
+        assert_eq!(detail.total_voting_power, snapshot);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().total_voting_power, snapshot);
+
+        // Or ahead of the participation checks
+        engine.backfill_voting_power().await;
+        assert_eq!(engine.indexer().get_proposal(2).unwrap().total_voting_power, snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_what_if_keeps_hidden_votes_hidden() {
+        let engine = mock_engine().await;
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.hide_votes_until_close = true;
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+        let voter = Address::random();
+        engine.cast_vote(voter, proposal.id, 0, None).await.unwrap();
+
+        let hypothetical = |voter: Option<Address>, power: Option<u64>| HypotheticalVote {
+            voter,
+            power: power.map(U256::from),
+            choice: 1,
+        };
+        let result = engine.what_if(proposal.id, hypothetical(Some(voter), None)).await;
+        assert!(matches!(result, Err(GovernanceError::Forbidden(_))));
+
+        // Voters who haven't voted, and bare amounts of power, reveal nothing
+        engine.what_if(proposal.id, hypothetical(Some(Address::random()), None)).await.unwrap();
+        engine.what_if(proposal.id, hypothetical(None, Some(500))).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_finalize_settles_overdue_proposal_once() {
+        let engine = mock_engine().await;
+        let now = engine.clock.timestamp();
+        index_tied_proposal(&engine, 1, now);
+        engine.indexer().index_vote(IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter: Address::random(),
+            choice: 1,
+            power: U256::from(500),
+            timestamp: now - 30,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        });
+
+        let finalization = engine.finalize_proposal(1).await.unwrap();
+        assert_eq!(finalization.previous_status, ProposalStatus::Active);
+        assert_eq!(finalization.status, ProposalStatus::Passed);
+        assert!(finalization.changed);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().status, ProposalStatus::Passed);
+
+        let again = engine.finalize_proposal(1).await.unwrap();
+        assert_eq!(again.previous_status, ProposalStatus::Passed);
+        assert_eq!(again.status, ProposalStatus::Passed);
+        assert!(!again.changed);
+
+        // Same outcome as settling a twin proposal the regular way
+        index_tied_proposal(&engine, 2, now);
+        assert_eq!(engine.evaluate_proposal(2).await.unwrap(), ProposalStatus::Rejected);
+        let tied = engine.finalize_proposal(2).await.unwrap();
+        assert_eq!(tied.status, ProposalStatus::Rejected);
+        assert!(!tied.changed);
+    }
+
+    #[tokio::test]
+    async fn test_tie_policy_extend_only_once() {
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone())).with_config(GovernanceConfig {
+            tie_policy: TiePolicy::Extend { period: 3600 },
+            ..Default::default()
+        });
+        let now = clock.timestamp();
+        index_tied_proposal(&engine, 1, now);
+
+        assert_eq!(engine.evaluate_proposal(1).await.unwrap(), ProposalStatus::Active);
+        let proposal = engine.indexer().get_proposal(1).unwrap();
+        assert_eq!(proposal.end_time, now + 3600);
+        assert!(proposal.tie_extended);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(engine.evaluate_proposal(1).await.is_err());
+
+        // Still tied after the extension
+        clock.advance(chrono::Duration::seconds(3600));
+        assert_eq!(engine.evaluate_proposal(1).await.unwrap(), ProposalStatus::Rejected);
+        assert_eq!(engine.indexer().get_proposal(1).unwrap().end_time, now + 3600);
+    }
+
+    #[tokio::test]
+    async fn test_signed_votes_aggregate_on_chain() {
+        use crate::utils::clock::MockClock;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let domain = engine.vote_signing_domain();
+        let sign = |wallet: &LocalWallet, choice: u8| {
+            let message = VoteMessage {
+                proposal_id: proposal.id,
+                voter: wallet.address(),
+                choice,
+            };
+            let signature = wallet.sign_hash(message.digest(&domain)).unwrap();
+            format!("0x{}", hex::encode(signature.to_vec()))
+        };
+
+        let wallets: Vec<LocalWallet> = (0..4)
+            .map(|_| LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap())
+            .collect();
+        for (wallet, choice) in wallets.iter().zip([1, 1, 0, 2]) {
+            engine
+                .submit_signed_vote(proposal.id, wallet.address(), choice, sign(wallet, choice))
+                .await
+                .unwrap();
+        }
+
+        // Signed by someone else, or for a different choice than claimed
+        let impostor = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let outsider = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        assert!(matches!(
+            engine.submit_signed_vote(proposal.id, outsider.address(), 1, sign(&impostor, 1)).await,
+            Err(GovernanceError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            engine.submit_signed_vote(proposal.id, outsider.address(), 0, sign(&outsider, 1)).await,
+            Err(GovernanceError::InvalidSignature(_))
+        ));
+        // Replaying or re-signing as the same voter
+        assert!(matches!(
+            engine.submit_signed_vote(proposal.id, wallets[0].address(), 1, sign(&wallets[0], 1)).await,
+            Err(GovernanceError::DuplicateVote { .. })
+        ));
+        assert!(matches!(
+            engine.submit_signed_vote(proposal.id, wallets[0].address(), 0, sign(&wallets[0], 0)).await,
+            Err(GovernanceError::DuplicateVote { .. })
+        ));
+
+        // A voter who signed and then also voted on-chain
+        engine.indexer().index_vote(IndexedVote {
+            proposal_id: proposal.id,
+            contract: None,
+            voter: wallets[2].address(),
+            choice: 1,
+            power: U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER),
+            timestamp: clock.timestamp(),
+            ipfs_hash: None,
+            weights: Vec::new(),
+        });
+
+        assert!(engine.aggregate_signed_votes(proposal.id).await.is_err());
+        clock.advance(chrono::Duration::seconds(86401));
+        let aggregate = engine.aggregate_signed_votes(proposal.id).await.unwrap();
+
+        let power = U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER);
+        assert_eq!(aggregate.tally.yes_votes, power * 2);
+        assert_eq!(aggregate.tally.no_votes, U256::zero());
+        assert_eq!(aggregate.tally.abstain_votes, power);
+        assert_eq!(aggregate.tally.voter_count, 3);
+        assert!(aggregate.excluded.is_empty());
+        assert_eq!(aggregate.superseded, vec![wallets[2].address()]);
+
+        // Posted once; later calls return the same aggregate
+        let again = engine.aggregate_signed_votes(proposal.id).await.unwrap();
+        assert_eq!(again.transaction_hash, aggregate.transaction_hash);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_execution_reports_success_and_revert() {
+        use crate::ipfs::content_types::ExecutionData;
+        use ethers::abi::{encode, Token};
+        use ethers::providers::{JsonRpcError, MockResponse, Provider};
+
+        let config = Config::default();
+        let (provider, mock) = Provider::mocked();
+        let client = SomniaClient::mock(&config).with_simulator(Arc::new(provider));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.execution_data = Some(ExecutionData {
+            target_contract: format!("{:?}", Address::random()),
+            function_signature: "setFee(uint256) returns (bool)".to_string(),
+            call_data: "0x69fe0e2d000000000000000000000000000000000000000000000000000000000000001e".to_string(),
+            value: "0".to_string(),
+        });
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        let returned = Bytes::from(encode(&[Token::Bool(true)]));
+        mock.push(returned.clone()).unwrap();
+        let simulation = engine.simulate_execution(proposal.id).await.unwrap();
+        assert!(simulation.success);
+        assert_eq!(simulation.return_data, returned);
+        assert_eq!(simulation.function_signature, "setFee(uint256) returns (bool)");
+        let decoded = simulation.decoded_return.unwrap();
+        assert_eq!((decoded[0].kind.as_str(), decoded[0].value.as_str()), ("bool", "true"));
+
+        let revert_data = [&[0x08, 0xc3, 0x79, 0xa0][..], &encode(&[Token::String("Fee too high".to_string())])].concat();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: 3,
+            message: "execution reverted: Fee too high".to_string(),
+            data: Some(serde_json::json!(Bytes::from(revert_data))),
+        }));
+        let simulation = engine.simulate_execution(proposal.id).await.unwrap();
+        assert!(!simulation.success);
+        assert_eq!(simulation.revert_reason.as_deref(), Some("Fee too high"));
+        assert!(simulation.decoded_return.is_none());
+
+        // Proposals without a call have nothing to simulate
+        let plain = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.simulate_execution(plain.id).await,
+            Err(GovernanceError::InvalidRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_quorum_feasibility_reflects_past_turnout() {
+        use crate::governance::analytics::QuorumOutlook;
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        assert_eq!(engine.quorum_feasibility().await.unwrap().outlook, QuorumOutlook::Unknown);
+
+        // Two closed proposals, each drawing 5% of the 100,000 total power
+        for _ in 0..2 {
+            let proposal = engine
+                .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+                .await
+                .unwrap();
+            for _ in 0..5 {
+                engine.cast_vote(Address::random(), proposal.id, 1, None).await.unwrap();
+            }
+        }
+        clock.advance(chrono::Duration::seconds(86401));
+
+        let low = engine.quorum_feasibility().await.unwrap();
+        assert_eq!(low.sample_size, 2);
+        assert_eq!(low.average_turnout_bps, 500);
+        assert_eq!(low.quorum_bps, 400);
+        assert_eq!(low.historical_success_bps, 10_000);
+        assert_eq!(low.expected_participation, U256::from(5000));
+        assert_eq!(low.outlook, QuorumOutlook::Likely);
+        assert!(low.warning.is_none());
+
+        let high = engine
+            .clone()
+            .with_config(GovernanceConfig {
+                proposal_rules: crate::config::ProposalRules {
+                    quorum_numerator: 20,
+                    quorum_denominator: 100,
+                },
+                ..Default::default()
+            })
+            .quorum_feasibility()
+            .await
+            .unwrap();
+        assert_eq!(high.required_quorum, U256::from(20_000));
+        assert_eq!(high.historical_success_bps, 0);
+        assert_eq!(high.outlook, QuorumOutlook::Unlikely);
+        assert!(high.warning.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_gated_category_limited_to_allowlisted_proposers() {
+        let core_dev = Address::random();
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            category_proposers: [("protocol-upgrade".to_string(), vec![format!("{:?}", core_dev)])].into(),
+            ..Default::default()
+        });
+        let content = |category: &str| {
+            let mut content = proposal_content(ProposalType::Simple, &[]);
+            content.metadata.category = category.to_string();
+            content
+        };
+
+        engine.create_proposal(core_dev, content("protocol-upgrade"), 86400).await.unwrap();
+        assert!(matches!(
+            engine.create_proposal(Address::random(), content("Protocol-Upgrade"), 86400).await,
+            Err(GovernanceError::ProposerNotAllowed { .. })
+        ));
+        assert_eq!(engine.indexer().proposal_count(), 1);
+
+        // Ungated categories stay open to everyone
+        engine.create_proposal(Address::random(), content("general"), 86400).await.unwrap();
+        engine.create_proposal(core_dev, content("treasury"), 86400).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_creation_aborts_when_content_unpinned() {
+        let engine = mock_engine().await;
+        engine.ipfs_client().drop_pins();
+
+        let result = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await;
+        assert!(matches!(result, Err(GovernanceError::Ipfs { .. })));
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 0);
+        assert_eq!(engine.indexer().proposal_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unique_titles_within_active_category() {
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            unique_titles: true,
+            ..Default::default()
+        });
+        let content = |title: &str, category: &str| {
+            let mut content = proposal_content(ProposalType::Simple, &[]);
+            content.title = title.to_string();
+            content.metadata.category = category.to_string();
+            content
+        };
+
+        let first = engine
+            .create_proposal(Address::random(), content("Fund the Grants Program", "treasury"), 86400)
+            .await
+            .unwrap();
+        let duplicate = engine
+            .create_proposal(Address::random(), content("fund the grants-program!", "Treasury"), 86400)
+            .await;
+        assert!(matches!(
+            duplicate,
+            Err(GovernanceError::DuplicateTitle { proposal_id, .. }) if proposal_id == first.id
+        ));
+
+        engine
+            .create_proposal(Address::random(), content("Fund the Grants Program", "general"), 86400)
+            .await
+            .unwrap();
+
+        engine.indexer().update_status(first.id, ProposalStatus::Rejected);
+        engine
+            .create_proposal(Address::random(), content("Fund the Grants Program", "treasury"), 86400)
+            .await
+            .unwrap();
+
+        // Off by default
+        let engine = engine.with_config(GovernanceConfig::default());
+        engine
+            .create_proposal(Address::random(), content("Fund the Grants Program", "treasury"), 86400)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_relayed_votes_require_trusted_relayer() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let relayer = Address::random();
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            trusted_relayers: vec![relayer],
+            ..Default::default()
+        });
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let wallet = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()).unwrap();
+        let message = VoteMessage {
+            proposal_id: proposal.id,
+            voter: wallet.address(),
+            choice: 1,
+        };
+        let signature = wallet.sign_hash(message.digest(&engine.vote_signing_domain())).unwrap();
+        let signature = format!("0x{}", hex::encode(signature.to_vec()));
+
+        assert!(matches!(
+            engine
+                .submit_relayed_vote(Address::random(), proposal.id, wallet.address(), 1, signature.clone())
+                .await,
+            Err(GovernanceError::UntrustedRelayer(_))
+        ));
+        // Nor can the relayers be bypassed by submitting directly
+        assert!(matches!(
+            engine.submit_signed_vote(proposal.id, wallet.address(), 1, signature.clone()).await,
+            Err(GovernanceError::Forbidden(_))
+        ));
+        let vote = engine
+            .submit_relayed_vote(relayer, proposal.id, wallet.address(), 1, signature)
+            .await
+            .unwrap();
+        assert_eq!(vote.relayer, Some(relayer));
+    }
+
+    #[tokio::test]
+    async fn test_min_vote_power_enforced() {
+        let engine = mock_engine().await;
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.min_vote_power = Some(5000);
+        let strict = engine.create_proposal(Address::random(), content.clone(), 86400).await.unwrap();
+        content.metadata.min_vote_power = Some(500);
+        let lenient = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+
+        // Mock hub reports 1000 power for every address
+        let voter = Address::random();
+        assert!(matches!(
+            engine.cast_vote(voter, strict.id, 1, None).await,
+            Err(GovernanceError::InsufficientVotingPower { required: 5000, available: 1000 })
+        ));
+        assert!(engine.indexer().get_votes(strict.id).is_empty());
+        engine.cast_vote(voter, lenient.id, 1, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_distribution_of_private_proposal_hidden_until_close() {
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let mut content = proposal_content(ProposalType::Simple, &[]);
+        content.metadata.hide_votes_until_close = true;
+        let proposal = engine.create_proposal(Address::random(), content, 86400).await.unwrap();
+        engine.cast_vote(Address::random(), proposal.id, 1, None).await.unwrap();
+        engine.cast_vote(Address::random(), proposal.id, 0, None).await.unwrap();
+
+        assert!(matches!(
+            engine.vote_distribution(proposal.id).await,
+            Err(GovernanceError::Forbidden(_))
+        ));
+
+        clock.advance(chrono::Duration::seconds(86401));
+        let distribution = engine.vote_distribution(proposal.id).await.unwrap();
+        assert_eq!(distribution.voter_count, 2);
+        assert_eq!(distribution.choices[1].label, "yes");
+        assert_eq!(distribution.choices[1].power_bps, 5000);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_creation_limited_per_address() {
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone())).with_config(GovernanceConfig {
+            max_proposals_per_address: Some(3),
+            ..Default::default()
+        });
+        let proposer = Address::random();
+        // Voting outlasts the day the test spans, so proposals don't end in the past
+        let create = |proposer| {
+            engine.create_proposal(proposer, proposal_content(ProposalType::Simple, &[]), 7 * 86400)
+        };
+
+        for _ in 0..3 {
+            create(proposer).await.unwrap();
+            clock.advance(chrono::Duration::hours(1));
+        }
+        match create(proposer).await {
+            Err(GovernanceError::ProposalCooldown { retry_after, .. }) => assert_eq!(retry_after, 21 * 3600),
+            other => panic!("expected a cooldown, got {:?}", other.map(|p| p.id)),
+        }
+        assert_eq!(engine.indexer().proposal_count(), 3);
+
+        // Other addresses have their own allowance
+        create(Address::random()).await.unwrap();
+
+        clock.advance(chrono::Duration::hours(21));
+        create(proposer).await.unwrap();
+        assert!(create(proposer).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_flags_unreachable_content() {
+        let engine = mock_engine().await;
+        let first = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let mut unreachable = first.clone();
+        unreachable.id = first.id + 1;
+        unreachable.ipfs_hash = "Qm".to_string() + &"0".repeat(44);
+        engine.indexer().index_proposal(unreachable.clone());
+        let mut last = first.clone();
+        last.id = first.id + 2;
+        engine.indexer().index_proposal(last);
+
+        let list = engine.list_proposals(&PaginationParams { page: None, limit: None }).await.unwrap();
+
+        assert_eq!(list.total, 3);
+        let ids: Vec<u64> = list.data.iter().map(|entry| entry.proposal.id).collect();
+        assert_eq!(ids, vec![first.id + 2, first.id + 1, first.id]);
+        let missing = &list.data[1];
+        assert!(!missing.content_available);
+        assert!(missing.content.is_none());
+        assert_eq!(missing.proposal.ipfs_hash, unreachable.ipfs_hash);
+        for entry in [&list.data[0], &list.data[2]] {
+            assert!(entry.content_available);
+            assert_eq!(entry.content.as_ref().unwrap().title, "Test Proposal");
+        }
+
+        let json = serde_json::to_value(missing).unwrap();
+        assert_eq!(json["content"], serde_json::Value::Null);
+        assert_eq!(json["id"], first.id + 1);
+    }
+
+    #[tokio::test]
+    async fn test_watchers_notified_of_status_changes_until_unwatched() {
+        use crate::governance::watch::{WatchEvent, WatchNotification};
+        use crate::ipfs::content_types::NotificationSettings;
+        use crate::utils::clock::MockClock;
+
+        /// Notifier that remembers every notification it receives
+        #[derive(Default)]
+        struct RecordingNotifier {
+            sent: Mutex<Vec<WatchNotification>>,
+        }
+
+        impl WatchNotifier for RecordingNotifier {
+            fn notify(&self, notification: &WatchNotification) {
+                self.sent.lock().unwrap().push(notification.clone());
+            }
+        }
+
+        let notifier = Arc::new(RecordingNotifier::default());
+        let clock = MockClock::default();
+        let engine = mock_engine()
+            .await
+            .with_clock(Arc::new(clock.clone()))
+            .with_watch_notifier(notifier.clone());
+        let create = || engine.create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400);
+        let (watched, unwatched) = (create().await.unwrap(), create().await.unwrap());
+
+        let (follower, quitter, muted) = (Address::random(), Address::random(), Address::random());
+        assert!(engine.watch_proposal(follower, watched.id, NotificationSettings::default()).unwrap());
+        assert!(!engine.watch_proposal(follower, watched.id, NotificationSettings::default()).unwrap());
+        let no_updates = NotificationSettings {
+            proposal_updates: false,
+            ..Default::default()
+        };
+        engine.watch_proposal(muted, watched.id, no_updates).unwrap();
+        engine.watch_proposal(quitter, unwatched.id, NotificationSettings::default()).unwrap();
+        assert!(engine.unwatch_proposal(quitter, unwatched.id));
+        assert!(matches!(
+            engine.watch_proposal(follower, 999, NotificationSettings::default()),
+            Err(GovernanceError::ProposalNotFound { .. })
+        ));
+
+        // Deadline reminders go out once, to watchers wanting them
+        clock.advance(chrono::Duration::seconds(3600));
+        assert_eq!(engine.remind_watchers(), 2);
+        assert_eq!(engine.remind_watchers(), 0);
+
+        clock.advance(chrono::Duration::seconds(86400));
+        engine.evaluate_proposal(watched.id).await.unwrap();
+        engine.evaluate_proposal(unwatched.id).await.unwrap();
+
+        let sent = notifier.sent.lock().unwrap();
+        let status_changes: Vec<_> = sent
+            .iter()
+            .filter(|n| matches!(n.event, WatchEvent::StatusChanged { .. }))
+            .collect();
+        assert_eq!(status_changes.len(), 1);
+        assert_eq!(status_changes[0].watcher, follower);
+        assert_eq!(status_changes[0].proposal_id, watched.id);
+        assert_eq!(status_changes[0].event, WatchEvent::StatusChanged { status: ProposalStatus::Rejected });
+        assert!(sent.iter().all(|n| n.watcher != quitter));
+    }
+
+    #[tokio::test]
+    async fn test_missing_attachments_rejected_when_verified() {
+        let engine = mock_engine().await.with_config(GovernanceConfig {
+            verify_attachments: true,
+            ..Default::default()
+        });
+        let attachment = engine
+            .ipfs_client()
+            .add_json(&serde_json::json!({ "budget": "10000 SOMI" }))
+            .await
+            .unwrap();
+        let missing = "Qm".to_string() + &"0".repeat(44);
+        let content = |attachments: Vec<String>| {
+            let mut content = proposal_content(ProposalType::Simple, &[]);
+            content.metadata.attachments = attachments;
+            content
+        };
+
+        engine.create_proposal(Address::random(), content(vec![attachment.clone()]), 86400).await.unwrap();
+
+        let result = engine
+            .create_proposal(Address::random(), content(vec![attachment, missing.clone()]), 86400)
+            .await;
+        match result {
+            Err(GovernanceError::InvalidRequest(message)) => assert!(message.contains(&missing), "{}", message),
+            other => panic!("expected a missing attachment error, got {:?}", other.map(|p| p.id)),
+        }
+        assert_eq!(engine.indexer().proposal_count(), 1);
+
+        // Without the check only the CID format is validated
+        let unchecked = mock_engine().await;
+        unchecked.create_proposal(Address::random(), content(vec![missing]), 86400).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_power_votes_rejected_unless_signalling() {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config)).await.unwrap();
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let (powerless, holder) = (Address::random(), Address::random());
+        hub.set_voting_power(powerless, U256::zero());
+
+        assert!(matches!(
+            engine.cast_vote(powerless, proposal.id, 1, None).await,
+            Err(GovernanceError::InsufficientVotingPower { required: 1, available: 0 })
+        ));
+        assert!(engine.indexer().get_votes(proposal.id).is_empty());
+
+        let engine = engine.with_config(GovernanceConfig {
+            zero_power_votes: ZeroPowerVotes::Signal,
+            ..Default::default()
+        });
+        let outcome = engine.cast_vote(powerless, proposal.id, 1, None).await.unwrap();
+        assert!(outcome.power.is_zero());
+        engine.cast_vote(holder, proposal.id, 0, None).await.unwrap();
+
+        let votes = engine.indexer().get_votes(proposal.id);
+        assert_eq!(votes.len(), 2);
+        match engine.get_proposal_detail(proposal.id).await.unwrap().results {
+            ProposalResults::Binary { yes_votes, no_votes, .. } => {
+                assert!(yes_votes.is_zero());
+                assert_eq!(no_votes, U256::from(MockGovernanceHub::DEFAULT_VOTING_POWER));
+            }
+            _ => panic!("Expected binary results"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_trail_follows_proposal_lifecycle() {
+        use crate::governance::audit_trail::ProposalEvent;
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let (proposer, voter, direct_voter) = (Address::random(), Address::random(), Address::random());
+        let proposal = engine
+            .create_proposal(proposer, proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+
+        clock.advance(chrono::Duration::seconds(60));
+        let cast = engine.cast_vote(voter, proposal.id, 1, None).await.unwrap();
+
+        // Cast against the contract directly, so only the indexer saw it
+        clock.advance(chrono::Duration::seconds(60));
+        engine.indexer().index_vote(IndexedVote {
+            proposal_id: proposal.id,
+            contract: None,
+            voter: direct_voter,
+            choice: 1,
+            power: U256::from(5000),
+            timestamp: clock.timestamp(),
+            ipfs_hash: None,
+            weights: Vec::new(),
+        });
+
+        clock.advance(chrono::Duration::seconds(86400));
+        let finalization = engine.finalize_proposal(proposal.id).await.unwrap();
+        assert_eq!(finalization.status, ProposalStatus::Passed);
+        clock.advance(chrono::Duration::seconds(60));
+        let execution = engine.execute_proposal(proposal.id).await.unwrap();
+
+        let trail = engine.proposal_audit_trail(proposal.id).unwrap();
+        let steps: Vec<_> = trail.iter().map(|entry| (entry.actor, entry.event.clone())).collect();
+        assert_eq!(
+            steps,
+            vec![
+                (Some(proposer), ProposalEvent::Created { ipfs_hash: proposal.ipfs_hash.clone() }),
+                (Some(voter), ProposalEvent::VoteCast { choice: 1, power: cast.power }),
+                (Some(direct_voter), ProposalEvent::VoteCast { choice: 1, power: U256::from(5000) }),
+                (
+                    None,
+                    ProposalEvent::StatusChanged {
+                        from: ProposalStatus::Active,
+                        to: ProposalStatus::Passed,
+                    }
+                ),
+                (None, ProposalEvent::Finalized { status: ProposalStatus::Passed }),
+                (None, ProposalEvent::Executed { outcome: ExecutionOutcome::Succeeded }),
+                (
+                    None,
+                    ProposalEvent::StatusChanged {
+                        from: ProposalStatus::Passed,
+                        to: ProposalStatus::Executed,
+                    }
+                ),
+            ]
+        );
+        assert!(trail[0].transaction_hash.is_some());
+        assert_eq!(trail[1].transaction_hash, Some(cast.receipt.transaction_hash));
+        assert_eq!(trail[2].transaction_hash, None);
+        assert_eq!(trail[5].transaction_hash, Some(execution.transaction_hash));
+        assert!(trail.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+
+        assert!(matches!(
+            engine.proposal_audit_trail(proposal.id + 1),
+            Err(GovernanceError::ProposalNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_voting_counts_power_held_at_creation() {
+        let config = Config::default();
+        let hub = Arc::new(MockGovernanceHub::new());
+        let client = SomniaClient::with_contracts(&config, hub.clone(), Arc::new(MockSimpleVoting::new()));
+        let engine = GovernanceEngine::new(client, IpfsClient::in_memory(&config))
+            .await
+            .unwrap()
+            .with_config(GovernanceConfig {
+                snapshot_voting: true,
+                ..Default::default()
+            });
+        let (holder, latecomer) = (Address::random(), Address::random());
+        hub.set_voting_power(holder, U256::from(2000));
+        hub.set_voting_power(latecomer, U256::zero());
+
+        let proposal = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+        let snapshot = *hub.block_number.lock().unwrap();
+        hub.mine_blocks(10);
+        hub.set_voting_power(holder, U256::from(9000));
+        hub.set_voting_power(latecomer, U256::from(5000));
+
+        let outcome = engine.cast_vote(holder, proposal.id, 1, None).await.unwrap();
+        assert_eq!(outcome.power, U256::from(2000));
+        assert_eq!(outcome.power_source, PowerSource::Snapshot);
+
+        let result = engine.cast_vote(latecomer, proposal.id, 1, None).await;
+        match result {
+            Err(GovernanceError::NoSnapshotPower { proposal_id, block }) => {
+                assert_eq!((proposal_id, block), (proposal.id, snapshot));
+            }
+            other => panic!("Expected a snapshot power error, got {:?}", other.map(|outcome| outcome.power)),
+        }
+        assert_eq!(engine.indexer().get_votes(proposal.id).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_ending_too_soon_is_flagged() {
+        use crate::utils::clock::MockClock;
+
+        // The server clock runs two days ahead of the chain's
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        clock.advance(chrono::Duration::days(2));
+
+        let result = engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await;
+        // Created on chain all the same, so the error names it and isn't a
+        // server error a client would retry into a duplicate
+        let error = match result {
+            Err(error @ GovernanceError::ProposalEndsTooSoon { .. }) => error,
+            other => panic!("Expected the end time to be flagged, got {:?}", other.map(|p| p.id)),
+        };
+        assert_eq!(error.status_code(), axum::http::StatusCode::CONFLICT);
+        let proposal_id = engine.indexer().proposals()[0].id;
+        assert!(error.to_string().starts_with(&format!("Proposal {} was created", proposal_id)), "{}", error);
+        assert_eq!(engine.indexer().proposal_count(), 1);
+
+        clock.advance(chrono::Duration::days(-2));
+        engine
+            .create_proposal(Address::random(), proposal_content(ProposalType::Simple, &[]), 86400)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_draft_estimate_submits_nothing() {
+        use crate::governance::drafts::DraftContent;
+        use crate::utils::clock::MockClock;
+
+        let clock = MockClock::default();
+        let engine = mock_engine().await.with_clock(Arc::new(clock.clone()));
+        let proposer = Address::random();
+        let draft = DraftContent {
+            title: "Fund the grants round".to_string(),
+            description: "Allocate the next quarter's grants.".to_string(),
+            metadata: ProposalMetadata::default(),
+        };
+
+        let estimate = engine.estimate_draft(proposer, draft.clone(), 86400).await.unwrap();
+        assert_eq!(estimate.estimated_gas, Some(U256::from(MockGovernanceHub::CREATE_PROPOSAL_GAS)));
+        assert!(!engine.ipfs_client().is_pinned(&estimate.ipfs_hash).await.unwrap());
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 0);
+
+        // Submitted at the same moment, the content lands under the estimated CID
+        let content = draft.clone().into_proposal_content(clock.now());
+        let proposal = engine.create_proposal(proposer, content, 86400).await.unwrap();
+        assert_eq!(proposal.ipfs_hash, estimate.ipfs_hash);
+
+        let untitled = DraftContent {
+            title: String::new(),
+            ..draft.clone()
+        };
+        assert!(engine.estimate_draft(proposer, untitled, 86400).await.is_err());
+        assert!(engine.estimate_draft(proposer, draft, 1).await.is_err());
+        assert_eq!(engine.blockchain_client().get_proposal_count().await.unwrap(), 1);
+    }
+
+}
@@ -1,4 +1,17 @@
 pub mod engine;
 pub mod proposals;
+pub mod proposal_limits;
 pub mod voting;
-pub mod analytics;
\ No newline at end of file
+pub mod activity;
+pub mod analytics;
+pub mod audit_trail;
+pub mod calldata;
+pub mod delegation;
+pub mod diff;
+pub mod drafts;
+pub mod participation;
+pub mod bundle;
+pub mod moderation;
+pub mod receipts;
+pub mod signed_votes;
+pub mod watch;
@@ -0,0 +1,149 @@
+use crate::config::ModerationConfig;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Outcome of reviewing user-written text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModerationVerdict {
+    Accept,
+    /// Allowed through, but kept for admin review
+    Flag(String),
+    Reject(String),
+}
+
+/// Reviews vote comments and reasoning before a vote is accepted
+pub trait ContentModerator: Send + Sync {
+    fn review(&self, text: &str) -> ModerationVerdict;
+}
+
+/// Default moderator: accepts everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopModerator;
+
+impl ContentModerator for NoopModerator {
+    fn review(&self, _text: &str) -> ModerationVerdict {
+        ModerationVerdict::Accept
+    }
+}
+
+/// Word-list and length based moderation. Words match whole words,
+/// case-insensitively; banned words reject, flagged words only flag.
+#[derive(Debug, Clone)]
+pub struct WordListModerator {
+    banned_words: Vec<String>,
+    flagged_words: Vec<String>,
+    max_length: Option<usize>,
+}
+
+impl WordListModerator {
+    pub fn new(config: &ModerationConfig) -> Self {
+        let normalize = |words: &[String]| words.iter().map(|word| word.trim().to_lowercase()).collect();
+        Self {
+            banned_words: normalize(&config.banned_words),
+            flagged_words: normalize(&config.flagged_words),
+            max_length: config.max_length,
+        }
+    }
+
+    fn first_match<'a>(list: &'a [String], words: &[String]) -> Option<&'a String> {
+        list.iter().find(|listed| words.contains(listed))
+    }
+}
+
+impl ContentModerator for WordListModerator {
+    fn review(&self, text: &str) -> ModerationVerdict {
+        if let Some(max_length) = self.max_length {
+            let length = text.chars().count();
+            if length > max_length {
+                return ModerationVerdict::Reject(format!(
+                    "{} characters exceeds the {} character limit",
+                    length, max_length
+                ));
+            }
+        }
+
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        if let Some(word) = Self::first_match(&self.banned_words, &words) {
+            return ModerationVerdict::Reject(format!("contains banned word \"{}\"", word));
+        }
+        if let Some(word) = Self::first_match(&self.flagged_words, &words) {
+            return ModerationVerdict::Flag(format!("contains flagged word \"{}\"", word));
+        }
+
+        ModerationVerdict::Accept
+    }
+}
+
+/// Moderator described by `config`: the no-op one unless any rule is set
+pub fn moderator_for(config: &ModerationConfig) -> Arc<dyn ContentModerator> {
+    if config.is_enabled() {
+        Arc::new(WordListModerator::new(config))
+    } else {
+        Arc::new(NoopModerator)
+    }
+}
+
+/// A vote accepted with flagged content, awaiting admin review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlaggedVote {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub ipfs_hash: String,
+    pub reason: String,
+    pub flagged_at: u64,
+}
+
+/// Flagged votes in the order they were cast
+#[derive(Clone, Default)]
+pub struct ModerationQueue {
+    inner: Arc<RwLock<Vec<FlaggedVote>>>,
+}
+
+impl ModerationQueue {
+    pub fn record(&self, flagged: FlaggedVote) {
+        tracing::info!(
+            proposal_id = flagged.proposal_id,
+            voter = ?flagged.voter,
+            reason = %flagged.reason,
+            "Vote content flagged for review"
+        );
+        self.inner.write().unwrap().push(flagged);
+    }
+
+    pub fn list(&self) -> Vec<FlaggedVote> {
+        self.inner.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moderator() -> WordListModerator {
+        WordListModerator::new(&ModerationConfig {
+            banned_words: vec!["Scam".to_string()],
+            flagged_words: vec!["shill".to_string()],
+            max_length: Some(40),
+        })
+    }
+
+    #[test]
+    fn test_banned_words_rejected_case_insensitively() {
+        let verdict = moderator().review("This whole thing is a SCAM!");
+        assert_eq!(verdict, ModerationVerdict::Reject("contains banned word \"scam\"".to_string()));
+        assert!(matches!(moderator().review(&"a".repeat(41)), ModerationVerdict::Reject(_)));
+    }
+
+    #[test]
+    fn test_clean_and_flagged_content() {
+        assert_eq!(moderator().review("Scampi for the offsite, yes"), ModerationVerdict::Accept);
+        assert!(matches!(moderator().review("Paid shill proposal"), ModerationVerdict::Flag(_)));
+        assert_eq!(NoopModerator.review("scam"), ModerationVerdict::Accept);
+    }
+}
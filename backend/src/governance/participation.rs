@@ -0,0 +1,210 @@
+use crate::blockchain::contracts::ProposalStatus;
+use crate::config::ProposalRules;
+use crate::indexer::content_indexer::{IndexedProposal, IndexedVote};
+use ethers::types::U256;
+use prometheus::{GaugeVec, IntCounter, Opts, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// An active proposal nearing its deadline without enough participation to
+/// meet quorum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipationAlert {
+    pub proposal_id: u64,
+    pub seconds_remaining: u64,
+    pub participation: U256,
+    pub required_quorum: U256,
+    pub shortfall: U256,
+}
+
+impl ParticipationAlert {
+    /// Alert for `proposal` if it closes within `window` seconds of `now` and
+    /// yes, no and abstain together fall short of quorum
+    pub fn evaluate(
+        proposal: &IndexedProposal,
+        votes: &[IndexedVote],
+        rules: &ProposalRules,
+        now: u64,
+        window: u64,
+    ) -> Option<Self> {
+        if proposal.status != ProposalStatus::Active || now >= proposal.end_time {
+            return None;
+        }
+
+        let seconds_remaining = proposal.end_time - now;
+        if seconds_remaining > window {
+            return None;
+        }
+
+        let participation = votes.iter().fold(U256::zero(), |sum, vote| sum + vote.power);
+        let required_quorum = rules.required_quorum(proposal.total_voting_power);
+        (participation < required_quorum).then(|| Self {
+            proposal_id: proposal.id,
+            seconds_remaining,
+            participation,
+            required_quorum,
+            shortfall: required_quorum - participation,
+        })
+    }
+}
+
+/// Receives governance alerts, e.g. to forward them to chat or paging
+pub trait Notifier: Send + Sync {
+    fn notify(&self, alert: &ParticipationAlert);
+}
+
+/// Default notifier: a warning in the service log
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify(&self, alert: &ParticipationAlert) {
+        tracing::warn!(
+            proposal_id = alert.proposal_id,
+            seconds_remaining = alert.seconds_remaining,
+            shortfall = %alert.shortfall,
+            "Proposal at risk of missing quorum"
+        );
+    }
+}
+
+/// Tracks low-participation alerts in metrics and notifies once per proposal
+#[derive(Clone)]
+pub struct ParticipationMonitor {
+    notifier: Arc<dyn Notifier>,
+    notified: Arc<Mutex<HashSet<u64>>>,
+    alerts_total: IntCounter,
+    shortfall: GaugeVec,
+}
+
+impl ParticipationMonitor {
+    pub fn new(notifier: Arc<dyn Notifier>) -> Self {
+        let alerts_total = IntCounter::new(
+            "low_participation_alerts_total",
+            "Proposals flagged as at risk of missing quorum",
+        )
+        .expect("valid metric definition");
+        let shortfall = GaugeVec::new(
+            Opts::new("proposal_quorum_shortfall", "Power still needed to reach quorum near the deadline"),
+            &["proposal_id"],
+        )
+        .expect("valid metric definition");
+
+        Self {
+            notifier,
+            notified: Arc::new(Mutex::new(HashSet::new())),
+            alerts_total,
+            shortfall,
+        }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.alerts_total.clone()))?;
+        registry.register(Box::new(self.shortfall.clone()))
+    }
+
+    /// Publish the current set of alerts. The shortfall gauge mirrors every
+    /// alert on each call; the notifier hears about a proposal only once.
+    pub fn record(&self, alerts: &[ParticipationAlert]) {
+        self.shortfall.reset();
+        let mut notified = self.notified.lock().unwrap();
+
+        for alert in alerts {
+            self.shortfall
+                .with_label_values(&[&alert.proposal_id.to_string()])
+                .set(alert.shortfall.low_u128() as f64);
+
+            if notified.insert(alert.proposal_id) {
+                self.alerts_total.inc();
+                self.notifier.notify(alert);
+            }
+        }
+    }
+
+    pub fn alerts_total(&self) -> u64 {
+        self.alerts_total.get()
+    }
+}
+
+impl Default for ParticipationMonitor {
+    fn default() -> Self {
+        Self::new(Arc::new(LogNotifier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+
+    /// Notifier that remembers every alert it receives
+    #[derive(Default)]
+    struct RecordingNotifier {
+        alerts: Mutex<Vec<ParticipationAlert>>,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, alert: &ParticipationAlert) {
+            self.alerts.lock().unwrap().push(alert.clone());
+        }
+    }
+
+    fn active_proposal(end_time: u64) -> IndexedProposal {
+        IndexedProposal {
+            id: 1,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash: "QmTest123".to_string(),
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time: 0,
+            end_time,
+            supersedes: None,
+            total_voting_power: U256::from(10_000),
+            tie_extended: false,
+        }
+    }
+
+    fn vote(power: u64) -> IndexedVote {
+        IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter: Address::random(),
+            choice: 1,
+            power: U256::from(power),
+            timestamp: 100,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_alert_fires_below_quorum_near_deadline() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let monitor = ParticipationMonitor::new(notifier.clone());
+        let rules = ProposalRules::default(); // 4% of 10_000 = 400
+
+        let alert = ParticipationAlert::evaluate(&active_proposal(7200), &[vote(150)], &rules, 6000, 3600)
+            .expect("proposal should be flagged");
+        assert_eq!(alert.seconds_remaining, 1200);
+        assert_eq!(alert.required_quorum, U256::from(400));
+        assert_eq!(alert.shortfall, U256::from(250));
+
+        monitor.record(&[alert.clone()]);
+        monitor.record(&[alert.clone()]);
+        assert_eq!(monitor.alerts_total(), 1);
+        assert_eq!(*notifier.alerts.lock().unwrap(), vec![alert]);
+        assert_eq!(monitor.shortfall.with_label_values(&["1"]).get(), 250.0);
+    }
+
+    #[test]
+    fn test_no_alert_above_quorum_or_outside_window() {
+        let rules = ProposalRules::default();
+        let proposal = active_proposal(7200);
+
+        assert!(ParticipationAlert::evaluate(&proposal, &[vote(900), vote(300)], &rules, 6000, 3600).is_none());
+        assert!(ParticipationAlert::evaluate(&proposal, &[vote(150)], &rules, 1000, 3600).is_none());
+        assert!(ParticipationAlert::evaluate(&proposal, &[vote(150)], &rules, 7200, 3600).is_none());
+    }
+}
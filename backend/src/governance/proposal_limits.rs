@@ -0,0 +1,60 @@
+use ethers::types::Address;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Creation times of each address's recent proposals, for capping how many
+/// proposals one address can create within a rolling window
+#[derive(Clone, Default)]
+pub struct ProposalRateLimiter {
+    created: Arc<Mutex<HashMap<Address, VecDeque<u64>>>>,
+}
+
+impl ProposalRateLimiter {
+    /// Seconds until `proposer` may create another proposal, or `None` if it
+    /// has created fewer than `limit` within the last `window` seconds
+    pub fn retry_after(&self, proposer: Address, limit: u32, window: u64, now: u64) -> Option<u64> {
+        if limit == 0 {
+            return Some(window.max(1));
+        }
+        let mut created = self.created.lock().unwrap();
+        let times = created.get_mut(&proposer)?;
+        while times.front().is_some_and(|created_at| created_at + window <= now) {
+            times.pop_front();
+        }
+        if times.is_empty() {
+            created.remove(&proposer);
+            return None;
+        }
+
+        if times.len() < limit as usize {
+            return None;
+        }
+        // The window frees up as the oldest counted proposal ages out
+        let oldest = times[times.len() - limit as usize];
+        Some((oldest + window).saturating_sub(now).max(1))
+    }
+
+    pub fn record(&self, proposer: Address, now: u64) {
+        self.created.lock().unwrap().entry(proposer).or_default().push_back(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_rolls_per_address() {
+        let limiter = ProposalRateLimiter::default();
+        let (spammer, other) = (Address::random(), Address::random());
+
+        limiter.record(spammer, 1_000);
+        limiter.record(spammer, 5_000);
+        assert_eq!(limiter.retry_after(spammer, 2, 86_400, 6_000), Some(81_400));
+        assert_eq!(limiter.retry_after(spammer, 3, 86_400, 6_000), None);
+        assert_eq!(limiter.retry_after(other, 2, 86_400, 6_000), None);
+
+        // Once the first ages out there's room for one more
+        assert_eq!(limiter.retry_after(spammer, 2, 86_400, 87_400), None);
+    }
+}
@@ -1,2 +1,471 @@
-// Placeholder for proposal management logic
-pub struct ProposalManager;
\ No newline at end of file
+use crate::blockchain::contracts::ProposalStatus;
+use crate::config::{OptionLimits, ProposalRules, TiePolicy};
+use crate::governance::calldata::{decode_execution_call, DecodedParameter, ExecutionCall};
+use crate::indexer::content_indexer::{IndexedProposal, IndexedVote};
+use crate::ipfs::content_types::{ProposalIPFSContent, ProposalMetadata, ProposalType};
+use ethers::types::{Address, Bytes, U256};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalDetail {
+    pub id: u64,
+    /// Governance contract the proposal lives in; omitted for the primary one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Address>,
+    pub proposer: Address,
+    pub ipfs_hash: String,
+    pub title: String,
+    pub description: String,
+    pub proposal_type: ProposalType,
+    pub status: ProposalStatus,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub metadata: ProposalMetadata,
+    /// `metadata.execution_data` decoded for display
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_call: Option<ExecutionCall>,
+    pub results: ProposalResults,
+    pub total_voting_power: U256,
+    /// Yes-power still needed to pass; `None` for option proposals
+    pub passage: Option<PassageGap>,
+    pub supersedes: Option<u64>,
+    pub superseded_by: Vec<u64>,
+}
+
+/// Current tally, shaped by proposal type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProposalResults {
+    Binary {
+        yes_votes: U256,
+        no_votes: U256,
+        abstain_votes: U256,
+    },
+    Options {
+        options: Vec<OptionTally>,
+    },
+}
+
+/// Additional yes-power needed for a binary proposal to pass. All zero once
+/// the proposal is passing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PassageGap {
+    /// Participation still missing to reach quorum
+    pub to_quorum: U256,
+    /// Yes-power needed to outweigh no
+    pub to_threshold: U256,
+    /// Yes-power that would satisfy both, since yes votes count towards quorum
+    pub yes_power_needed: U256,
+}
+
+impl PassageGap {
+    pub fn compute(
+        yes_votes: U256,
+        no_votes: U256,
+        abstain_votes: U256,
+        total_voting_power: U256,
+        rules: &ProposalRules,
+    ) -> Self {
+        let participation = yes_votes + no_votes + abstain_votes;
+        let to_quorum = rules.required_quorum(total_voting_power).saturating_sub(participation);
+        let to_threshold = if yes_votes > no_votes {
+            U256::zero()
+        } else {
+            no_votes - yes_votes + 1
+        };
+
+        Self {
+            to_quorum,
+            to_threshold,
+            yes_power_needed: to_quorum.max(to_threshold),
+        }
+    }
+}
+
+/// Outcome of a closed binary proposal under the passage rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryVerdict {
+    Passed,
+    Rejected,
+    /// Quorum met with yes exactly equal to no
+    Tied,
+}
+
+impl BinaryVerdict {
+    pub fn evaluate(
+        yes_votes: U256,
+        no_votes: U256,
+        abstain_votes: U256,
+        total_voting_power: U256,
+        rules: &ProposalRules,
+    ) -> Self {
+        let participation = yes_votes + no_votes + abstain_votes;
+        if participation < rules.required_quorum(total_voting_power) {
+            return BinaryVerdict::Rejected;
+        }
+
+        match yes_votes.cmp(&no_votes) {
+            std::cmp::Ordering::Greater => BinaryVerdict::Passed,
+            std::cmp::Ordering::Less => BinaryVerdict::Rejected,
+            std::cmp::Ordering::Equal => BinaryVerdict::Tied,
+        }
+    }
+}
+
+impl ProposalResults {
+    /// Tally `votes` as the proposal's type dictates
+    pub fn tally(metadata: &ProposalMetadata, votes: &[IndexedVote]) -> Self {
+        if metadata.proposal_type.uses_options() {
+            ProposalResults::Options {
+                options: tally_options(&metadata.options, votes),
+            }
+        } else {
+            tally_binary(votes)
+        }
+    }
+}
+
+/// Most proposals one status batch may ask for
+pub const MAX_STATUS_BATCH: usize = 100;
+
+/// Current status and tally of one proposal, from the index alone. Option
+/// proposals carry no yes/no/abstain split.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalStatusSummary {
+    pub status: ProposalStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yes_votes: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_votes: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abstain_votes: Option<U256>,
+    pub participation: U256,
+    pub has_quorum: bool,
+}
+
+impl ProposalStatusSummary {
+    pub fn new(proposal: &IndexedProposal, votes: &[IndexedVote], rules: &ProposalRules) -> Self {
+        let participation = votes.iter().fold(U256::zero(), |sum, vote| sum + vote.power);
+        let binary = match tally_binary(votes) {
+            _ if ProposalType::from(proposal.proposal_type).uses_options() => None,
+            ProposalResults::Binary { yes_votes, no_votes, abstain_votes } => Some((yes_votes, no_votes, abstain_votes)),
+            ProposalResults::Options { .. } => None,
+        };
+
+        Self {
+            status: proposal.status,
+            yes_votes: binary.map(|(yes, _, _)| yes),
+            no_votes: binary.map(|(_, no, _)| no),
+            abstain_votes: binary.map(|(_, _, abstain)| abstain),
+            participation,
+            has_quorum: participation >= rules.required_quorum(proposal.total_voting_power),
+        }
+    }
+}
+
+/// One id's entry in a status batch: its summary, or why it has none
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProposalStatusEntry {
+    Found(ProposalStatusSummary),
+    Failed { error: String },
+}
+
+/// One proposal of a list. The indexed on-chain fields are always present;
+/// while its IPFS content can't be fetched, `content` is null and
+/// `content_available` false so clients can retry the detail later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalListEntry {
+    #[serde(flatten)]
+    pub proposal: IndexedProposal,
+    pub content_available: bool,
+    pub content: Option<ProposalIPFSContent>,
+}
+
+/// Result of finalizing a proposal out of band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalFinalization {
+    pub proposal_id: u64,
+    pub previous_status: ProposalStatus,
+    pub status: ProposalStatus,
+    /// Whether this call moved the proposal; `false` when it was already settled
+    pub changed: bool,
+}
+
+/// A vote that hasn't been cast, for projecting a tally. Counts `power` if
+/// given, otherwise `voter`'s power as a real vote would.
+#[derive(Debug, Clone)]
+pub struct HypotheticalVote {
+    pub voter: Option<Address>,
+    pub power: Option<U256>,
+    pub choice: u8,
+}
+
+/// A binary proposal's tally and verdict now and with a hypothetical vote
+/// added. Verdicts are judged as if voting closed at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatIfTally {
+    pub proposal_id: u64,
+    pub choice: u8,
+    /// Power the hypothetical vote counts with
+    pub power: U256,
+    pub current: ProposalResults,
+    pub current_verdict: BinaryVerdict,
+    pub projected: ProposalResults,
+    pub projected_verdict: BinaryVerdict,
+    /// Status the proposal would settle in with the projected tally
+    pub projected_status: ProposalStatus,
+}
+
+/// What submitting a proposal would involve, without submitting it: the CID
+/// its content would be pinned under and the gas creating it would cost.
+/// `estimated_gas` is `None` when the estimate isn't available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalEstimate {
+    pub ipfs_hash: String,
+    pub voting_duration: u64,
+    pub estimated_gas: Option<U256>,
+}
+
+/// What a proposal's execution call would do if run now, without sending it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionSimulation {
+    pub proposal_id: u64,
+    pub target_contract: Address,
+    pub function_signature: String,
+    pub success: bool,
+    pub return_data: Bytes,
+    /// `return_data` decoded against the outputs `function_signature`
+    /// declares, e.g. `returns (bool)`, when it declares any
+    pub decoded_return: Option<Vec<DecodedParameter>>,
+    /// Decoded `Error(string)` or `Panic(uint256)` reason, when reverted
+    pub revert_reason: Option<String>,
+}
+
+/// Voting durations a proposer may choose, for UIs. Free-form durations
+/// must fall within `min_duration..=max_duration` unless `presets_only`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VotingDurationOptions {
+    pub presets: Vec<u64>,
+    pub presets_only: bool,
+    pub min_duration: u64,
+    pub max_duration: u64,
+}
+
+/// Features and limits of this deployment, derived from its governance
+/// config, so UIs can adapt to what it supports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceCapabilities {
+    pub proposal_types: Vec<ProposalType>,
+    pub option_limits: OptionLimits,
+    pub voting_durations: VotingDurationOptions,
+    pub quorum: ProposalRules,
+    pub tie_policy: TiePolicy,
+    /// Cap on the power any one delegate can receive, if any
+    pub max_delegated_power: Option<U256>,
+    /// Whether trusted relayers may submit signed votes for voters
+    pub relayed_votes: bool,
+    pub vote_moderation: bool,
+    pub unique_titles: bool,
+    /// Categories only allowlisted proposers may create proposals in
+    pub gated_categories: Vec<String>,
+}
+
+/// A proposal's votes as seen by one viewer. While `votes_hidden` is set,
+/// `votes` holds only the viewer's own vote; `results` is always complete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalVotes {
+    pub proposal_id: u64,
+    pub results: ProposalResults,
+    pub votes_hidden: bool,
+    pub votes: Vec<IndexedVote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionTally {
+    pub index: u8,
+    pub label: String,
+    pub votes: U256,
+}
+
+impl ProposalDetail {
+    pub fn new(proposal: &IndexedProposal, content: ProposalIPFSContent, votes: &[IndexedVote]) -> Self {
+        let proposal_type = content.metadata.proposal_type;
+        let results = ProposalResults::tally(&content.metadata, votes);
+        let execution_call = content.metadata.execution_data.as_ref().map(decode_execution_call);
+
+        Self {
+            id: proposal.id,
+            contract: proposal.contract,
+            proposer: proposal.proposer,
+            ipfs_hash: proposal.ipfs_hash.clone(),
+            title: content.title,
+            description: content.description,
+            proposal_type,
+            status: proposal.status,
+            start_time: proposal.start_time,
+            end_time: proposal.end_time,
+            metadata: content.metadata,
+            execution_call,
+            results,
+            total_voting_power: proposal.total_voting_power,
+            passage: None,
+            supersedes: proposal.supersedes,
+            superseded_by: Vec::new(),
+        }
+    }
+
+    /// Fill in `passage` for binary proposals under `rules`
+    pub fn with_rules(mut self, rules: &ProposalRules) -> Self {
+        self.passage = match self.results {
+            ProposalResults::Binary { yes_votes, no_votes, abstain_votes } => Some(PassageGap::compute(
+                yes_votes,
+                no_votes,
+                abstain_votes,
+                self.total_voting_power,
+                rules,
+            )),
+            ProposalResults::Options { .. } => None,
+        };
+        self
+    }
+
+    pub fn with_superseded_by(mut self, superseded_by: Vec<u64>) -> Self {
+        self.superseded_by = superseded_by;
+        self
+    }
+}
+
+/// Yes/no/abstain power, where choice 0 = no, 1 = yes, 2 = abstain
+pub fn tally_binary(votes: &[IndexedVote]) -> ProposalResults {
+    let (mut yes_votes, mut no_votes, mut abstain_votes) = (U256::zero(), U256::zero(), U256::zero());
+    for vote in votes {
+        match vote.choice {
+            0 => no_votes += vote.power,
+            1 => yes_votes += vote.power,
+            2 => abstain_votes += vote.power,
+            _ => {}
+        }
+    }
+
+    ProposalResults::Binary {
+        yes_votes,
+        no_votes,
+        abstain_votes,
+    }
+}
+
+/// Power per option, where the vote choice is the option index. Weighted
+/// votes contribute each weight to its option instead.
+pub fn tally_options(options: &[String], votes: &[IndexedVote]) -> Vec<OptionTally> {
+    let mut tallies: Vec<OptionTally> = options
+        .iter()
+        .enumerate()
+        .map(|(index, label)| OptionTally {
+            index: index as u8,
+            label: label.clone(),
+            votes: U256::zero(),
+        })
+        .collect();
+
+    for vote in votes {
+        if vote.weights.is_empty() {
+            if let Some(tally) = tallies.get_mut(vote.choice as usize) {
+                tally.votes += vote.power;
+            }
+            continue;
+        }
+
+        for (tally, weight) in tallies.iter_mut().zip(&vote.weights) {
+            tally.votes += *weight;
+        }
+    }
+
+    tallies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(choice: u8, power: u64) -> IndexedVote {
+        IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter: Address::random(),
+            choice,
+            power: U256::from(power),
+            timestamp: 0,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_tally_options() {
+        let options = vec!["Alpha".to_string(), "Beta".to_string(), "Gamma".to_string()];
+        let votes = vec![vote(0, 10), vote(2, 5), vote(0, 1), vote(7, 100)];
+
+        let tallies = tally_options(&options, &votes);
+        assert_eq!(tallies.len(), 3);
+        assert_eq!(tallies[0].votes, U256::from(11));
+        assert_eq!(tallies[1].votes, U256::zero());
+        assert_eq!(tallies[2].label, "Gamma");
+        assert_eq!(tallies[2].votes, U256::from(5));
+    }
+
+    #[test]
+    fn test_tally_binary() {
+        match tally_binary(&[vote(1, 10), vote(0, 4), vote(2, 1)]) {
+            ProposalResults::Binary { yes_votes, no_votes, abstain_votes } => {
+                assert_eq!(yes_votes, U256::from(10));
+                assert_eq!(no_votes, U256::from(4));
+                assert_eq!(abstain_votes, U256::from(1));
+            }
+            _ => panic!("Expected binary results"),
+        }
+    }
+
+    #[test]
+    fn test_binary_verdict_ties_only_with_quorum() {
+        let rules = ProposalRules::default();
+        let total = U256::from(100_000);
+        let verdict = |yes: u64, no: u64| {
+            BinaryVerdict::evaluate(U256::from(yes), U256::from(no), U256::zero(), total, &rules)
+        };
+
+        assert_eq!(verdict(3000, 3000), BinaryVerdict::Tied);
+        assert_eq!(verdict(3001, 3000), BinaryVerdict::Passed);
+        assert_eq!(verdict(2999, 3000), BinaryVerdict::Rejected);
+        // Below the 4% quorum a tie is simply rejected
+        assert_eq!(verdict(1000, 1000), BinaryVerdict::Rejected);
+    }
+
+    #[test]
+    fn test_passage_gap_short_of_quorum() {
+        // 4% of 100,000 = 4,000 required, 1,500 participating
+        let gap = PassageGap::compute(
+            U256::from(1000),
+            U256::from(400),
+            U256::from(100),
+            U256::from(100_000),
+            &ProposalRules::default(),
+        );
+        assert_eq!(gap.to_quorum, U256::from(2500));
+        assert_eq!(gap.to_threshold, U256::zero());
+        assert_eq!(gap.yes_power_needed, U256::from(2500));
+    }
+
+    #[test]
+    fn test_passage_gap_below_threshold() {
+        let rules = ProposalRules::default();
+        let gap = PassageGap::compute(U256::from(1000), U256::from(4000), U256::zero(), U256::from(100_000), &rules);
+        assert_eq!(gap.to_quorum, U256::zero());
+        assert_eq!(gap.to_threshold, U256::from(3001));
+        assert_eq!(gap.yes_power_needed, U256::from(3001));
+
+        let passing = PassageGap::compute(U256::from(5000), U256::from(4000), U256::zero(), U256::from(100_000), &rules);
+        assert_eq!(passing.yes_power_needed, U256::zero());
+    }
+}
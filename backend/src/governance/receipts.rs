@@ -0,0 +1,213 @@
+use crate::indexer::content_indexer::IndexedVote;
+use ethers::types::{Address, H256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Leaf committing to every field of a vote that affects the tally
+pub fn vote_leaf(vote: &IndexedVote) -> H256 {
+    let mut encoded = Vec::with_capacity(69 + 32 * vote.weights.len());
+    encoded.extend_from_slice(&vote.proposal_id.to_be_bytes());
+    encoded.extend_from_slice(vote.voter.as_bytes());
+    encoded.push(vote.choice);
+    encoded.extend_from_slice(&u256_bytes(vote.power));
+    encoded.extend_from_slice(&vote.timestamp.to_be_bytes());
+    for weight in &vote.weights {
+        encoded.extend_from_slice(&u256_bytes(*weight));
+    }
+    H256::from(keccak256(encoded))
+}
+
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+/// Pairs are hashed in sorted order so a proof is just the sibling path
+fn hash_pair(a: H256, b: H256) -> H256 {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    H256::from(keccak256([low.as_bytes(), high.as_bytes()].concat()))
+}
+
+/// Binary Merkle tree over vote leaves. An odd node out is carried up unhashed.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    layers: Vec<Vec<H256>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<H256>) -> Self {
+        let mut layers = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(*a, *b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    /// Zero for an empty vote set
+    pub fn root(&self) -> H256 {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or_default()
+    }
+
+    /// Siblings from the leaf at `index` up to the root
+    pub fn proof(&self, mut index: usize) -> Vec<H256> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// Whether `proof` links `leaf` to `root`
+pub fn verify_proof(leaf: H256, proof: &[H256], root: H256) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling)) == root
+}
+
+/// Root of a proposal's final vote set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteSetCommitment {
+    pub proposal_id: u64,
+    pub root: H256,
+    pub vote_count: usize,
+    pub finalized_at: u64,
+    /// Where the commitment was published, if IPFS accepted it
+    pub ipfs_hash: Option<String>,
+}
+
+/// Evidence that `vote` is part of the committed vote set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteInclusionProof {
+    pub vote: IndexedVote,
+    pub leaf: H256,
+    pub proof: Vec<H256>,
+    pub root: H256,
+}
+
+/// A proposal's votes in canonical order with their tree
+pub struct FinalizedVotes {
+    pub commitment: VoteSetCommitment,
+    votes: Vec<IndexedVote>,
+    tree: MerkleTree,
+}
+
+impl FinalizedVotes {
+    /// Order votes by time, then voter, and build their tree
+    pub fn new(proposal_id: u64, mut votes: Vec<IndexedVote>, finalized_at: u64) -> Self {
+        votes.sort_by_key(|vote| (vote.timestamp, vote.voter));
+        let tree = MerkleTree::new(votes.iter().map(vote_leaf).collect());
+
+        Self {
+            commitment: VoteSetCommitment {
+                proposal_id,
+                root: tree.root(),
+                vote_count: votes.len(),
+                finalized_at,
+                ipfs_hash: None,
+            },
+            votes,
+            tree,
+        }
+    }
+
+    /// Inclusion proof for `voter`'s vote, if they voted
+    pub fn proof(&self, voter: Address) -> Option<VoteInclusionProof> {
+        let index = self.votes.iter().position(|vote| vote.voter == voter)?;
+        let vote = self.votes[index].clone();
+
+        Some(VoteInclusionProof {
+            leaf: vote_leaf(&vote),
+            proof: self.tree.proof(index),
+            root: self.commitment.root,
+            vote,
+        })
+    }
+}
+
+/// Finalized vote sets per proposal, built once voting closes
+#[derive(Clone, Default)]
+pub struct VoteCommitments {
+    inner: Arc<RwLock<HashMap<u64, Arc<FinalizedVotes>>>>,
+}
+
+impl VoteCommitments {
+    /// Store a finalized set. The first one stored for a proposal wins.
+    pub fn insert(&self, finalized: FinalizedVotes) -> VoteSetCommitment {
+        let mut inner = self.inner.write().unwrap();
+        inner
+            .entry(finalized.commitment.proposal_id)
+            .or_insert_with(|| Arc::new(finalized))
+            .commitment
+            .clone()
+    }
+
+    pub fn get(&self, proposal_id: u64) -> Option<VoteSetCommitment> {
+        self.inner.read().unwrap().get(&proposal_id).map(|finalized| finalized.commitment.clone())
+    }
+
+    /// Inclusion proof for `voter`'s vote, if the proposal is finalized and they voted
+    pub fn proof(&self, proposal_id: u64, voter: Address) -> Option<VoteInclusionProof> {
+        let finalized = self.inner.read().unwrap().get(&proposal_id)?.clone();
+        finalized.proof(voter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(power: u64, timestamp: u64) -> IndexedVote {
+        IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter: Address::random(),
+            choice: 1,
+            power: U256::from(power),
+            timestamp,
+            ipfs_hash: None,
+            weights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_every_vote_proves_against_root() {
+        let votes: Vec<_> = (0..7).map(|i| vote(100 + i, i)).collect();
+        let commitments = VoteCommitments::default();
+        let commitment = commitments.insert(FinalizedVotes::new(1, votes.clone(), 100));
+
+        for vote in &votes {
+            let receipt = commitments.proof(1, vote.voter).unwrap();
+            assert_eq!(receipt.root, commitment.root);
+            assert!(verify_proof(receipt.leaf, &receipt.proof, commitment.root));
+        }
+        assert!(commitments.proof(1, Address::random()).is_none());
+    }
+
+    #[test]
+    fn test_tampered_leaf_fails_verification() {
+        let votes: Vec<_> = (0..4).map(|i| vote(100, i)).collect();
+        let finalized = FinalizedVotes::new(1, votes.clone(), 100);
+        let root = finalized.commitment.root;
+
+        let receipt = finalized.proof(votes[2].voter).unwrap();
+        let mut tampered = receipt.vote.clone();
+        tampered.power = U256::from(1_000_000);
+        assert!(verify_proof(receipt.leaf, &receipt.proof, root));
+        assert!(!verify_proof(vote_leaf(&tampered), &receipt.proof, root));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::auth::signature_verification::SignatureVerifier;
+use crate::blockchain::contracts::AggregateTally;
+use crate::storage::kv::{MemoryKvStore, SharedKvStore};
+use crate::utils::errors::Result;
+use ethers::abi::{encode, Token};
+use ethers::types::transaction::eip712::EIP712Domain;
+use ethers::types::{Address, H256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+pub const SIGNING_DOMAIN_NAME: &str = "Somnia Governance";
+pub const SIGNING_DOMAIN_VERSION: &str = "1";
+pub const VOTE_TYPE: &str = "Vote(uint256 proposalId,address voter,uint8 choice)";
+
+/// EIP-712 domain signed votes are bound to, so a signature can't be
+/// replayed on another chain or voting contract
+pub fn vote_domain(chain_id: u64, verifying_contract: Option<Address>) -> EIP712Domain {
+    EIP712Domain {
+        name: Some(SIGNING_DOMAIN_NAME.to_string()),
+        version: Some(SIGNING_DOMAIN_VERSION.to_string()),
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract,
+        salt: None,
+    }
+}
+
+/// A vote as the voter signs it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteMessage {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub choice: u8,
+}
+
+impl VoteMessage {
+    pub fn struct_hash(&self) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::FixedBytes(keccak256(VOTE_TYPE).to_vec()),
+            Token::Uint(U256::from(self.proposal_id)),
+            Token::Address(self.voter),
+            Token::Uint(U256::from(self.choice)),
+        ]))
+    }
+
+    /// Typed-data digest the wallet signs: `keccak256(0x1901 ‖ domainSeparator ‖ structHash)`
+    pub fn digest(&self, domain: &EIP712Domain) -> H256 {
+        let input = [&[0x19, 0x01], &domain.separator()[..], &self.struct_hash()[..]].concat();
+        H256::from(keccak256(input))
+    }
+}
+
+/// A signed vote collected off-chain, awaiting aggregation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedVote {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub choice: u8,
+    pub signature: String,
+    /// Effective power when the vote was accepted
+    pub power: U256,
+    pub submitted_at: u64,
+    /// Trusted relayer that submitted the vote for the voter, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relayer: Option<Address>,
+}
+
+impl SignedVote {
+    pub fn message(&self) -> VoteMessage {
+        VoteMessage {
+            proposal_id: self.proposal_id,
+            voter: self.voter,
+            choice: self.choice,
+        }
+    }
+}
+
+/// Result of posting a proposal's signed votes on-chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedVoteAggregate {
+    pub proposal_id: u64,
+    pub tally: AggregateTally,
+    /// Stored votes whose signatures did not verify and were left out
+    pub excluded: Vec<Address>,
+    /// Voters who also voted on-chain; only their on-chain vote counts
+    #[serde(default)]
+    pub superseded: Vec<Address>,
+    pub transaction_hash: H256,
+    pub aggregated_at: u64,
+}
+
+/// Re-verify every signature and tally the votes that hold up, returning the
+/// tally and the voters left out
+pub fn tally_signed_votes(
+    votes: &[SignedVote],
+    domain: &EIP712Domain,
+    verifier: &SignatureVerifier,
+) -> (AggregateTally, Vec<Address>) {
+    let mut tally = AggregateTally::default();
+    let mut excluded = Vec::new();
+
+    for vote in votes {
+        let signer = verifier.recover_signer(vote.message().digest(domain), &vote.signature);
+        if signer.ok() != Some(vote.voter) {
+            excluded.push(vote.voter);
+            continue;
+        }
+
+        match vote.choice {
+            0 => tally.no_votes += vote.power,
+            1 => tally.yes_votes += vote.power,
+            2 => tally.abstain_votes += vote.power,
+            _ => {
+                excluded.push(vote.voter);
+                continue;
+            }
+        }
+        tally.voter_count += 1;
+    }
+
+    (tally, excluded)
+}
+
+/// Signed votes per proposal, at most one per voter, and each proposal's
+/// aggregate once posted, kept in a `KvStore` under
+/// `signed-vote/<proposal>/<voter>` and `signed-vote-aggregate/<proposal>`
+#[derive(Clone)]
+pub struct SignedVoteStore {
+    store: SharedKvStore,
+    /// Serializes the check-then-insert of `insert`
+    insert_lock: Arc<Mutex<()>>,
+}
+
+impl SignedVoteStore {
+    pub fn new(store: SharedKvStore) -> Self {
+        Self {
+            store,
+            insert_lock: Arc::default(),
+        }
+    }
+
+    /// Store `vote` unless its voter already signed one for the proposal.
+    /// Returns whether it was stored.
+    pub fn insert(&self, vote: &SignedVote) -> Result<bool> {
+        let _guard = self.insert_lock.lock().unwrap();
+        let key = vote_key(vote.proposal_id, vote.voter);
+        if self.store.get(&key)?.is_some() {
+            return Ok(false);
+        }
+        self.store.put(&key, &serde_json::to_vec(vote)?, None)?;
+        Ok(true)
+    }
+
+    /// A proposal's signed votes, ordered by voter
+    pub fn votes(&self, proposal_id: u64) -> Result<Vec<SignedVote>> {
+        self.store
+            .list(&format!("signed-vote/{}/", proposal_id))?
+            .into_iter()
+            .map(|(_, value)| Ok(serde_json::from_slice(&value)?))
+            .collect()
+    }
+
+    pub fn aggregate(&self, proposal_id: u64) -> Result<Option<SignedVoteAggregate>> {
+        self.store
+            .get(&aggregate_key(proposal_id))?
+            .map(|value| Ok(serde_json::from_slice(&value)?))
+            .transpose()
+    }
+
+    pub fn record_aggregate(&self, aggregate: &SignedVoteAggregate) -> Result<()> {
+        self.store
+            .put(&aggregate_key(aggregate.proposal_id), &serde_json::to_vec(aggregate)?, None)
+    }
+}
+
+impl Default for SignedVoteStore {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryKvStore::new()))
+    }
+}
+
+fn vote_key(proposal_id: u64, voter: Address) -> String {
+    format!("signed-vote/{}/{:?}", proposal_id, voter)
+}
+
+fn aggregate_key(proposal_id: u64) -> String {
+    format!("signed-vote-aggregate/{}", proposal_id)
+}
@@ -1,2 +1,167 @@
-// Placeholder for voting mechanism logic
-pub struct VotingManager;
\ No newline at end of file
+use ethers::types::{Address, TransactionReceipt, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Where the power counted for a vote came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Live,
+    /// Live lookup failed; the last known value was used per `VotingPowerFallback`
+    LastKnown,
+    /// Read as of the proposal's snapshot block under `snapshot_voting`
+    Snapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastVoteOutcome {
+    pub receipt: TransactionReceipt,
+    pub power: U256,
+    pub power_source: PowerSource,
+}
+
+/// One eligibility check run by a vote preflight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+impl PreflightCheck {
+    pub fn new(name: &str, passed: bool, failure: impl FnOnce() -> String) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            detail: (!passed).then(failure),
+        }
+    }
+}
+
+/// Everything a client needs before asking the user to sign a vote. Nothing
+/// is submitted; `estimated_gas` is `None` when the estimate isn't available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VotePreflight {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub choice: u8,
+    pub eligible: bool,
+    pub checks: Vec<PreflightCheck>,
+    pub voting_power: U256,
+    pub power_source: PowerSource,
+    pub estimated_gas: Option<U256>,
+}
+
+impl VotePreflight {
+    pub fn failed_checks(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+/// Last voting power successfully read per address
+#[derive(Clone, Default)]
+pub struct PowerSnapshots {
+    inner: Arc<RwLock<HashMap<Address, U256>>>,
+}
+
+impl PowerSnapshots {
+    pub fn record(&self, address: Address, power: U256) {
+        self.inner.write().unwrap().insert(address, power);
+    }
+
+    pub fn get(&self, address: Address) -> Option<U256> {
+        self.inner.read().unwrap().get(&address).copied()
+    }
+}
+
+/// Block each proposal was created in, whose power counts under `snapshot_voting`
+#[derive(Clone, Default)]
+pub struct SnapshotBlocks {
+    inner: Arc<RwLock<HashMap<u64, u64>>>,
+}
+
+impl SnapshotBlocks {
+    pub fn record(&self, proposal_id: u64, block: u64) {
+        self.inner.write().unwrap().insert(proposal_id, block);
+    }
+
+    pub fn get(&self, proposal_id: u64) -> Option<u64> {
+        self.inner.read().unwrap().get(&proposal_id).copied()
+    }
+}
+
+/// Tracks votes submitted to the chain but not yet confirmed, so a quick
+/// double-submit doesn't pay gas twice.
+#[derive(Clone, Default)]
+pub struct PendingVotes {
+    inner: Arc<Mutex<HashSet<(u64, Address)>>>,
+}
+
+impl PendingVotes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a slot for `(proposal_id, voter)`. Returns `None` if a vote from
+    /// the same voter is already in flight. The slot is released when the
+    /// returned guard is dropped.
+    pub fn try_reserve(&self, proposal_id: u64, voter: Address) -> Option<PendingVoteGuard> {
+        let mut pending = self.inner.lock().unwrap();
+        if !pending.insert((proposal_id, voter)) {
+            return None;
+        }
+
+        Some(PendingVoteGuard {
+            pending: self.inner.clone(),
+            key: (proposal_id, voter),
+        })
+    }
+
+    pub fn is_pending(&self, proposal_id: u64, voter: Address) -> bool {
+        self.inner.lock().unwrap().contains(&(proposal_id, voter))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Releases a pending vote reservation on confirmation, failure or cancellation
+pub struct PendingVoteGuard {
+    pending: Arc<Mutex<HashSet<(u64, Address)>>>,
+    key: (u64, Address),
+}
+
+impl Drop for PendingVoteGuard {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservation_released_on_drop() {
+        let pending = PendingVotes::new();
+        let voter = Address::random();
+
+        let guard = pending.try_reserve(1, voter).unwrap();
+        assert!(pending.is_pending(1, voter));
+        assert!(pending.try_reserve(1, voter).is_none());
+
+        // Different proposal or voter is independent
+        assert!(pending.try_reserve(2, voter).is_some());
+        assert!(pending.try_reserve(1, Address::random()).is_some());
+
+        drop(guard);
+        assert!(!pending.is_pending(1, voter));
+        assert!(pending.is_empty());
+    }
+}
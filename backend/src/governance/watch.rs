@@ -0,0 +1,123 @@
+use crate::blockchain::contracts::ProposalStatus;
+use crate::indexer::content_indexer::ContentIndexer;
+use crate::ipfs::content_types::NotificationSettings;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Something a watcher of a proposal is told about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WatchEvent {
+    StatusChanged { status: ProposalStatus },
+    DeadlineApproaching { seconds_remaining: u64 },
+}
+
+impl WatchEvent {
+    /// Whether a watcher with `settings` asked for this kind of event and
+    /// has any channel to receive it on
+    pub fn wanted_by(&self, settings: &NotificationSettings) -> bool {
+        let reachable = settings.email_enabled || settings.browser_enabled;
+        reachable
+            && match self {
+                WatchEvent::StatusChanged { .. } => settings.proposal_updates,
+                WatchEvent::DeadlineApproaching { .. } => settings.vote_reminders,
+            }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchNotification {
+    pub watcher: Address,
+    pub proposal_id: u64,
+    pub event: WatchEvent,
+}
+
+/// Delivers notifications to the addresses watching a proposal
+pub trait WatchNotifier: Send + Sync {
+    fn notify(&self, notification: &WatchNotification);
+}
+
+/// Default watch notifier: an entry in the service log
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogWatchNotifier;
+
+impl WatchNotifier for LogWatchNotifier {
+    fn notify(&self, notification: &WatchNotification) {
+        tracing::info!(
+            watcher = ?notification.watcher,
+            proposal_id = notification.proposal_id,
+            event = ?notification.event,
+            "Watched proposal update"
+        );
+    }
+}
+
+/// Fans proposal events out to the proposal's watchers, as recorded in the
+/// indexer, within each watcher's notification settings
+#[derive(Clone)]
+pub struct WatchDispatcher {
+    notifier: Arc<dyn WatchNotifier>,
+    /// Watchers already reminded of a proposal's deadline
+    reminded: Arc<Mutex<HashSet<(u64, Address)>>>,
+}
+
+impl WatchDispatcher {
+    pub fn new(notifier: Arc<dyn WatchNotifier>) -> Self {
+        Self {
+            notifier,
+            reminded: Arc::default(),
+        }
+    }
+
+    /// Tell watchers of `proposal_id` it moved to `status`. Returns how many
+    /// were notified.
+    pub fn status_changed(&self, indexer: &ContentIndexer, proposal_id: u64, status: ProposalStatus) -> usize {
+        let event = WatchEvent::StatusChanged { status };
+        indexer
+            .watchers(proposal_id)
+            .into_iter()
+            .filter(|(_, settings)| event.wanted_by(settings))
+            .map(|(watcher, _)| self.send(watcher, proposal_id, event.clone()))
+            .count()
+    }
+
+    /// Remind watchers of active proposals closing within `window` seconds
+    /// of `now`, once per watcher and proposal. Returns how many were
+    /// notified.
+    pub fn deadline_reminders(&self, indexer: &ContentIndexer, now: u64, window: u64) -> usize {
+        let mut reminded = self.reminded.lock().unwrap();
+        let mut sent = 0;
+
+        for proposal in indexer.proposals() {
+            if proposal.status != ProposalStatus::Active || now >= proposal.end_time || proposal.end_time - now > window {
+                continue;
+            }
+            let event = WatchEvent::DeadlineApproaching {
+                seconds_remaining: proposal.end_time - now,
+            };
+            for (watcher, settings) in indexer.watchers(proposal.id) {
+                if event.wanted_by(&settings) && reminded.insert((proposal.id, watcher)) {
+                    self.send(watcher, proposal.id, event.clone());
+                    sent += 1;
+                }
+            }
+        }
+        sent
+    }
+
+    fn send(&self, watcher: Address, proposal_id: u64, event: WatchEvent) {
+        self.notifier.notify(&WatchNotification {
+            watcher,
+            proposal_id,
+            event,
+        });
+    }
+}
+
+impl Default for WatchDispatcher {
+    fn default() -> Self {
+        Self::new(Arc::new(LogWatchNotifier))
+    }
+}
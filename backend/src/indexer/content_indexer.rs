@@ -1,2 +1,701 @@
-// Placeholder for content indexer
-pub struct ContentIndexer;
\ No newline at end of file
+use crate::blockchain::contracts::{ExecutionResult, ProposalCreatedEvent, ProposalStatus, VoteCastEvent};
+use crate::blockchain::events::EventHandler;
+use crate::indexer::slow_queries::SlowQueryLog;
+use crate::ipfs::content_types::NotificationSettings;
+use crate::storage::kv::SharedKvStore;
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Identifies a proposal across governance contracts, each of which numbers
+/// its proposals from 1. `contract` is `None` for the primary contract, whose
+/// proposals keep their bare numeric ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ProposalKey {
+    pub contract: Option<Address>,
+    pub id: u64,
+}
+
+impl ProposalKey {
+    pub fn primary(id: u64) -> Self {
+        Self { contract: None, id }
+    }
+
+    pub fn scoped(contract: Address, id: u64) -> Self {
+        Self {
+            contract: Some(contract),
+            id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedProposal {
+    pub id: u64,
+    /// Governance contract the proposal lives in; `None` for the primary one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Address>,
+    pub proposer: Address,
+    pub ipfs_hash: String,
+    pub proposal_type: u8,
+    pub status: ProposalStatus,
+    pub start_time: u64,
+    pub end_time: u64,
+    #[serde(default)]
+    pub supersedes: Option<u64>,
+    /// Total voting power at the proposal's snapshot block
+    #[serde(default)]
+    pub total_voting_power: U256,
+    /// Whether voting was already reopened once for a tie
+    #[serde(default)]
+    pub tie_extended: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedVote {
+    pub proposal_id: u64,
+    /// Contract of the proposal voted on; `None` for the primary one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Address>,
+    pub voter: Address,
+    pub choice: u8,
+    pub power: U256,
+    pub timestamp: u64,
+    pub ipfs_hash: Option<String>,
+    /// Power per option for weighted votes, summing to `power`; empty for
+    /// single-choice votes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<U256>,
+}
+
+/// Activity summary for ranking open proposals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendingProposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub ipfs_hash: String,
+    pub end_time: u64,
+    /// Votes cast within the ranking window
+    pub recent_votes: u64,
+    pub total_votes: u64,
+    /// Total power voted so far
+    pub participation: U256,
+}
+
+impl IndexedProposal {
+    pub fn key(&self) -> ProposalKey {
+        ProposalKey {
+            contract: self.contract,
+            id: self.id,
+        }
+    }
+}
+
+impl IndexedVote {
+    pub fn key(&self) -> ProposalKey {
+        ProposalKey {
+            contract: self.contract,
+            id: self.proposal_id,
+        }
+    }
+}
+
+/// One address following a proposal
+#[derive(Debug, Clone)]
+struct Watch {
+    settings: NotificationSettings,
+    /// When the watch started; kept when its settings change
+    since: u64,
+}
+
+/// In-memory index of proposals and votes built from contract events. Lookups
+/// by bare id are in the primary contract; the `scoped_` variants take a key.
+#[derive(Clone, Default)]
+pub struct ContentIndexer {
+    proposals: Arc<RwLock<BTreeMap<ProposalKey, IndexedProposal>>>,
+    votes: Arc<RwLock<BTreeMap<ProposalKey, Vec<IndexedVote>>>>,
+    executions: Arc<RwLock<BTreeMap<u64, ExecutionResult>>>,
+    /// Addresses following each proposal, with how they want to be notified
+    watchers: Arc<RwLock<BTreeMap<u64, BTreeMap<Address, Watch>>>>,
+    slow_queries: SlowQueryLog,
+    /// Where weighted votes' weights are kept, as the chain only records
+    /// their heaviest option
+    vote_weights: Option<SharedKvStore>,
+    /// Contract whose events are indexed under bare ids
+    primary_contract: Option<Address>,
+}
+
+impl ContentIndexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log and count queries taking at least `threshold`
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_queries = SlowQueryLog::new(threshold);
+        self
+    }
+
+    pub fn slow_queries(&self) -> &SlowQueryLog {
+        &self.slow_queries
+    }
+
+    /// Keep weighted votes' weights in `store`, so the same votes indexed
+    /// again from contract events, e.g. after a restart, tally the same
+    pub fn with_kv_store(mut self, store: SharedKvStore) -> Self {
+        self.vote_weights = Some(store);
+        self
+    }
+
+    /// Index events emitted by `contract` under bare ids and those of any
+    /// other contract under keys scoped to it. Without a primary contract,
+    /// every event is taken to be the primary contract's.
+    pub fn with_primary_contract(mut self, contract: Address) -> Self {
+        self.primary_contract = Some(contract);
+        self
+    }
+
+    /// Key of proposal `id` of the contract that emitted an event
+    pub fn event_key(&self, contract: Address, id: u64) -> ProposalKey {
+        match self.primary_contract {
+            Some(primary) if primary != contract => ProposalKey::scoped(contract, id),
+            _ => ProposalKey::primary(id),
+        }
+    }
+
+    pub fn index_proposal(&self, proposal: IndexedProposal) {
+        let mut proposals = self.proposals.write().unwrap();
+        proposals.insert(proposal.key(), proposal);
+    }
+
+    /// Index a vote, replacing any earlier one from the same voter on the
+    /// proposal: the event for a vote already indexed when it was cast
+    /// mustn't count it twice. Weights are kept when given and restored when
+    /// not, as events don't carry them.
+    pub fn index_vote(&self, mut vote: IndexedVote) {
+        if vote.weights.is_empty() {
+            if let Some(weights) = self.load_weights(&vote) {
+                vote.power = weights.iter().fold(U256::zero(), |total, weight| total.saturating_add(*weight));
+                vote.weights = weights;
+            }
+        } else {
+            self.store_weights(&vote);
+        }
+
+        let mut votes = self.votes.write().unwrap();
+        let votes = votes.entry(vote.key()).or_default();
+        match votes.iter_mut().find(|existing| existing.voter == vote.voter) {
+            Some(existing) => *existing = vote,
+            None => votes.push(vote),
+        }
+    }
+
+    fn load_weights(&self, vote: &IndexedVote) -> Option<Vec<U256>> {
+        let store = self.vote_weights.as_ref()?;
+        match store.get(&weights_key(vote)) {
+            Ok(value) => value.and_then(|value| serde_json::from_slice(&value).ok()),
+            Err(e) => {
+                tracing::warn!("Failed to load weights of {:?}'s vote on {}: {}", vote.voter, vote.proposal_id, e);
+                None
+            }
+        }
+    }
+
+    fn store_weights(&self, vote: &IndexedVote) {
+        let Some(store) = &self.vote_weights else { return };
+        let stored = serde_json::to_vec(&vote.weights)
+            .map_err(Into::into)
+            .and_then(|value| store.put(&weights_key(vote), &value, None));
+        if let Err(e) = stored {
+            tracing::warn!("Failed to store weights of {:?}'s vote on {}: {}", vote.voter, vote.proposal_id, e);
+        }
+    }
+
+    pub fn update_status(&self, proposal_id: u64, status: ProposalStatus) {
+        self.update_scoped_status(ProposalKey::primary(proposal_id), status);
+    }
+
+    pub fn update_scoped_status(&self, key: ProposalKey, status: ProposalStatus) {
+        if let Some(proposal) = self.proposals.write().unwrap().get_mut(&key) {
+            proposal.status = status;
+        }
+    }
+
+    /// Record the total voting power of a proposal's snapshot, for proposals
+    /// indexed from events, which don't carry it
+    pub fn set_total_voting_power(&self, key: ProposalKey, power: U256) {
+        if let Some(proposal) = self.proposals.write().unwrap().get_mut(&key) {
+            proposal.total_voting_power = power;
+        }
+    }
+
+    /// Move a tied proposal's deadline to `end_time`, unless it was already
+    /// extended once. Returns whether it was extended now.
+    pub fn extend_for_tie(&self, proposal_id: u64, end_time: u64) -> bool {
+        let mut proposals = self.proposals.write().unwrap();
+        match proposals.get_mut(&ProposalKey::primary(proposal_id)) {
+            Some(proposal) if !proposal.tie_extended => {
+                proposal.tie_extended = true;
+                proposal.end_time = end_time;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn record_execution(&self, result: ExecutionResult) {
+        self.executions.write().unwrap().insert(result.proposal_id, result);
+    }
+
+    pub fn get_execution(&self, proposal_id: u64) -> Option<ExecutionResult> {
+        self.slow_queries.time(
+            "execution",
+            || format!("proposal_id={}", proposal_id),
+            || self.executions.read().unwrap().get(&proposal_id).cloned(),
+        )
+    }
+
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<IndexedProposal> {
+        self.get_scoped_proposal(ProposalKey::primary(proposal_id))
+    }
+
+    pub fn get_scoped_proposal(&self, key: ProposalKey) -> Option<IndexedProposal> {
+        self.slow_queries.time(
+            "proposal",
+            || format!("key={:?}", key),
+            || self.proposals.read().unwrap().get(&key).cloned(),
+        )
+    }
+
+    /// Follow a proposal for notifications from `now`, replacing the settings
+    /// of an existing watch. Returns whether the address wasn't watching yet.
+    pub fn watch(&self, proposal_id: u64, watcher: Address, settings: NotificationSettings, now: u64) -> bool {
+        let mut watchers = self.watchers.write().unwrap();
+        match watchers.entry(proposal_id).or_default().entry(watcher) {
+            Entry::Occupied(mut watch) => {
+                watch.get_mut().settings = settings;
+                false
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Watch { settings, since: now });
+                true
+            }
+        }
+    }
+
+    /// Stop following a proposal. Returns whether the address was watching.
+    pub fn unwatch(&self, proposal_id: u64, watcher: Address) -> bool {
+        let mut watchers = self.watchers.write().unwrap();
+        let Some(proposal_watchers) = watchers.get_mut(&proposal_id) else {
+            return false;
+        };
+        let removed = proposal_watchers.remove(&watcher).is_some();
+        if proposal_watchers.is_empty() {
+            watchers.remove(&proposal_id);
+        }
+        removed
+    }
+
+    pub fn watchers(&self, proposal_id: u64) -> Vec<(Address, NotificationSettings)> {
+        self.slow_queries.time(
+            "watchers",
+            || format!("proposal_id={}", proposal_id),
+            || {
+                self.watchers
+                    .read()
+                    .unwrap()
+                    .get(&proposal_id)
+                    .map(|watchers| {
+                        watchers
+                            .iter()
+                            .map(|(address, watch)| (*address, watch.settings.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            },
+        )
+    }
+
+    /// Proposals `watcher` follows, with when each watch started
+    pub fn watched_by(&self, watcher: Address) -> Vec<(u64, u64)> {
+        self.slow_queries.time(
+            "watched_by",
+            || format!("watcher={:?}", watcher),
+            || {
+                self.watchers
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|(proposal_id, watchers)| {
+                        watchers.get(&watcher).map(|watch| (*proposal_id, watch.since))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Proposals created by `proposer`, across all contracts
+    pub fn proposals_by(&self, proposer: Address) -> Vec<IndexedProposal> {
+        self.slow_queries.time(
+            "proposals_by",
+            || format!("proposer={:?}", proposer),
+            || {
+                self.proposals
+                    .read()
+                    .unwrap()
+                    .values()
+                    .filter(|p| p.proposer == proposer)
+                    .cloned()
+                    .collect()
+            },
+        )
+    }
+
+    /// Votes cast by any of `voters`, across all contracts
+    pub fn votes_by(&self, voters: &[Address]) -> Vec<IndexedVote> {
+        self.slow_queries.time(
+            "votes_by",
+            || format!("voters={:?}", voters),
+            || {
+                self.votes
+                    .read()
+                    .unwrap()
+                    .values()
+                    .flatten()
+                    .filter(|v| voters.contains(&v.voter))
+                    .cloned()
+                    .collect()
+            },
+        )
+    }
+
+    /// Primary contract proposals in ascending id order
+    pub fn proposals(&self) -> Vec<IndexedProposal> {
+        self.scoped_proposals(None)
+    }
+
+    /// Proposals of one contract (`None` for the primary) in ascending id order
+    pub fn scoped_proposals(&self, contract: Option<Address>) -> Vec<IndexedProposal> {
+        self.slow_queries.time(
+            "proposals",
+            || format!("contract={:?}", contract),
+            || {
+                self.proposals
+                    .read()
+                    .unwrap()
+                    .values()
+                    .filter(|p| p.contract == contract)
+                    .cloned()
+                    .collect()
+            },
+        )
+    }
+
+    /// Votes for a proposal ordered by timestamp
+    pub fn get_votes(&self, proposal_id: u64) -> Vec<IndexedVote> {
+        self.get_scoped_votes(ProposalKey::primary(proposal_id))
+    }
+
+    pub fn get_scoped_votes(&self, key: ProposalKey) -> Vec<IndexedVote> {
+        self.slow_queries.time(
+            "votes",
+            || format!("key={:?}", key),
+            || {
+                let mut votes = self.votes.read().unwrap().get(&key).cloned().unwrap_or_default();
+                votes.sort_by_key(|v| v.timestamp);
+                votes
+            },
+        )
+    }
+
+    /// Proposals open for voting at `now`, most active first: by votes cast in
+    /// the last `window` seconds, then by total power voted
+    pub fn trending(&self, now: u64, window: u64) -> Vec<TrendingProposal> {
+        self.slow_queries.time(
+            "trending",
+            || format!("now={} window={}", now, window),
+            || self.rank_trending(now, window),
+        )
+    }
+
+    fn rank_trending(&self, now: u64, window: u64) -> Vec<TrendingProposal> {
+        let since = now.saturating_sub(window);
+        let proposals = self.proposals.read().unwrap();
+        let votes = self.votes.read().unwrap();
+
+        let mut trending: Vec<TrendingProposal> = proposals
+            .values()
+            .filter(|p| p.contract.is_none())
+            .filter(|p| p.status == ProposalStatus::Active && (p.start_time..p.end_time).contains(&now))
+            .map(|p| {
+                let cast = votes.get(&p.key()).map(Vec::as_slice).unwrap_or_default();
+                TrendingProposal {
+                    id: p.id,
+                    proposer: p.proposer,
+                    ipfs_hash: p.ipfs_hash.clone(),
+                    end_time: p.end_time,
+                    recent_votes: cast.iter().filter(|v| v.timestamp >= since).count() as u64,
+                    total_votes: cast.len() as u64,
+                    participation: cast.iter().fold(U256::zero(), |sum, v| sum + v.power),
+                }
+            })
+            .collect();
+
+        trending.sort_by(|a, b| {
+            b.recent_votes
+                .cmp(&a.recent_votes)
+                .then(b.participation.cmp(&a.participation))
+                .then(a.id.cmp(&b.id))
+        });
+        trending
+    }
+
+    /// Proposals whose `supersedes` link points at `proposal_id`
+    pub fn superseded_by(&self, proposal_id: u64) -> Vec<u64> {
+        self.slow_queries.time(
+            "superseded_by",
+            || format!("proposal_id={}", proposal_id),
+            || {
+                self.proposals
+                    .read()
+                    .unwrap()
+                    .values()
+                    .filter(|p| p.contract.is_none() && p.supersedes == Some(proposal_id))
+                    .map(|p| p.id)
+                    .collect()
+            },
+        )
+    }
+
+    /// Proposals indexed across all contracts
+    pub fn proposal_count(&self) -> usize {
+        self.proposals.read().unwrap().len()
+    }
+}
+
+/// `vote-weights/<proposal>/<voter>`, with the contract ahead of the
+/// proposal for contracts other than the primary one
+fn weights_key(vote: &IndexedVote) -> String {
+    match vote.contract {
+        Some(contract) => format!("vote-weights/{:?}/{}/{:?}", contract, vote.proposal_id, vote.voter),
+        None => format!("vote-weights/{}/{:?}", vote.proposal_id, vote.voter),
+    }
+}
+
+impl EventHandler for ContentIndexer {
+    fn handle_proposal_created(&self, event: &ProposalCreatedEvent) {
+        let key = self.event_key(event.contract, event.proposal_id);
+        self.index_proposal(IndexedProposal {
+            id: key.id,
+            contract: key.contract,
+            proposer: event.proposer,
+            ipfs_hash: event.ipfs_hash.clone(),
+            proposal_type: event.proposal_type,
+            status: ProposalStatus::Active,
+            start_time: event.start_time.as_u64(),
+            end_time: event.end_time.as_u64(),
+            supersedes: None, // Only known once the IPFS content is read
+            total_voting_power: U256::zero(), // Not carried by the event; the engine reads it from the hub
+            tie_extended: false,
+        });
+    }
+
+    fn handle_vote_cast(&self, event: &VoteCastEvent) {
+        let key = self.event_key(event.contract, event.proposal_id);
+        self.index_vote(IndexedVote {
+            proposal_id: key.id,
+            contract: key.contract,
+            voter: event.voter,
+            choice: event.choice,
+            power: event.power,
+            timestamp: event.timestamp.as_u64(),
+            ipfs_hash: event.ipfs_hash.clone(),
+            weights: Vec::new(),
+        });
+    }
+
+    fn handle_proposal_executed(&self, contract: Address, proposal_id: u64, _executor: Address) {
+        self.update_scoped_status(self.event_key(contract, proposal_id), ProposalStatus::Executed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexes_events() {
+        let indexer = ContentIndexer::new();
+
+        indexer.handle_proposal_created(&ProposalCreatedEvent {
+            contract: Address::zero(),
+            proposal_id: 1,
+            proposer: Address::zero(),
+            ipfs_hash: "QmTest123".to_string(),
+            start_time: U256::from(1000),
+            end_time: U256::from(2000),
+            proposal_type: 0,
+        });
+
+        for timestamp in [1500u64, 1200] {
+            indexer.handle_vote_cast(&VoteCastEvent {
+                contract: Address::zero(),
+                proposal_id: 1,
+                voter: Address::random(),
+                choice: 1,
+                power: U256::from(10),
+                timestamp: U256::from(timestamp),
+                ipfs_hash: None,
+            });
+        }
+
+        indexer.handle_proposal_executed(Address::zero(), 1, Address::zero());
+
+        let proposal = indexer.get_proposal(1).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+
+        let votes = indexer.get_votes(1);
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes[0].timestamp, 1200);
+        assert!(indexer.get_votes(2).is_empty());
+    }
+
+    #[test]
+    fn test_events_keyed_by_emitting_contract() {
+        let hub = Address::random();
+        let other = Address::random();
+        let indexer = ContentIndexer::new().with_primary_contract(hub);
+        for contract in [hub, other] {
+            indexer.handle_proposal_created(&ProposalCreatedEvent {
+                contract,
+                proposal_id: 1,
+                proposer: Address::zero(),
+                ipfs_hash: format!("Qm{:?}", contract),
+                start_time: U256::from(1000),
+                end_time: U256::from(2000),
+                proposal_type: 0,
+            });
+        }
+        indexer.handle_vote_cast(&VoteCastEvent {
+            contract: other,
+            proposal_id: 1,
+            voter: Address::random(),
+            choice: 1,
+            power: U256::from(10),
+            timestamp: U256::from(1500),
+            ipfs_hash: None,
+        });
+        indexer.handle_proposal_executed(other, 1, Address::zero());
+
+        let primary = indexer.get_proposal(1).unwrap();
+        assert_eq!(primary.contract, None);
+        assert_eq!(primary.status, ProposalStatus::Active);
+        assert!(indexer.get_votes(1).is_empty());
+
+        let scoped = indexer.get_scoped_proposal(ProposalKey::scoped(other, 1)).unwrap();
+        assert_eq!(scoped.ipfs_hash, format!("Qm{:?}", other));
+        assert_eq!(scoped.status, ProposalStatus::Executed);
+        assert_eq!(indexer.get_scoped_votes(scoped.key()).len(), 1);
+        assert_eq!(indexer.scoped_proposals(Some(other)).len(), 1);
+    }
+
+    #[test]
+    fn test_weights_survive_reindexing_from_events() {
+        use crate::storage::kv::MemoryKvStore;
+
+        let store: SharedKvStore = Arc::new(MemoryKvStore::new());
+        let voter = Address::random();
+        let weights = vec![U256::from(300), U256::zero(), U256::from(200)];
+        let cast = ContentIndexer::new().with_kv_store(store.clone());
+        cast.index_vote(IndexedVote {
+            proposal_id: 1,
+            contract: None,
+            voter,
+            choice: 0,
+            power: U256::from(500),
+            timestamp: 1500,
+            ipfs_hash: None,
+            weights: weights.clone(),
+        });
+        let event = VoteCastEvent {
+            contract: Address::zero(),
+            proposal_id: 1,
+            voter,
+            choice: 0,
+            power: U256::from(1000),
+            timestamp: U256::from(1500),
+            ipfs_hash: None,
+        };
+
+        // The event for the same vote replaces it rather than counting twice
+        cast.handle_vote_cast(&event);
+        let votes = cast.get_votes(1);
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].weights, weights);
+        assert_eq!(votes[0].power, U256::from(500));
+
+        // As does a fresh index rebuilt from events
+        let rebuilt = ContentIndexer::new().with_kv_store(store);
+        rebuilt.handle_vote_cast(&event);
+        let votes = rebuilt.get_votes(1);
+        assert_eq!(votes[0].weights, weights);
+        assert_eq!(votes[0].power, U256::from(500));
+
+        // Without the store, only the heaviest option is known
+        let forgetful = ContentIndexer::new();
+        forgetful.handle_vote_cast(&event);
+        assert!(forgetful.get_votes(1)[0].weights.is_empty());
+    }
+
+    #[test]
+    fn test_trending_ranks_recent_activity_first() {
+        let indexer = ContentIndexer::new();
+        let now = 100_000;
+        for (id, end_time) in [(1, now + 3600), (2, now + 3600), (3, now + 3600), (4, now - 1)] {
+            indexer.index_proposal(IndexedProposal {
+                id,
+                contract: None,
+                proposer: Address::zero(),
+                ipfs_hash: "QmTest123".to_string(),
+                proposal_type: 0,
+                status: ProposalStatus::Active,
+                start_time: 0,
+                end_time,
+                supersedes: None,
+                total_voting_power: U256::zero(),
+                tie_extended: false,
+            });
+        }
+
+        // Proposal 1: busy early on; 2: a couple of fresh votes; 3: untouched;
+        // 4: busiest of all but already closed
+        let seeds = [(1, now - 50_000, 5), (2, now - 600, 2), (4, now - 60, 10)];
+        for (proposal_id, timestamp, count) in seeds {
+            for _ in 0..count {
+                indexer.index_vote(IndexedVote {
+                    proposal_id,
+                    contract: None,
+                    voter: Address::random(),
+                    choice: 1,
+                    power: U256::from(100),
+                    timestamp,
+                    ipfs_hash: None,
+                    weights: Vec::new(),
+                });
+            }
+        }
+
+        let trending = indexer.trending(now, 3600);
+        let ids: Vec<u64> = trending.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(trending[0].recent_votes, 2);
+        assert_eq!(trending[1].recent_votes, 0);
+        assert_eq!(trending[1].participation, U256::from(500));
+
+        // A wide enough window counts proposal 1's early votes as recent
+        assert_eq!(indexer.trending(now, 60_000)[0].id, 1);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::config::DEFAULT_SLOW_QUERY_THRESHOLD_MS;
+use prometheus::{IntCounterVec, Opts, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Most slow queries kept for inspection; the oldest are dropped first
+pub const MAX_RECENT_SLOW_QUERIES: usize = 100;
+
+/// An indexer query that took at least the slow query threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQuery {
+    pub query: String,
+    pub params: String,
+    pub elapsed_ms: u64,
+}
+
+/// Indexer queries that ran over the slow query threshold, by query type
+#[derive(Clone)]
+pub struct IndexerQueryMetrics {
+    slow_queries: IntCounterVec,
+}
+
+impl IndexerQueryMetrics {
+    pub fn new() -> Self {
+        let slow_queries = IntCounterVec::new(
+            Opts::new("indexer_slow_queries_total", "Indexer queries slower than the slow query threshold"),
+            &["query"],
+        )
+        .expect("valid metric definition");
+
+        Self { slow_queries }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.slow_queries.clone()))
+    }
+
+    pub fn slow_queries(&self, query: &str) -> u64 {
+        self.slow_queries.with_label_values(&[query]).get()
+    }
+}
+
+impl Default for IndexerQueryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Times indexer queries, logging and counting those at or over `threshold`
+/// so operators can spot pathological lookups as the index grows
+#[derive(Clone)]
+pub struct SlowQueryLog {
+    threshold: Duration,
+    metrics: IndexerQueryMetrics,
+    recent: Arc<Mutex<VecDeque<SlowQuery>>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            metrics: IndexerQueryMetrics::new(),
+            recent: Arc::default(),
+        }
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Run `run` as query `query`. `params` describes the query's arguments
+    /// and is only built when the query turns out slow.
+    pub fn time<T>(&self, query: &'static str, params: impl FnOnce() -> String, run: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = run();
+        let elapsed = started.elapsed();
+        if elapsed >= self.threshold {
+            self.record(query, params(), elapsed);
+        }
+        result
+    }
+
+    /// Slow queries seen so far, newest first
+    pub fn recent(&self) -> Vec<SlowQuery> {
+        self.recent.lock().unwrap().iter().rev().cloned().collect()
+    }
+
+    pub fn metrics(&self) -> &IndexerQueryMetrics {
+        &self.metrics
+    }
+
+    fn record(&self, query: &'static str, params: String, elapsed: Duration) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        tracing::warn!(query, params = %params, elapsed_ms, "Slow indexer query");
+        self.metrics.slow_queries.with_label_values(&[query]).inc();
+
+        let mut recent = self.recent.lock().unwrap();
+        recent.push_back(SlowQuery {
+            query: query.to_string(),
+            params,
+            elapsed_ms,
+        });
+        while recent.len() > MAX_RECENT_SLOW_QUERIES {
+            recent.pop_front();
+        }
+    }
+}
+
+impl Default for SlowQueryLog {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(DEFAULT_SLOW_QUERY_THRESHOLD_MS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::content_indexer::ContentIndexer;
+
+    #[test]
+    fn test_only_slow_queries_are_logged_and_counted() {
+        let indexer = ContentIndexer::new().with_slow_query_threshold(Duration::from_millis(20));
+        let log = indexer.slow_queries();
+
+        let answer = log.time(
+            "mock_slow",
+            || "delay_ms=40".to_string(),
+            || {
+                std::thread::sleep(Duration::from_millis(40));
+                42
+            },
+        );
+        assert_eq!(answer, 42);
+        assert!(indexer.get_proposal(1).is_none());
+
+        let recent = log.recent();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].query, "mock_slow");
+        assert_eq!(recent[0].params, "delay_ms=40");
+        assert!(recent[0].elapsed_ms >= 40);
+        assert_eq!(log.metrics().slow_queries("mock_slow"), 1);
+        assert_eq!(log.metrics().slow_queries("proposal"), 0);
+    }
+}
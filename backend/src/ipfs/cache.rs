@@ -4,6 +4,7 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
+use crate::utils::clock::{system_clock, Clock, SharedClock};
 
 #[derive(Debug, Clone)]
 pub struct CachedItem {
@@ -15,8 +16,7 @@ pub struct CachedItem {
 }
 
 impl CachedItem {
-    pub fn new(content: Value, ttl: Option<Duration>) -> Self {
-        let now = Utc::now();
+    pub fn new(content: Value, ttl: Option<Duration>, now: DateTime<Utc>) -> Self {
         Self {
             content,
             cached_at: now,
@@ -26,17 +26,17 @@ impl CachedItem {
         }
     }
 
-    pub fn is_expired(&self) -> bool {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
         if let Some(ttl) = self.ttl {
-            Utc::now() > self.cached_at + ttl
+            now > self.cached_at + ttl
         } else {
             false // No TTL means never expires (immutable IPFS content)
         }
     }
 
-    pub fn access(&mut self) -> &Value {
+    pub fn access(&mut self, now: DateTime<Utc>) -> &Value {
         self.access_count += 1;
-        self.last_accessed = Utc::now();
+        self.last_accessed = now;
         &self.content
     }
 }
@@ -45,10 +45,15 @@ impl CachedItem {
 pub struct IpfsCache {
     cache: Arc<RwLock<LruCache<String, CachedItem>>>,
     max_size: usize,
+    clock: SharedClock,
 }
 
 impl IpfsCache {
     pub fn new(max_size: usize) -> Self {
+        Self::with_clock(max_size, system_clock())
+    }
+
+    pub fn with_clock(max_size: usize, clock: SharedClock) -> Self {
         let cache = Arc::new(RwLock::new(
             LruCache::new(NonZeroUsize::new(max_size).unwrap())
         ));
@@ -56,27 +61,36 @@ impl IpfsCache {
         Self {
             cache,
             max_size,
+            clock,
         }
     }
 
     pub async fn get(&self, hash: &str) -> Option<Value> {
+        let now = self.clock.now();
         let mut cache = self.cache.write().await;
         
         if let Some(item) = cache.get_mut(hash) {
-            if item.is_expired() {
+            if item.is_expired(now) {
                 cache.pop(hash);
                 None
             } else {
-                Some(item.access().clone())
+                Some(item.access(now).clone())
             }
         } else {
             None
         }
     }
 
+    /// Whether unexpired content for `hash` is cached, without counting an access
+    pub async fn contains(&self, hash: &str) -> bool {
+        let now = self.clock.now();
+        let cache = self.cache.read().await;
+        cache.peek(hash).is_some_and(|item| !item.is_expired(now))
+    }
+
     pub async fn put(&self, hash: String, content: Value, ttl: Option<Duration>) {
         let mut cache = self.cache.write().await;
-        let item = CachedItem::new(content, ttl);
+        let item = CachedItem::new(content, ttl, self.clock.now());
         cache.put(hash, item);
     }
 
@@ -96,13 +110,14 @@ impl IpfsCache {
     }
 
     pub async fn stats(&self) -> CacheStats {
+        let now = self.clock.now();
         let cache = self.cache.read().await;
         let mut total_access_count = 0;
         let mut expired_count = 0;
 
         for (_, item) in cache.iter() {
             total_access_count += item.access_count;
-            if item.is_expired() {
+            if item.is_expired(now) {
                 expired_count += 1;
             }
         }
@@ -116,11 +131,12 @@ impl IpfsCache {
     }
 
     pub async fn cleanup_expired(&self) -> usize {
+        let now = self.clock.now();
         let mut cache = self.cache.write().await;
         let mut expired_keys = Vec::new();
         
         for (key, item) in cache.iter() {
-            if item.is_expired() {
+            if item.is_expired(now) {
                 expired_keys.push(key.clone());
             }
         }
@@ -154,9 +170,10 @@ pub struct CacheStats {
     pub expired_items: usize,
 }
 
-// Background task to periodically clean up expired items
-pub async fn start_cache_cleanup_task(cache: Arc<IpfsCache>) {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300)); // 5 minutes
+// Background task to clean up expired items every `period`
+// (`IpfsConfig::cache_cleanup_interval`, which must not be zero)
+pub async fn start_cache_cleanup_task(cache: Arc<IpfsCache>, period: tokio::time::Duration) {
+    let mut interval = tokio::time::interval(period);
     
     loop {
         interval.tick().await;
@@ -170,6 +187,7 @@ pub async fn start_cache_cleanup_task(cache: Arc<IpfsCache>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::clock::MockClock;
     use serde_json::json;
 
     #[tokio::test]
@@ -191,7 +209,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_cache_expiration() {
-        let cache = IpfsCache::new(10);
+        let clock = MockClock::default();
+        let cache = IpfsCache::with_clock(10, Arc::new(clock.clone()));
         let test_hash = "QmTest456";
         let test_content = json!({"test": "expiring_data"});
         let short_ttl = Duration::milliseconds(100);
@@ -202,10 +221,27 @@ mod tests {
         // Should be available immediately
         assert!(cache.get(test_hash).await.is_some());
         
-        // Wait for expiration
-        tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+        // Advance past expiration
+        clock.advance(Duration::milliseconds(150));
         
         // Should be expired and removed
         assert!(cache.get(test_hash).await.is_none());
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cleanup_task_runs_on_configured_interval() {
+        let clock = MockClock::default();
+        let cache = Arc::new(IpfsCache::with_clock(10, Arc::new(clock.clone())));
+        tokio::spawn(start_cache_cleanup_task(cache.clone(), tokio::time::Duration::from_secs(10)));
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+        cache.put("QmExpiring".to_string(), json!({}), Some(Duration::seconds(1))).await;
+        clock.advance(Duration::seconds(2));
+
+        // Expired, but only swept on the next tick
+        tokio::time::sleep(tokio::time::Duration::from_secs(8)).await;
+        assert_eq!(cache.stats().await.total_items, 1);
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        assert_eq!(cache.stats().await.total_items, 0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,86 @@
+use crate::utils::errors::{GovernanceError, Result};
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Integral floats up to 2^53 are exact, so they can be written as integers
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Serialize `content` to canonical JSON: object keys sorted at every level,
+/// no whitespace, and integral floats written as integers. Logically equal
+/// content always produces the same bytes, and so the same CID.
+pub fn to_canonical_vec<T: Serialize + ?Sized>(content: &T) -> Result<Vec<u8>> {
+    let value = serde_json::to_value(content).map_err(GovernanceError::Serialization)?;
+    let mut out = Vec::new();
+    write_value(&value, &mut out)?;
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => serde_json::to_writer(&mut *out, value)?,
+        Value::Number(number) => write_number(number, out)?,
+        Value::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            out.push(b'{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, key)?;
+                out.push(b':');
+                write_value(item, out)?;
+            }
+            out.push(b'}');
+        }
+    }
+    Ok(())
+}
+
+fn write_number(number: &Number, out: &mut Vec<u8>) -> Result<()> {
+    match number.as_f64() {
+        Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() <= MAX_SAFE_INTEGER => {
+            serde_json::to_writer(&mut *out, &(float as i64))?
+        }
+        _ => serde_json::to_writer(&mut *out, number)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_keys_sorted_at_every_level() {
+        // Parsed from text, so the input order is the one written here rather
+        // than whatever order a `json!` literal builds its map in
+        let raw = r#"{ "b": { "z": 1.0, "a": [ { "y": true, "x": null } ] }, "a": "text", "B": [] }"#;
+        let value: Value = serde_json::from_str(raw).unwrap();
+        let bytes = to_canonical_vec(&value).unwrap();
+        assert_eq!(bytes, br#"{"B":[],"a":"text","b":{"a":[{"x":null,"y":true}],"z":1}}"#.to_vec());
+    }
+
+    #[test]
+    fn test_map_order_and_integral_floats_do_not_matter() {
+        let first: HashMap<String, u64> = (0..32).map(|i| (format!("key{}", i), i)).collect();
+        let second: HashMap<String, u64> = (0..32).rev().map(|i| (format!("key{}", i), i)).collect();
+        assert_eq!(to_canonical_vec(&first).unwrap(), to_canonical_vec(&second).unwrap());
+
+        assert_eq!(to_canonical_vec(&json!({ "amount": 1.0 })).unwrap(), b"{\"amount\":1}");
+        assert_eq!(to_canonical_vec(&json!({ "amount": 1.5 })).unwrap(), b"{\"amount\":1.5}");
+    }
+}
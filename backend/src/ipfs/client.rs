@@ -1,43 +1,86 @@
-use crate::config::Config;
+use crate::config::{Config, PinPolicy, PinningConfig};
+use crate::ipfs::cache::IpfsCache;
+use crate::ipfs::canonical::to_canonical_vec;
 use crate::ipfs::content_types::*;
+use crate::ipfs::pinning::PinLeases;
+use crate::ipfs::probe::{check_endpoints, HttpProbe};
+use crate::ipfs::strict::Strict;
 use crate::utils::errors::{GovernanceError, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use futures::StreamExt;
 use ipfs_api_backend_hyper::{IpfsApi, IpfsClient as IpfsHttpClient, TryFromUri};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use lru::LruCache;
-use std::num::NonZeroUsize;
 
 #[derive(Clone)]
 pub struct IpfsClient {
-    client: IpfsHttpClient,
+    backend: IpfsBackend,
     gateway_url: String,
-    cache: Arc<RwLock<LruCache<String, CachedContent>>>,
+    compression_min_bytes: Option<usize>, // None when compression is disabled
+    cache: Arc<IpfsCache>,
+    pinning: PinningConfig,
+    pin_leases: PinLeases,
+    strict_content: bool, // reject typed content with fields its schema doesn't define
+    max_content_size: usize, // largest content read back, after decompression
 }
 
-#[derive(Debug, Clone)]
-struct CachedContent {
-    content: serde_json::Value,
-    cached_at: chrono::DateTime<chrono::Utc>,
-    ttl: Option<chrono::Duration>,
+/// gzip magic bytes. JSON can never start with them, so they double as the
+/// marker telling `get_json` to decompress.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Longest `is_available` waits to fetch content that isn't pinned locally
+pub const AVAILABILITY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// `node_version` of the in-memory store
+pub const MEMORY_NODE_VERSION: &str = "in-memory";
+
+/// Pin attempts before new content is reported as unpinned
+const PIN_VERIFY_ATTEMPTS: u32 = 3;
+
+/// Storage backend behind the client. An enum rather than a trait object keeps
+/// the client `Send` without boxing futures.
+#[derive(Clone)]
+enum IpfsBackend {
+    Http(IpfsHttpClient),
+    Memory(Arc<MemoryStore>),
 }
 
-impl CachedContent {
-    fn new(content: serde_json::Value, ttl: Option<chrono::Duration>) -> Self {
-        Self {
-            content,
-            cached_at: chrono::Utc::now(),
-            ttl,
-        }
+/// In-memory content store for tests and lightweight deployments
+#[derive(Default)]
+struct MemoryStore {
+    objects: std::sync::RwLock<HashMap<String, Vec<u8>>>,
+    pins: std::sync::RwLock<HashSet<String>>,
+    /// Acknowledge pins without keeping them, like a node that drops them
+    #[cfg(test)]
+    drop_pins: std::sync::atomic::AtomicBool,
+}
+
+impl MemoryStore {
+    /// Content-addressed like IPFS: identical bytes yield the same hash
+    fn hash_of(bytes: &[u8]) -> String {
+        let digest = hex::encode(Keccak256::digest(bytes));
+        format!("Qm{}", &digest[..44])
     }
 
-    fn is_expired(&self) -> bool {
-        if let Some(ttl) = self.ttl {
-            chrono::Utc::now() > self.cached_at + ttl
-        } else {
-            false // No TTL means never expires (immutable content)
+    fn add(&self, bytes: Vec<u8>) -> String {
+        let hash = Self::hash_of(&bytes);
+        self.objects.write().unwrap().insert(hash.clone(), bytes);
+        hash
+    }
+
+    fn cat(&self, hash: &str) -> Option<Vec<u8>> {
+        self.objects.read().unwrap().get(hash).cloned()
+    }
+
+    fn pin(&self, hash: &str) {
+        #[cfg(test)]
+        if self.drop_pins.load(std::sync::atomic::Ordering::SeqCst) {
+            return;
         }
+        self.pins.write().unwrap().insert(hash.to_string());
     }
 }
 
@@ -45,55 +88,146 @@ impl CachedContent {
 
 impl IpfsClient {
     pub async fn new(config: &Config) -> Result<Self> {
-        let client = IpfsHttpClient::from_str(&config.ipfs.api_url)
+        // Catch swapped or wrong URLs before the first confusing failure. Opt
+        // in, as it makes extra requests to both endpoints.
+        if config.ipfs.probe_endpoints {
+            let probe = HttpProbe::new(config.ipfs.auth.clone())?;
+            check_endpoints(&config.ipfs, &probe).await?;
+        }
+
+        let mut client = IpfsHttpClient::from_str(&config.ipfs.api_url)
             .map_err(|e| GovernanceError::ipfs(format!("Failed to create IPFS client: {}", e)))?;
-        
+        if let Some(auth) = &config.ipfs.auth {
+            client = client.with_credentials(&auth.username, &auth.password);
+        }
+
         // Test connection
         client
             .version()
             .await
             .map_err(|e| GovernanceError::ipfs(format!("Failed to connect to IPFS: {}", e)))?;
 
-        let cache = Arc::new(RwLock::new(LruCache::new(NonZeroUsize::new(1000).unwrap())));
+        Ok(Self::with_backend(IpfsBackend::Http(client), config))
+    }
 
-        Ok(Self {
-            client,
+    /// Client backed by an in-memory store instead of an IPFS node
+    pub fn in_memory(config: &Config) -> Self {
+        Self::with_backend(IpfsBackend::Memory(Arc::new(MemoryStore::default())), config)
+    }
+
+    fn with_backend(backend: IpfsBackend, config: &Config) -> Self {
+        let cache = Arc::new(IpfsCache::new(1000));
+
+        Self {
+            backend,
             gateway_url: config.ipfs.gateway_url.clone(),
+            compression_min_bytes: config.ipfs.compress.then_some(config.ipfs.compression_min_bytes),
             cache,
-        })
+            pinning: config.ipfs.pinning,
+            pin_leases: PinLeases::default(),
+            strict_content: config.ipfs.strict_content,
+            max_content_size: config.ipfs.max_content_size(),
+        }
+    }
+
+    /// Keep the expiry of TTL pins in `leases`, e.g. ones backed by a
+    /// persistent store so they survive restarts
+    pub fn with_pin_leases(mut self, leases: PinLeases) -> Self {
+        self.pin_leases = leases;
+        self
+    }
+
+    /// Version reported by the IPFS node, or `in-memory` for the in-memory store
+    pub async fn node_version(&self) -> Result<String> {
+        match &self.backend {
+            IpfsBackend::Http(client) => client
+                .version()
+                .await
+                .map(|response| response.version)
+                .map_err(|e| GovernanceError::ipfs(format!("Failed to read IPFS version: {}", e))),
+            IpfsBackend::Memory(_) => Ok(MEMORY_NODE_VERSION.to_string()),
+        }
     }
 
     pub async fn add_proposal_content(&self, content: &ProposalIPFSContent) -> Result<String> {
         validator::Validate::validate(content)
             .map_err(GovernanceError::Validation)?;
         
-        self.add_json(content).await
+        self.add_json_pinned(content, self.pinning.proposals).await
     }
 
     pub async fn get_proposal_content(&self, hash: &str) -> Result<ProposalIPFSContent> {
-        self.get_json(hash).await
+        self.get_typed_content(hash, "proposal").await
     }
 
     pub async fn add_vote_content(&self, content: &VoteIPFSContent) -> Result<String> {
         validator::Validate::validate(content)
             .map_err(GovernanceError::Validation)?;
         
-        self.add_json(content).await
+        self.add_json_pinned(content, self.pinning.votes).await
     }
 
     pub async fn get_vote_content(&self, hash: &str) -> Result<VoteIPFSContent> {
-        self.get_json(hash).await
+        self.get_typed_content(hash, "vote").await
     }
 
     pub async fn add_user_profile(&self, content: &UserProfileIPFS) -> Result<String> {
         validator::Validate::validate(content)
             .map_err(GovernanceError::Validation)?;
         
-        self.add_json(content).await
+        self.add_json_pinned(content, self.pinning.profiles).await
     }
 
     pub async fn get_user_profile(&self, hash: &str) -> Result<UserProfileIPFS> {
-        self.get_json(hash).await
+        self.get_typed_content(hash, "userProfile").await
+    }
+
+    /// Fetch JSON content, checking its `content_type` discriminator before
+    /// deserializing so a CID of the wrong kind gets a clear error
+    async fn get_typed_content<T>(&self, hash: &str, expected: &'static str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de> + Serialize + Send,
+    {
+        let value: serde_json::Value = self.get_json(hash).await?;
+        let found = value.get("content_type").and_then(|v| v.as_str()).unwrap_or("untyped");
+        if found != expected {
+            return Err(GovernanceError::ContentTypeMismatch {
+                hash: hash.to_string(),
+                expected,
+                found: found.to_string(),
+            });
+        }
+
+        self.decode_content(value)
+    }
+
+    /// Deserialize governance content, rejecting unknown fields in strict mode
+    fn decode_content<T>(&self, value: serde_json::Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de> + Serialize,
+    {
+        let content = match self.strict_content {
+            true => serde_json::from_value::<Strict<T>>(value).map(Strict::into_inner),
+            false => serde_json::from_value(value),
+        };
+        content.map_err(GovernanceError::Serialization)
+    }
+
+    /// Fetch any JSON content and type it by its `content_type`. Results
+    /// come from, and land in, the content cache like every other read.
+    pub async fn resolve_content(&self, hash: &str) -> Result<ResolvedContent> {
+        if !crate::utils::helpers::validate_ipfs_hash(hash) {
+            return Err(GovernanceError::invalid_request(format!("Invalid IPFS hash: {}", hash)));
+        }
+
+        let value: serde_json::Value = self.get_json(hash).await?;
+        let resolved = match value.get("content_type").and_then(|v| v.as_str()) {
+            Some("proposal") => ResolvedContent::Proposal(self.decode_content(value)?),
+            Some("vote") => ResolvedContent::Vote(self.decode_content(value)?),
+            Some("userProfile") => ResolvedContent::Profile(self.decode_content(value)?),
+            _ => ResolvedContent::Raw(value),
+        };
+        Ok(resolved)
     }
 
     pub async fn get_gateway_url(&self, hash: &str) -> String {
@@ -104,42 +238,126 @@ impl IpfsClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let mut cache = self.cache.write().await;
-        if let Some(cached) = cache.get(hash) {
-            if !cached.is_expired() {
-                return serde_json::from_value(cached.content.clone()).ok();
-            }
-        }
-        None
+        let content = self.cache.get(hash).await?;
+        serde_json::from_value(content).ok()
+    }
+
+    /// Whether unexpired content for `hash` is in the cache
+    pub async fn is_cached(&self, hash: &str) -> bool {
+        self.cache.contains(hash).await
+    }
+
+    /// Content cache, e.g. to run its cleanup task on
+    pub fn cache(&self) -> Arc<IpfsCache> {
+        self.cache.clone()
     }
 
     async fn store_in_cache(&self, hash: &str, content: serde_json::Value, ttl: Option<chrono::Duration>) {
-        let mut cache = self.cache.write().await;
-        cache.put(hash.to_string(), CachedContent::new(content, ttl));
+        self.cache.put(hash.to_string(), content, ttl).await;
     }
 
+    /// Upload `content` as JSON, pinned permanently
     pub async fn add_json<T>(&self, content: &T) -> Result<String>
     where
         T: Serialize + Send + Sync,
     {
-        let json_bytes = serde_json::to_vec(content)
-            .map_err(GovernanceError::Serialization)?;
+        self.add_json_pinned(content, PinPolicy::Permanent).await
+    }
 
-        let response = self
-            .client
-            .add(std::io::Cursor::new(json_bytes))
-            .await
-            .map_err(|e| GovernanceError::ipfs(format!("Failed to add content to IPFS: {}", e)))?;
+    /// The CID `content` would get if added now, without storing or pinning it
+    pub async fn content_hash<T>(&self, content: &T) -> Result<String>
+    where
+        T: Serialize + Send + Sync,
+    {
+        let json_bytes = self.encode_json(content)?;
+        match &self.backend {
+            IpfsBackend::Http(client) => {
+                let options = ipfs_api_backend_hyper::request::Add {
+                    only_hash: Some(true),
+                    ..Default::default()
+                };
+                Ok(client
+                    .add_with_options(std::io::Cursor::new(json_bytes), options)
+                    .await
+                    .map_err(|e| GovernanceError::ipfs(format!("Failed to hash content on IPFS: {}", e)))?
+                    .hash)
+            }
+            IpfsBackend::Memory(_) => Ok(MemoryStore::hash_of(&json_bytes)),
+        }
+    }
+
+    /// The bytes stored for `content`: canonical JSON, so logically identical
+    /// content dedups to one CID, gzipped when large enough
+    fn encode_json<T: Serialize>(&self, content: &T) -> Result<Vec<u8>> {
+        let json_bytes = to_canonical_vec(content)?;
+        Ok(match self.compression_min_bytes {
+            Some(min_bytes) if json_bytes.len() >= min_bytes => gzip(&json_bytes)?,
+            _ => json_bytes,
+        })
+    }
 
-        let hash = response.hash;
+    async fn add_json_pinned<T>(&self, content: &T, policy: PinPolicy) -> Result<String>
+    where
+        T: Serialize + Send + Sync,
+    {
+        let json_bytes = self.encode_json(content)?;
+
+        let hash = match &self.backend {
+            IpfsBackend::Http(client) => {
+                client
+                    .add(std::io::Cursor::new(json_bytes))
+                    .await
+                    .map_err(|e| GovernanceError::ipfs(format!("Failed to add content to IPFS: {}", e)))?
+                    .hash
+            }
+            IpfsBackend::Memory(store) => store.add(json_bytes),
+        };
         
+        // Content pinned with no lease is already permanent; a TTL must not cut that short
+        let permanent = match policy {
+            PinPolicy::Permanent => true,
+            PinPolicy::Ttl { .. } => self.pin_leases.expires_at(&hash)?.is_none() && self.is_pinned(&hash).await?,
+        };
+
         // Pin the content to ensure it stays available
-        self.pin_content(&hash).await?;
+        self.pin_verified(&hash).await?;
+        match policy {
+            PinPolicy::Ttl { ttl } if !permanent => {
+                self.pin_leases.extend(&hash, chrono::Duration::seconds(ttl as i64))?;
+            }
+            _ => self.pin_leases.clear(&hash)?,
+        }
         
         tracing::info!("Added content to IPFS: {}", hash);
         Ok(hash)
     }
 
+    /// Pin `hash` and confirm through the pin list that the pin took, pinning
+    /// again if it didn't. Nodes can acknowledge a pin they never keep, and
+    /// unpinned content may be garbage collected.
+    async fn pin_verified(&self, hash: &str) -> Result<()> {
+        for attempt in 1..=PIN_VERIFY_ATTEMPTS {
+            self.pin_content(hash).await?;
+            if self.is_pinned(hash).await? {
+                return Ok(());
+            }
+            tracing::warn!("Pin of {} not found after attempt {}", hash, attempt);
+        }
+
+        Err(GovernanceError::ipfs(format!(
+            "Content {} is still unpinned after {} attempts",
+            hash, PIN_VERIFY_ATTEMPTS
+        )))
+    }
+
+    /// Make the in-memory store acknowledge pins without keeping them
+    #[cfg(test)]
+    pub(crate) fn drop_pins(&self) {
+        if let IpfsBackend::Memory(store) = &self.backend {
+            store.drop_pins.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
     pub async fn get_json<T>(&self, hash: &str) -> Result<T>
     where
         T: for<'de> Deserialize<'de> + Send,
@@ -149,16 +367,13 @@ impl IpfsClient {
             return Ok(cached);
         }
 
-        let response = self
-            .client
-            .cat(hash);
-
-        let mut bytes = Vec::new();
-        let mut stream = response;
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| GovernanceError::ipfs(format!("Failed to read IPFS chunk: {}", e)))?;
-            bytes.extend_from_slice(&chunk);
-        }
+        let bytes = self.cat_bytes(hash).await?;
+        // Legacy and small content is plain JSON
+        let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+            gunzip(&bytes, self.max_content_size)?
+        } else {
+            bytes
+        };
         let json_value: serde_json::Value = serde_json::from_slice(&bytes)
             .map_err(GovernanceError::Serialization)?;
         
@@ -172,31 +387,213 @@ impl IpfsClient {
         Ok(content)
     }
 
+    /// Raw content at `hash`, e.g. a proposal attachment, exactly as stored
+    pub async fn get_bytes(&self, hash: &str) -> Result<Vec<u8>> {
+        self.cat_bytes(hash).await
+    }
+
+    async fn cat_bytes(&self, hash: &str) -> Result<Vec<u8>> {
+        match &self.backend {
+            IpfsBackend::Http(client) => {
+                let mut bytes = Vec::new();
+                let mut stream = client.cat(hash);
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk.map_err(|e| GovernanceError::ipfs(format!("Failed to read IPFS chunk: {}", e)))?;
+                    if bytes.len() + chunk.len() > self.max_content_size {
+                        return Err(content_too_large(self.max_content_size));
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+                Ok(bytes)
+            }
+            IpfsBackend::Memory(store) => {
+                let bytes = store
+                    .cat(hash)
+                    .ok_or_else(|| GovernanceError::ipfs(format!("Content not found: {}", hash)))?;
+                if bytes.len() > self.max_content_size {
+                    return Err(content_too_large(self.max_content_size));
+                }
+                Ok(bytes)
+            }
+        }
+    }
+
     pub async fn pin_content(&self, hash: &str) -> Result<()> {
-        self.client
-            .pin_add(hash, true)
-            .await
-            .map_err(|e| GovernanceError::ipfs(format!("Failed to pin content: {}", e)))?;
+        match &self.backend {
+            IpfsBackend::Http(client) => {
+                client
+                    .pin_add(hash, true)
+                    .await
+                    .map_err(|e| GovernanceError::ipfs(format!("Failed to pin content: {}", e)))?;
+            }
+            IpfsBackend::Memory(store) => {
+                if store.cat(hash).is_none() {
+                    return Err(GovernanceError::ipfs(format!("Failed to pin content: {} not found", hash)));
+                }
+                store.pin(hash);
+            }
+        }
         
         tracing::debug!("Pinned content: {}", hash);
         Ok(())
     }
 
+    pub async fn is_pinned(&self, hash: &str) -> Result<bool> {
+        match &self.backend {
+            IpfsBackend::Http(client) => match client.pin_ls(Some(hash), None).await {
+                Ok(response) => Ok(response.keys.contains_key(hash)),
+                // The node reports unpinned content as an API error
+                Err(e) if e.to_string().contains("not pinned") => Ok(false),
+                Err(e) => Err(GovernanceError::ipfs(format!("Failed to list pins: {}", e))),
+            },
+            IpfsBackend::Memory(store) => Ok(store.pins.read().unwrap().contains(hash)),
+        }
+    }
+
+    /// Whether content at `hash` is pinned on the node or can be fetched
+    /// within `AVAILABILITY_TIMEOUT`
+    pub async fn is_available(&self, hash: &str) -> bool {
+        if matches!(self.is_pinned(hash).await, Ok(true)) {
+            return true;
+        }
+        matches!(tokio::time::timeout(AVAILABILITY_TIMEOUT, self.cat_bytes(hash)).await, Ok(Ok(_)))
+    }
+
     pub async fn unpin_content(&self, hash: &str) -> Result<()> {
-        self.client
-            .pin_rm(hash, true)
-            .await
-            .map_err(|e| GovernanceError::ipfs(format!("Failed to unpin content: {}", e)))?;
+        match &self.backend {
+            IpfsBackend::Http(client) => {
+                client
+                    .pin_rm(hash, true)
+                    .await
+                    .map_err(|e| GovernanceError::ipfs(format!("Failed to unpin content: {}", e)))?;
+            }
+            IpfsBackend::Memory(store) => {
+                store.pins.write().unwrap().remove(hash);
+            }
+        }
+        self.pin_leases.clear(hash)?;
         
         tracing::debug!("Unpinned content: {}", hash);
         Ok(())
     }
+
+    /// When the pin on `hash` lapses, if it was pinned with a TTL
+    pub fn pin_expires_at(&self, hash: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        self.pin_leases.expires_at(hash)
+    }
+
+    /// Unpin content whose pin TTL has passed, so the node can garbage
+    /// collect it. Returns how many were unpinned; failures are retried on
+    /// the next sweep.
+    pub async fn sweep_expired_pins(&self) -> Result<usize> {
+        let mut unpinned = 0;
+        for hash in self.pin_leases.expired()? {
+            match self.unpin_content(&hash).await {
+                Ok(()) => unpinned += 1,
+                Err(e) => tracing::warn!("Failed to unpin expired content {}: {}", hash, e),
+            }
+        }
+        Ok(unpinned)
+    }
+
+    /// Start the background sweep of expired pins, every `period`
+    pub fn start_pin_sweep_task(&self, period: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match client.sweep_expired_pins().await {
+                    Ok(0) => {}
+                    Ok(unpinned) => tracing::info!("Unpinned {} IPFS objects past their pin TTL", unpinned),
+                    Err(e) => tracing::warn!("Failed to sweep expired IPFS pins: {}", e),
+                }
+            }
+        })
+    }
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| GovernanceError::ipfs(format!("Failed to compress content: {}", e)))
+}
+
+/// Decompress at most `max_size` bytes, refusing content that inflates
+/// past it rather than expanding it in full
+fn gunzip(bytes: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes)
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| GovernanceError::ipfs(format!("Failed to decompress content: {}", e)))?;
+    if decompressed.len() > max_size {
+        return Err(content_too_large(max_size));
+    }
+    Ok(decompressed)
+}
+
+fn content_too_large(max_size: usize) -> GovernanceError {
+    GovernanceError::ipfs(format!("Content exceeds the {} byte limit", max_size))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::Config;
+    use crate::storage::kv::MemoryKvStore;
+    use crate::utils::clock::{Clock, MockClock, SharedClock};
+
+    /// One-shot IPFS API stand-in: answers `/api/v0/version` and hands back
+    /// the raw request head so tests can inspect outgoing headers
+    async fn capture_version_request(config: &mut Config) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        config.ipfs.api_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+
+            let body = r#"{"Version":"0.20.0","Commit":"","Repo":"15","System":"","Golang":""}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        IpfsClient::new(config).await.unwrap();
+        server.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_credentials_attached_to_api_requests() {
+        let mut config = Config::default();
+        config.ipfs.auth = Some(crate::config::IpfsAuth {
+            username: "project".to_string(),
+            password: "secret".to_string(),
+        });
+
+        let request = capture_version_request(&mut config).await;
+        assert!(request.contains("/api/v0/version"));
+        assert!(request.to_lowercase().contains("authorization: basic "));
+        assert!(request.contains("cHJvamVjdDpzZWNyZXQ=")); // base64("project:secret")
+    }
+
+    #[tokio::test]
+    async fn test_no_credentials_sends_no_authorization_header() {
+        let mut config = Config::default();
+        let request = capture_version_request(&mut config).await;
+        assert!(request.contains("/api/v0/version"));
+        assert!(!request.to_lowercase().contains("authorization:"));
+    }
 
     #[tokio::test]
     async fn test_ipfs_operations() {
@@ -221,4 +618,256 @@ mod tests {
         let retrieved: serde_json::Value = client.get_json(&hash).await.unwrap();
         assert_eq!(retrieved, test_content);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_in_memory_operations() {
+        let client = IpfsClient::in_memory(&Config::default());
+        let test_content = serde_json::json!({"test": "data"});
+
+        let hash = client.add_json(&test_content).await.unwrap();
+        assert!(crate::utils::helpers::validate_ipfs_hash(&hash));
+        assert_eq!(hash, client.add_json(&test_content).await.unwrap());
+
+        let retrieved: serde_json::Value = client.get_json(&hash).await.unwrap();
+        assert_eq!(retrieved, test_content);
+
+        let missing = "Qm".to_string() + &"0".repeat(44);
+        assert!(client.get_json::<serde_json::Value>(&missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_add_fails_when_pin_does_not_take() {
+        let client = IpfsClient::in_memory(&Config::default());
+        client.drop_pins();
+
+        let err = client.add_json(&serde_json::json!({"test": "data"})).await.unwrap_err();
+        assert!(err.to_string().contains("unpinned after 3 attempts"), "{}", err);
+    }
+
+    fn compressing_client() -> IpfsClient {
+        let mut config = Config::default();
+        config.ipfs.compress = true;
+        config.ipfs.compression_min_bytes = 1024;
+        IpfsClient::in_memory(&config)
+    }
+
+    #[tokio::test]
+    async fn test_large_content_round_trips_compressed() {
+        let client = compressing_client();
+        let content = ProposalIPFSContent {
+            title: "Large Proposal".to_string(),
+            description: "Lorem ipsum dolor sit amet. ".repeat(500),
+            metadata: ProposalMetadata::default(),
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+
+        let hash = client.add_proposal_content(&content).await.unwrap();
+        let stored = client.cat_bytes(&hash).await.unwrap();
+        assert!(stored.starts_with(&GZIP_MAGIC));
+        assert!(stored.len() < content.description.len());
+
+        let retrieved = client.get_proposal_content(&hash).await.unwrap();
+        assert_eq!(retrieved.description, content.description);
+    }
+
+    #[tokio::test]
+    async fn test_small_content_stored_uncompressed() {
+        let client = compressing_client();
+        let test_content = serde_json::json!({"test": "data"});
+
+        let hash = client.add_json(&test_content).await.unwrap();
+        let stored = client.cat_bytes(&hash).await.unwrap();
+        assert_eq!(stored, serde_json::to_vec(&test_content).unwrap());
+
+        let retrieved: serde_json::Value = client.get_json(&hash).await.unwrap();
+        assert_eq!(retrieved, test_content);
+    }
+
+    #[test]
+    fn test_decompression_stops_at_size_limit() {
+        // Highly compressible, so far smaller stored than decompressed
+        let inflated = vec![b' '; 1024 * 1024];
+        let compressed = gzip(&inflated).unwrap();
+        assert!(compressed.len() < 4096);
+
+        let err = gunzip(&compressed, 64 * 1024).unwrap_err();
+        assert!(err.to_string().contains("exceeds the 65536 byte limit"), "{}", err);
+        assert_eq!(gunzip(&compressed, inflated.len()).unwrap(), inflated);
+    }
+
+    #[tokio::test]
+    async fn test_equal_proposals_share_cid() {
+        let client = IpfsClient::in_memory(&Config::default());
+        let created_at = chrono::Utc::now();
+        let proposal = serde_json::to_value(ProposalIPFSContent {
+            title: "Stable CID".to_string(),
+            description: "Identical content should dedup.".to_string(),
+            metadata: ProposalMetadata::default(),
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at,
+        })
+        .unwrap();
+
+        // The same proposal assembled field by field in reverse order
+        let mut reordered = serde_json::Map::new();
+        for (key, value) in proposal.as_object().unwrap().iter().rev() {
+            reordered.insert(key.clone(), value.clone());
+        }
+        let reordered = serde_json::Value::Object(reordered);
+
+        assert_eq!(to_canonical_vec(&proposal).unwrap(), to_canonical_vec(&reordered).unwrap());
+        let hash = client.add_json(&proposal).await.unwrap();
+        assert_eq!(hash, client.add_json(&reordered).await.unwrap());
+        let retrieved: ProposalIPFSContent = client.get_json(&hash).await.unwrap();
+        assert_eq!(retrieved.created_at, created_at);
+    }
+
+    #[tokio::test]
+    async fn test_vote_cid_rejected_as_proposal() {
+        let client = IpfsClient::in_memory(&Config::default());
+        let vote = VoteIPFSContent {
+            choice: VoteChoice::Yes,
+            comment: None,
+            reasoning: None,
+            metadata: VoteMetadata {
+                voting_power: "1000".to_string(),
+                delegated_votes: None,
+                timestamp: chrono::Utc::now(),
+                version: "1.0".to_string(),
+            },
+            content_type: "vote".to_string(),
+        };
+        let hash = client.add_vote_content(&vote).await.unwrap();
+
+        match client.get_proposal_content(&hash).await {
+            Err(GovernanceError::ContentTypeMismatch { expected, found, .. }) => {
+                assert_eq!(expected, "proposal");
+                assert_eq!(found, "vote");
+            }
+            other => panic!("Expected content type mismatch, got {:?}", other.map(|c| c.title)),
+        }
+        assert!(client.get_vote_content(&hash).await.is_ok());
+
+        let arbitrary = client.add_json(&serde_json::json!({"test": "data"})).await.unwrap();
+        let error = client.get_proposal_content(&arbitrary).await.unwrap_err();
+        assert_eq!(error.to_string(), format!("Expected proposal content at {}, found untyped", arbitrary));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_invalid_cid() {
+        let client = IpfsClient::in_memory(&Config::default());
+        assert!(matches!(
+            client.resolve_content("not-a-cid").await,
+            Err(GovernanceError::InvalidRequest(_))
+        ));
+    }
+
+    fn pinning_client(clock: &MockClock) -> IpfsClient {
+        let mut config = Config::default();
+        config.ipfs.pinning.votes = PinPolicy::Ttl { ttl: 30 * 86_400 };
+        let clock: SharedClock = Arc::new(clock.clone());
+        let leases = PinLeases::new(Arc::new(MemoryKvStore::new().with_clock(clock.clone()))).with_clock(clock);
+        IpfsClient::in_memory(&config).with_pin_leases(leases)
+    }
+
+    fn vote_with_reasoning(reasoning: &str) -> VoteIPFSContent {
+        VoteIPFSContent {
+            choice: VoteChoice::No,
+            comment: None,
+            reasoning: Some(reasoning.to_string()),
+            metadata: VoteMetadata {
+                voting_power: "1000".to_string(),
+                delegated_votes: None,
+                timestamp: chrono::Utc::now(),
+                version: "1.0".to_string(),
+            },
+            content_type: "vote".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vote_pins_expire_while_proposals_stay_pinned() {
+        let clock = MockClock::default();
+        let client = pinning_client(&clock);
+        let proposal = client
+            .add_proposal_content(&ProposalIPFSContent {
+                title: "Treasury top-up".to_string(),
+                description: "Move funds to the grants multisig".to_string(),
+                metadata: ProposalMetadata::default(),
+                version: "1.0".to_string(),
+                content_type: "proposal".to_string(),
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let vote = client.add_vote_content(&vote_with_reasoning("Too early")).await.unwrap();
+
+        assert!(client.is_pinned(&proposal).await.unwrap());
+        assert_eq!(client.pin_expires_at(&proposal).unwrap(), None);
+        assert!(client.is_pinned(&vote).await.unwrap());
+        assert_eq!(client.pin_expires_at(&vote).unwrap(), Some(clock.now() + chrono::Duration::days(30)));
+
+        clock.advance(chrono::Duration::days(30) - chrono::Duration::seconds(1));
+        assert_eq!(client.sweep_expired_pins().await.unwrap(), 0);
+        assert!(client.is_pinned(&vote).await.unwrap());
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(client.sweep_expired_pins().await.unwrap(), 1);
+        assert!(!client.is_pinned(&vote).await.unwrap());
+        assert_eq!(client.pin_expires_at(&vote).unwrap(), None);
+        assert!(client.is_pinned(&proposal).await.unwrap());
+        assert_eq!(client.sweep_expired_pins().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_pin_never_shortens_a_permanent_one() {
+        let clock = MockClock::default();
+        let client = pinning_client(&clock);
+        let vote = vote_with_reasoning("Same bytes, uploaded twice");
+
+        let hash = client.add_json(&vote).await.unwrap();
+        assert_eq!(client.add_vote_content(&vote).await.unwrap(), hash);
+        assert_eq!(client.pin_expires_at(&hash).unwrap(), None);
+
+        clock.advance(chrono::Duration::days(31));
+        assert_eq!(client.sweep_expired_pins().await.unwrap(), 0);
+        assert!(client.is_pinned(&hash).await.unwrap());
+    }
+
+
+    #[tokio::test]
+    async fn test_strict_mode_rejects_unknown_fields() {
+        let mut proposal = serde_json::to_value(ProposalIPFSContent {
+            title: "Schema drift".to_string(),
+            description: "Carries a misspelled metadata field".to_string(),
+            metadata: ProposalMetadata::default(),
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        })
+        .unwrap();
+        proposal["metadata"]["hide_votes_untill_close"] = serde_json::json!(true);
+
+        let lenient = IpfsClient::in_memory(&Config::default());
+        let hash = lenient.add_json(&proposal).await.unwrap();
+        let content = lenient.get_proposal_content(&hash).await.unwrap();
+        assert!(!content.metadata.hide_votes_until_close);
+
+        let mut config = Config::default();
+        config.ipfs.strict_content = true;
+        let strict = IpfsClient::in_memory(&config);
+        let hash = strict.add_json(&proposal).await.unwrap();
+        let error = strict.get_proposal_content(&hash).await.unwrap_err();
+        assert!(error.to_string().contains("metadata.hide_votes_untill_close"), "{}", error);
+        assert!(strict.resolve_content(&hash).await.is_err());
+
+        // Content matching the schema still reads in strict mode
+        proposal["metadata"].as_object_mut().unwrap().remove("hide_votes_untill_close");
+        let hash = strict.add_json(&proposal).await.unwrap();
+        assert_eq!(strict.get_proposal_content(&hash).await.unwrap().title, "Schema drift");
+    }
+
+}
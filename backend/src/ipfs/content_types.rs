@@ -23,9 +23,17 @@ pub struct ProposalMetadata {
     pub attachments: Vec<String>, // IPFS hashes
     pub proposal_type: ProposalType,
     pub execution_data: Option<ExecutionData>,
+    #[serde(default)]
+    pub options: Vec<String>, // Labels for option-based proposal types
+    #[serde(default)]
+    pub supersedes: Option<u64>, // Earlier proposal this one replaces
+    #[serde(default)]
+    pub hide_votes_until_close: bool, // Only aggregates are public while voting is open
+    #[serde(default)]
+    pub min_vote_power: Option<u64>, // Voters with less effective power are turned away
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProposalType {
     #[serde(rename = "simple")]
     Simple,
@@ -35,6 +43,57 @@ pub enum ProposalType {
     RankedChoice,
     #[serde(rename = "liquid")]
     LiquidDemocracy,
+    #[serde(rename = "multiple_choice")]
+    MultipleChoice,
+    /// Voters split their power across options, e.g. budget allocation
+    #[serde(rename = "weighted")]
+    Weighted,
+}
+
+impl ProposalType {
+    pub const ALL: [ProposalType; 6] = [
+        ProposalType::Simple,
+        ProposalType::Quadratic,
+        ProposalType::RankedChoice,
+        ProposalType::LiquidDemocracy,
+        ProposalType::MultipleChoice,
+        ProposalType::Weighted,
+    ];
+
+    /// Whether votes select among `metadata.options` rather than yes/no/abstain
+    pub fn uses_options(&self) -> bool {
+        matches!(
+            self,
+            ProposalType::RankedChoice | ProposalType::MultipleChoice | ProposalType::Weighted
+        )
+    }
+}
+
+impl From<u8> for ProposalType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ProposalType::Simple,
+            1 => ProposalType::Quadratic,
+            2 => ProposalType::RankedChoice,
+            3 => ProposalType::LiquidDemocracy,
+            4 => ProposalType::MultipleChoice,
+            5 => ProposalType::Weighted,
+            _ => ProposalType::Simple,
+        }
+    }
+}
+
+impl From<ProposalType> for u8 {
+    fn from(proposal_type: ProposalType) -> Self {
+        match proposal_type {
+            ProposalType::Simple => 0,
+            ProposalType::Quadratic => 1,
+            ProposalType::RankedChoice => 2,
+            ProposalType::LiquidDemocracy => 3,
+            ProposalType::MultipleChoice => 4,
+            ProposalType::Weighted => 5,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +139,18 @@ impl From<u8> for VoteChoice {
     }
 }
 
+impl VoteChoice {
+    /// Strict counterpart to `From<u8>`: `None` for anything but 0, 1 or 2
+    pub fn from_choice(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(VoteChoice::No),
+            1 => Some(VoteChoice::Yes),
+            2 => Some(VoteChoice::Abstain),
+            _ => None,
+        }
+    }
+}
+
 impl From<VoteChoice> for u8 {
     fn from(choice: VoteChoice) -> Self {
         match choice {
@@ -120,6 +191,17 @@ pub struct UserProfileIPFS {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Content at an arbitrary CID, typed by its `content_type` discriminator.
+/// Anything unrecognized is passed through as raw JSON.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "content", rename_all = "snake_case")]
+pub enum ResolvedContent {
+    Proposal(ProposalIPFSContent),
+    Vote(VoteIPFSContent),
+    Profile(UserProfileIPFS),
+    Raw(serde_json::Value),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SocialLinks {
     pub twitter: Option<String>,
@@ -168,6 +250,10 @@ impl Default for ProposalMetadata {
             attachments: vec![],
             proposal_type: ProposalType::Simple,
             execution_data: None,
+            options: vec![],
+            supersedes: None,
+            hide_votes_until_close: false,
+            min_vote_power: None,
         }
     }
 }
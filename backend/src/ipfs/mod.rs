@@ -1,4 +1,9 @@
+pub mod canonical;
 pub mod client;
 pub mod content_types;
 pub mod cache;
-pub mod validation;
\ No newline at end of file
+pub mod pinning;
+pub mod probe;
+pub mod strict;
+pub mod validation;
+pub mod warmup;
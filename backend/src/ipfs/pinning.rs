@@ -0,0 +1,348 @@
+use crate::indexer::content_indexer::{ContentIndexer, IndexedProposal};
+use crate::ipfs::client::IpfsClient;
+use crate::storage::kv::{MemoryKvStore, SharedKvStore};
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::utils::errors::{GovernanceError, Result};
+use crate::utils::retry::{retry_with_backoff, RetryPolicy};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Period of the background sweep unpinning content past its pin TTL
+pub const PIN_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Where an interrupted `repin_all` run picks up again. Persist it between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepinCheckpoint {
+    /// Highest proposal id whose content has been processed
+    pub last_proposal_id: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepinOptions {
+    /// Proposals processed in parallel
+    pub concurrency: usize,
+    /// Attempts per hash before it is reported as failed
+    pub max_attempts: u32,
+    /// Stop after this many proposals, leaving the rest for a resumed run
+    pub max_proposals: Option<usize>,
+}
+
+impl Default for RepinOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            max_attempts: 3,
+            max_proposals: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepinProgress {
+    pub total_proposals: usize,
+    pub proposals_processed: usize,
+    pub pinned: usize,
+    pub already_pinned: usize,
+    pub failed: Vec<RepinFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepinFailure {
+    pub proposal_id: u64,
+    pub hash: String,
+    pub error: String,
+}
+
+#[derive(Default)]
+struct ProposalRepin {
+    pinned: usize,
+    already_pinned: usize,
+    failed: Vec<RepinFailure>,
+}
+
+/// Re-pin every indexed proposal's content and attachments, e.g. after moving
+/// to a new IPFS node. Content that is already pinned is skipped. Proposals are
+/// processed in id order and `checkpoint` advances as each one completes, so a
+/// run that stops early resumes where it left off.
+pub async fn repin_all<F>(
+    ipfs: &IpfsClient,
+    indexer: &ContentIndexer,
+    checkpoint: &mut RepinCheckpoint,
+    options: &RepinOptions,
+    mut on_progress: F,
+) -> RepinProgress
+where
+    F: FnMut(&RepinProgress),
+{
+    let remaining: Vec<IndexedProposal> = indexer
+        .proposals()
+        .into_iter()
+        .filter(|p| match checkpoint.last_proposal_id {
+            Some(last) => p.id > last,
+            None => true,
+        })
+        .take(options.max_proposals.unwrap_or(usize::MAX))
+        .collect();
+
+    let mut progress = RepinProgress {
+        total_proposals: remaining.len(),
+        ..Default::default()
+    };
+
+    // `buffered` yields in input order, keeping the checkpoint monotonic
+    let mut results = stream::iter(remaining)
+        .map(|proposal| async move {
+            let result = repin_proposal(ipfs, &proposal, options.max_attempts).await;
+            (proposal.id, result)
+        })
+        .buffered(options.concurrency.max(1));
+
+    while let Some((proposal_id, result)) = results.next().await {
+        progress.proposals_processed += 1;
+        progress.pinned += result.pinned;
+        progress.already_pinned += result.already_pinned;
+        progress.failed.extend(result.failed);
+        checkpoint.last_proposal_id = Some(proposal_id);
+
+        on_progress(&progress);
+    }
+
+    tracing::info!(
+        "Re-pin finished: {} pinned, {} already pinned, {} failed",
+        progress.pinned,
+        progress.already_pinned,
+        progress.failed.len()
+    );
+    progress
+}
+
+async fn repin_proposal(ipfs: &IpfsClient, proposal: &IndexedProposal, max_attempts: u32) -> ProposalRepin {
+    let mut result = ProposalRepin::default();
+
+    // Content must be pinned before its attachments can be listed
+    if !ensure_pinned(ipfs, proposal.id, &proposal.ipfs_hash, max_attempts, &mut result).await {
+        return result;
+    }
+
+    match ipfs.get_proposal_content(&proposal.ipfs_hash).await {
+        Ok(content) => {
+            for attachment in &content.metadata.attachments {
+                ensure_pinned(ipfs, proposal.id, attachment, max_attempts, &mut result).await;
+            }
+        }
+        Err(e) => result.failed.push(RepinFailure {
+            proposal_id: proposal.id,
+            hash: proposal.ipfs_hash.clone(),
+            error: format!("Failed to read attachments: {}", e),
+        }),
+    }
+
+    result
+}
+
+/// Pin `hash` unless already pinned, retrying transient failures
+async fn ensure_pinned(
+    ipfs: &IpfsClient,
+    proposal_id: u64,
+    hash: &str,
+    max_attempts: u32,
+    result: &mut ProposalRepin,
+) -> bool {
+    if let Ok(true) = ipfs.is_pinned(hash).await {
+        result.already_pinned += 1;
+        return true;
+    }
+
+    let policy = RetryPolicy::default().with_max_attempts(max_attempts);
+    match retry_with_backoff(&policy, || ipfs.pin_content(hash), GovernanceError::is_retryable).await {
+        Ok(()) => {
+            result.pinned += 1;
+            true
+        }
+        Err(e) => {
+            result.failed.push(RepinFailure {
+                proposal_id,
+                hash: hash.to_string(),
+                error: e.to_string(),
+            });
+            false
+        }
+    }
+}
+
+/// Expiry of content pinned under `PinPolicy::Ttl`, kept in a `KvStore`
+/// under `pin/<hash>`. Content without a lease is pinned permanently.
+#[derive(Clone)]
+pub struct PinLeases {
+    store: SharedKvStore,
+    clock: SharedClock,
+}
+
+impl PinLeases {
+    pub fn new(store: SharedKvStore) -> Self {
+        Self {
+            store,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Keep `hash` pinned for at least `ttl` from now. An existing lease is
+    /// only ever extended, as other uploads of the same content rely on it.
+    pub fn extend(&self, hash: &str, ttl: Duration) -> Result<DateTime<Utc>> {
+        let expires_at = self.clock.now() + ttl;
+        let expires_at = match self.expires_at(hash)? {
+            Some(current) if current > expires_at => current,
+            _ => expires_at,
+        };
+        // No TTL on the entry itself: the sweep has to find it once it lapses
+        self.store.put(&key(hash), expires_at.timestamp_millis().to_string().as_bytes(), None)?;
+        Ok(expires_at)
+    }
+
+    /// Drop the lease on `hash`, making its pin permanent
+    pub fn clear(&self, hash: &str) -> Result<()> {
+        self.store.delete(&key(hash))?;
+        Ok(())
+    }
+
+    /// When the pin on `hash` lapses; `None` for permanent pins
+    pub fn expires_at(&self, hash: &str) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.store.get(&key(hash))?.as_deref().and_then(decode))
+    }
+
+    /// Hashes whose lease has lapsed, due to be unpinned
+    pub fn expired(&self) -> Result<Vec<String>> {
+        let now = self.clock.now();
+        Ok(self
+            .store
+            .list("pin/")?
+            .into_iter()
+            .filter(|(_, value)| decode(value).is_some_and(|expires_at| expires_at <= now))
+            .map(|(key, _)| key["pin/".len()..].to_string())
+            .collect())
+    }
+}
+
+impl Default for PinLeases {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryKvStore::new()))
+    }
+}
+
+fn key(hash: &str) -> String {
+    format!("pin/{}", hash)
+}
+
+fn decode(value: &[u8]) -> Option<DateTime<Utc>> {
+    let millis = std::str::from_utf8(value).ok()?.parse().ok()?;
+    Utc.timestamp_millis_opt(millis).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::contracts::ProposalStatus;
+    use crate::config::Config;
+    use crate::ipfs::content_types::{ProposalIPFSContent, ProposalMetadata};
+    use ethers::types::{Address, U256};
+
+    async fn index_proposal(ipfs: &IpfsClient, indexer: &ContentIndexer, id: u64, attachments: Vec<String>) -> String {
+        let content = ProposalIPFSContent {
+            title: format!("Proposal {}", id),
+            description: "Re-pin test".to_string(),
+            metadata: ProposalMetadata {
+                attachments,
+                ..Default::default()
+            },
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        let ipfs_hash = ipfs.add_proposal_content(&content).await.unwrap();
+
+        indexer.index_proposal(IndexedProposal {
+            id,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash: ipfs_hash.clone(),
+            proposal_type: 0,
+            status: ProposalStatus::Active,
+            start_time: 0,
+            end_time: 86400,
+            supersedes: None,
+            total_voting_power: U256::zero(),
+            tie_extended: false,
+        });
+        ipfs_hash
+    }
+
+    async fn attachment(ipfs: &IpfsClient, name: &str) -> String {
+        ipfs.add_json(&serde_json::json!({ "attachment": name })).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_unpinned_content_is_pinned() {
+        let ipfs = IpfsClient::in_memory(&Config::default());
+        let indexer = ContentIndexer::new();
+
+        let doc = attachment(&ipfs, "doc").await;
+        let first = index_proposal(&ipfs, &indexer, 1, vec![doc.clone()]).await;
+        index_proposal(&ipfs, &indexer, 2, vec![]).await;
+
+        // Simulate a fresh node that lost some pins
+        ipfs.unpin_content(&first).await.unwrap();
+        ipfs.unpin_content(&doc).await.unwrap();
+
+        let mut checkpoint = RepinCheckpoint::default();
+        let progress = repin_all(&ipfs, &indexer, &mut checkpoint, &RepinOptions::default(), |_| {}).await;
+
+        assert_eq!(progress.pinned, 2);
+        assert_eq!(progress.already_pinned, 1);
+        assert!(progress.failed.is_empty());
+        assert!(ipfs.is_pinned(&first).await.unwrap());
+        assert!(ipfs.is_pinned(&doc).await.unwrap());
+        assert_eq!(checkpoint.last_proposal_id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_run_resumes_from_checkpoint() {
+        let ipfs = IpfsClient::in_memory(&Config::default());
+        let indexer = ContentIndexer::new();
+
+        let mut hashes = Vec::new();
+        for id in 1..=5 {
+            let hash = index_proposal(&ipfs, &indexer, id, vec![]).await;
+            ipfs.unpin_content(&hash).await.unwrap();
+            hashes.push(hash);
+        }
+
+        let mut checkpoint = RepinCheckpoint::default();
+        let interrupted = RepinOptions {
+            max_proposals: Some(2),
+            ..Default::default()
+        };
+        let mut reports = 0;
+        let progress = repin_all(&ipfs, &indexer, &mut checkpoint, &interrupted, |_| reports += 1).await;
+
+        assert_eq!(progress.pinned, 2);
+        assert_eq!(reports, 2);
+        assert_eq!(checkpoint.last_proposal_id, Some(2));
+        assert!(!ipfs.is_pinned(&hashes[2]).await.unwrap());
+
+        let progress = repin_all(&ipfs, &indexer, &mut checkpoint, &RepinOptions::default(), |_| {}).await;
+        assert_eq!(progress.total_proposals, 3);
+        assert_eq!(progress.pinned, 3);
+        assert_eq!(progress.already_pinned, 0);
+        assert_eq!(checkpoint.last_proposal_id, Some(5));
+        for hash in &hashes {
+            assert!(ipfs.is_pinned(hash).await.unwrap());
+        }
+    }
+}
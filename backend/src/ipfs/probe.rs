@@ -0,0 +1,197 @@
+use crate::config::{IpfsAuth, IpfsConfig};
+use crate::utils::errors::{GovernanceError, Result};
+use async_trait::async_trait;
+use std::fmt;
+use std::time::Duration;
+
+/// Empty unixfs directory. Every node can serve it without fetching anything.
+pub const PROBE_CID: &str = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Checks what an endpoint answers to. `Err` means it couldn't be reached at all.
+#[async_trait]
+pub trait EndpointProbe: Send + Sync {
+    /// Whether `url` answers the RPC API's `POST /api/v0/version`
+    async fn answers_version(&self, url: &str) -> Result<bool>;
+    /// Whether `url` serves `GET /ipfs/{cid}` like a gateway
+    async fn serves_gateway(&self, url: &str, cid: &str) -> Result<bool>;
+}
+
+/// Probe over HTTP, sending the configured API credentials with version calls
+pub struct HttpProbe {
+    client: reqwest::Client,
+    auth: Option<IpfsAuth>,
+}
+
+impl HttpProbe {
+    pub fn new(auth: Option<IpfsAuth>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .map_err(|e| GovernanceError::ipfs(format!("Failed to build probe client: {}", e)))?;
+        Ok(Self { client, auth })
+    }
+}
+
+#[async_trait]
+impl EndpointProbe for HttpProbe {
+    async fn answers_version(&self, url: &str) -> Result<bool> {
+        let mut request = self.client.post(format!("{}/api/v0/version", url.trim_end_matches('/')));
+        if let Some(auth) = &self.auth {
+            request = request.basic_auth(&auth.username, Some(&auth.password));
+        }
+
+        let response = request.send().await.map_err(|e| GovernanceError::ipfs(e.to_string()))?;
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+        // Gateways may answer any path with 200; only the API returns a version
+        let body: serde_json::Value = match response.json().await {
+            Ok(body) => body,
+            Err(_) => return Ok(false),
+        };
+        Ok(body.get("Version").is_some())
+    }
+
+    async fn serves_gateway(&self, url: &str, cid: &str) -> Result<bool> {
+        let response = self
+            .client
+            .get(format!("{}/ipfs/{}", url.trim_end_matches('/'), cid))
+            .send()
+            .await
+            .map_err(|e| GovernanceError::ipfs(e.to_string()))?;
+        Ok(response.status().is_success())
+    }
+}
+
+/// Something wrong with the configured IPFS endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeIssue {
+    /// The API URL looks like a gateway and the gateway URL like an API
+    Swapped,
+    ApiUnavailable(String),
+    GatewayUnavailable(String),
+}
+
+impl fmt::Display for ProbeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeIssue::Swapped => write!(
+                f,
+                "IPFS api_url and gateway_url appear to be swapped (the API usually listens on :5001, the gateway on :8080)"
+            ),
+            ProbeIssue::ApiUnavailable(detail) => write!(f, "IPFS API is not usable: {}", detail),
+            ProbeIssue::GatewayUnavailable(detail) => write!(f, "IPFS gateway is not usable: {}", detail),
+        }
+    }
+}
+
+/// Check that the API URL answers `version` and the gateway URL serves
+/// `/ipfs/` paths. A failing API is reported as `Swapped` when the gateway URL
+/// is the one answering `version`.
+pub async fn probe_endpoints(config: &IpfsConfig, probe: &dyn EndpointProbe) -> Vec<ProbeIssue> {
+    let mut issues = Vec::new();
+
+    let api = probe.answers_version(&config.api_url).await;
+    if !matches!(api, Ok(true)) {
+        if matches!(probe.answers_version(&config.gateway_url).await, Ok(true)) {
+            return vec![ProbeIssue::Swapped];
+        }
+        issues.push(ProbeIssue::ApiUnavailable(match api {
+            Err(e) => format!("{} is unreachable: {}", config.api_url, e),
+            _ => format!("{} does not answer /api/v0/version", config.api_url),
+        }));
+    }
+
+    match probe.serves_gateway(&config.gateway_url, PROBE_CID).await {
+        Ok(true) => {}
+        Ok(false) => issues.push(ProbeIssue::GatewayUnavailable(format!(
+            "{} does not serve /ipfs/{}",
+            config.gateway_url, PROBE_CID
+        ))),
+        Err(e) => issues.push(ProbeIssue::GatewayUnavailable(format!(
+            "{} is unreachable: {}",
+            config.gateway_url, e
+        ))),
+    }
+
+    issues
+}
+
+/// Probe the endpoints at startup, logging each issue. In strict mode any
+/// issue fails startup instead.
+pub async fn check_endpoints(config: &IpfsConfig, probe: &dyn EndpointProbe) -> Result<()> {
+    let issues = probe_endpoints(config, probe).await;
+    for issue in &issues {
+        tracing::warn!("{}", issue);
+    }
+
+    match issues.first() {
+        Some(issue) if config.strict_probe => Err(GovernanceError::ipfs(issue.to_string())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// Node with its API on :5001 and gateway on :8080
+    struct MockNode;
+
+    const API_URL: &str = "http://localhost:5001";
+    const GATEWAY_URL: &str = "http://localhost:8080";
+
+    #[async_trait]
+    impl EndpointProbe for MockNode {
+        async fn answers_version(&self, url: &str) -> Result<bool> {
+            match url {
+                API_URL => Ok(true),
+                GATEWAY_URL => Ok(false),
+                _ => Err(GovernanceError::ipfs("connection refused")),
+            }
+        }
+
+        async fn serves_gateway(&self, url: &str, _cid: &str) -> Result<bool> {
+            match url {
+                API_URL | GATEWAY_URL => Ok(true),
+                _ => Err(GovernanceError::ipfs("connection refused")),
+            }
+        }
+    }
+
+    fn ipfs_config(api_url: &str, gateway_url: &str) -> IpfsConfig {
+        IpfsConfig {
+            api_url: api_url.to_string(),
+            gateway_url: gateway_url.to_string(),
+            ..Config::default().ipfs
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swapped_urls_detected() {
+        assert!(probe_endpoints(&ipfs_config(API_URL, GATEWAY_URL), &MockNode).await.is_empty());
+
+        let swapped = ipfs_config(GATEWAY_URL, API_URL);
+        assert_eq!(probe_endpoints(&swapped, &MockNode).await, vec![ProbeIssue::Swapped]);
+
+        // Warns by default, fails startup in strict mode
+        assert!(check_endpoints(&swapped, &MockNode).await.is_ok());
+        let strict = IpfsConfig {
+            strict_probe: true,
+            ..swapped
+        };
+        assert!(check_endpoints(&strict, &MockNode).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoints_reported() {
+        let issues = probe_endpoints(&ipfs_config("http://localhost:9999", GATEWAY_URL), &MockNode).await;
+        assert!(matches!(issues.as_slice(), [ProbeIssue::ApiUnavailable(_)]));
+
+        let issues = probe_endpoints(&ipfs_config(API_URL, "http://localhost:9999"), &MockNode).await;
+        assert!(matches!(issues.as_slice(), [ProbeIssue::GatewayUnavailable(_)]));
+    }
+}
@@ -0,0 +1,53 @@
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// `T` deserialized as if it and every type nested in it had
+/// `#[serde(deny_unknown_fields)]`. A field is known when `T` writes it back
+/// on serialization, so this suits the plain-struct governance content types,
+/// without aliases or flattening.
+#[derive(Debug, Clone)]
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Strict<T>
+where
+    T: DeserializeOwned + Serialize,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = Value::deserialize(deserializer)?;
+        let content: T = serde_json::from_value(input.clone()).map_err(D::Error::custom)?;
+        let known = serde_json::to_value(&content).map_err(D::Error::custom)?;
+        match unknown_field(&input, &known, "") {
+            Some(path) => Err(D::Error::custom(format!("unknown field `{}`", path))),
+            None => Ok(Strict(content)),
+        }
+    }
+}
+
+/// Path of the first field of `input` that `known` lacks, e.g. `metadata.categry`
+fn unknown_field(input: &Value, known: &Value, path: &str) -> Option<String> {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => input.iter().find_map(|(key, value)| {
+            let path = match path {
+                "" => key.clone(),
+                _ => format!("{}.{}", path, key),
+            };
+            match known.get(key) {
+                Some(known) => unknown_field(value, known, &path),
+                None => Some(path),
+            }
+        }),
+        (Value::Array(input), Value::Array(known)) => input
+            .iter()
+            .zip(known)
+            .enumerate()
+            .find_map(|(index, (value, known))| unknown_field(value, known, &format!("{}[{}]", path, index))),
+        _ => None,
+    }
+}
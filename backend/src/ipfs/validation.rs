@@ -2,7 +2,8 @@ use crate::ipfs::content_types::*;
 use crate::utils::errors::{GovernanceError, Result};
 use validator::Validate;
 
-pub fn validate_proposal_content(content: &ProposalIPFSContent) -> Result<()> {
+/// Validate proposal content, normalizing tags in place
+pub fn validate_proposal_content(content: &mut ProposalIPFSContent) -> Result<()> {
     // Basic validation using validator crate
     content.validate()
         .map_err(GovernanceError::Validation)?;
@@ -25,7 +26,7 @@ pub fn validate_proposal_content(content: &ProposalIPFSContent) -> Result<()> {
     }
 
     // Validate metadata
-    validate_proposal_metadata(&content.metadata)?;
+    validate_proposal_metadata(&mut content.metadata)?;
 
     Ok(())
 }
@@ -79,14 +80,15 @@ pub fn validate_user_profile(content: &UserProfileIPFS) -> Result<()> {
     Ok(())
 }
 
-fn validate_proposal_metadata(metadata: &ProposalMetadata) -> Result<()> {
+fn validate_proposal_metadata(metadata: &mut ProposalMetadata) -> Result<()> {
     if metadata.category.trim().is_empty() {
         return Err(GovernanceError::ipfs("Proposal category cannot be empty"));
     }
 
     // Validate tags
+    metadata.tags = normalize_tags(&metadata.tags);
     for tag in &metadata.tags {
-        if tag.trim().is_empty() {
+        if tag.is_empty() {
             return Err(GovernanceError::ipfs("Tags cannot be empty"));
         }
         if tag.len() > 50 {
@@ -109,6 +111,8 @@ fn validate_proposal_metadata(metadata: &ProposalMetadata) -> Result<()> {
         return Err(GovernanceError::ipfs("Maximum 20 attachments allowed"));
     }
 
+    validate_proposal_options(metadata)?;
+
     // Validate execution data if present
     if let Some(execution_data) = &metadata.execution_data {
         validate_execution_data(execution_data)?;
@@ -117,6 +121,32 @@ fn validate_proposal_metadata(metadata: &ProposalMetadata) -> Result<()> {
     Ok(())
 }
 
+fn validate_proposal_options(metadata: &ProposalMetadata) -> Result<()> {
+    if !metadata.proposal_type.uses_options() {
+        if !metadata.options.is_empty() {
+            return Err(GovernanceError::ipfs("Options are only allowed for multi-choice proposals"));
+        }
+        return Ok(());
+    }
+
+    if metadata.options.is_empty() {
+        return Err(GovernanceError::ipfs("Multi-choice proposals require at least one option"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for option in &metadata.options {
+        let label = option.trim().to_lowercase();
+        if label.is_empty() {
+            return Err(GovernanceError::ipfs("Option labels cannot be empty"));
+        }
+        if !seen.insert(label) {
+            return Err(GovernanceError::ipfs(format!("Duplicate option label: {}", option.trim())));
+        }
+    }
+
+    Ok(())
+}
+
 fn validate_execution_data(execution_data: &ExecutionData) -> Result<()> {
     if !crate::utils::helpers::validate_ethereum_address(&execution_data.target_contract) {
         return Err(GovernanceError::ipfs("Invalid target contract address"));
@@ -166,6 +196,40 @@ fn is_valid_url(url: &str) -> bool {
     url::Url::parse(url).is_ok()
 }
 
+/// Canonical form of a tag: lowercase, characters other than letters, digits,
+/// `-`, `_` and whitespace stripped, and whitespace runs collapsed to one space
+pub fn normalize_tag(tag: &str) -> String {
+    let cleaned: String = tag
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '-' || *c == '_')
+        .collect();
+
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Form titles are compared in: lowercase, with punctuation treated as
+/// whitespace and whitespace runs collapsed, so "Fund the Grants Program!"
+/// and "fund the grants-program" match
+pub fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalize tags and drop duplicates, keeping first-seen order. Tags that
+/// normalize to nothing are kept as empty strings so validation rejects them.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    tags.iter()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| seen.insert(tag.clone()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,12 +246,61 @@ mod tests {
             created_at: Utc::now(),
         };
 
-        assert!(validate_proposal_content(&content).is_ok());
+        assert!(validate_proposal_content(&mut content.clone()).is_ok());
 
         // Test empty title
         let mut invalid_content = content.clone();
         invalid_content.title = "".to_string();
-        assert!(validate_proposal_content(&invalid_content).is_err());
+        assert!(validate_proposal_content(&mut invalid_content).is_err());
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("  Fund the Grants   Program! "), "fund the grants program");
+        assert_eq!(normalize_title("fund the grants-program"), "fund the grants program");
+        assert_ne!(normalize_title("Fund the Grants Program 2"), "fund the grants program");
+    }
+
+    #[test]
+    fn test_validate_proposal_options() {
+        let mut metadata = ProposalMetadata {
+            proposal_type: ProposalType::MultipleChoice,
+            options: vec!["Alpha".to_string(), "Beta".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_proposal_metadata(&mut metadata).is_ok());
+
+        metadata.options.push(" alpha ".to_string());
+        assert!(validate_proposal_metadata(&mut metadata).is_err());
+
+        metadata.options.clear();
+        assert!(validate_proposal_metadata(&mut metadata).is_err());
+
+        // Binary proposals don't carry options
+        let mut binary = ProposalMetadata {
+            options: vec!["Alpha".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_proposal_metadata(&mut binary).is_err());
+    }
+
+    #[test]
+    fn test_tag_variants_collapse_to_canonical_tag() {
+        for variant in ["DeFi", "defi", " defi ", "#DeFi!"] {
+            assert_eq!(normalize_tag(variant), "defi");
+        }
+        assert_eq!(normalize_tag("  Layer   2\tScaling "), "layer 2 scaling");
+
+        let mut metadata = ProposalMetadata {
+            tags: vec!["DeFi".to_string(), " defi ".to_string(), "Treasury".to_string(), "DEFI".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_proposal_metadata(&mut metadata).is_ok());
+        assert_eq!(metadata.tags, vec!["defi", "treasury"]);
+
+        // Tags made only of disallowed characters are rejected
+        metadata.tags = vec!["!!!".to_string()];
+        assert!(validate_proposal_metadata(&mut metadata).is_err());
     }
 
     #[test]
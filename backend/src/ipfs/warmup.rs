@@ -0,0 +1,179 @@
+use crate::blockchain::contracts::ProposalStatus;
+use crate::indexer::content_indexer::{ContentIndexer, IndexedProposal};
+use crate::ipfs::client::IpfsClient;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct WarmupOptions {
+    /// Proposals fetched in parallel
+    pub concurrency: usize,
+    /// Closed proposals that ended within this many seconds are warmed too
+    pub recent_window: u64,
+}
+
+impl Default for WarmupOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            recent_window: 7 * 86400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmupReport {
+    pub total_proposals: usize,
+    pub warmed: usize,
+    pub failed: Vec<WarmupFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupFailure {
+    pub proposal_id: u64,
+    pub hash: String,
+    pub error: String,
+}
+
+/// Fetch the content of active and recently closed proposals into the IPFS
+/// cache, so list and detail views are fast right after a restart.
+/// Best-effort: a proposal whose content can't be fetched is reported and
+/// the rest are still warmed.
+pub async fn warm_proposal_cache(
+    ipfs: &IpfsClient,
+    indexer: &ContentIndexer,
+    now: u64,
+    options: &WarmupOptions,
+) -> WarmupReport {
+    let since = now.saturating_sub(options.recent_window);
+    let proposals: Vec<IndexedProposal> = indexer
+        .proposals()
+        .into_iter()
+        .filter(|p| p.status == ProposalStatus::Active || p.end_time >= since)
+        .collect();
+
+    let mut report = WarmupReport {
+        total_proposals: proposals.len(),
+        ..Default::default()
+    };
+
+    let mut results = stream::iter(proposals)
+        .map(|proposal| async move {
+            let result = ipfs.get_proposal_content(&proposal.ipfs_hash).await;
+            (proposal, result)
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    while let Some((proposal, result)) = results.next().await {
+        match result {
+            Ok(_) => report.warmed += 1,
+            Err(e) => {
+                tracing::debug!("Failed to warm content of proposal {}: {}", proposal.id, e);
+                report.failed.push(WarmupFailure {
+                    proposal_id: proposal.id,
+                    hash: proposal.ipfs_hash,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        "Cache warm-up finished: {} of {} proposals warmed, {} failed",
+        report.warmed,
+        report.total_proposals,
+        report.failed.len()
+    );
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::ipfs::content_types::{ProposalIPFSContent, ProposalMetadata};
+    use ethers::types::{Address, U256};
+
+    const NOW: u64 = 30 * 86400;
+
+    fn index_proposal(indexer: &ContentIndexer, id: u64, ipfs_hash: String, status: ProposalStatus, end_time: u64) {
+        indexer.index_proposal(IndexedProposal {
+            id,
+            contract: None,
+            proposer: Address::zero(),
+            ipfs_hash,
+            proposal_type: 0,
+            status,
+            start_time: 0,
+            end_time,
+            supersedes: None,
+            total_voting_power: U256::zero(),
+            tie_extended: false,
+        });
+    }
+
+    async fn add_content(ipfs: &IpfsClient, id: u64) -> String {
+        ipfs.add_proposal_content(&ProposalIPFSContent {
+            title: format!("Proposal {}", id),
+            description: "Warm-up test".to_string(),
+            metadata: ProposalMetadata::default(),
+            version: "1.0".to_string(),
+            content_type: "proposal".to_string(),
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_active_and_recent_proposals_cached() {
+        let ipfs = IpfsClient::in_memory(&Config::default());
+        let indexer = ContentIndexer::new();
+
+        let active = add_content(&ipfs, 1).await;
+        let recent = add_content(&ipfs, 2).await;
+        let old = add_content(&ipfs, 3).await;
+        index_proposal(&indexer, 1, active.clone(), ProposalStatus::Active, NOW + 3600);
+        index_proposal(&indexer, 2, recent.clone(), ProposalStatus::Passed, NOW - 86400);
+        index_proposal(&indexer, 3, old.clone(), ProposalStatus::Rejected, NOW - 20 * 86400);
+        assert!(!ipfs.is_cached(&active).await);
+
+        let report = warm_proposal_cache(&ipfs, &indexer, NOW, &WarmupOptions::default()).await;
+
+        assert_eq!(report.total_proposals, 2);
+        assert_eq!(report.warmed, 2);
+        assert!(report.failed.is_empty());
+        assert!(ipfs.is_cached(&active).await);
+        assert!(ipfs.is_cached(&recent).await);
+        assert!(!ipfs.is_cached(&old).await);
+    }
+
+    #[tokio::test]
+    async fn test_failed_fetch_does_not_stop_warmup() {
+        let ipfs = IpfsClient::in_memory(&Config::default());
+        let indexer = ContentIndexer::new();
+
+        let missing = "Qm".to_string() + &"0".repeat(44);
+        index_proposal(&indexer, 1, missing.clone(), ProposalStatus::Active, NOW + 3600);
+        let mut hashes = Vec::new();
+        for id in 2..=4 {
+            let hash = add_content(&ipfs, id).await;
+            index_proposal(&indexer, id, hash.clone(), ProposalStatus::Active, NOW + 3600);
+            hashes.push(hash);
+        }
+
+        let options = WarmupOptions {
+            concurrency: 2,
+            ..Default::default()
+        };
+        let report = warm_proposal_cache(&ipfs, &indexer, NOW, &options).await;
+
+        assert_eq!(report.warmed, 3);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].proposal_id, 1);
+        assert_eq!(report.failed[0].hash, missing);
+        for hash in &hashes {
+            assert!(ipfs.is_cached(hash).await);
+        }
+    }
+}
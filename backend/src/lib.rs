@@ -6,15 +6,11 @@ pub mod api;
 pub mod auth;
 pub mod indexer;
 pub mod performance;
+pub mod self_test;
+pub mod state;
+pub mod storage;
 pub mod utils;
 
 pub use config::Config;
+pub use state::{AppState, AppStateBuilder};
 pub use utils::errors::Result;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub config: Config,
-    pub blockchain_client: blockchain::client::SomniaClient,
-    pub ipfs_client: ipfs::client::IpfsClient,
-    pub governance_engine: governance::engine::GovernanceEngine,
-}
\ No newline at end of file
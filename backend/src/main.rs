@@ -1,18 +1,24 @@
-use axum::Router;
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::CorsLayer,
+    cors::{AllowOrigin, CorsLayer},
     trace::TraceLayer,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use somnia_governance_engine::{
-    api::{
-        routes::{auth_routes, governance_routes, health_routes, websocket_routes},
-    },
+    api::routes::app_router,
+    blockchain::{client::SomniaClient, transactions::PENDING_CLEANUP_INTERVAL},
     config::Config,
-    AppState,
+    governance::drafts::DRAFT_PURGE_INTERVAL,
+    ipfs::{
+        cache::start_cache_cleanup_task,
+        client::IpfsClient,
+        pinning::PIN_SWEEP_INTERVAL,
+        warmup::{warm_proposal_cache, WarmupOptions},
+    },
+    self_test::run_self_test,
+    AppStateBuilder,
 };
 
 #[tokio::main]
@@ -26,44 +32,80 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // `--self-test` checks the deployment's wiring and exits without serving.
+    // It reports config problems itself; serving refuses to start on them.
+    if std::env::args().skip(1).any(|arg| arg == "--self-test") {
+        let config = Config::from_env_unchecked()?;
+        let ipfs = IpfsClient::new(&config).await;
+        let blockchain = SomniaClient::new(&config).await;
+        let report = run_self_test(&config, ipfs, blockchain).await;
+        println!("{}", report);
+        std::process::exit(report.exit_code());
+    }
+
     // Load configuration
     let config = Config::from_env()?;
-    
-    // Initialize clients
-    let blockchain_client = somnia_governance_engine::blockchain::client::SomniaClient::new(&config).await?;
-    let ipfs_client = somnia_governance_engine::ipfs::client::IpfsClient::new(&config).await?;
-    let governance_engine = somnia_governance_engine::governance::engine::GovernanceEngine::new(
-        blockchain_client.clone(),
-        ipfs_client.clone(),
-    ).await?;
 
-    // Create application state
-    let app_state = AppState {
-        config: config.clone(),
-        blockchain_client,
-        ipfs_client,
-        governance_engine,
+    // Initialize clients and create application state
+    let app_state = AppStateBuilder::new()
+        .config(config.clone())
+        .build()
+        .await?;
+
+    if config.governance.participation_alert_window.is_some() {
+        app_state.governance_engine.start_participation_task();
+    }
+
+    app_state.auth_service.start_cleanup_task();
+    app_state.drafts.start_purge_task(DRAFT_PURGE_INTERVAL);
+    app_state
+        .pending_transactions
+        .start_cleanup_task(PENDING_CLEANUP_INTERVAL, chrono::Duration::hours(1));
+    tokio::spawn(start_cache_cleanup_task(
+        app_state.ipfs_client.cache(),
+        config.ipfs.cache_cleanup_interval(),
+    ));
+    if config.ipfs.pinning.has_ttl() {
+        app_state.ipfs_client.start_pin_sweep_task(PIN_SWEEP_INTERVAL);
+    }
+
+    // Best-effort: requests are served while the cache fills
+    if config.ipfs.warm_cache {
+        let ipfs = app_state.ipfs_client.clone();
+        let indexer = app_state.governance_engine.indexer().clone();
+        tokio::spawn(async move {
+            let now = chrono::Utc::now().timestamp() as u64;
+            warm_proposal_cache(&ipfs, &indexer, now, &WarmupOptions::default()).await;
+        });
+    }
+
+    // Restrict CORS to the configured origins, if any
+    let cors = if config.server.allowed_origins.is_empty() {
+        CorsLayer::permissive()
+    } else {
+        let origins = config
+            .server
+            .allowed_origins
+            .iter()
+            .map(|origin| origin.parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        CorsLayer::permissive().allow_origin(AllowOrigin::list(origins))
     };
 
     // Build application routes
-    let app = Router::new()
-        .nest("/api/health", health_routes())
-        .nest("/api/auth", auth_routes())
-        .nest("/api/governance", governance_routes())
-        .nest("/ws", websocket_routes())
+    let app = app_router(app_state)
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
-                .layer(CorsLayer::permissive())
-        )
-        .with_state(app_state);
+                .layer(cors)
+        );
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
     tracing::info!("🚀 Somnia Governance Engine starting on {}", addr);
     
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
@@ -0,0 +1,291 @@
+use crate::blockchain::client::SomniaClient;
+use crate::config::Config;
+use crate::ipfs::client::IpfsClient;
+use crate::utils::errors::{GovernanceError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Exit status when every check passed or was skipped
+pub const EXIT_OK: i32 = 0;
+/// Exit status when any check failed
+pub const EXIT_FAILED: i32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not run because something it depends on failed or isn't configured
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl SelfTestCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    fn from_result(name: &str, result: std::result::Result<String, String>) -> Self {
+        match result {
+            Ok(detail) => Self::new(name, CheckStatus::Pass, detail),
+            Err(detail) => Self::new(name, CheckStatus::Fail, detail),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Fail)
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        if self.passed() {
+            EXIT_OK
+        } else {
+            EXIT_FAILED
+        }
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Pass => "PASS",
+                CheckStatus::Fail => "FAIL",
+                CheckStatus::Skip => "SKIP",
+            };
+            writeln!(f, "[{}] {:<16} {}", status, check.name, check.detail)?;
+        }
+        write!(f, "Self-test {}", if self.passed() { "passed" } else { "failed" })
+    }
+}
+
+/// Check that the deployment is wired correctly without serving traffic:
+/// the config is usable, IPFS and the RPC node answer, the configured
+/// contracts have code and IPFS stores and returns content. `ipfs` and
+/// `blockchain` are the outcome of connecting, so connection failures are
+/// reported rather than aborting the run.
+pub async fn run_self_test(
+    config: &Config,
+    ipfs: Result<IpfsClient>,
+    blockchain: Result<SomniaClient>,
+) -> SelfTestReport {
+    let problems = config.problems();
+    let mut checks = vec![SelfTestCheck::from_result(
+        "config",
+        if problems.is_empty() {
+            Ok("Configuration is valid".to_string())
+        } else {
+            Err(problems.join("; "))
+        },
+    )];
+
+    match &ipfs {
+        Ok(ipfs) => checks.push(SelfTestCheck::from_result(
+            "ipfs_connection",
+            ipfs.node_version()
+                .await
+                .map(|version| format!("IPFS node version {}", version))
+                .map_err(|e| e.to_string()),
+        )),
+        Err(e) => checks.push(SelfTestCheck::new("ipfs_connection", CheckStatus::Fail, e.to_string())),
+    }
+
+    match &blockchain {
+        Ok(blockchain) => checks.push(SelfTestCheck::from_result(
+            "rpc_connection",
+            blockchain
+                .node_info()
+                .await
+                .map(|info| format!("{} on network {}", info.client_version, info.net_version))
+                .map_err(|e| e.to_string()),
+        )),
+        Err(e) => checks.push(SelfTestCheck::new("rpc_connection", CheckStatus::Fail, e.to_string())),
+    }
+
+    checks.push(match &blockchain {
+        Ok(blockchain) => check_contract_code(blockchain).await,
+        Err(_) => SelfTestCheck::new("contract_code", CheckStatus::Skip, "No RPC connection"),
+    });
+
+    checks.push(match &ipfs {
+        Ok(ipfs) => check_ipfs_round_trip(ipfs).await,
+        Err(_) => SelfTestCheck::new("ipfs_round_trip", CheckStatus::Skip, "No IPFS connection"),
+    });
+
+    SelfTestReport { checks }
+}
+
+async fn check_contract_code(blockchain: &SomniaClient) -> SelfTestCheck {
+    let addresses = blockchain.contract_addresses();
+    let configured: Vec<_> = [
+        ("governance_hub", addresses.governance_hub),
+        ("simple_voting", addresses.simple_voting),
+    ]
+    .into_iter()
+    .filter_map(|(name, address)| address.map(|address| (name, address)))
+    .collect();
+
+    if configured.is_empty() {
+        return SelfTestCheck::new("contract_code", CheckStatus::Skip, "No contract addresses configured");
+    }
+
+    let mut missing = Vec::new();
+    for (name, address) in &configured {
+        match blockchain.contract_code(*address).await {
+            Ok(code) if !code.is_empty() => {}
+            Ok(_) => missing.push(format!("{} has no code at {:?}", name, address)),
+            Err(e) => missing.push(format!("{} at {:?}: {}", name, address, e)),
+        }
+    }
+
+    SelfTestCheck::from_result(
+        "contract_code",
+        if missing.is_empty() {
+            Ok(format!("{} contracts deployed", configured.len()))
+        } else {
+            Err(missing.join("; "))
+        },
+    )
+}
+
+/// Store a small object, read it back and unpin it again
+async fn check_ipfs_round_trip(ipfs: &IpfsClient) -> SelfTestCheck {
+    let probe = serde_json::json!({
+        "content_type": "self_test",
+        "nonce": uuid::Uuid::new_v4().to_string(),
+    });
+
+    let result = async {
+        let hash = ipfs.add_json(&probe).await?;
+        let fetched: serde_json::Value = ipfs.get_json(&hash).await?;
+        if let Err(e) = ipfs.unpin_content(&hash).await {
+            tracing::warn!("Failed to unpin self-test object {}: {}", hash, e);
+        }
+        Ok::<_, GovernanceError>((hash, fetched))
+    }
+    .await;
+
+    SelfTestCheck::from_result(
+        "ipfs_round_trip",
+        match result {
+            Ok((hash, fetched)) if fetched == probe => Ok(format!("Stored and read back {}", hash)),
+            Ok((hash, _)) => Err(format!("{} came back different from what was stored", hash)),
+            Err(e) => Err(e.to_string()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::node_info::{ContractCodeSource, NodeInfoSource, RpcNodeInfo};
+    use async_trait::async_trait;
+    use ethers::types::{Address, Bytes};
+    use std::sync::Arc;
+
+    /// RPC node with `code` deployed at every address
+    struct MockNode {
+        code: Bytes,
+    }
+
+    #[async_trait]
+    impl NodeInfoSource for MockNode {
+        async fn node_info(&self) -> Result<RpcNodeInfo> {
+            Ok(RpcNodeInfo {
+                client_version: "Geth/v1.13.5".to_string(),
+                net_version: "1337".to_string(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ContractCodeSource for MockNode {
+        async fn code_at(&self, _address: Address) -> Result<Bytes> {
+            Ok(self.code.clone())
+        }
+    }
+
+    fn config_with_contracts() -> Config {
+        let mut config = Config::default();
+        config.blockchain.contracts.governance_hub = Some(format!("{:?}", Address::random()));
+        config.blockchain.contracts.simple_voting = Some(format!("{:?}", Address::random()));
+        config
+    }
+
+    fn blockchain(config: &Config, code: &[u8]) -> SomniaClient {
+        let node = Arc::new(MockNode {
+            code: Bytes::from(code.to_vec()),
+        });
+        SomniaClient::mock(config).with_node_info(node.clone()).with_code_source(node)
+    }
+
+    fn status(report: &SelfTestReport, name: &str) -> CheckStatus {
+        report.checks.iter().find(|check| check.name == name).unwrap().status
+    }
+
+    #[tokio::test]
+    async fn test_healthy_deployment_passes() {
+        let config = config_with_contracts();
+        let report = run_self_test(
+            &config,
+            Ok(IpfsClient::in_memory(&config)),
+            Ok(blockchain(&config, &[0x60, 0x80])),
+        )
+        .await;
+
+        assert!(report.checks.iter().all(|check| check.status == CheckStatus::Pass), "{}", report);
+        assert_eq!(report.exit_code(), EXIT_OK);
+    }
+
+    #[tokio::test]
+    async fn test_broken_dependencies_fail() {
+        let mut config = config_with_contracts();
+        config.auth.admins = vec!["not-an-address".to_string()];
+        let report = run_self_test(
+            &config,
+            Err(GovernanceError::ipfs("Failed to connect to IPFS: connection refused")),
+            Ok(blockchain(&config, &[])),
+        )
+        .await;
+
+        assert_eq!(status(&report, "config"), CheckStatus::Fail);
+        assert_eq!(status(&report, "ipfs_connection"), CheckStatus::Fail);
+        assert_eq!(status(&report, "rpc_connection"), CheckStatus::Pass);
+        assert_eq!(status(&report, "contract_code"), CheckStatus::Fail);
+        assert_eq!(status(&report, "ipfs_round_trip"), CheckStatus::Skip);
+        assert_eq!(report.exit_code(), EXIT_FAILED);
+    }
+
+    #[tokio::test]
+    async fn test_unpinned_round_trip_and_missing_rpc_fail() {
+        let config = Config::default();
+        let ipfs = IpfsClient::in_memory(&config);
+        ipfs.drop_pins();
+
+        // Without a provider the node can't be asked anything
+        let report = run_self_test(&config, Ok(ipfs), Ok(SomniaClient::mock(&config))).await;
+
+        assert_eq!(status(&report, "config"), CheckStatus::Pass);
+        assert_eq!(status(&report, "rpc_connection"), CheckStatus::Fail);
+        assert_eq!(status(&report, "contract_code"), CheckStatus::Skip);
+        assert_eq!(status(&report, "ipfs_round_trip"), CheckStatus::Fail);
+        assert_eq!(report.exit_code(), EXIT_FAILED);
+    }
+}
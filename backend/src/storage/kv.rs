@@ -0,0 +1,342 @@
+use crate::config::KvStoreConfig;
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::utils::errors::Result;
+use chrono::Duration;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// Small persistent key-value storage shared by features that keep per-key
+/// state, such as drafts, delegations, watches and idempotency keys. Keys are
+/// namespaced by prefix, e.g. `watch/<proposal>/<address>`. Entries written
+/// with a TTL read as absent once it has passed. Calls may block on disk;
+/// implementations that do keep the async runtime's other tasks moving.
+pub trait KvStore: Send + Sync {
+    /// Insert or replace `key`, expiring after `ttl` if given
+    fn put(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> Result<()>;
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Remove `key`. Returns whether a live entry was removed.
+    fn delete(&self, key: &str) -> Result<bool>;
+
+    /// Live entries whose key starts with `prefix`, in key order
+    fn list(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>>;
+
+    /// Drop expired entries. Returns how many were dropped.
+    fn purge_expired(&self) -> Result<usize>;
+}
+
+pub type SharedKvStore = Arc<dyn KvStore>;
+
+#[derive(Clone)]
+struct MemoryEntry {
+    value: Vec<u8>,
+    expires_at: Option<i64>, // Unix milliseconds
+}
+
+/// `KvStore` held in process memory, for tests and single-node deployments
+/// that don't need the data to survive a restart
+#[derive(Clone)]
+pub struct MemoryKvStore {
+    entries: Arc<RwLock<BTreeMap<String, MemoryEntry>>>,
+    clock: SharedClock,
+}
+
+impl MemoryKvStore {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::default(),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn now(&self) -> i64 {
+        self.clock.now().timestamp_millis()
+    }
+}
+
+impl Default for MemoryKvStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KvStore for MemoryKvStore {
+    fn put(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> Result<()> {
+        let entry = MemoryEntry {
+            value: value.to_vec(),
+            expires_at: ttl.map(|ttl| self.now() + ttl.num_milliseconds()),
+        };
+        self.entries.write().unwrap().insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let now = self.now();
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .get(key)
+            .filter(|entry| is_live(entry.expires_at, now))
+            .map(|entry| entry.value.clone()))
+    }
+
+    fn delete(&self, key: &str) -> Result<bool> {
+        let now = self.now();
+        let removed = self.entries.write().unwrap().remove(key);
+        Ok(removed.is_some_and(|entry| is_live(entry.expires_at, now)))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let now = self.now();
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter(|(_, entry)| is_live(entry.expires_at, now))
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect())
+    }
+
+    fn purge_expired(&self) -> Result<usize> {
+        let now = self.now();
+        let mut entries = self.entries.write().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| is_live(entry.expires_at, now));
+        Ok(before - entries.len())
+    }
+}
+
+/// `KvStore` in a SQLite database, one `kv` table shared by every feature.
+/// Queries run with `block_in_place` on a multi-threaded runtime, so a slow
+/// disk stalls only the calling task.
+pub struct SqliteKvStore {
+    conn: Mutex<Connection>,
+    clock: SharedClock,
+}
+
+impl SqliteKvStore {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Database that lives only as long as the store, for tests
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS kv (
+                key TEXT PRIMARY KEY,
+                value BLOB NOT NULL,
+                expires_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS kv_expires_at ON kv (expires_at);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            clock: system_clock(),
+        })
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn now(&self) -> i64 {
+        self.clock.now().timestamp_millis()
+    }
+
+    /// Run `query` on the connection, off the worker thread's other tasks
+    /// when on a multi-threaded runtime. `block_in_place` would panic on a
+    /// current-thread runtime, where there is nobody to hand them to anyway.
+    fn with_conn<T>(&self, query: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T> {
+        let run = || -> Result<T> { Ok(query(&self.conn.lock().unwrap())?) };
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => tokio::task::block_in_place(run),
+            _ => run(),
+        }
+    }
+}
+
+impl KvStore for SqliteKvStore {
+    fn put(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> Result<()> {
+        let expires_at = ttl.map(|ttl| self.now() + ttl.num_milliseconds());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO kv (key, value, expires_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value, expires_at = excluded.expires_at",
+                params![key, value, expires_at],
+            )
+        })?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let now = self.now();
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT value FROM kv WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                params![key, now],
+                |row| row.get(0),
+            )
+            .optional()
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<bool> {
+        let now = self.now();
+        self.with_conn(|conn| {
+            let live = conn.execute(
+                "DELETE FROM kv WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                params![key, now],
+            )?;
+            // An expired entry is removed too, but didn't count as present
+            conn.execute("DELETE FROM kv WHERE key = ?1", params![key])?;
+            Ok(live > 0)
+        })
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let now = self.now();
+        self.with_conn(|conn| {
+            // substr rather than LIKE, so `%` and `_` in prefixes match literally
+            let mut statement = conn.prepare(
+                "SELECT key, value FROM kv
+                 WHERE substr(key, 1, length(?1)) = ?1 AND (expires_at IS NULL OR expires_at > ?2)
+                 ORDER BY key",
+            )?;
+            let entries = statement
+                .query_map(params![prefix, now], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(entries)
+        })
+    }
+
+    fn purge_expired(&self) -> Result<usize> {
+        let now = self.now();
+        self.with_conn(|conn| conn.execute("DELETE FROM kv WHERE expires_at <= ?1", params![now]))
+    }
+}
+
+/// The store `config` selects, reading the time from `clock`
+pub fn kv_store(config: &KvStoreConfig, clock: SharedClock) -> Result<SharedKvStore> {
+    Ok(match config {
+        KvStoreConfig::Memory => Arc::new(MemoryKvStore::new().with_clock(clock)),
+        KvStoreConfig::Sqlite { path } => Arc::new(SqliteKvStore::open(path)?.with_clock(clock)),
+    })
+}
+
+fn is_live(expires_at: Option<i64>, now: i64) -> bool {
+    expires_at.is_none_or(|expires_at| expires_at > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+
+    /// Behavior every `KvStore` must share
+    fn check_contract(store: &dyn KvStore, clock: &MockClock) {
+        assert_eq!(store.get("draft/1").unwrap(), None);
+
+        store.put("draft/1", b"first", None).unwrap();
+        store.put("draft/1", b"revised", None).unwrap();
+        assert_eq!(store.get("draft/1").unwrap(), Some(b"revised".to_vec()));
+
+        store.put("draft/2", b"second", None).unwrap();
+        store.put("draft%/3", b"literal", None).unwrap();
+        store.put("watch/1", b"watching", None).unwrap();
+        let drafts = store.list("draft/").unwrap();
+        assert_eq!(
+            drafts,
+            vec![
+                ("draft/1".to_string(), b"revised".to_vec()),
+                ("draft/2".to_string(), b"second".to_vec()),
+            ]
+        );
+        assert_eq!(store.list("draft%").unwrap().len(), 1);
+        assert_eq!(store.list("").unwrap().len(), 4);
+
+        assert!(store.delete("draft/2").unwrap());
+        assert!(!store.delete("draft/2").unwrap());
+        assert_eq!(store.get("draft/2").unwrap(), None);
+
+        store.put("idempotency/abc", b"response", Some(Duration::seconds(60))).unwrap();
+        clock.advance(Duration::seconds(59));
+        assert_eq!(store.get("idempotency/abc").unwrap(), Some(b"response".to_vec()));
+        clock.advance(Duration::seconds(1));
+        assert_eq!(store.get("idempotency/abc").unwrap(), None);
+        assert!(store.list("idempotency/").unwrap().is_empty());
+        assert_eq!(store.purge_expired().unwrap(), 1);
+        assert_eq!(store.purge_expired().unwrap(), 0);
+
+        // A TTL expires the entry but a later put without one keeps it
+        store.put("watch/2", b"expiring", Some(Duration::seconds(10))).unwrap();
+        store.put("watch/2", b"kept", None).unwrap();
+        clock.advance(Duration::seconds(11));
+        assert_eq!(store.get("watch/2").unwrap(), Some(b"kept".to_vec()));
+    }
+
+    #[test]
+    fn test_memory_store_contract() {
+        let clock = MockClock::default();
+        let store = MemoryKvStore::new().with_clock(Arc::new(clock.clone()));
+        check_contract(&store, &clock);
+    }
+
+    #[test]
+    fn test_sqlite_store_contract() {
+        let clock = MockClock::default();
+        let store = SqliteKvStore::open_in_memory().unwrap().with_clock(Arc::new(clock.clone()));
+        check_contract(&store, &clock);
+    }
+
+    #[test]
+    fn test_sqlite_store_persists_across_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kv.db");
+
+        SqliteKvStore::open(&path).unwrap().put("delegation/0xabc", b"0xdef", None).unwrap();
+        let reopened = SqliteKvStore::open(&path).unwrap();
+        assert_eq!(reopened.get("delegation/0xabc").unwrap(), Some(b"0xdef".to_vec()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_configured_sqlite_store_usable_from_async_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = KvStoreConfig::Sqlite {
+            path: dir.path().join("kv.db").to_string_lossy().into_owned(),
+        };
+        let store = kv_store(&config, system_clock()).unwrap();
+
+        let writer = store.clone();
+        tokio::spawn(async move { writer.put("watch/1/0xabc", b"{}", None) })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(store.list("watch/1/").unwrap().len(), 1);
+
+        // The file outlives the store
+        drop(store);
+        let reopened = kv_store(&config, system_clock()).unwrap();
+        assert_eq!(reopened.get("watch/1/0xabc").unwrap(), Some(b"{}".to_vec()));
+        assert!(kv_store(&KvStoreConfig::Memory, system_clock()).unwrap().get("watch/1/0xabc").unwrap().is_none());
+    }
+}
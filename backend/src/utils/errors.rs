@@ -1,3 +1,10 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use crate::blockchain::rate_limit::RpcRateLimit;
+use crate::utils::retry::RetryDecision;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, GovernanceError>;
@@ -5,11 +12,21 @@ pub type Result<T> = std::result::Result<T, GovernanceError>;
 #[derive(Error, Debug)]
 pub enum GovernanceError {
     #[error("Blockchain error: {0}")]
-    Blockchain(#[from] ethers::providers::ProviderError),
+    Blockchain(ethers::providers::ProviderError),
+
+    #[error("RPC provider rate limit exceeded: {message}")]
+    RpcRateLimited { message: String, retry_after: Option<std::time::Duration> },
 
     #[error("IPFS error: {message}")]
     Ipfs { message: String },
 
+    #[error("Expected {expected} content at {hash}, found {found}")]
+    ContentTypeMismatch {
+        hash: String,
+        expected: &'static str,
+        found: String,
+    },
+
     #[error("Proposal not found: {proposal_id}")]
     ProposalNotFound { proposal_id: u64 },
 
@@ -19,9 +36,60 @@ pub enum GovernanceError {
     #[error("Insufficient voting power: required {required}, available {available}")]
     InsufficientVotingPower { required: u64, available: u64 },
 
+    #[error("No voting power at snapshot block {block} of proposal {proposal_id}; only power held at creation counts")]
+    NoSnapshotPower { proposal_id: u64, block: u64 },
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Too many requests")]
+    RateLimited,
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
+
+    #[error("Vote already submitted for proposal {proposal_id} by {voter}")]
+    DuplicateVote { proposal_id: u64, voter: String },
+
+    #[error("Active proposal {proposal_id} in category {category} already has this title")]
+    DuplicateTitle { proposal_id: u64, category: String },
+
+    #[error("{proposer} may not create proposals in category {category}")]
+    ProposerNotAllowed { category: String, proposer: String },
+
+    #[error("{proposer} reached the proposal limit; next proposal allowed in {retry_after}s")]
+    ProposalCooldown { proposer: String, retry_after: u64 },
+
+    #[error("Relayer {0} is not trusted")]
+    UntrustedRelayer(String),
+
+    #[error("Content rejected by moderation: {0}")]
+    ContentRejected(String),
+
     #[error("Voting period ended: {proposal_id}")]
     VotingPeriodEnded { proposal_id: u64 },
 
+    #[error("Votes on proposal {proposal_id} are not final until it is settled")]
+    VotesNotFinal { proposal_id: u64 },
+
+    #[error(
+        "Proposal {proposal_id} was created but its voting ends at {end_time}, less than {min_remaining}s from now \
+         ({now}); check the voting duration and the server clock"
+    )]
+    ProposalEndsTooSoon { proposal_id: u64, end_time: u64, now: u64, min_remaining: u64 },
+
     #[error("Validation error: {0}")]
     Validation(#[from] validator::ValidationErrors),
 
@@ -31,6 +99,9 @@ pub enum GovernanceError {
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
 
+    #[error("Storage error: {0}")]
+    Storage(#[from] rusqlite::Error),
+
     #[error("Network error: {0}")]
     Network(#[from] hyper::Error),
 
@@ -48,4 +119,96 @@ impl GovernanceError {
     pub fn invalid_signature<T: Into<String>>(message: T) -> Self {
         Self::InvalidSignature(message.into())
     }
+
+    pub fn invalid_request<T: Into<String>>(message: T) -> Self {
+        Self::InvalidRequest(message.into())
+    }
+
+    pub fn not_found<T: Into<String>>(message: T) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn unauthorized<T: Into<String>>(message: T) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn forbidden<T: Into<String>>(message: T) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    /// Whether the failure may be transient, e.g. a node that timed out or
+    /// dropped the connection, so the same call could succeed when retried
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Blockchain(_) | Self::RpcRateLimited { .. } | Self::Ipfs { .. } | Self::Network(_)
+        )
+    }
+
+    /// Retry only provider rate limits, after the delay the provider asked
+    /// for when it gave one
+    pub fn rate_limit_retry(&self) -> RetryDecision {
+        match self {
+            Self::RpcRateLimited {
+                retry_after: Some(delay),
+                ..
+            } => RetryDecision::After(*delay),
+            Self::RpcRateLimited { .. } => RetryDecision::Backoff,
+            _ => RetryDecision::Stop,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Blockchain(_) | Self::Ipfs { .. } | Self::Network(_) => StatusCode::BAD_GATEWAY,
+            Self::ProposalNotFound { .. } | Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::InvalidSignature(_) | Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::RateLimited | Self::ProposalCooldown { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::ServiceUnavailable(_) | Self::RpcRateLimited { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            Self::InsufficientVotingPower { .. }
+            | Self::NoSnapshotPower { .. }
+            | Self::ProposerNotAllowed { .. }
+            | Self::UntrustedRelayer(_)
+            | Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::DuplicateVote { .. }
+            | Self::DuplicateTitle { .. }
+            | Self::VotesNotFinal { .. }
+            | Self::ProposalEndsTooSoon { .. } => StatusCode::CONFLICT,
+            Self::VotingPeriodEnded { .. }
+            | Self::InvalidRequest(_)
+            | Self::ContentTypeMismatch { .. }
+            | Self::ContentRejected(_)
+            | Self::Validation(_)
+            | Self::Serialization(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::Config(_) | Self::Storage(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Provider errors, with rate-limit rejections told apart from other failures
+impl From<ethers::providers::ProviderError> for GovernanceError {
+    fn from(error: ethers::providers::ProviderError) -> Self {
+        match RpcRateLimit::detect(&error) {
+            Some(limit) => Self::RpcRateLimited {
+                message: error.to_string(),
+                retry_after: limit.retry_after,
+            },
+            None => Self::Blockchain(error),
+        }
+    }
+}
+
+impl IntoResponse for GovernanceError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        if status.is_server_error() {
+            tracing::error!("Request failed: {}", self);
+        }
+
+        let body = crate::auth::middleware::ApiResponse::<()>::error(self.to_string());
+        (status, Json(body)).into_response()
+    }
 }
\ No newline at end of file
@@ -1,6 +1,12 @@
+use crate::utils::errors::{GovernanceError, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+pub const MAX_PAGE_LIMIT: u64 = 100;
+
+/// Set on list responses whose requested `limit` was clamped
+pub const PAGINATION_NOTICE_HEADER: &str = "x-pagination-notice";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaginationParams {
     pub page: Option<u64>,
@@ -9,11 +15,29 @@ pub struct PaginationParams {
 
 impl PaginationParams {
     pub fn page(&self) -> u64 {
-        self.page.unwrap_or(1)
+        self.page.unwrap_or(1).max(1)
     }
 
     pub fn limit(&self) -> u64 {
-        self.limit.unwrap_or(20).min(100) // Max 100 items per page
+        self.limit.unwrap_or(20).min(MAX_PAGE_LIMIT)
+    }
+
+    /// Pages are numbered from 1 and must hold at least one item
+    pub fn validate(&self) -> Result<()> {
+        if self.page == Some(0) {
+            return Err(GovernanceError::invalid_request("page must be at least 1"));
+        }
+        if self.limit == Some(0) {
+            return Err(GovernanceError::invalid_request("limit must be at least 1"));
+        }
+        Ok(())
+    }
+
+    /// Explanation for clients whose `limit` was above the maximum
+    pub fn clamp_notice(&self) -> Option<String> {
+        self.limit
+            .filter(|limit| *limit > MAX_PAGE_LIMIT)
+            .map(|limit| format!("limit {} clamped to {}", limit, MAX_PAGE_LIMIT))
     }
 
     pub fn offset(&self) -> u64 {
@@ -1,3 +1,6 @@
+pub mod clock;
 pub mod errors;
 pub mod helpers;
-pub mod validation;
\ No newline at end of file
+pub mod retry;
+pub mod validation;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,193 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// How often and how patiently to retry a failing call. The delay before
+/// retry `n` is `initial_delay * multiplier^(n - 1)`, capped at `max_delay`,
+/// less up to `jitter` of itself at random so clients don't retry in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: u32,
+    /// Fraction of each delay, between 0 and 1, that may be randomly shaved off
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Delay before retry `retry` (1 for the first retry), without jitter
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.max(1).saturating_pow(retry.saturating_sub(1));
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return base;
+        }
+        base.mul_f64(1.0 - jitter * rand::random::<f64>())
+    }
+}
+
+/// What to do about a failed attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Give up and return the error
+    Stop,
+    /// Retry after the policy's backoff delay
+    Backoff,
+    /// Retry after this delay, e.g. a server's `Retry-After`
+    After(Duration),
+}
+
+/// Run `operation` until it succeeds, fails with an error `is_retryable`
+/// rejects, or `policy.max_attempts` attempts have been made, sleeping with
+/// backoff between attempts. Returns the last result.
+pub async fn retry_with_backoff<T, E, F, Fut, R>(policy: &RetryPolicy, operation: F, is_retryable: R) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    R: Fn(&E) -> bool,
+    E: Display,
+{
+    let decide = |e: &E| if is_retryable(e) { RetryDecision::Backoff } else { RetryDecision::Stop };
+    retry_with_decision(policy, operation, decide).await
+}
+
+/// `retry_with_backoff`, with `decide` choosing per error whether and when to
+/// retry. A requested delay longer than `policy.max_delay` ends the retries
+/// rather than stalling the caller.
+pub async fn retry_with_decision<T, E, F, Fut, D>(policy: &RetryPolicy, mut operation: F, decide: D) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    D: Fn(&E) -> RetryDecision,
+    E: Display,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let e = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max_attempts => e,
+            Err(e) => return Err(e),
+        };
+        let delay = match decide(&e) {
+            RetryDecision::Stop => return Err(e),
+            RetryDecision::Backoff => policy.delay(attempt),
+            RetryDecision::After(delay) if delay <= policy.max_delay => delay,
+            RetryDecision::After(delay) => {
+                tracing::debug!("Not retrying, asked to wait {:?}: {}", delay, e);
+                return Err(e);
+            }
+        };
+        tracing::debug!("Attempt {} failed, retrying in {:?}: {}", attempt, delay, e);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::errors::GovernanceError;
+    use std::sync::Mutex;
+    use tokio::time::Instant;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+            multiplier: 2,
+            jitter: 0.0,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_with_increasing_delays_up_to_max_attempts() {
+        let attempts = Mutex::new(Vec::new());
+        let result: Result<(), GovernanceError> = retry_with_backoff(
+            &policy(4),
+            || {
+                attempts.lock().unwrap().push(Instant::now());
+                async { Err(GovernanceError::ipfs("connection reset")) }
+            },
+            GovernanceError::is_retryable,
+        )
+        .await;
+
+        assert!(result.is_err());
+        let attempts = attempts.into_inner().unwrap();
+        assert_eq!(attempts.len(), 4);
+        let delays: Vec<_> = attempts.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let expected = [100, 200, 300].map(Duration::from_millis);
+        assert_eq!(delays, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stops_on_success_or_non_retryable_error() {
+        let calls = Mutex::new(0);
+        let result = retry_with_backoff(
+            &policy(5),
+            || {
+                let mut calls = calls.lock().unwrap();
+                *calls += 1;
+                let call = *calls;
+                async move {
+                    match call {
+                        1 => Err(GovernanceError::ipfs("timed out")),
+                        _ => Ok(call),
+                    }
+                }
+            },
+            GovernanceError::is_retryable,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 2);
+
+        let calls = Mutex::new(0);
+        let result: Result<(), _> = retry_with_backoff(
+            &policy(5),
+            || {
+                *calls.lock().unwrap() += 1;
+                async { Err(GovernanceError::invalid_request("bad input")) }
+            },
+            GovernanceError::is_retryable,
+        )
+        .await;
+        assert!(matches!(result, Err(GovernanceError::InvalidRequest(_))));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_jitter_only_shortens_delays() {
+        let policy = RetryPolicy::default();
+        for retry in 1..=5 {
+            let delay = policy.delay(retry);
+            assert!(delay <= policy.base_delay(retry));
+            assert!(delay >= policy.base_delay(retry).mul_f64(1.0 - policy.jitter));
+        }
+    }
+}
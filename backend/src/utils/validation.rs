@@ -33,15 +33,16 @@ pub fn validate_ipfs_hash(hash: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+pub const MIN_VOTING_DURATION: u64 = 3600; // 1 hour
+pub const MAX_VOTING_DURATION: u64 = 2592000; // 30 days
+pub const MIN_REMAINING_VOTING_TIME: u64 = 600; // 10 minutes, left on a proposal just created
+
 pub fn validate_voting_duration(duration: u64) -> Result<(), ValidationError> {
-    const MIN_DURATION: u64 = 3600; // 1 hour
-    const MAX_DURATION: u64 = 2592000; // 30 days
-    
-    if duration < MIN_DURATION {
+    if duration < MIN_VOTING_DURATION {
         return Err(ValidationError::new("duration_too_short"));
     }
     
-    if duration > MAX_DURATION {
+    if duration > MAX_VOTING_DURATION {
         return Err(ValidationError::new("duration_too_long"));
     }
     